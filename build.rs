@@ -0,0 +1,36 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/scanner.proto");
+        // 使用内置的 protoc，编译时不需要另外安装
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("找不到内置的protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::configure()
+            .build_client(true)
+            .compile_protos(&["proto/scanner.proto"], &["proto"])
+            .expect("proto编译失败");
+    }
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi");
+        // 只解析 C 接口模块，不需要 cargo metadata
+        let config = cbindgen::Config {
+            usize_is_size_t: true,
+            ..Default::default()
+        };
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src("src/capi/mod.rs")
+            .with_language(cbindgen::Language::C)
+            .with_include_guard("KIM_SCANNER_H")
+            .with_cpp_compat(true)
+            .with_header("/* 由 build.rs 通过 cbindgen 生成，不要手动修改 */")
+            .generate()
+            .expect("C 头文件生成失败")
+            // 不写入源码目录，测试检查生成的头文件和 include/kim_scanner.h 是否一致
+            .write_to_file(
+                std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("kim_scanner.h"),
+            );
+    }
+}
@@ -0,0 +1,138 @@
+/* 由 build.rs 通过 cbindgen 生成，不要手动修改 */
+
+#ifndef KIM_SCANNER_H
+#define KIM_SCANNER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * 成功
+ */
+#define KIM_OK 0
+
+/**
+ * 参数错误(空指针、字符串不是 UTF-8 等)
+ */
+#define KIM_ERR_PARAM -1
+
+/**
+ * 扫码枪返回错误
+ */
+#define KIM_ERR_SCANNER -2
+
+/**
+ * 缓冲区太小，条码没有取出，可以加大缓冲区后重新读取
+ */
+#define KIM_ERR_BUFFER -3
+
+/**
+ * 库内部错误，错误信息中有详细原因
+ */
+#define KIM_ERR_INTERNAL -4
+
+/**
+ * 扫码枪句柄，由[`scanner_new`]创建，[`scanner_free`]释放
+ */
+typedef struct KimScanner KimScanner;
+
+/**
+ * 条码回调函数
+ *
+ * * `barcode` 条码文本，以`\0`结尾，只在回调期间有效
+ * * `len` 条码文本的字节数(不包括结尾的`\0`)
+ * * `user_data` 注册回调时传入的用户数据
+ */
+typedef void (*KimBarcodeCallback)(const char *barcode, size_t len, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * 获取当前线程最后一次失败的错误信息(UTF-8)，没有错误时返回`NULL`
+ *
+ * 返回的字符串在当前线程下一次调用本库函数前有效，不需要释放
+ */
+const char *scanner_last_error(void);
+
+/**
+ * 从连接 URL 创建扫码枪，URL 格式见[`Connector`]的`from_str`，例如`tcp://192.168.1.10:9004`、
+ * `serial://COM3?baud=115200`
+ *
+ * 条码末尾的回车换行会被去掉，失败时返回`NULL`
+ *
+ * # Safety
+ * `url`为以`\0`结尾的有效字符串
+ */
+struct KimScanner *scanner_new(const char *url);
+
+/**
+ * 启动扫码枪，后台连接并自动重连，成功返回 0。已经启动时直接返回 0
+ *
+ * # Safety
+ * `scanner`为[`scanner_new`]返回的未释放的句柄
+ */
+int32_t scanner_start(struct KimScanner *scanner);
+
+/**
+ * 停止扫码枪，断开连接，之后可以重新调用[`scanner_start`]
+ *
+ * # Safety
+ * `scanner`为空或[`scanner_new`]返回的未释放的句柄
+ */
+void scanner_stop(struct KimScanner *scanner);
+
+/**
+ * 释放扫码枪，先停止扫码枪。返回后不会再调用回调函数
+ *
+ * # Safety
+ * `scanner`为空或[`scanner_new`]返回的未释放的句柄，释放后不能再使用
+ */
+void scanner_free(struct KimScanner *scanner);
+
+/**
+ * 注册条码回调函数，传入`NULL`取消注册，成功返回 0
+ *
+ * 注册后条码不再进入轮询队列。回调在库的独立线程中调用，不能阻塞太久，
+ * 也不能在回调中调用[`scanner_free`]
+ *
+ * # Safety
+ * `scanner`为[`scanner_new`]返回的未释放的句柄，`user_data`在取消注册或释放扫码枪前有效
+ */
+int32_t scanner_set_callback(struct KimScanner *scanner,
+                             KimBarcodeCallback callback,
+                             void *user_data);
+
+/**
+ * 从轮询队列取出一个条码，写入`buf`(以`\0`结尾)
+ *
+ * * `timeout_ms` 队列为空时最多等待的毫秒数，0 表示不等待
+ *
+ * 返回条码的字节数(不包括结尾的`\0`)，超时没有条码时返回 0。
+ * `buf`放不下时返回[`KIM_ERR_BUFFER`]，条码留在队列中
+ *
+ * # Safety
+ * `scanner`为[`scanner_new`]返回的未释放的句柄，`buf`至少可以写入`len`个字节
+ */
+int32_t scanner_poll_barcode(struct KimScanner *scanner,
+                             char *buf,
+                             size_t len,
+                             uint32_t timeout_ms);
+
+/**
+ * 给扫码枪发送指令(反控)，成功返回 0，可以在回调函数中调用
+ *
+ * # Safety
+ * `scanner`为[`scanner_new`]返回的未释放的句柄，`cmd`为以`\0`结尾的有效字符串
+ */
+int32_t scanner_send(struct KimScanner *scanner, const char *cmd);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KIM_SCANNER_H */
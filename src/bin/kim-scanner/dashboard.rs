@@ -0,0 +1,264 @@
+//! 终端看板，实时显示所有扫码枪的连接状态、最后的条码和扫码速率，按 q 或 Esc 退出
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use kim_scanner::prelude::*;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+/// 事件日志保留的行数
+const LOG_LINES: usize = 100;
+/// 扫码速率的统计时长
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+/// 界面刷新间隔
+const TICK: Duration = Duration::from_millis(250);
+
+/// 启动配置文件和 URL 中的扫码枪并显示看板，URL 扫码枪以 URL 作为编号
+pub(crate) async fn dashboard(config: Option<&Path>, urls: &[String]) -> Result<(), ScannerError> {
+    let mut manager = match config {
+        Some(config) => ScannerManager::from_config(config)?,
+        None => ScannerManager::new(),
+    };
+    for url in urls {
+        let scanner = Scanner::new(url.parse::<Connector>()?).trim_end([b'\r', b'\n']);
+        manager = manager.with_scanner(url, scanner);
+    }
+    if manager.is_empty() {
+        return Err(ScannerError::Param("没有配置扫码枪".into()));
+    }
+    let manager = Arc::new(manager);
+    manager.start().await?;
+
+    // 接收所有事件写入日志，同时避免事件队列满了阻塞统计
+    let log = Arc::new(Mutex::new(VecDeque::new()));
+    let receiver = {
+        let (manager, log) = (Arc::clone(&manager), Arc::clone(&log));
+        tokio::spawn(async move {
+            while let Some(ev) = manager.recv().await {
+                let mut log = log.lock().unwrap();
+                if log.len() == LOG_LINES {
+                    log.pop_back();
+                }
+                log.push_front(format!(
+                    "{:<16} {:<12} {}",
+                    ev.id(),
+                    ev.event().kind(),
+                    ev.event().printable()
+                ));
+            }
+        })
+    };
+
+    let mut dashboard = Dashboard::new(Arc::clone(&manager), log);
+    let result = tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::try_init()?;
+        let result = dashboard.run(&mut terminal);
+        ratatui::restore();
+        result
+    })
+    .await
+    .map_err(|err| ScannerError::Comm(err.to_string()))?;
+    receiver.abort();
+    manager.stop();
+    result.map_err(ScannerError::Io)
+}
+
+/// 看板状态
+struct Dashboard {
+    manager: Arc<ScannerManager>,
+    log: Arc<Mutex<VecDeque<String>>>,
+    /// 每个扫码枪最近的扫码数采样，用于计算扫码速率
+    samples: HashMap<String, VecDeque<(Instant, u64)>>,
+}
+
+impl Dashboard {
+    fn new(manager: Arc<ScannerManager>, log: Arc<Mutex<VecDeque<String>>>) -> Self {
+        Dashboard {
+            manager,
+            log,
+            samples: HashMap::new(),
+        }
+    }
+
+    /// 刷新界面直到按下退出键
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            self.sample(Instant::now());
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(TICK)? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                    || (key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL));
+                if key.kind == KeyEventKind::Press && quit {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// 记录各扫码枪当前的扫码数，丢弃统计时长以外的采样
+    fn sample(&mut self, now: Instant) {
+        for id in self.manager.ids() {
+            let Some(stats) = self.manager.stats(&id) else {
+                continue;
+            };
+            let samples = self.samples.entry(id).or_default();
+            samples.push_back((now, stats.scans()));
+            while samples
+                .front()
+                .is_some_and(|(time, _)| now.duration_since(*time) > RATE_WINDOW)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// 每分钟扫码数，采样不足一秒时为 0
+    fn rate(&self, id: &str) -> f64 {
+        let Some(samples) = self.samples.get(id) else {
+            return 0.0;
+        };
+        let (Some((first, from)), Some((last, to))) = (samples.front(), samples.back()) else {
+            return 0.0;
+        };
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        if elapsed < 1.0 {
+            return 0.0;
+        }
+        (to - from) as f64 * 60.0 / elapsed
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, table, log] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(12),
+        ])
+        .areas(frame.area());
+        frame.render_widget(
+            Line::from(format!(
+                " kim-scanner 看板  扫码枪 {} 台  按 q 退出",
+                self.manager.len()
+            ))
+            .bold(),
+            header,
+        );
+
+        let rows = self.manager.ids().into_iter().map(|id| {
+            let stats = self.manager.stats(&id).unwrap_or_default();
+            let running = self.manager.get(&id).is_some_and(|s| s.is_running());
+            let (state, color) = match (running, stats.connected()) {
+                (false, _) => ("已停止", Color::DarkGray),
+                (true, Some(true)) => ("已连接", Color::Green),
+                (true, Some(false)) => ("已断开", Color::Red),
+                (true, None) => ("运行中", Color::Yellow),
+            };
+            let last_scan = stats
+                .last_scan()
+                .and_then(|time| SystemTime::now().duration_since(time).ok())
+                .map(|elapsed| format!("{}秒前", elapsed.as_secs()))
+                .unwrap_or_default();
+            Row::new(vec![
+                id.clone().into(),
+                Line::from(state).style(Style::new().fg(color)),
+                stats
+                    .last_barcode()
+                    .map(Barcode::printable)
+                    .unwrap_or_default()
+                    .into(),
+                stats.scans().to_string().into(),
+                stats.no_reads().to_string().into(),
+                stats.errors().to_string().into(),
+                stats.disconnects().to_string().into(),
+                format!("{:.1}", self.rate(&id)).into(),
+                last_scan.into(),
+            ])
+        });
+        let widths = [
+            Constraint::Length(24),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ];
+        let header_row = Row::new([
+            "编号",
+            "状态",
+            "最后条码",
+            "扫码",
+            "未读",
+            "错误",
+            "断开",
+            "次/分",
+            "最后扫码",
+        ])
+        .bold();
+        frame.render_widget(
+            Table::new(rows, widths)
+                .header(header_row)
+                .block(Block::bordered().title(" 扫码枪 ")),
+            table,
+        );
+
+        let lines = self.log.lock().unwrap().iter().cloned().collect::<Vec<_>>();
+        frame.render_widget(
+            List::new(lines).block(Block::bordered().title(" 事件 ")),
+            log,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use super::*;
+
+    #[test]
+    fn draw() {
+        let manager = ScannerManager::new().with_scanner(
+            "line1",
+            Scanner::new(Network::new_client("127.0.0.1", 9004)),
+        );
+        let log = Arc::new(Mutex::new(VecDeque::from([
+            "line1 barcode SN0001".to_string()
+        ])));
+        let mut dashboard = Dashboard::new(Arc::new(manager), log);
+
+        let now = Instant::now();
+        dashboard.sample(now);
+        assert_eq!(dashboard.rate("line1"), 0.0);
+        dashboard.samples.insert(
+            "line1".into(),
+            VecDeque::from([(now, 10), (now + Duration::from_secs(30), 40)]),
+        );
+        assert_eq!(dashboard.rate("line1"), 60.0);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let text = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(text.contains("line1"));
+        assert!(text.contains("SN0001"));
+        assert!(text.contains("60.0"));
+    }
+}
@@ -0,0 +1,306 @@
+//! 扫码枪命令行工具，现场调试时查看扫码、发送指令、列出串口，不需要写代码
+//!
+//! ```text
+//! kim-scanner monitor tcp://192.168.1.10:9004
+//! kim-scanner send serial:///dev/ttyUSB0?baud=115200 "LON\r"
+//! kim-scanner ports
+//! kim-scanner emulate --listen 127.0.0.1:9004 --no-read 0.1 SN0001 SN0002
+//! kim-scanner dashboard --config fleet.toml
+//! ```
+#[cfg(feature = "tui")]
+mod dashboard;
+
+use std::net::SocketAddr;
+#[cfg(feature = "tui")]
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use kim_scanner::prelude::*;
+use tracing::Level;
+
+/// 扫码枪命令行工具
+#[derive(Parser)]
+#[command(name = "kim-scanner", version)]
+struct Cli {
+    /// 输出连接日志，-v 为 INFO，-vv 为 DEBUG
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 连接扫码枪，实时打印扫码事件，Ctrl+C 退出
+    Monitor {
+        /// 连接 URL，例如 tcp://192.168.1.10:9004、serial://COM3?baud=115200
+        url: String,
+        /// 同时打印条码的十六进制
+        #[arg(long)]
+        hex: bool,
+        /// 保留条码末尾的回车换行
+        #[arg(long)]
+        raw: bool,
+    },
+    /// 给扫码枪发送一条指令，并打印等待期间收到的数据
+    Send {
+        /// 连接 URL
+        url: String,
+        /// 指令，支持转义 \r \n \t \\ \xHH
+        command: String,
+        /// 发送后等待应答的秒数
+        #[arg(long, default_value_t = 2.0)]
+        wait: f64,
+    },
+    /// 列出本机串口
+    Ports,
+    /// 模拟扫码枪，收到触发指令(LON)后输出预设的条码，用于测试应用程序
+    Emulate {
+        /// 按顺序输出的条码
+        #[arg(required = true)]
+        barcodes: Vec<String>,
+        /// 网络扫码枪的监听地址
+        #[arg(long, default_value = "127.0.0.1:9004")]
+        listen: SocketAddr,
+        /// 创建虚拟串口代替网络监听(仅 Linux/macOS)
+        #[arg(long)]
+        pty: bool,
+        /// 解码延时(毫秒)
+        #[arg(long, default_value_t = 100)]
+        delay: u64,
+        /// 连续模式，每隔指定毫秒输出一个条码，不需要触发
+        #[arg(long)]
+        interval: Option<u64>,
+        /// 未读的概率(0~1)
+        #[arg(long, default_value_t = 0.0)]
+        no_read: f64,
+        /// 输出损坏条码的概率(0~1)
+        #[arg(long, default_value_t = 0.0)]
+        corrupt: f64,
+        /// 不应答的概率(0~1)
+        #[arg(long, default_value_t = 0.0)]
+        silent: f64,
+        /// 断开连接的概率(0~1)
+        #[arg(long, default_value_t = 0.0)]
+        disconnect: f64,
+        /// 故障注入的随机数种子
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// 终端看板，实时显示多个扫码枪的状态、最后的条码和扫码速率，按 q 退出
+    #[cfg(feature = "tui")]
+    Dashboard {
+        /// 扫码枪配置文件(TOML/YAML/JSON)
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// 另外要显示的扫码枪连接 URL，以 URL 作为编号
+        urls: Vec<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let level = match cli.verbose {
+        0 => Level::WARN,
+        1 => Level::INFO,
+        _ => Level::DEBUG,
+    };
+    // 看板占用整个终端，不输出日志
+    #[cfg(feature = "tui")]
+    let logging = !matches!(cli.command, Command::Dashboard { .. });
+    #[cfg(not(feature = "tui"))]
+    let logging = true;
+    if logging {
+        tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    let result = match cli.command {
+        Command::Monitor { url, hex, raw } => monitor(&url, hex, raw).await,
+        Command::Send { url, command, wait } => send(&url, &command, wait).await,
+        Command::Ports => ports(),
+        Command::Emulate {
+            barcodes,
+            listen,
+            pty,
+            delay,
+            interval,
+            no_read,
+            corrupt,
+            silent,
+            disconnect,
+            seed,
+        } => {
+            let mut emulator = Emulator::new(barcodes)
+                .with_delay(Duration::from_millis(delay))
+                .with_fault(Fault::NoRead, no_read)
+                .with_fault(Fault::Corrupt, corrupt)
+                .with_fault(Fault::Silent, silent)
+                .with_fault(Fault::Disconnect, disconnect);
+            if let Some(interval) = interval {
+                emulator = emulator.with_interval(Duration::from_millis(interval));
+            }
+            if let Some(seed) = seed {
+                emulator = emulator.with_seed(seed);
+            }
+            emulate(emulator, listen, pty).await
+        }
+        #[cfg(feature = "tui")]
+        Command::Dashboard { config, urls } => dashboard::dashboard(config.as_deref(), &urls).await,
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 创建并启动扫码枪
+async fn start(url: &str, raw: bool) -> Result<Scanner, ScannerError> {
+    let mut scanner = Scanner::new(url.parse::<Connector>()?);
+    if !raw {
+        scanner = scanner.trim_end([b'\r', b'\n']);
+    }
+    scanner.start().await??;
+    Ok(scanner)
+}
+
+/// 打印一个事件
+fn print(ev: &ScanEvent, hex: bool) {
+    match ev.as_hex().filter(|_| hex) {
+        Some(hex) => println!("{:<12} {}\t{}", ev.kind(), ev.printable(), hex),
+        None => println!("{:<12} {}", ev.kind(), ev.printable()),
+    }
+}
+
+async fn monitor(url: &str, hex: bool, raw: bool) -> Result<(), ScannerError> {
+    let scanner = start(url, raw).await?;
+    eprintln!("正在监听 {}，Ctrl+C 退出", scanner.connector);
+    loop {
+        tokio::select! {
+            ev = scanner.recv() => match ev {
+                Some(ev) => print(&ev, hex),
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    scanner.stop();
+    Ok(())
+}
+
+async fn send(url: &str, command: &str, wait: f64) -> Result<(), ScannerError> {
+    let command = unescape(command)
+        .ok_or_else(|| ScannerError::Param(format!("无效的转义字符,command={}", command)))?;
+    let wait = Duration::try_from_secs_f64(wait)
+        .map_err(|_| ScannerError::Param(format!("无效的等待时长,wait={}", wait)))?;
+    let scanner = start(url, false).await?;
+    scanner.send_message(command).await??;
+    let _ = tokio::time::timeout(wait, async {
+        while let Some(ev) = scanner.recv().await {
+            print(&ev, false);
+        }
+    })
+    .await;
+    scanner.stop();
+    Ok(())
+}
+
+fn ports() -> Result<(), ScannerError> {
+    let ports = Serial::discover()?;
+    if ports.is_empty() {
+        eprintln!("没有找到串口");
+    }
+    for port in ports {
+        let usb = match (port.vid(), port.pid()) {
+            (Some(vid), Some(pid)) => format!("{:04X}:{:04X}", vid, pid),
+            _ => String::new(),
+        };
+        println!(
+            "{:<16} {:<10} {:<10} {} {}",
+            port.name(),
+            format!("{:?}", port.port_type()),
+            usb,
+            port.manufacturer().unwrap_or_default(),
+            port.product().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+async fn emulate(emulator: Emulator, listen: SocketAddr, pty: bool) -> Result<(), ScannerError> {
+    if !pty {
+        eprintln!("模拟扫码枪监听 {}，Ctrl+C 退出", listen);
+        return tokio::select! {
+            r = emulator.serve(listen) => r,
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        };
+    }
+    #[cfg(unix)]
+    {
+        let name = emulator.virtual_serial()?;
+        eprintln!("模拟扫码枪串口 {}，Ctrl+C 退出", name);
+        let _ = tokio::signal::ctrl_c().await;
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    Err(ScannerError::Param(
+        "当前平台不支持虚拟串口，请使用 com0com 等工具".into(),
+    ))
+}
+
+/// 处理指令中的转义字符，`\xHH`只支持 ASCII
+fn unescape(text: &str) -> Option<String> {
+    let mut command = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            command.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'r' => command.push('\r'),
+            'n' => command.push('\n'),
+            't' => command.push('\t'),
+            '\\' => command.push('\\'),
+            'x' => {
+                let hex: String = [chars.next()?, chars.next()?].iter().collect();
+                let b = u8::from_str_radix(&hex, 16).ok().filter(u8::is_ascii)?;
+                command.push(b as char);
+            }
+            _ => return None,
+        }
+    }
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_command() {
+        assert_eq!(unescape(r"LON\r").as_deref(), Some("LON\r"));
+        assert_eq!(unescape(r"\x16T\x0D").as_deref(), Some("\x16T\r"));
+        assert_eq!(unescape(r"a\\b\n").as_deref(), Some("a\\b\n"));
+        assert_eq!(unescape(r"\q"), None);
+        assert_eq!(unescape(r"\xFF"), None);
+        assert_eq!(unescape(r"\x1"), None);
+    }
+
+    #[test]
+    fn parse_args() {
+        let cli =
+            Cli::try_parse_from(["kim-scanner", "-vv", "send", "tcp://127.0.0.1:9004", "LON"])
+                .unwrap();
+        assert_eq!(cli.verbose, 2);
+        assert!(matches!(cli.command, Command::Send { wait, .. } if wait == 2.0));
+        assert!(Cli::try_parse_from(["kim-scanner", "monitor"]).is_err());
+    }
+}
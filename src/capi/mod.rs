@@ -0,0 +1,554 @@
+//! C 语言接口，供 C/C++/C# 工位软件以动态库(DLL/so)或静态库的方式调用
+//!
+//! 编译动态库(头文件为仓库中的`include/kim_scanner.h`)：
+//!
+//! ```text
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//!
+//! 头文件由 build.rs 通过 cbindgen 生成到`OUT_DIR`，修改接口后把生成的头文件复制到`include/`，
+//! 测试会检查两者是否一致。
+//!
+//! 所有扫码枪共用一个内部的 tokio 运行时，函数可以在任意线程调用。回调函数在库的独立线程中调用，
+//! 回调中可以调用[`scanner_send`]等函数。
+//! 失败的函数返回`NULL`或负数，错误信息通过[`scanner_last_error`]获取
+//!
+//! ```c
+//! KimScanner *scanner = scanner_new("tcp://192.168.1.10:9004");
+//! scanner_start(scanner);
+//! char barcode[256];
+//! int32_t len = scanner_poll_barcode(scanner, barcode, sizeof(barcode), 1000);
+//! if (len > 0) printf("%s\n", barcode);
+//! scanner_free(scanner);
+//! ```
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+use tokio::task::JoinHandle;
+use tracing::{event, Level};
+
+use crate::prelude::*;
+
+/// 成功
+pub const KIM_OK: i32 = 0;
+/// 参数错误(空指针、字符串不是 UTF-8 等)
+pub const KIM_ERR_PARAM: i32 = -1;
+/// 扫码枪返回错误
+pub const KIM_ERR_SCANNER: i32 = -2;
+/// 缓冲区太小，条码没有取出，可以加大缓冲区后重新读取
+pub const KIM_ERR_BUFFER: i32 = -3;
+/// 库内部错误，错误信息中有详细原因
+pub const KIM_ERR_INTERNAL: i32 = -4;
+
+/// 轮询队列最多缓存的条码数，超过时丢弃新条码
+const QUEUE_CAPACITY: usize = 1000;
+
+/// 条码回调函数
+///
+/// * `barcode` 条码文本，以`\0`结尾，只在回调期间有效
+/// * `len` 条码文本的字节数(不包括结尾的`\0`)
+/// * `user_data` 注册回调时传入的用户数据
+pub type KimBarcodeCallback =
+    Option<unsafe extern "C" fn(barcode: *const c_char, len: usize, user_data: *mut c_void)>;
+
+/// 扫码枪句柄，由[`scanner_new`]创建，[`scanner_free`]释放
+pub struct KimScanner {
+    scanner: Scanner,
+    shared: Arc<Shared>,
+    queue: Mutex<Queue>,
+    pump: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// 轮询队列
+struct Queue {
+    receiver: Receiver<CString>,
+    /// 缓冲区太小没有取出的条码，下次轮询优先返回
+    pending: Option<CString>,
+}
+
+/// 接收任务和调用方共享的状态
+struct Shared {
+    sender: SyncSender<CString>,
+    callback: Mutex<Option<Callback>>,
+}
+
+/// 回调函数和用户数据，用户数据由调用方保证可以跨线程使用
+struct Callback {
+    func: unsafe extern "C" fn(*const c_char, usize, *mut c_void),
+    user_data: *mut c_void,
+}
+unsafe impl Send for Callback {}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// 记录当前线程最后的错误
+fn set_error(message: impl Into<String>) {
+    let message = message.into().replace('\0', " ");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+/// 执行导出函数，内部 panic 不会穿过 C 接口，记录错误后返回`fallback`
+fn guard<T>(fallback: T, f: impl FnOnce() -> T) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(r) => r,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            event!(Level::ERROR, error = %message, error.kind = "panic", "C 接口内部错误");
+            set_error(format!("内部错误: {}", message));
+            fallback
+        }
+    }
+}
+
+/// 在共用的运行时中等待异步操作完成
+///
+/// 在 tokio 运行时的线程中无法阻塞等待，这时返回`None`并记录错误
+fn block_on<F: std::future::Future>(runtime: &Runtime, future: F) -> Option<F::Output> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        set_error("不能在异步运行时的线程中调用");
+        return None;
+    }
+    Some(runtime.block_on(future))
+}
+
+/// 共用的 tokio 运行时，第一次使用时创建
+fn runtime() -> Option<&'static Runtime> {
+    static RUNTIME: OnceLock<Option<Runtime>> = OnceLock::new();
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("kim-scanner")
+            .enable_all()
+            .build()
+            .map_err(|err| event!(Level::ERROR, error = %err, error.kind = "io", "运行时创建失败"))
+            .ok()
+    });
+    if runtime.is_none() {
+        set_error("运行时创建失败");
+    }
+    runtime.as_ref()
+}
+
+/// 读取 C 字符串参数
+///
+/// # Safety
+/// `text`为空或以`\0`结尾的有效字符串
+unsafe fn text_arg<'a>(text: *const c_char, name: &str) -> Option<&'a str> {
+    if text.is_null() {
+        set_error(format!("{}不能为空", name));
+        return None;
+    }
+    match CStr::from_ptr(text).to_str() {
+        Ok(text) => Some(text),
+        Err(_) => {
+            set_error(format!("{}不是有效的 UTF-8 字符串", name));
+            None
+        }
+    }
+}
+
+/// 读取句柄参数
+///
+/// # Safety
+/// `scanner`为空或[`scanner_new`]返回的未释放的句柄
+unsafe fn handle<'a>(scanner: *const KimScanner) -> Option<&'a KimScanner> {
+    let scanner = scanner.as_ref();
+    if scanner.is_none() {
+        set_error("扫码枪句柄不能为空");
+    }
+    scanner
+}
+
+/// 获取当前线程最后一次失败的错误信息(UTF-8)，没有错误时返回`NULL`
+///
+/// 返回的字符串在当前线程下一次调用本库函数前有效，不需要释放
+#[no_mangle]
+pub extern "C" fn scanner_last_error() -> *const c_char {
+    guard(std::ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
+
+/// 从连接 URL 创建扫码枪，URL 格式见[`Connector`]的`from_str`，例如`tcp://192.168.1.10:9004`、
+/// `serial://COM3?baud=115200`
+///
+/// 条码末尾的回车换行会被去掉，失败时返回`NULL`
+///
+/// # Safety
+/// `url`为以`\0`结尾的有效字符串
+#[no_mangle]
+pub unsafe extern "C" fn scanner_new(url: *const c_char) -> *mut KimScanner {
+    guard(std::ptr::null_mut(), || {
+        let Some(url) = text_arg(url, "url") else {
+            return std::ptr::null_mut();
+        };
+        let connector = match url.parse::<Connector>() {
+            Ok(connector) => connector,
+            Err(err) => {
+                set_error(err.to_string());
+                return std::ptr::null_mut();
+            }
+        };
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let scanner = KimScanner {
+            scanner: Scanner::new(connector).trim_end([b'\r', b'\n']),
+            shared: Arc::new(Shared {
+                sender,
+                callback: Mutex::new(None),
+            }),
+            queue: Mutex::new(Queue {
+                receiver,
+                pending: None,
+            }),
+            pump: Mutex::new(None),
+        };
+        Box::into_raw(Box::new(scanner))
+    })
+}
+
+/// 启动扫码枪，后台连接并自动重连，成功返回 0。已经启动时直接返回 0
+///
+/// # Safety
+/// `scanner`为[`scanner_new`]返回的未释放的句柄
+#[no_mangle]
+pub unsafe extern "C" fn scanner_start(scanner: *mut KimScanner) -> i32 {
+    guard(KIM_ERR_INTERNAL, || {
+        let (Some(scanner), Some(runtime)) = (handle(scanner), runtime()) else {
+            return KIM_ERR_PARAM;
+        };
+        match block_on(runtime, scanner.scanner.start()) {
+            None => return KIM_ERR_PARAM,
+            Some(Ok(Ok(()))) => {}
+            Some(Ok(Err(err)) | Err(err)) => {
+                set_error(err.to_string());
+                return KIM_ERR_SCANNER;
+            }
+        }
+        let mut pump = scanner.pump.lock().unwrap();
+        if pump.is_none() {
+            // 条码在独立线程中交给回调函数，回调中可以调用其他函数
+            let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+            let shared = Arc::clone(&scanner.shared);
+            let addr = scanner.scanner.connector.to_string();
+            if let Err(err) = std::thread::Builder::new()
+                .name("kim-scanner-callback".into())
+                .spawn(move || deliver(addr, receiver, shared))
+            {
+                set_error(format!("回调线程创建失败: {}", err));
+                return KIM_ERR_INTERNAL;
+            }
+            *pump = Some(runtime.spawn(forward(scanner.scanner.clone(), sender)));
+        }
+        KIM_OK
+    })
+}
+
+/// 停止扫码枪，断开连接，之后可以重新调用[`scanner_start`]
+///
+/// # Safety
+/// `scanner`为空或[`scanner_new`]返回的未释放的句柄
+#[no_mangle]
+pub unsafe extern "C" fn scanner_stop(scanner: *mut KimScanner) {
+    guard((), || {
+        if let Some(scanner) = scanner.as_ref() {
+            scanner.scanner.stop();
+        }
+    })
+}
+
+/// 释放扫码枪，先停止扫码枪。返回后不会再调用回调函数
+///
+/// # Safety
+/// `scanner`为空或[`scanner_new`]返回的未释放的句柄，释放后不能再使用
+#[no_mangle]
+pub unsafe extern "C" fn scanner_free(scanner: *mut KimScanner) {
+    guard((), || {
+        if scanner.is_null() {
+            return;
+        }
+        let scanner = Box::from_raw(scanner);
+        scanner.scanner.stop();
+        // 接收任务结束后回调线程随之退出
+        if let Some(pump) = scanner.pump.lock().unwrap().take() {
+            pump.abort();
+        }
+        // 等待正在执行的回调结束
+        scanner.shared.callback.lock().unwrap().take();
+    })
+}
+
+/// 注册条码回调函数，传入`NULL`取消注册，成功返回 0
+///
+/// 注册后条码不再进入轮询队列。回调在库的独立线程中调用，不能阻塞太久，
+/// 也不能在回调中调用[`scanner_free`]
+///
+/// # Safety
+/// `scanner`为[`scanner_new`]返回的未释放的句柄，`user_data`在取消注册或释放扫码枪前有效
+#[no_mangle]
+pub unsafe extern "C" fn scanner_set_callback(
+    scanner: *mut KimScanner,
+    callback: KimBarcodeCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    guard(KIM_ERR_INTERNAL, || {
+        let Some(scanner) = handle(scanner) else {
+            return KIM_ERR_PARAM;
+        };
+        *scanner.shared.callback.lock().unwrap() =
+            callback.map(|func| Callback { func, user_data });
+        KIM_OK
+    })
+}
+
+/// 从轮询队列取出一个条码，写入`buf`(以`\0`结尾)
+///
+/// * `timeout_ms` 队列为空时最多等待的毫秒数，0 表示不等待
+///
+/// 返回条码的字节数(不包括结尾的`\0`)，超时没有条码时返回 0。
+/// `buf`放不下时返回[`KIM_ERR_BUFFER`]，条码留在队列中
+///
+/// # Safety
+/// `scanner`为[`scanner_new`]返回的未释放的句柄，`buf`至少可以写入`len`个字节
+#[no_mangle]
+pub unsafe extern "C" fn scanner_poll_barcode(
+    scanner: *mut KimScanner,
+    buf: *mut c_char,
+    len: usize,
+    timeout_ms: u32,
+) -> i32 {
+    guard(KIM_ERR_INTERNAL, || {
+        let Some(scanner) = handle(scanner) else {
+            return KIM_ERR_PARAM;
+        };
+        if buf.is_null() {
+            set_error("buf不能为空");
+            return KIM_ERR_PARAM;
+        }
+        let mut queue = scanner.queue.lock().unwrap();
+        let barcode = match queue.pending.take() {
+            Some(barcode) => barcode,
+            None => match queue
+                .receiver
+                .recv_timeout(Duration::from_millis(timeout_ms.into()))
+            {
+                Ok(barcode) => barcode,
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => return 0,
+            },
+        };
+        let bytes = barcode.as_bytes_with_nul();
+        if bytes.len() > len {
+            set_error(format!("缓冲区太小，需要{}字节", bytes.len()));
+            queue.pending = Some(barcode);
+            return KIM_ERR_BUFFER;
+        }
+        std::ptr::copy_nonoverlapping(bytes.as_ptr().cast(), buf, bytes.len());
+        (bytes.len() - 1) as i32
+    })
+}
+
+/// 给扫码枪发送指令(反控)，成功返回 0，可以在回调函数中调用
+///
+/// # Safety
+/// `scanner`为[`scanner_new`]返回的未释放的句柄，`cmd`为以`\0`结尾的有效字符串
+#[no_mangle]
+pub unsafe extern "C" fn scanner_send(scanner: *mut KimScanner, cmd: *const c_char) -> i32 {
+    guard(KIM_ERR_INTERNAL, || {
+        let (Some(scanner), Some(cmd), Some(runtime)) =
+            (handle(scanner), text_arg(cmd, "cmd"), runtime())
+        else {
+            return KIM_ERR_PARAM;
+        };
+        match block_on(runtime, scanner.scanner.send_message(cmd.into())) {
+            None => KIM_ERR_PARAM,
+            Some(Ok(Ok(()))) => KIM_OK,
+            Some(Ok(Err(err)) | Err(err)) => {
+                set_error(err.to_string());
+                KIM_ERR_SCANNER
+            }
+        }
+    })
+}
+
+/// 接收扫码枪事件，条码交给回调线程
+async fn forward(scanner: Scanner, sender: SyncSender<CString>) {
+    while let Some(ev) = scanner.recv().await {
+        let barcode = match &ev {
+            ScanEvent::Barcode(barcode)
+            | ScanEvent::Match(barcode)
+            | ScanEvent::Mismatch(barcode) => barcode,
+            _ => continue,
+        };
+        // 条码中的 \0 无法放入 C 字符串，替换为空格
+        let text = barcode.text().replace('\0', " ");
+        let Ok(text) = CString::new(text) else {
+            continue;
+        };
+        if let Err(TrySendError::Full(_)) = sender.try_send(text) {
+            event!(
+                Level::WARN,
+                scanner.addr = %scanner.connector,
+                barcode = %barcode.printable(),
+                "回调线程处理不过来，丢弃条码",
+            );
+        }
+    }
+}
+
+/// 回调线程，条码交给回调函数或放入轮询队列，接收任务结束后退出
+fn deliver(addr: String, receiver: Receiver<CString>, shared: Arc<Shared>) {
+    for text in receiver {
+        let callback = shared.callback.lock().unwrap();
+        if let Some(callback) = callback.as_ref() {
+            unsafe { (callback.func)(text.as_ptr(), text.as_bytes().len(), callback.user_data) };
+            continue;
+        }
+        drop(callback);
+        if let Err(TrySendError::Full(text)) = shared.sender.try_send(text) {
+            event!(
+                Level::WARN,
+                scanner.addr = %addr,
+                barcode = %text.to_string_lossy(),
+                "条码队列已满，丢弃条码",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use super::*;
+
+    unsafe extern "C" fn collect(barcode: *const c_char, len: usize, user_data: *mut c_void) {
+        let sender = &*(user_data as *const std::sync::mpsc::Sender<(String, usize)>);
+        let text = CStr::from_ptr(barcode).to_str().unwrap().to_string();
+        sender.send((text, len)).unwrap();
+    }
+
+    #[test]
+    fn capi() {
+        unsafe {
+            let url = CString::new("ftp://127.0.0.1").unwrap();
+            assert!(scanner_new(url.as_ptr()).is_null());
+            assert!(!scanner_last_error().is_null());
+            assert_eq!(scanner_start(std::ptr::null_mut()), KIM_ERR_PARAM);
+
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("tcp://{}", listener.local_addr().unwrap());
+            let url = CString::new(url).unwrap();
+            let scanner = scanner_new(url.as_ptr());
+            assert!(!scanner.is_null());
+            assert_eq!(scanner_start(scanner), KIM_OK);
+            let (mut client, _) = listener.accept().unwrap();
+
+            // 轮询
+            let mut buf = [0 as c_char; 32];
+            assert_eq!(scanner_poll_barcode(scanner, buf.as_mut_ptr(), 32, 0), 0);
+            client.write_all(b"SN0001\r\n").unwrap();
+            assert_eq!(
+                scanner_poll_barcode(scanner, buf.as_mut_ptr(), 4, 5000),
+                KIM_ERR_BUFFER
+            );
+            assert_eq!(scanner_poll_barcode(scanner, buf.as_mut_ptr(), 32, 0), 6);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "SN0001");
+
+            // 回调
+            let (tx, rx) = std::sync::mpsc::channel::<(String, usize)>();
+            let user_data = &tx as *const _ as *mut c_void;
+            assert_eq!(
+                scanner_set_callback(scanner, Some(collect), user_data),
+                KIM_OK
+            );
+            client.write_all(b"SN0002\r\n").unwrap();
+            let received = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(received, ("SN0002".to_string(), 6));
+
+            let cmd = CString::new("LON\r").unwrap();
+            assert_eq!(scanner_send(scanner, cmd.as_ptr()), KIM_OK);
+            scanner_free(scanner);
+        }
+    }
+
+    /// 回调中发送指令，`user_data`为扫码枪句柄
+    unsafe extern "C" fn reply(_barcode: *const c_char, _len: usize, user_data: *mut c_void) {
+        let cmd = CString::new("OK\r").unwrap();
+        assert_eq!(scanner_send(user_data.cast(), cmd.as_ptr()), KIM_OK);
+    }
+
+    #[test]
+    fn send_from_callback() {
+        use std::io::Read;
+
+        unsafe {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("tcp://{}", listener.local_addr().unwrap());
+            let url = CString::new(url).unwrap();
+            let scanner = scanner_new(url.as_ptr());
+            assert_eq!(
+                scanner_set_callback(scanner, Some(reply), scanner.cast()),
+                KIM_OK
+            );
+            assert_eq!(scanner_start(scanner), KIM_OK);
+            // 重复启动不会建立第二个连接
+            assert_eq!(scanner_start(scanner), KIM_OK);
+            let (mut client, _) = listener.accept().unwrap();
+            client.write_all(b"SN0001\r\n").unwrap();
+            client
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let mut buf = [0u8; 3];
+            client.read_exact(&mut buf).unwrap();
+            assert_eq!(&buf, b"OK\r");
+            listener.set_nonblocking(true).unwrap();
+            assert!(listener.accept().is_err());
+            scanner_free(scanner);
+        }
+    }
+
+    #[test]
+    fn guard_panic() {
+        let r = guard(KIM_ERR_INTERNAL, || -> i32 { panic!("boom") });
+        assert_eq!(r, KIM_ERR_INTERNAL);
+        let message = unsafe { CStr::from_ptr(scanner_last_error()) };
+        assert!(message.to_str().unwrap().contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn block_on_in_runtime() {
+        let url = CString::new("tcp://127.0.0.1:9").unwrap();
+        unsafe {
+            let scanner = scanner_new(url.as_ptr());
+            // 异步运行时的线程中不能阻塞等待，返回错误而不是 panic
+            assert_eq!(scanner_start(scanner), KIM_ERR_PARAM);
+            assert!(!scanner_last_error().is_null());
+            scanner_free(scanner);
+        }
+    }
+
+    #[test]
+    fn header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/kim_scanner.h"));
+        let committed = include_str!("../../include/kim_scanner.h");
+        assert!(
+            generated == committed,
+            "include/kim_scanner.h 已过期，请复制 OUT_DIR 中生成的头文件"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_serial::SerialStream;
+use tracing::{event, Level};
+
+use crate::Serial;
+
+/// 自动波特率检测
+///
+/// 依次用常用波特率打开串口，收到有效数据后锁定该波特率，重连时优先使用：
+/// * 未设置查询指令时，等待扫码枪发送以 CR/LF 结尾的可打印字符，需要现场扫一次码
+/// * 设置查询指令后，发送指令并等待包含期望内容的应答
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let conn = Serial::new("COM3", 9600, 8, StopBits::One, Parity::None)
+///     .with_auto_baud(AutoBaud::new().query(b"\x16M\rREVINF.", b"REVINF"));
+/// assert_eq!(conn.auto_baud().unwrap().locked(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AutoBaud {
+    rates: Vec<u32>,
+    query: Option<(Vec<u8>, Vec<u8>)>,
+    window: Duration,
+    /// 检测成功的波特率，0 表示未检测
+    locked: Arc<AtomicU32>,
+}
+
+impl Default for AutoBaud {
+    fn default() -> Self {
+        AutoBaud {
+            rates: vec![9600, 19200, 38400, 57600, 115200],
+            query: None,
+            window: Duration::from_secs(2),
+            locked: Arc::new(AtomicU32::new(0)),
+        }
+    }
+}
+
+impl AutoBaud {
+    /// 创建自动波特率检测，默认检测 9600/19200/38400/57600/115200
+    pub fn new() -> Self {
+        AutoBaud::default()
+    }
+
+    /// 设置需要检测的波特率，按顺序检测
+    pub fn rates(mut self, rates: impl IntoIterator<Item = u32>) -> Self {
+        self.rates = rates.into_iter().collect();
+        self
+    }
+
+    /// 设置查询指令及应答中应包含的内容
+    pub fn query(mut self, query: &[u8], expect: &[u8]) -> Self {
+        self.query = Some((query.to_vec(), expect.to_vec()));
+        self
+    }
+
+    /// 设置每个波特率的等待时长，默认2秒
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// 获取已锁定的波特率
+    pub fn locked(&self) -> Option<u32> {
+        match self.locked.load(Ordering::Relaxed) {
+            0 => None,
+            rate => Some(rate),
+        }
+    }
+
+    /// 依次检测波特率，成功后返回打开的串口及检测期间收到的数据
+    pub(crate) async fn detect(
+        &self,
+        serial: &Serial,
+        timeout: Option<Duration>,
+    ) -> Option<(SerialStream, Vec<u8>)> {
+        let name = serial.name();
+        let mut rates = vec![];
+        for rate in self
+            .locked()
+            .into_iter()
+            .chain([serial.baudrate()])
+            .chain(self.rates.iter().copied())
+        {
+            if !rates.contains(&rate) {
+                rates.push(rate);
+            }
+        }
+        for rate in rates {
+            let mut com = match serial.open_at(rate, timeout) {
+                Ok(com) => com,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %name,
+                        error = %err,
+                        error.kind = "io",
+                        "串口连接错误",
+                    );
+                    return None;
+                }
+            };
+            if let Some((query, _)) = &self.query {
+                if let Err(err) = com.write_all(query).await {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %name,
+                        error = %err,
+                        error.kind = "io",
+                        "发送数据错误",
+                    );
+                    continue;
+                }
+            }
+            let mut data = vec![];
+            let probe = async {
+                let mut buf = [0u8; 256];
+                loop {
+                    match com.read(&mut buf).await {
+                        Ok(0) | Err(_) => return false,
+                        Ok(n) => data.extend_from_slice(&buf[..n]),
+                    }
+                    if self.is_valid(&data) {
+                        return true;
+                    }
+                }
+            };
+            let valid = tokio::time::timeout(self.window, probe)
+                .await
+                .unwrap_or(false);
+            if valid {
+                self.locked.store(rate, Ordering::Relaxed);
+                event!(Level::INFO, scanner.addr = %name, baudrate = rate, "波特率检测成功");
+                // 查询应答不是条码，不再交给扫码枪处理
+                if self.query.is_some() {
+                    data.clear();
+                }
+                return Some((com, data));
+            }
+            event!(Level::DEBUG, scanner.addr = %name, baudrate = rate, "波特率不匹配");
+        }
+        event!(Level::WARN, scanner.addr = %name, "波特率检测失败");
+        None
+    }
+
+    /// 判断收到的数据是否有效
+    fn is_valid(&self, data: &[u8]) -> bool {
+        if let Some((_, expect)) = &self.query {
+            return data.windows(expect.len()).any(|w| w == expect.as_slice());
+        }
+        // 至少一帧完整的可打印数据
+        let Some(end) = data.iter().rposition(|b| *b == b'\r' || *b == b'\n') else {
+            return false;
+        };
+        let frames = &data[..end];
+        frames.iter().any(|b| b.is_ascii_graphic())
+            && frames
+                .iter()
+                .all(|b| b.is_ascii_graphic() || matches!(b, b' ' | b'\t' | b'\r' | b'\n' | 0x1D))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutoBaud;
+
+    #[test]
+    fn is_valid() {
+        let auto = AutoBaud::new();
+        assert!(auto.is_valid(b"SN0001\r\n"));
+        assert!(auto.is_valid(b"01095\x1d10ABC\r"));
+        assert!(!auto.is_valid(b"SN0001"));
+        assert!(!auto.is_valid(b"\xf0\x80\x00\xfe\r"));
+        assert!(!auto.is_valid(b"\r\n"));
+
+        let auto = AutoBaud::new().query(b"\x16M\rREVINF.", b"REVINF");
+        assert!(auto.is_valid(b"\x02REVINF Firmware 1.0\x06."));
+        assert!(!auto.is_valid(b"SN0001\r\n"));
+    }
+}
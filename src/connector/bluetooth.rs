@@ -0,0 +1,277 @@
+use std::fmt::Display;
+
+use crate::{PortType, ScannerError, Serial};
+
+/// 蓝牙 SPP 连接器(RFCOMM)
+///
+/// Linux 下直接通过 RFCOMM 套接字连接扫码枪，无需`rfcomm bind`；
+/// Windows、macOS 配对后系统会映射出虚拟串口，可通过[`Bluetooth::ports`]查找后使用[`crate::Serial`]连接
+///
+/// 断开(超出范围、取消配对、扫码枪关机)后会自动重新连接
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bluetooth {
+    address: String,
+    channel: u8,
+}
+
+impl Bluetooth {
+    /// 创建蓝牙 SPP 连接器
+    ///
+    /// * `address` 扫码枪蓝牙地址，例如`00:11:22:AA:BB:CC`
+    /// * `channel` RFCOMM 通道，SPP 一般为 1
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn: Connector = Bluetooth::new("00:11:22:AA:BB:CC", 1).into();
+    /// assert_eq!(conn.to_string(), "BT:00:11:22:AA:BB:CC/1");
+    /// ```
+    pub fn new(address: &str, channel: u8) -> Self {
+        Bluetooth {
+            address: address.into(),
+            channel,
+        }
+    }
+
+    /// 获取蓝牙地址
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// 获取 RFCOMM 通道
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// 查找系统映射的蓝牙虚拟串口，例如 Windows 下的`COM5`、Linux 下的`/dev/rfcomm0`
+    pub fn ports() -> Result<Vec<String>, ScannerError> {
+        let ports = Serial::discover()?;
+        Ok(ports
+            .into_iter()
+            .filter(|port| port.port_type() == &PortType::Bluetooth)
+            .map(|port| port.name().to_owned())
+            .collect())
+    }
+
+    /// 解析蓝牙地址，按 bdaddr_t 的要求低字节在前
+    pub(crate) fn bdaddr(&self) -> Result<[u8; 6], ScannerError> {
+        let err = || ScannerError::Param(format!("无效的蓝牙地址,address={}", self.address));
+        let parts: Vec<&str> = self.address.trim().split([':', '-']).collect();
+        if parts.len() != 6 {
+            return Err(err());
+        }
+        let mut addr = [0u8; 6];
+        for (i, part) in parts.iter().enumerate() {
+            addr[5 - i] = u8::from_str_radix(part, 16).map_err(|_| err())?;
+        }
+        Ok(addr)
+    }
+
+    /// 建立 RFCOMM 连接
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn connect(&self) -> std::io::Result<RfcommStream> {
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+        #[repr(C)]
+        struct SockaddrRc {
+            rc_family: libc::sa_family_t,
+            rc_bdaddr: [u8; 6],
+            rc_channel: u8,
+        }
+        const BTPROTO_RFCOMM: libc::c_int = 3;
+
+        let bdaddr = self
+            .bdaddr()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+        let channel = self.channel;
+        let fd = tokio::task::spawn_blocking(move || {
+            let fd = unsafe {
+                libc::socket(
+                    libc::AF_BLUETOOTH,
+                    libc::SOCK_STREAM | libc::SOCK_CLOEXEC,
+                    BTPROTO_RFCOMM,
+                )
+            };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // 出错时自动关闭
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+            let addr = SockaddrRc {
+                rc_family: libc::AF_BLUETOOTH as libc::sa_family_t,
+                rc_bdaddr: bdaddr,
+                rc_channel: channel,
+            };
+            let r = unsafe {
+                libc::connect(
+                    fd.as_raw_fd(),
+                    &addr as *const SockaddrRc as *const libc::sockaddr,
+                    std::mem::size_of::<SockaddrRc>() as libc::socklen_t,
+                )
+            };
+            if r < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // 连接成功后改为非阻塞，由 tokio 等待可读写
+            let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+            if flags < 0
+                || unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) }
+                    < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(fd)
+        })
+        .await??;
+        Ok(RfcommStream(tokio::io::unix::AsyncFd::new(fd)?))
+    }
+}
+
+/// RFCOMM 套接字，通过`AsyncFd`接入 tokio
+#[cfg(target_os = "linux")]
+pub(crate) struct RfcommStream(tokio::io::unix::AsyncFd<std::os::fd::OwnedFd>);
+
+#[cfg(target_os = "linux")]
+impl tokio::io::AsyncRead for RfcommStream {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        use std::os::fd::AsRawFd;
+
+        loop {
+            let mut guard = std::task::ready!(self.0.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            let r = guard.try_io(|fd| {
+                let n = unsafe {
+                    libc::recv(
+                        fd.as_raw_fd(),
+                        unfilled.as_mut_ptr().cast(),
+                        unfilled.len(),
+                        0,
+                    )
+                };
+                if n < 0 {
+                    Err(std::io::Error::last_os_error())
+                } else {
+                    Ok(n as usize)
+                }
+            });
+            match r {
+                Ok(Ok(n)) => {
+                    buf.advance(n);
+                    return std::task::Poll::Ready(Ok(()));
+                }
+                Ok(Err(err)) => return std::task::Poll::Ready(Err(err)),
+                // 没有数据可读，重新等待
+                Err(_) => continue,
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl tokio::io::AsyncWrite for RfcommStream {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        use std::os::fd::AsRawFd;
+
+        loop {
+            let mut guard = std::task::ready!(self.0.poll_write_ready(cx))?;
+            // 扫码枪断开时不产生 SIGPIPE，返回错误
+            let r = guard.try_io(|fd| {
+                let n = unsafe {
+                    libc::send(
+                        fd.as_raw_fd(),
+                        buf.as_ptr().cast(),
+                        buf.len(),
+                        libc::MSG_NOSIGNAL,
+                    )
+                };
+                if n < 0 {
+                    Err(std::io::Error::last_os_error())
+                } else {
+                    Ok(n as usize)
+                }
+            });
+            match r {
+                Ok(r) => return std::task::Poll::Ready(r),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        use std::os::fd::AsRawFd;
+
+        let r = unsafe { libc::shutdown(self.0.as_raw_fd(), libc::SHUT_WR) };
+        if r < 0 {
+            return std::task::Poll::Ready(Err(std::io::Error::last_os_error()));
+        }
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+impl Display for Bluetooth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BT:{}/{}", self.address, self.channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bluetooth;
+
+    #[test]
+    fn bdaddr() {
+        let bt = Bluetooth::new("00:11:22:AA:BB:CC", 1);
+        assert_eq!(bt.bdaddr().unwrap(), [0xCC, 0xBB, 0xAA, 0x22, 0x11, 0x00]);
+        assert!(Bluetooth::new("00:11:22:AA:BB", 1).bdaddr().is_err());
+        assert!(Bluetooth::new("00:11:22:AA:BB:XX", 1).bdaddr().is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(target_os = "linux")]
+    async fn rfcomm_stream() {
+        use std::os::fd::OwnedFd;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        use super::RfcommStream;
+
+        // 用 Unix 套接字对代替 RFCOMM 套接字，读写方式相同
+        let (socket, peer) = std::os::unix::net::UnixStream::pair().unwrap();
+        socket.set_nonblocking(true).unwrap();
+        peer.set_nonblocking(true).unwrap();
+        let fd = tokio::io::unix::AsyncFd::new(OwnedFd::from(socket)).unwrap();
+        let mut stream = RfcommStream(fd);
+        let mut peer = tokio::net::UnixStream::from_std(peer).unwrap();
+
+        peer.write_all(b"SN0001\r").await.unwrap();
+        let mut buf = [0u8; 7];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"SN0001\r");
+        stream.write_all(b"OK\r").await.unwrap();
+        let mut buf = [0u8; 3];
+        peer.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"OK\r");
+        // 对端断开后读取到结尾
+        drop(peer);
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 0);
+    }
+}
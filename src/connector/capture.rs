@@ -0,0 +1,208 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::{event, Level};
+
+use crate::connector::replay::escape;
+
+/// 写入队列最多缓存的记录数
+const QUEUE_CAPACITY: usize = 10000;
+
+/// 原始数据抓包，把每次连接收发的原始字节(带时间)追加写入抓包文件，
+/// 用于和厂商技术支持一起排查协议问题
+///
+/// 抓包文件为文本格式，每行一条记录，数据按回放脚本的规则转义(见[`crate::Replay`])：
+///
+/// ```text
+/// # 1760000000000 连接 192.168.1.10:9004
+/// 1760000000120 < SN0001\r\n
+/// 1760000000300 > LON\r
+/// ```
+///
+/// 时间为 Unix 毫秒，`<`为收到扫码枪的数据，`>`为发送给扫码枪的数据，`#`开头的行为连接记录。
+/// 接收的数据按读取到的原样记录(一次读取一行)，不经过拆帧和过滤
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+///     .capture(Capture::new("line1-in.cap"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capture {
+    path: PathBuf,
+}
+
+impl Capture {
+    /// 创建抓包配置
+    ///
+    /// * `path` 抓包文件路径，已存在时追加
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Capture {
+            path: path.as_ref().into(),
+        }
+    }
+
+    /// 获取抓包文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// 抓包文件，由扫码枪的各个副本共享
+///
+/// 收发任务只把记录放入队列，由独立的写入线程写文件，文件读写不会阻塞收发
+#[derive(Debug)]
+pub(crate) struct Capturing {
+    capture: Capture,
+    sender: Option<SyncSender<String>>,
+}
+
+impl Capturing {
+    pub(crate) fn new(capture: Capture) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let path = capture.path.clone();
+        let spawned = std::thread::Builder::new()
+            .name("kim-scanner-capture".into())
+            .spawn(move || write_lines(&path, receiver));
+        let sender = match spawned {
+            Ok(_) => Some(sender),
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    file = %capture.path.display(),
+                    error = %err,
+                    error.kind = "io",
+                    "抓包线程创建失败",
+                );
+                None
+            }
+        };
+        Capturing { capture, sender }
+    }
+
+    /// 记录一次连接
+    pub(crate) fn connected(&self, addr: &str) {
+        self.write(format!("# {} 连接 {}\n", now(), addr));
+    }
+
+    /// 记录收到的数据
+    pub(crate) fn received(&self, data: &[u8]) {
+        self.write(format!("{} < {}\n", now(), escape(data)));
+    }
+
+    /// 记录发送的数据
+    pub(crate) fn sent(&self, data: &[u8]) {
+        self.write(format!("{} > {}\n", now(), escape(data)));
+    }
+
+    /// 把一行放入写入队列，队列已满(磁盘写入跟不上)时丢弃
+    fn write(&self, line: String) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if let Err(TrySendError::Full(_)) = sender.try_send(line) {
+            event!(
+                Level::WARN,
+                file = %self.capture.path.display(),
+                "抓包写入队列已满,丢弃记录",
+            );
+        }
+    }
+}
+
+/// 写入线程，第一次写入时打开文件，写入失败时关闭文件，下次重新打开。抓包配置被丢弃后退出
+fn write_lines(path: &Path, receiver: Receiver<String>) {
+    let mut file: Option<BufWriter<File>> = None;
+    while let Ok(line) = receiver.recv() {
+        if file.is_none() {
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(opened) => file = Some(BufWriter::new(opened)),
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        file = %path.display(),
+                        error = %err,
+                        error.kind = "io",
+                        "抓包文件打开失败",
+                    );
+                    continue;
+                }
+            }
+        }
+        let Some(writer) = file.as_mut() else {
+            continue;
+        };
+        // 队列中没有更多记录时才刷新到文件
+        let mut result = writer.write_all(line.as_bytes());
+        while result.is_ok() {
+            let Ok(line) = receiver.try_recv() else {
+                break;
+            };
+            result = writer.write_all(line.as_bytes());
+        }
+        if let Err(err) = result.and_then(|_| writer.flush()) {
+            event!(
+                Level::ERROR,
+                file = %path.display(),
+                error = %err,
+                error.kind = "io",
+                "抓包文件写入失败",
+            );
+            file = None;
+        }
+    }
+}
+
+fn now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn capture() {
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!("kim_scanner-{}.cap", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone()).capture(Capture::new(&path));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(
+            scanner.recv().await.unwrap(),
+            ScanEvent::Connected
+        ));
+        mock.push("SN0001\x1d01\r\n");
+        scanner.recv().await.unwrap();
+        scanner.send_message("LON\r".into()).await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("# ") && lines[0].ends_with(" 连接 MOCK"));
+        assert!(lines[1].ends_with(r" < SN0001\x1D01\r\n"));
+        assert!(lines[2].ends_with(r" > LON\r"));
+
+        // 回放抓包文件，得到相同的条码
+        let replay = Scanner::new(Replay::from_capture_file(&path).unwrap());
+        replay.start().await.unwrap().unwrap();
+        assert!(matches!(replay.recv().await.unwrap(), ScanEvent::Connected));
+        assert_eq!(
+            replay.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001\x1d01"
+        );
+        replay.stop();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use crate::ScannerError;
+
+/// 发送队列中的指令，由发送线程按顺序执行
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Command {
+    /// 写入数据
+    Write(Bytes),
+    /// 刷新发送缓冲
+    Flush,
+    /// 断开当前连接，之后按重连策略重新连接
+    Reconnect,
+    /// 关闭连接并停止扫码枪
+    Shutdown,
+}
+
+impl From<String> for Command {
+    fn from(cmd: String) -> Self {
+        Command::Write(Bytes::from(cmd))
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Write(data) => write!(f, "{}", String::from_utf8_lossy(data)),
+            Command::Flush => write!(f, "FLUSH"),
+            Command::Reconnect => write!(f, "RECONNECT"),
+            Command::Shutdown => write!(f, "SHUTDOWN"),
+        }
+    }
+}
+
+/// 发送队列中的一条指令
+#[derive(Debug)]
+pub(crate) struct Queued {
+    pub(crate) cmd: Command,
+    /// 放入队列的时间
+    pub(crate) at: Instant,
+    /// 执行结果通知
+    pub(crate) confirm: Confirm,
+}
+
+impl Queued {
+    pub(crate) fn new(cmd: Command) -> Self {
+        Queued {
+            cmd,
+            at: Instant::now(),
+            confirm: Confirm(None),
+        }
+    }
+
+    /// 创建需要等待执行结果的指令，指令未执行就被丢弃时接收端返回错误
+    pub(crate) fn confirmed(cmd: Command) -> (Self, oneshot::Receiver<Result<(), ScannerError>>) {
+        let (tx, rx) = oneshot::channel();
+        let queued = Queued {
+            cmd,
+            at: Instant::now(),
+            confirm: Confirm(Some(tx)),
+        };
+        (queued, rx)
+    }
+}
+
+/// 指令执行结果的通知，没有等待方时忽略
+#[derive(Debug)]
+pub(crate) struct Confirm(Option<oneshot::Sender<Result<(), ScannerError>>>);
+
+impl Confirm {
+    /// 通知等待方指令的执行结果
+    pub(crate) fn done(self, r: Result<(), ScannerError>) {
+        if let Some(tx) = self.0 {
+            let _ = tx.send(r);
+        }
+    }
+}
+
+/// 扫码枪未连接时发送的指令如何处理，见[`crate::Scanner::queue_policy`]
+///
+/// 默认缓存指令，重新连接后按顺序发送，不限制缓存时长
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// // 断开超过 5 秒的 OK/NG 指令已经没有意义，重新连接后丢弃
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+///     .queue_policy(QueuePolicy::buffer(Duration::from_secs(5)));
+///
+/// // 未连接时立即返回错误，由调用方决定是否重发
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+///     .queue_policy(QueuePolicy::FailFast);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum QueuePolicy {
+    /// 缓存指令，重新连接后发送，放入队列超过`max_age`的写入指令被丢弃
+    Buffer {
+        /// 最长缓存时长，`None`表示不限制
+        max_age: Option<Duration>,
+    },
+    /// 未连接时不放入队列，立即返回通讯错误
+    FailFast,
+}
+
+impl Default for QueuePolicy {
+    fn default() -> Self {
+        QueuePolicy::Buffer { max_age: None }
+    }
+}
+
+impl QueuePolicy {
+    /// 缓存指令，超过`max_age`后丢弃
+    pub fn buffer(max_age: Duration) -> Self {
+        QueuePolicy::Buffer {
+            max_age: Some(max_age),
+        }
+    }
+
+    /// 写入指令是否已超过最长缓存时长，刷新、重新连接等控制指令不过期
+    pub(crate) fn expired(&self, queued: &Queued) -> bool {
+        match (self, &queued.cmd) {
+            (
+                QueuePolicy::Buffer {
+                    max_age: Some(max_age),
+                },
+                Command::Write(_),
+            ) => queued.at.elapsed() > *max_age,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn queue_policy() {
+        use std::time::Duration;
+        use tokio::io::AsyncReadExt;
+
+        // 未连接时立即返回错误
+        let scanner =
+            Scanner::new(Network::new_client("127.0.0.1", 9)).queue_policy(QueuePolicy::FailFast);
+        assert!(!scanner.is_connected());
+        let r = scanner.send_message("BEEP".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+
+        // 缓存超时的指令在重新连接后丢弃
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .queue_policy(QueuePolicy::buffer(Duration::from_millis(50)));
+        scanner.send_message("OLD".into()).await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        scanner.send_message("NEW".into()).await.unwrap().unwrap();
+        scanner.start().await.unwrap().unwrap();
+        let (mut device, _) = listener.accept().await.unwrap();
+        let mut received = [0u8; 3];
+        device.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"NEW");
+        assert!(scanner.is_connected());
+        scanner.stop();
+    }
+}
@@ -1,29 +1,136 @@
-use crate::{Network, Serial};
+use std::fmt::Display;
+use std::sync::Arc;
 
+#[cfg(feature = "bluetooth")]
+use crate::Bluetooth;
+#[cfg(feature = "hid")]
+use crate::Hid;
+#[cfg(feature = "websocket")]
+use crate::WebSocket;
+use crate::{MockConnector, Network, Replay, Serial, Transport};
+
+/// 连接器
+///
+/// 启用`serde`特性后可以序列化，以`type`字段区分连接方式(`serial`、`network`、`bluetooth`、`websocket`)，
+/// 反序列化时也可以直接使用[`std::str::FromStr`]支持的 URL 字符串。
+/// HID、模拟、回放和自定义传输层连接器不能序列化
+///
+/// ```toml
+/// connector = "tcp://192.168.1.10:9004"
+/// # 或者
+/// connector = { type = "serial", name = "COM3", baudrate = 9600, databits = 8, stopbits = "one", parity = "none" }
+/// ```
+// 连接器只在创建扫码枪时使用一次，不需要为了大小装箱
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
 pub enum Connector {
     Serial(Serial),
     Network(Network),
+    /// USB HID 键盘模式扫码枪
+    #[cfg(feature = "hid")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Hid(Hid),
+    /// 蓝牙 SPP 扫码枪
+    #[cfg(feature = "bluetooth")]
+    Bluetooth(Bluetooth),
+    /// WebSocket 扫码设备
+    #[cfg(feature = "websocket")]
+    #[cfg_attr(feature = "serde", serde(rename = "websocket"))]
+    WebSocket(WebSocket),
+    /// 模拟扫码枪，用于测试
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Mock(MockConnector),
+    /// 脚本回放，用于压力测试和演示
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Replay(Replay),
+    /// 自定义传输层
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn Transport>),
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Connector {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        #[serde(tag = "type", rename_all = "snake_case")]
+        enum Tagged {
+            Serial(Serial),
+            Network(Network),
+            #[cfg(feature = "bluetooth")]
+            Bluetooth(Bluetooth),
+            #[cfg(feature = "websocket")]
+            #[serde(rename = "websocket")]
+            WebSocket(WebSocket),
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        #[allow(clippy::large_enum_variant)]
+        enum Repr {
+            Url(String),
+            Tagged(Tagged),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Url(url) => url.parse().map_err(D::Error::custom),
+            Repr::Tagged(Tagged::Serial(conn)) => Ok(conn.into()),
+            Repr::Tagged(Tagged::Network(conn)) => Ok(conn.into()),
+            #[cfg(feature = "bluetooth")]
+            Repr::Tagged(Tagged::Bluetooth(conn)) => Ok(conn.into()),
+            #[cfg(feature = "websocket")]
+            Repr::Tagged(Tagged::WebSocket(conn)) => Ok(conn.into()),
+        }
+    }
 }
 
 impl Connector {
-    /// 自定义转字符串
-    ///
-    /// # Examples
-    /// ```
-    /// use scanner::prelude::*;
-    ///
-    /// let mut conn:Connector = Network::new_server("127.0.0.1", 5000).into();
-    /// assert_eq!(conn.to_string(), "127.0.0.1:5000");
-    ///
-    /// let mut conn:Connector = Serial::new("COM1", 9600, 8, StopBits::One, Parity::None).into();
-    /// assert_eq!(conn.to_string(), "COM1");
-    ///
-    /// ```
-    pub fn to_string(&self) -> String {
+    /// 使用自定义传输层，详见[`Transport`]
+    pub fn transport(transport: impl Transport + 'static) -> Self {
+        Connector::Custom(Arc::new(transport))
+    }
+
+    /// 是否可以给扫码枪发送指令，USB HID(键盘模式)和脚本回放只能接收数据
+    pub fn is_writable(&self) -> bool {
+        match self {
+            #[cfg(feature = "hid")]
+            Connector::Hid(_) => false,
+            Connector::Replay(_) => false,
+            _ => true,
+        }
+    }
+}
+
+/// 自定义转字符串
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let mut conn:Connector = Network::new_server("127.0.0.1", 5000).into();
+/// assert_eq!(conn.to_string(), "127.0.0.1:5000");
+///
+/// let mut conn:Connector = Serial::new("COM1", 9600, 8, StopBits::One, Parity::None).into();
+/// assert_eq!(conn.to_string(), "COM1");
+///
+/// ```
+impl Display for Connector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Connector::Serial(serial) => format!("{}", serial.name()),
-            Connector::Network(network) => format!("{}:{}", network.ip(), network.port()),
+            Connector::Serial(serial) => write!(f, "{}", serial.name()),
+            Connector::Network(network) => write!(f, "{}", network.addr()),
+            #[cfg(feature = "hid")]
+            Connector::Hid(hid) => write!(f, "{}", hid),
+            #[cfg(feature = "bluetooth")]
+            Connector::Bluetooth(bt) => write!(f, "{}", bt),
+            #[cfg(feature = "websocket")]
+            Connector::WebSocket(ws) => write!(f, "{}", ws),
+            Connector::Mock(mock) => write!(f, "{}", mock),
+            Connector::Replay(replay) => write!(f, "{}", replay),
+            Connector::Custom(transport) => write!(f, "{}", transport.name()),
         }
     }
 }
@@ -39,3 +146,36 @@ impl From<Network> for Connector {
         Connector::Network(value)
     }
 }
+
+#[cfg(feature = "hid")]
+impl From<Hid> for Connector {
+    fn from(value: Hid) -> Self {
+        Connector::Hid(value)
+    }
+}
+
+#[cfg(feature = "bluetooth")]
+impl From<Bluetooth> for Connector {
+    fn from(value: Bluetooth) -> Self {
+        Connector::Bluetooth(value)
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl From<WebSocket> for Connector {
+    fn from(value: WebSocket) -> Self {
+        Connector::WebSocket(value)
+    }
+}
+
+impl From<MockConnector> for Connector {
+    fn from(value: MockConnector) -> Self {
+        Connector::Mock(value)
+    }
+}
+
+impl From<Replay> for Connector {
+    fn from(value: Replay) -> Self {
+        Connector::Replay(value)
+    }
+}
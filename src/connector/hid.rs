@@ -0,0 +1,203 @@
+use std::fmt::Display;
+
+/// USB HID 连接器(键盘模式扫码枪)
+///
+/// 扫码枪以 HID 键盘的方式接入，按 VID/PID 打开设备读取按键报告，
+/// 按美式键盘布局还原成字符，收到回车后作为一帧数据处理
+///
+/// 注意：Windows、macOS 会独占系统键盘设备，一般需要把扫码枪设置为
+/// `HID POS`或关闭键盘输出后才能打开；Linux 需要对`/dev/hidraw*`有读权限
+#[derive(Clone, Debug)]
+pub struct Hid {
+    vid: u16,
+    pid: u16,
+    serial_number: Option<String>,
+}
+
+impl Hid {
+    /// 创建 USB HID 连接器
+    ///
+    /// * `vid` 厂商 ID
+    /// * `pid` 产品 ID
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn: Connector = Hid::new(0x0C2E, 0x0B61).into();
+    /// assert_eq!(conn.to_string(), "HID:0C2E:0B61");
+    /// ```
+    pub fn new(vid: u16, pid: u16) -> Self {
+        Hid {
+            vid,
+            pid,
+            serial_number: None,
+        }
+    }
+
+    /// 指定设备序列号，用于区分多把相同型号的扫码枪
+    pub fn with_serial_number(mut self, serial_number: &str) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    /// 获取厂商 ID
+    pub fn vid(&self) -> u16 {
+        self.vid
+    }
+
+    /// 获取产品 ID
+    pub fn pid(&self) -> u16 {
+        self.pid
+    }
+
+    /// 获取设备序列号
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+
+    /// 打开设备
+    pub(crate) fn open(&self) -> hidapi::HidResult<hidapi::HidDevice> {
+        let api = hidapi::HidApi::new()?;
+        match &self.serial_number {
+            Some(sn) => api.open_serial(self.vid, self.pid, sn),
+            None => api.open(self.vid, self.pid),
+        }
+    }
+}
+
+impl Display for Hid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HID:{:04X}:{:04X}", self.vid, self.pid)?;
+        if let Some(sn) = &self.serial_number {
+            write!(f, ":{}", sn)?;
+        }
+        Ok(())
+    }
+}
+
+/// 修饰键：左右 Ctrl
+const CTRL: u8 = 0x01 | 0x10;
+/// 修饰键：左右 Shift
+const SHIFT: u8 = 0x02 | 0x20;
+
+/// HID 键盘报告解码器
+///
+/// 标准键盘报告为 8 字节：修饰键、保留、6 个按键码，
+/// 只有新按下的键才会输出，避免按住时重复
+#[derive(Default)]
+pub(crate) struct KeyboardDecoder {
+    pressed: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl KeyboardDecoder {
+    /// 输入一个报告，遇到回车时返回完整的一帧
+    pub(crate) fn feed(&mut self, report: &[u8]) -> Option<Vec<u8>> {
+        // 带报告 ID 的设备第一个字节为报告 ID
+        let report = if report.len() > 8 {
+            &report[1..]
+        } else {
+            report
+        };
+        if report.len() < 3 {
+            return None;
+        }
+        let modifier = report[0];
+        let keys: Vec<u8> = report[2..].iter().copied().filter(|k| *k > 0x03).collect();
+        let mut frame = None;
+        for key in keys.iter().filter(|k| !self.pressed.contains(k)) {
+            match *key {
+                // 回车、小键盘回车
+                0x28 | 0x58 => frame = Some(std::mem::take(&mut self.buf)),
+                key => {
+                    if let Some(c) = usage_to_ascii(key, modifier) {
+                        self.buf.push(c);
+                    }
+                }
+            }
+        }
+        self.pressed = keys;
+        frame
+    }
+}
+
+/// 按美式键盘布局把按键码转换为 ASCII
+fn usage_to_ascii(key: u8, modifier: u8) -> Option<u8> {
+    let shift = modifier & SHIFT != 0;
+    let c = match key {
+        0x04..=0x1D => {
+            let c = b'a' + key - 0x04;
+            if shift {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        }
+        0x1E..=0x27 => {
+            let (normal, shifted) = (b"1234567890", b"!@#$%^&*()");
+            let i = (key - 0x1E) as usize;
+            if shift {
+                shifted[i]
+            } else {
+                normal[i]
+            }
+        }
+        0x2B => b'\t',
+        0x2C => b' ',
+        0x2D..=0x38 => {
+            let (normal, shifted) = (b"-=[]\\\0;'`,./", b"_+{}|\0:\"~<>?");
+            let i = (key - 0x2D) as usize;
+            let c = if shift { shifted[i] } else { normal[i] };
+            if c == 0 {
+                return None;
+            }
+            c
+        }
+        0x54 => b'/',
+        0x55 => b'*',
+        0x56 => b'-',
+        0x57 => b'+',
+        0x59..=0x61 => b'1' + key - 0x59,
+        0x62 => b'0',
+        0x63 => b'.',
+        _ => return None,
+    };
+    // Ctrl 组合键输出控制字符，例如 Ctrl+] 为 GS(0x1D)
+    if modifier & CTRL != 0 {
+        let upper = c.to_ascii_uppercase();
+        if (b'@'..=b'_').contains(&upper) {
+            return Some(upper & 0x1F);
+        }
+    }
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyboardDecoder;
+
+    fn report(modifier: u8, key: u8) -> [u8; 8] {
+        [modifier, 0, key, 0, 0, 0, 0, 0]
+    }
+
+    #[test]
+    fn keyboard_decoder() {
+        let mut decoder = KeyboardDecoder::default();
+        let keys = [
+            (0x02, 0x04), // A
+            (0x00, 0x05), // b
+            (0x00, 0x1E), // 1
+            (0x01, 0x30), // Ctrl+] => GS
+            (0x20, 0x2D), // _
+        ];
+        for (modifier, key) in keys {
+            assert_eq!(decoder.feed(&report(modifier, key)), None);
+            assert_eq!(decoder.feed(&report(0, 0)), None);
+        }
+        // 按住不放不会重复输出
+        assert_eq!(decoder.feed(&report(0, 0x27)), None);
+        assert_eq!(decoder.feed(&report(0, 0x27)), None);
+        assert_eq!(decoder.feed(&report(0, 0x28)), Some(b"Ab1\x1d_0".to_vec()));
+    }
+}
@@ -0,0 +1,123 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use ipnet::IpNet;
+
+use crate::ScannerError;
+
+/// 服务器模式下的客户端 IP 过滤
+///
+/// * 命中黑名单的连接一律拒绝
+/// * 白名单不为空时，只接受白名单中的地址
+///
+/// 地址可以是单个 IP 或 CIDR 网段，被拒绝的连接以`kim_scanner::security`为 target 记录安全日志
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let filter = IpFilter::new()
+///     .allow("192.168.1.0/24")
+///     .allow("fd00::10")
+///     .deny("192.168.1.100");
+/// assert!(filter.is_allowed("192.168.1.10".parse().unwrap()));
+/// assert!(!filter.is_allowed("192.168.1.100".parse().unwrap()));
+/// assert!(!filter.is_allowed("10.0.0.1".parse().unwrap()));
+///
+/// let conn = Network::new_server("0.0.0.0", 9004).with_ip_filter(filter);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IpFilter {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    /// 无法解析的地址，启动时报错
+    invalid: Vec<String>,
+}
+
+impl IpFilter {
+    /// 创建 IP 过滤器，默认接受所有地址
+    pub fn new() -> Self {
+        IpFilter::default()
+    }
+
+    /// 添加白名单地址或网段
+    pub fn allow(mut self, range: &str) -> Self {
+        match parse(range) {
+            Some(net) => self.allow.push(net),
+            None => self.invalid.push(range.into()),
+        }
+        self
+    }
+
+    /// 添加黑名单地址或网段
+    pub fn deny(mut self, range: &str) -> Self {
+        match parse(range) {
+            Some(net) => self.deny.push(net),
+            None => self.invalid.push(range.into()),
+        }
+        self
+    }
+
+    /// 是否接受该地址的连接
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        // 双栈监听时 IPv4 客户端地址为 `::ffff:a.b.c.d`
+        let ip = ip.to_canonical();
+        if self.deny.iter().any(|net| net.contains(&ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|net| net.contains(&ip))
+    }
+
+    /// 检查是否有无法解析的地址
+    pub(crate) fn check(&self) -> Result<(), ScannerError> {
+        if self.invalid.is_empty() {
+            return Ok(());
+        }
+        Err(ScannerError::Param(format!(
+            "无效的IP地址或网段,ip={}",
+            self.invalid.join(",")
+        )))
+    }
+}
+
+/// 序列化为白名单和黑名单字符串
+///
+/// ```json
+/// { "allow": ["192.168.1.0/24"], "deny": ["192.168.1.100/32"] }
+/// ```
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IpFilterConfig {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for IpFilter {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        IpFilterConfig {
+            allow: self.allow.iter().map(IpNet::to_string).collect(),
+            deny: self.deny.iter().map(IpNet::to_string).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for IpFilter {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = IpFilterConfig::deserialize(deserializer)?;
+        let filter = config.allow.iter().fold(IpFilter::new(), |f, r| f.allow(r));
+        Ok(config.deny.iter().fold(filter, |f, r| f.deny(r)))
+    }
+}
+
+/// 解析单个 IP 或 CIDR 网段
+fn parse(range: &str) -> Option<IpNet> {
+    let range = range.trim();
+    IpNet::from_str(range)
+        .ok()
+        .or_else(|| IpAddr::from_str(range).ok().map(IpNet::from))
+}
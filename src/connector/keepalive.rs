@@ -0,0 +1,270 @@
+use std::future::pending;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::time::{interval_at, Instant, Interval, MissedTickBehavior};
+use tracing::{event, Level};
+
+/// 保活指令
+///
+/// 与[`crate::Watchdog`]只在空闲时发送心跳不同，保活指令按固定间隔发送(例如查询版本号等无副作用的指令)，
+/// 在下一次发送前没有收到应答记为一次丢失，连续丢失`max_misses`次后发出[`crate::ScanEvent::Unhealthy`]事件并重新连接。
+///
+/// 设置了应答前缀时，以该前缀开头的数据视为保活应答，不作为条码发出；
+/// 未设置时收到任何数据都视为扫码枪正常
+///
+/// 适用于串口、网络、蓝牙、WebSocket 和模拟连接
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// // 每 10 秒查询一次型号，连续 3 次没有应答则重连
+/// let keepalive = Keepalive::new("\x16M\rREVINF.", Duration::from_secs(10))
+///     .with_response(b"REVINF")
+///     .with_max_misses(3);
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).keepalive(keepalive);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keepalive {
+    cmd: String,
+    interval: Duration,
+    response: Option<Vec<u8>>,
+    max_misses: u32,
+}
+
+impl Keepalive {
+    /// 创建保活指令，默认连续丢失3次应答后重连
+    ///
+    /// * `cmd` 保活指令
+    /// * `interval` 发送间隔，也是等待应答的时长
+    pub fn new(cmd: &str, interval: Duration) -> Self {
+        Keepalive {
+            cmd: cmd.into(),
+            interval,
+            response: None,
+            max_misses: 3,
+        }
+    }
+
+    /// 设置应答前缀
+    pub fn with_response(mut self, response: &[u8]) -> Self {
+        self.response = Some(response.to_vec());
+        self
+    }
+
+    /// 设置连续丢失多少次应答后重连
+    pub fn with_max_misses(mut self, max_misses: u32) -> Self {
+        self.max_misses = max_misses.max(1);
+        self
+    }
+
+    /// 获取保活指令
+    pub fn cmd(&self) -> &str {
+        &self.cmd
+    }
+
+    /// 获取发送间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 获取应答前缀
+    pub fn response(&self) -> Option<&[u8]> {
+        self.response.as_deref()
+    }
+
+    /// 获取连续丢失多少次应答后重连
+    pub fn max_misses(&self) -> u32 {
+        self.max_misses
+    }
+}
+
+/// 保活下一步动作
+pub(crate) enum Pulse {
+    /// 发送保活指令
+    Ping(String),
+    /// 连续丢失应答，需要重新连接
+    Dead(String),
+}
+
+/// 保活状态，接收数据时标记应答
+#[derive(Debug)]
+pub(crate) struct Keepaliving {
+    keepalive: Keepalive,
+    answered: AtomicBool,
+}
+
+impl Keepaliving {
+    pub(crate) fn new(keepalive: Keepalive) -> Self {
+        Keepaliving {
+            keepalive,
+            answered: AtomicBool::new(false),
+        }
+    }
+
+    /// 收到数据时标记应答，返回 true 表示是保活应答，不再作为条码处理
+    pub(crate) fn intercept(&self, frame: &[u8]) -> bool {
+        match &self.keepalive.response {
+            Some(response) if frame.starts_with(response) => {
+                self.answered.store(true, Ordering::Release);
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.answered.store(true, Ordering::Release);
+                false
+            }
+        }
+    }
+
+    /// 开始一次连接的保活计时
+    pub(crate) fn beat(&self) -> Beat<'_> {
+        let period = self.keepalive.interval;
+        let mut interval = interval_at(Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Beat {
+            state: self,
+            interval,
+            misses: 0,
+            pending: false,
+        }
+    }
+}
+
+/// 一次连接的保活计时
+pub(crate) struct Beat<'a> {
+    state: &'a Keepaliving,
+    interval: Interval,
+    misses: u32,
+    pending: bool,
+}
+
+impl Beat<'_> {
+    /// 等待下一次保活，未设置保活时永远等待
+    pub(crate) async fn tick(beat: &mut Option<Beat<'_>>, addr: &str) -> Pulse {
+        let Some(beat) = beat else {
+            return pending().await;
+        };
+        beat.interval.tick().await;
+        let keepalive = &beat.state.keepalive;
+        if beat.pending {
+            if beat.state.answered.swap(false, Ordering::AcqRel) {
+                beat.misses = 0;
+            } else {
+                beat.misses += 1;
+                event!(Level::WARN, scanner.addr = %addr, missed = beat.misses, "保活指令没有应答");
+                if beat.misses >= keepalive.max_misses {
+                    return Pulse::Dead(format!("连续{}次没有收到保活应答", beat.misses));
+                }
+            }
+        }
+        beat.state.answered.store(false, Ordering::Release);
+        beat.pending = true;
+        Pulse::Ping(keepalive.cmd.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Beat, Keepaliving, Pulse};
+    use crate::prelude::*;
+
+    #[test]
+    fn intercept() {
+        let state =
+            Keepaliving::new(Keepalive::new("PING", Duration::from_secs(1)).with_response(b"PONG"));
+        assert!(state.intercept(b"PONG 1.0"));
+        assert!(!state.intercept(b"SN0001"));
+        // 没有指定应答时任何数据都算应答，但仍作为条码处理
+        let state = Keepaliving::new(Keepalive::new("PING", Duration::from_secs(1)));
+        assert!(!state.intercept(b"SN0001"));
+        assert!(state.answered.load(std::sync::atomic::Ordering::Acquire));
+    }
+
+    #[tokio::test]
+    async fn beat_misses() {
+        let keepalive = Keepalive::new("PING", Duration::from_millis(10))
+            .with_response(b"PONG")
+            .with_max_misses(2);
+        let state = Keepaliving::new(keepalive);
+        let mut beat = Some(state.beat());
+        assert!(matches!(Beat::tick(&mut beat, "test").await, Pulse::Ping(cmd) if cmd == "PING"));
+        // 第一次丢失应答，继续发送
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Ping(_)
+        ));
+        // 收到应答后丢失次数清零
+        assert!(state.intercept(b"PONG"));
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Ping(_)
+        ));
+        assert_eq!(beat.as_ref().unwrap().misses, 0);
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Ping(_)
+        ));
+        assert_eq!(beat.as_ref().unwrap().misses, 1);
+        // 连续丢失达到上限
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Dead(_)
+        ));
+        // 发送前收到的数据不算应答
+        let mut beat = Some(state.beat());
+        state.intercept(b"PONG");
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Ping(_)
+        ));
+        assert!(matches!(
+            Beat::tick(&mut beat, "test").await,
+            Pulse::Ping(_)
+        ));
+        assert_eq!(beat.as_ref().unwrap().misses, 1);
+    }
+
+    #[tokio::test]
+    async fn beat_disabled() {
+        let mut beat = None;
+        let tick = tokio::time::timeout(Duration::from_millis(20), Beat::tick(&mut beat, "test"));
+        assert!(tick.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn keepalive() {
+        let mock = MockConnector::new();
+        let keepalive = Keepalive::new("PING", Duration::from_millis(50))
+            .with_response(b"PONG")
+            .with_max_misses(2);
+        let scanner = Scanner::new(mock.clone())
+            .keepalive(keepalive)
+            .retry(RetryPolicy::fixed(Duration::from_millis(10)));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        assert_eq!(mock.next_command().await.unwrap(), "PING");
+        // 保活应答不作为条码发出
+        mock.push("PONG");
+        mock.push("SN0001");
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        // 连续两次没有应答则重连
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Unhealthy(_))
+        ));
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Disconnected)
+        ));
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+
+/// 模拟输入
+#[derive(Debug)]
+pub(crate) enum MockInput {
+    /// 扫码枪发送的一帧数据
+    Data(Vec<u8>),
+    /// 断开连接
+    Disconnect,
+}
+
+/// 模拟连接器，用于在没有扫码枪的情况下测试业务代码
+///
+/// 测试代码通过[`MockConnector::push`]模拟扫码，通过[`MockConnector::next_command`]
+/// 检查业务代码用[`crate::Scanner::send_message`]发送的指令。
+/// 克隆的连接器共享同一个模拟设备
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mock = MockConnector::new();
+/// let scanner = Scanner::new(mock.clone());
+/// scanner.start().await.unwrap().unwrap();
+///
+/// mock.push("SN0001");
+/// assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+/// assert_eq!(scanner.recv().await.unwrap().as_str_lossy().unwrap(), "SN0001");
+///
+/// scanner.send_message("OK".into()).await.unwrap().unwrap();
+/// assert_eq!(mock.next_command().await.unwrap(), "OK");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockConnector {
+    input: UnboundedSender<MockInput>,
+    pub(crate) input_receiver: Arc<Mutex<UnboundedReceiver<MockInput>>>,
+    pub(crate) command_sender: UnboundedSender<String>,
+    commands: Arc<Mutex<UnboundedReceiver<String>>>,
+}
+
+impl Default for MockConnector {
+    fn default() -> Self {
+        let (input, input_receiver) = mpsc::unbounded_channel();
+        let (command_sender, commands) = mpsc::unbounded_channel();
+        MockConnector {
+            input,
+            input_receiver: Arc::new(Mutex::new(input_receiver)),
+            command_sender,
+            commands: Arc::new(Mutex::new(commands)),
+        }
+    }
+}
+
+impl MockConnector {
+    /// 创建模拟连接器
+    pub fn new() -> Self {
+        MockConnector::default()
+    }
+
+    /// 模拟扫码枪发送一帧数据
+    pub fn push(&self, data: impl AsRef<[u8]>) {
+        let _ = self.input.send(MockInput::Data(data.as_ref().to_vec()));
+    }
+
+    /// 模拟连接断开，之后按重连策略重新连接
+    pub fn disconnect(&self) {
+        let _ = self.input.send(MockInput::Disconnect);
+    }
+
+    /// 等待下一条发送给扫码枪的指令
+    pub async fn next_command(&self) -> Option<String> {
+        self.commands.lock().await.recv().await
+    }
+
+    /// 获取已发送给扫码枪但还没有取出的指令，不等待
+    pub fn try_commands(&self) -> Vec<String> {
+        let mut commands = vec![];
+        if let Ok(mut receiver) = self.commands.try_lock() {
+            while let Ok(cmd) = receiver.try_recv() {
+                commands.push(cmd);
+            }
+        }
+        commands
+    }
+}
+
+impl Display for MockConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MOCK")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn mock_reconnect() {
+        use std::time::Duration;
+
+        let mock = MockConnector::new();
+        let scanner =
+            Scanner::new(mock.clone()).retry(RetryPolicy::fixed(Duration::from_millis(10)));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        mock.disconnect();
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Disconnected)
+        ));
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        mock.push("SN0001");
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        scanner.send_message("BEEP".into()).await.unwrap().unwrap();
+        assert_eq!(mock.next_command().await.unwrap(), "BEEP");
+        assert!(mock.try_commands().is_empty());
+    }
+}
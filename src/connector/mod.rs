@@ -1,3 +1,28 @@
+pub mod baud;
+#[cfg(feature = "bluetooth")]
+pub mod bluetooth;
+pub mod capture;
+pub mod command;
+#[allow(clippy::module_inception)]
 pub mod connector;
+#[cfg(feature = "hid")]
+pub mod hid;
+pub mod ip_filter;
+pub mod keepalive;
+pub mod mock;
 pub mod network;
+pub mod port;
+pub mod proxy;
+pub mod replay;
+pub mod retry;
+pub mod rs485;
 pub mod serial;
+pub mod socket;
+pub mod timeout;
+#[cfg(feature = "tls")]
+pub mod tls;
+pub mod transport;
+pub mod url;
+pub mod watchdog;
+#[cfg(feature = "websocket")]
+pub mod websocket;
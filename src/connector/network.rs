@@ -1,5 +1,16 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use tokio::net::TcpStream;
+use tracing::{event, Level};
+
+#[cfg(feature = "tls")]
+use crate::Tls;
+use crate::{Connecting, IpFilter, Proxy, ScannerError, SocketOptions, Transport, TransportStream};
+
 /// 网络连接器
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Network {
     ip: String,
     port: u16,
@@ -7,17 +18,28 @@ pub struct Network {
     ///
     /// * `true` 服务器模式
     /// * `false` 客户端模式
+    #[cfg_attr(feature = "serde", serde(default))]
     is_server: bool,
+    /// TCP 连接参数
+    socket_options: Option<SocketOptions>,
+    /// 服务器模式下的客户端 IP 过滤
+    ip_filter: Option<IpFilter>,
+    /// 客户端模式下使用的代理
+    proxy: Option<Proxy>,
+    /// TLS 加密参数
+    #[cfg(feature = "tls")]
+    tls: Option<Tls>,
 }
 
 impl Network {
     /// 创建一个TCP服务器连接器
     ///
-    /// * `ip` ip 地址
+    /// * `ip` IPv4/IPv6 地址或主机名
     /// * `port` 端口
-    /// #Examples
+    ///
+    /// # Examples
     /// ```
-    /// use scanner::prelude::*;
+    /// use kim_scanner::prelude::*;
     ///
     /// let conn:Connector = Network::new_server("127.0.0.1", 5000).into();
     ///
@@ -29,19 +51,25 @@ impl Network {
     /// ```
     pub fn new_server(ip: &str, port: u16) -> Network {
         Network {
-            ip: ip.into(),
+            ip: unbracket(ip).into(),
             port,
             is_server: true,
+            socket_options: None,
+            ip_filter: None,
+            proxy: None,
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
     /// 创建一个TCP客户端连接器
     ///
-    /// * `ip` ip 地址
+    /// * `ip` IPv4/IPv6 地址或主机名
     /// * `port` 端口
-    /// #Examples
+    ///
+    /// # Examples
     /// ```
-    /// use scanner::prelude::*;
+    /// use kim_scanner::prelude::*;
     ///
     /// let conn:Connector = Network::new_client("127.0.0.1", 5000).into();
     ///
@@ -53,9 +81,14 @@ impl Network {
     /// ```
     pub fn new_client(ip: &str, port: u16) -> Network {
         Network {
-            ip: ip.into(),
+            ip: unbracket(ip).into(),
             port,
             is_server: false,
+            socket_options: None,
+            ip_filter: None,
+            proxy: None,
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
@@ -64,13 +97,267 @@ impl Network {
         self.is_server
     }
 
-    /// 获取IP地址
+    /// 获取IP地址或主机名
     pub fn ip(&self) -> &str {
         &self.ip
     }
 
+    /// 检查地址是否为 IP 地址或合法的主机名，主机名在每次连接时解析
+    pub(crate) fn check(&self) -> Result<(), ScannerError> {
+        if let Some(filter) = &self.ip_filter {
+            filter.check()?;
+        }
+        if self.is_server && self.proxy.is_some() {
+            return Err(ScannerError::Param(format!(
+                "服务器模式不支持代理,addr={}",
+                self.addr()
+            )));
+        }
+        if IpAddr::from_str(&self.ip).is_ok() || is_hostname(&self.ip) {
+            return Ok(());
+        }
+        Err(ScannerError::Param(format!(
+            "无效的IP地址或主机名,ip={}",
+            self.ip
+        )))
+    }
+
+    /// 获取连接地址，IPv6 地址带方括号，例如`[fe80::1]:5000`
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert_eq!(Network::new_server("::", 5000).addr(), "[::]:5000");
+    /// assert_eq!(Network::new_client("[fd00::10]", 9004).addr(), "[fd00::10]:9004");
+    /// assert_eq!(Network::new_client("192.168.1.10", 9004).addr(), "192.168.1.10:9004");
+    /// ```
+    pub fn addr(&self) -> String {
+        host_port(&self.ip, self.port)
+    }
+
     /// 获取端口
     pub fn port(&self) -> u16 {
         self.port
     }
+
+    /// 设置 TCP 连接参数(keepalive、nodelay、linger)，服务器模式下作用于接入的扫码枪连接
+    pub fn with_socket_options(mut self, options: SocketOptions) -> Self {
+        self.socket_options = Some(options);
+        self
+    }
+
+    /// 获取 TCP 连接参数
+    pub fn socket_options(&self) -> Option<&SocketOptions> {
+        self.socket_options.as_ref()
+    }
+
+    /// 设置客户端 IP 过滤(仅服务器模式)，只允许指定的扫码枪连接
+    pub fn with_ip_filter(mut self, filter: IpFilter) -> Self {
+        self.ip_filter = Some(filter);
+        self
+    }
+
+    /// 获取客户端 IP 过滤
+    pub fn ip_filter(&self) -> Option<&IpFilter> {
+        self.ip_filter.as_ref()
+    }
+
+    /// 设置代理(仅客户端模式)，通过 SOCKS5 或 HTTP 代理连接扫码枪
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// 获取代理
+    pub fn proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    /// 启用 TLS 加密(需要启用`tls`特性)
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: Tls) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// 获取 TLS 加密参数
+    #[cfg(feature = "tls")]
+    pub fn tls(&self) -> Option<&Tls> {
+        self.tls.as_ref()
+    }
+}
+
+/// 客户端模式连接扫码枪，服务器模式不支持
+impl Transport for Network {
+    fn name(&self) -> String {
+        self.addr()
+    }
+
+    fn connect(&self) -> Connecting<'_> {
+        Box::pin(async move {
+            let addr = self.addr();
+            if self.is_server {
+                return Err(ScannerError::Param(format!(
+                    "服务器模式不能主动连接,addr={}",
+                    addr
+                )));
+            }
+            #[cfg(feature = "tls")]
+            let connector = match self.tls() {
+                Some(tls) => Some((tls.connector()?, tls.name(self.ip())?)),
+                None => None,
+            };
+            // 连接扫码枪服务
+            let client = match self.proxy() {
+                // 主机名由代理服务器解析
+                Some(proxy) => {
+                    event!(Level::DEBUG, scanner.addr = %addr, proxy = %proxy, "通过代理连接");
+                    proxy.connect(self.ip(), self.port()).await
+                }
+                None => {
+                    // 每次连接时重新解析主机名，扫码枪地址可能由 DHCP 分配
+                    let addrs = match tokio::net::lookup_host(&addr).await {
+                        Ok(addrs) => addrs.collect::<Vec<_>>(),
+                        Err(err) => {
+                            event!(
+                                Level::ERROR,
+                                scanner.addr = %addr,
+                                error = %err,
+                                error.kind = "io",
+                                "域名解析失败",
+                            );
+                            return Err(ScannerError::Comm(err.to_string()));
+                        }
+                    };
+                    TcpStream::connect(&addrs[..]).await
+                }
+            };
+            let client = match client {
+                Ok(client) => client,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "扫码枪连接错误",
+                    );
+                    return Err(ScannerError::Comm(err.to_string()));
+                }
+            };
+            // 连接刚建立就被对端关闭时获取不到对端地址，按连接失败处理，由重连策略重试
+            let peer = match client.peer_addr() {
+                Ok(peer) => peer,
+                Err(err) => {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "扫码枪连接已断开",
+                    );
+                    return Err(ScannerError::Comm(err.to_string()));
+                }
+            };
+            event!(
+                Level::INFO,
+                scanner.addr = %addr,
+                peer.addr = %peer,
+                "扫码枪连接成功",
+            );
+            if let Some(options) = self.socket_options() {
+                if let Err(err) = options.apply(&client) {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "TCP连接参数设置失败",
+                    );
+                }
+            }
+            #[cfg(feature = "tls")]
+            if let Some((connector, server_name)) = connector {
+                let stream = match connector.connect(server_name, client).await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        event!(
+                            Level::ERROR,
+                            scanner.addr = %addr,
+                            error = %err,
+                            error.kind = "io",
+                            "TLS握手失败",
+                        );
+                        return Err(ScannerError::Comm(err.to_string()));
+                    }
+                };
+                return Ok(Box::new(stream) as Box<dyn TransportStream>);
+            }
+            Ok(Box::new(client) as Box<dyn TransportStream>)
+        })
+    }
+}
+
+/// 是否为合法的主机名(RFC 1123)，最后一段不能全是数字，避免把错误的 IP 地址当成主机名
+fn is_hostname(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    !name.is_empty()
+        && name.len() <= 253
+        && !name
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+/// 拼接`host:port`，IPv6 地址加上方括号
+pub(crate) fn host_port(ip: &str, port: u16) -> String {
+    match IpAddr::from_str(unbracket(ip)) {
+        Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+        _ => format!("{}:{}", ip, port),
+    }
+}
+
+/// 去掉 IPv6 地址两边的方括号
+fn unbracket(ip: &str) -> &str {
+    ip.strip_prefix('[')
+        .and_then(|ip| ip.strip_suffix(']'))
+        .unwrap_or(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn network_hostname() {
+        assert!(Network::new_client("192.168.1.10", 9004).check().is_ok());
+        assert!(Network::new_client("scanner-01.local", 9004)
+            .check()
+            .is_ok());
+        assert!(Network::new_server("localhost", 9004).check().is_ok());
+        assert!(Network::new_client("scanner_01.local", 9004)
+            .check()
+            .is_err());
+        assert!(Network::new_client("-scanner.local", 9004).check().is_err());
+        assert!(Network::new_client("192.168.1.300", 9004).check().is_err());
+        assert!(Network::new_client("", 9004).check().is_err());
+        assert!(Network::new_client("fe80::1", 9004).check().is_ok());
+        assert!(Network::new_server("[::]", 9004).check().is_ok());
+        assert!(Network::new_server("0.0.0.0", 9004)
+            .with_proxy(Proxy::socks5("10.0.0.1:1080"))
+            .check()
+            .is_err());
+    }
 }
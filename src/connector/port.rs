@@ -0,0 +1,193 @@
+/// 串口连接方式
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PortType {
+    /// USB 转串口
+    Usb,
+    /// 主板或 PCI 串口卡
+    Pci,
+    /// 蓝牙虚拟串口
+    Bluetooth,
+    /// 未知
+    Unknown,
+}
+
+/// 串口信息
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PortInfo {
+    name: String,
+    port_type: PortType,
+    vid: Option<u16>,
+    pid: Option<u16>,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    serial_number: Option<String>,
+}
+
+impl PortInfo {
+    /// 获取串口名称，例如`COM3`、`/dev/ttyUSB0`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 获取连接方式
+    pub fn port_type(&self) -> &PortType {
+        &self.port_type
+    }
+
+    /// 获取 USB 厂商 ID
+    pub fn vid(&self) -> Option<u16> {
+        self.vid
+    }
+
+    /// 获取 USB 产品 ID
+    pub fn pid(&self) -> Option<u16> {
+        self.pid
+    }
+
+    /// 获取厂商名称
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.manufacturer.as_deref()
+    }
+
+    /// 获取产品名称
+    pub fn product(&self) -> Option<&str> {
+        self.product.as_deref()
+    }
+
+    /// 获取设备序列号
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+}
+
+impl From<tokio_serial::SerialPortInfo> for PortInfo {
+    fn from(value: tokio_serial::SerialPortInfo) -> Self {
+        let mut info = PortInfo {
+            name: value.port_name,
+            port_type: PortType::Unknown,
+            vid: None,
+            pid: None,
+            manufacturer: None,
+            product: None,
+            serial_number: None,
+        };
+        match value.port_type {
+            tokio_serial::SerialPortType::UsbPort(usb) => {
+                info.port_type = PortType::Usb;
+                info.vid = Some(usb.vid);
+                info.pid = Some(usb.pid);
+                info.manufacturer = usb.manufacturer;
+                info.product = usb.product;
+                info.serial_number = usb.serial_number;
+            }
+            tokio_serial::SerialPortType::PciPort => info.port_type = PortType::Pci,
+            tokio_serial::SerialPortType::BluetoothPort => info.port_type = PortType::Bluetooth,
+            tokio_serial::SerialPortType::Unknown => {}
+        }
+        info
+    }
+}
+
+/// 串口过滤条件，未设置的条件不参与匹配
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// // 查找 Honeywell 扫码枪
+/// let filter = PortFilter::new().vid(0x0C2E);
+/// if let Some(port) = Serial::find(&filter).unwrap() {
+///     let conn = Serial::new(port.name(), 9600, 8, StopBits::One, Parity::None);
+/// }
+///
+/// // 按厂商名称查找(不区分大小写)
+/// let filter = PortFilter::new().manufacturer("honeywell");
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PortFilter {
+    vid: Option<u16>,
+    pid: Option<u16>,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    serial_number: Option<String>,
+}
+
+impl PortFilter {
+    /// 创建过滤条件
+    pub fn new() -> Self {
+        PortFilter::default()
+    }
+
+    /// USB 厂商 ID
+    pub fn vid(mut self, vid: u16) -> Self {
+        self.vid = Some(vid);
+        self
+    }
+
+    /// USB 产品 ID
+    pub fn pid(mut self, pid: u16) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// 厂商名称包含的文字，不区分大小写
+    pub fn manufacturer(mut self, manufacturer: &str) -> Self {
+        self.manufacturer = Some(manufacturer.to_lowercase());
+        self
+    }
+
+    /// 产品名称包含的文字，不区分大小写
+    pub fn product(mut self, product: &str) -> Self {
+        self.product = Some(product.to_lowercase());
+        self
+    }
+
+    /// 设备序列号
+    pub fn serial_number(mut self, serial_number: &str) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    /// 串口是否满足条件
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        let contains = |value: Option<&str>, pattern: &Option<String>| match pattern {
+            Some(pattern) => value.is_some_and(|v| v.to_lowercase().contains(pattern)),
+            None => true,
+        };
+        self.vid.is_none_or(|vid| port.vid == Some(vid))
+            && self.pid.is_none_or(|pid| port.pid == Some(pid))
+            && contains(port.manufacturer(), &self.manufacturer)
+            && contains(port.product(), &self.product)
+            && self
+                .serial_number
+                .as_deref()
+                .is_none_or(|sn| port.serial_number() == Some(sn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_filter() {
+        let port = PortInfo {
+            name: "/dev/ttyACM0".into(),
+            port_type: PortType::Usb,
+            vid: Some(0x0C2E),
+            pid: Some(0x0B61),
+            manufacturer: Some("Honeywell Imaging & Mobility".into()),
+            product: Some("Xenon 1900".into()),
+            serial_number: Some("17123B0001".into()),
+        };
+        assert!(PortFilter::new().matches(&port));
+        assert!(PortFilter::new().vid(0x0C2E).pid(0x0B61).matches(&port));
+        assert!(PortFilter::new().manufacturer("HONEYWELL").matches(&port));
+        assert!(!PortFilter::new().vid(0x05E0).matches(&port));
+        assert!(!PortFilter::new().product("Gryphon").matches(&port));
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt::Display;
+use std::io::{Error, ErrorKind};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use base64::Engine;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 代理类型
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ProxyKind {
+    /// SOCKS5 代理
+    Socks5,
+    /// HTTP CONNECT 代理
+    Http,
+}
+
+/// 代理服务器，仅用于网络连接器的客户端模式
+///
+/// 扫码枪的主机名由代理服务器解析
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let conn = Network::new_client("scanner-01.plant.local", 9004)
+///     .with_proxy(Proxy::socks5("10.0.0.1:1080").with_auth("mes", "secret"));
+/// assert_eq!(conn.proxy().unwrap().to_string(), "socks5://10.0.0.1:1080");
+///
+/// let conn = Network::new_client("192.168.1.10", 9004).with_proxy(Proxy::http("10.0.0.1:3128"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proxy {
+    kind: ProxyKind,
+    /// 代理服务器地址`host:port`
+    addr: String,
+    /// 用户名和密码
+    auth: Option<(String, String)>,
+}
+
+impl Proxy {
+    /// 创建 SOCKS5 代理
+    ///
+    /// * `addr` 代理服务器地址`host:port`
+    pub fn socks5(addr: &str) -> Self {
+        Proxy {
+            kind: ProxyKind::Socks5,
+            addr: addr.into(),
+            auth: None,
+        }
+    }
+
+    /// 创建 HTTP CONNECT 代理
+    ///
+    /// * `addr` 代理服务器地址`host:port`
+    pub fn http(addr: &str) -> Self {
+        Proxy {
+            kind: ProxyKind::Http,
+            addr: addr.into(),
+            auth: None,
+        }
+    }
+
+    /// 设置代理认证的用户名和密码
+    pub fn with_auth(mut self, username: &str, password: &str) -> Self {
+        self.auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// 获取代理类型
+    pub fn kind(&self) -> &ProxyKind {
+        &self.kind
+    }
+
+    /// 获取代理服务器地址
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// 通过代理连接`host:port`
+    pub(crate) async fn connect(&self, host: &str, port: u16) -> std::io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        match self.kind {
+            ProxyKind::Socks5 => self.socks5_handshake(&mut stream, host, port).await?,
+            ProxyKind::Http => self.http_handshake(&mut stream, host, port).await?,
+        }
+        Ok(stream)
+    }
+
+    /// SOCKS5 握手(RFC 1928/1929)
+    async fn socks5_handshake(
+        &self,
+        stream: &mut TcpStream,
+        host: &str,
+        port: u16,
+    ) -> std::io::Result<()> {
+        // 协议中用户名、密码和主机名的长度只有一个字节，发送前检查
+        let auth_len = match &self.auth {
+            Some((username, password)) => {
+                Some((field_len("用户名", username)?, field_len("密码", password)?))
+            }
+            None => None,
+        };
+        let host_len = field_len("主机名", host)?;
+        // 认证方式协商：0x00 无认证，0x02 用户名密码
+        let methods: &[u8] = match self.auth {
+            Some(_) => &[0x05, 0x02, 0x00, 0x02],
+            None => &[0x05, 0x01, 0x00],
+        };
+        stream.write_all(methods).await?;
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        match (reply, &self.auth, auth_len) {
+            ([0x05, 0x00], _, _) => {}
+            ([0x05, 0x02], Some((username, password)), Some((username_len, password_len))) => {
+                let mut buf = vec![0x01, username_len];
+                buf.extend_from_slice(username.as_bytes());
+                buf.push(password_len);
+                buf.extend_from_slice(password.as_bytes());
+                stream.write_all(&buf).await?;
+                stream.read_exact(&mut reply).await?;
+                if reply[1] != 0x00 {
+                    return Err(proxy_error("SOCKS5代理认证失败"));
+                }
+            }
+            _ => return Err(proxy_error("SOCKS5代理不支持的认证方式")),
+        }
+        // 连接请求
+        let mut buf = vec![0x05, 0x01, 0x00];
+        match IpAddr::from_str(host) {
+            Ok(IpAddr::V4(ip)) => {
+                buf.push(0x01);
+                buf.extend_from_slice(&ip.octets());
+            }
+            Ok(IpAddr::V6(ip)) => {
+                buf.push(0x04);
+                buf.extend_from_slice(&ip.octets());
+            }
+            Err(_) => {
+                buf.push(0x03);
+                buf.push(host_len);
+                buf.extend_from_slice(host.as_bytes());
+            }
+        }
+        buf.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&buf).await?;
+        let mut head = [0u8; 4];
+        stream.read_exact(&mut head).await?;
+        if head[1] != 0x00 {
+            return Err(proxy_error(&format!(
+                "SOCKS5代理连接失败,错误码={}",
+                head[1]
+            )));
+        }
+        // 跳过代理返回的绑定地址
+        let len = match head[3] {
+            0x01 => 4,
+            0x04 => 16,
+            0x03 => stream.read_u8().await? as usize,
+            _ => return Err(proxy_error("SOCKS5代理返回了未知的地址类型")),
+        };
+        let mut bind = vec![0u8; len + 2];
+        stream.read_exact(&mut bind).await?;
+        Ok(())
+    }
+
+    /// HTTP CONNECT 握手
+    async fn http_handshake(
+        &self,
+        stream: &mut TcpStream,
+        host: &str,
+        port: u16,
+    ) -> std::io::Result<()> {
+        let target = match IpAddr::from_str(host) {
+            Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+            _ => format!("{}:{}", host, port),
+        };
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target);
+        if let Some((username, password)) = &self.auth {
+            let token = base64::engine::general_purpose::STANDARD
+                .encode(format!("{}:{}", username, password));
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+        // 逐字节读取响应头，避免读走扫码枪的数据
+        let mut head = vec![];
+        while !head.ends_with(b"\r\n\r\n") {
+            if head.len() > 8192 {
+                return Err(proxy_error("HTTP代理响应头过长"));
+            }
+            head.push(stream.read_u8().await?);
+        }
+        let head = String::from_utf8_lossy(&head);
+        let status = head.lines().next().unwrap_or_default();
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(()),
+            _ => Err(proxy_error(&format!("HTTP代理连接失败,响应={}", status))),
+        }
+    }
+}
+
+impl Display for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ProxyKind::Socks5 => write!(f, "socks5://{}", self.addr),
+            ProxyKind::Http => write!(f, "http://{}", self.addr),
+        }
+    }
+}
+
+fn proxy_error(msg: &str) -> Error {
+    Error::new(ErrorKind::ConnectionRefused, msg)
+}
+
+/// SOCKS5 字段的长度，超过 255 字节时返回`InvalidInput`错误
+fn field_len(name: &str, value: &str) -> std::io::Result<u8> {
+    u8::try_from(value.len()).map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("SOCKS5{}长度超过255字节: {}", name, value.len()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::{field_len, Proxy};
+
+    #[tokio::test]
+    async fn socks5() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy =
+            Proxy::socks5(&listener.local_addr().unwrap().to_string()).with_auth("mes", "pw");
+        tokio::spawn(async move {
+            let (mut s, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 64];
+            let n = s.read(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], &[0x05, 0x02, 0x00, 0x02]);
+            s.write_all(&[0x05, 0x02]).await.unwrap();
+            let n = s.read(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"\x01\x03mes\x02pw");
+            s.write_all(&[0x01, 0x00]).await.unwrap();
+            let n = s.read(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"\x05\x01\x00\x03\x0ascanner-01\x23\x8c");
+            s.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            s.write_all(b"SN0001").await.unwrap();
+        });
+        let mut stream = proxy.connect("scanner-01", 9100).await.unwrap();
+        let mut buf = [0u8; 6];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"SN0001");
+    }
+
+    #[tokio::test]
+    async fn http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = Proxy::http(&listener.local_addr().unwrap().to_string());
+        tokio::spawn(async move {
+            let (mut s, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 256];
+            let n = s.read(&mut buf).await.unwrap();
+            assert!(buf[..n].starts_with(b"CONNECT 192.168.1.10:9004 HTTP/1.1\r\n"));
+            s.write_all(b"HTTP/1.1 200 Connection established\r\n\r\nSN0001")
+                .await
+                .unwrap();
+            let (mut s, _) = listener.accept().await.unwrap();
+            let _ = s.read(&mut buf).await.unwrap();
+            s.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\n")
+                .await
+                .unwrap();
+        });
+        let mut stream = proxy.connect("192.168.1.10", 9004).await.unwrap();
+        let mut buf = [0u8; 6];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"SN0001");
+        assert!(proxy.connect("192.168.1.10", 9004).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn socks5_field_too_long() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let long = "a".repeat(256);
+        // 代理不应答，超长的字段在发送之前就被拒绝
+        let err = Proxy::socks5(&addr).connect(&long, 9100).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        let proxy = Proxy::socks5(&addr).with_auth(&long, "pw");
+        let err = proxy.connect("scanner-01", 9100).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        let proxy = Proxy::socks5(&addr).with_auth("mes", &long);
+        let err = proxy.connect("scanner-01", 9100).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        // 正好 255 字节的主机名可以发送
+        assert!(field_len("主机名", &long[..255]).is_ok());
+    }
+}
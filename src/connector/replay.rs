@@ -0,0 +1,304 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ScannerError;
+
+/// 脚本回放连接器，按脚本中的间隔依次发送条码，用于压力测试和演示
+///
+/// 脚本文件每行一条，格式为`间隔毫秒 条码`，间隔为距上一条的时长。
+/// 空行和`#`开头的行忽略，条码中可以使用`\r`、`\n`、`\t`、`\\`、`\xHH`转义
+///
+/// ```text
+/// # 产线节拍 1.5 秒
+/// 0    SN0001
+/// 1500 SN0002
+/// 1500 01095011010209171719050810ABCD1234\x1d2110
+/// ```
+///
+/// 也可以回放[`crate::Capture`]录制的抓包文件(见[`Replay::parse_capture`])，
+/// 按原始时间间隔重放产线上收到的原始数据，不需要实体扫码枪就能在测试中复现问题
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// let replay = Replay::parse("0 SN0001\n500 SN0002\\x1d01")
+///     .unwrap()
+///     .with_repeat(true)
+///     .with_speed(2.0);
+/// assert_eq!(replay.entries()[1], (Duration::from_millis(500), b"SN0002\x1d01".to_vec()));
+///
+/// let replay = Replay::new([(Duration::from_millis(100), "SN0001"), (Duration::from_millis(100), "SN0002")]);
+/// let scanner = Scanner::new(replay);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    entries: Vec<(Duration, Vec<u8>)>,
+    repeat: bool,
+    speed: f64,
+}
+
+impl Replay {
+    /// 创建回放连接器
+    ///
+    /// * `entries` (距上一条的间隔，条码)
+    pub fn new<T: Into<Vec<u8>>>(entries: impl IntoIterator<Item = (Duration, T)>) -> Self {
+        Replay {
+            entries: entries
+                .into_iter()
+                .map(|(delay, data)| (delay, data.into()))
+                .collect(),
+            repeat: false,
+            speed: 1.0,
+        }
+    }
+
+    /// 解析回放脚本
+    pub fn parse(script: &str) -> Result<Self, ScannerError> {
+        let mut entries = vec![];
+        for (no, line) in script.lines().enumerate() {
+            let line = line.trim_start();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (delay, data) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let delay = delay.parse::<u64>().map_err(|_| {
+                ScannerError::Param(format!("回放脚本第{}行间隔无效,line={}", no + 1, line))
+            })?;
+            let data = unescape(data.trim_start()).ok_or_else(|| {
+                ScannerError::Param(format!("回放脚本第{}行转义无效,line={}", no + 1, line))
+            })?;
+            entries.push((Duration::from_millis(delay), data));
+        }
+        Ok(Replay::new(entries))
+    }
+
+    /// 解析[`crate::Capture`]录制的抓包文件，回放其中收到的数据(`<`)，保持原始的时间间隔
+    ///
+    /// 发送的数据(`>`)和连接记录(`#`)忽略，多次连接的数据按时间顺序连续回放
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let capture = "# 1760000000000 连接 192.168.1.10:9004\n\
+    ///                1760000000120 < SN0001\\r\\n\n\
+    ///                1760000000300 > LON\\r\n\
+    ///                1760000001620 < SN0002\\r\\n\n";
+    /// let replay = Replay::parse_capture(capture).unwrap();
+    /// assert_eq!(replay.entries()[0], (Duration::ZERO, b"SN0001\r\n".to_vec()));
+    /// assert_eq!(replay.entries()[1], (Duration::from_millis(1500), b"SN0002\r\n".to_vec()));
+    /// ```
+    pub fn parse_capture(capture: &str) -> Result<Self, ScannerError> {
+        let mut entries = vec![];
+        let mut last = None;
+        for (no, line) in capture.lines().enumerate() {
+            let line = line.trim_start();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                || ScannerError::Param(format!("抓包文件第{}行无效,line={}", no + 1, line));
+            let (time, rest) = line.split_once(' ').ok_or_else(invalid)?;
+            let time = time.parse::<u64>().map_err(|_| invalid())?;
+            let data = match rest.split_once(' ') {
+                Some(("<", data)) => data,
+                None if rest == "<" => "",
+                Some((">", _)) => continue,
+                None if rest == ">" => continue,
+                _ => return Err(invalid()),
+            };
+            let data = unescape(data).ok_or_else(invalid)?;
+            let delay = last.map_or(0, |last| time.saturating_sub(last));
+            last = Some(time);
+            entries.push((Duration::from_millis(delay), data));
+        }
+        Ok(Replay::new(entries))
+    }
+
+    /// 读取并解析[`crate::Capture`]录制的抓包文件
+    pub fn from_capture_file(path: impl AsRef<Path>) -> Result<Self, ScannerError> {
+        let capture = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+        Replay::parse_capture(&capture)
+    }
+
+    /// 读取并解析回放脚本文件
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ScannerError> {
+        let script = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+        Replay::parse(&script)
+    }
+
+    /// 回放结束后是否从头开始，默认只回放一次
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// 设置回放速度倍数，例如`2.0`表示间隔减半，默认`1.0`
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// 获取回放条目
+    pub fn entries(&self) -> &[(Duration, Vec<u8>)] {
+        &self.entries
+    }
+
+    /// 是否循环回放
+    pub fn repeat(&self) -> bool {
+        self.repeat
+    }
+
+    /// 获取回放速度倍数
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// 检查回放速度
+    pub(crate) fn check(&self) -> Result<(), ScannerError> {
+        if self.speed.is_finite() && self.speed > 0.0 {
+            return Ok(());
+        }
+        Err(ScannerError::Param(format!(
+            "回放速度必须大于0,speed={}",
+            self.speed
+        )))
+    }
+
+    /// 按回放速度计算实际间隔
+    pub(crate) fn delay(&self, delay: Duration) -> Duration {
+        delay.div_f64(self.speed)
+    }
+}
+
+impl Display for Replay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "REPLAY")
+    }
+}
+
+/// 转义为回放脚本中的数据，可打印 ASCII 字符保持不变
+pub(crate) fn escape(data: &[u8]) -> String {
+    let mut text = String::new();
+    for b in data {
+        match b {
+            b'\r' => text.push_str("\\r"),
+            b'\n' => text.push_str("\\n"),
+            b'\t' => text.push_str("\\t"),
+            b'\\' => text.push_str("\\\\"),
+            0x20..=0x7e => text.push(*b as char),
+            _ => text.push_str(&format!("\\x{:02X}", b)),
+        }
+    }
+    text
+}
+
+/// 处理转义字符
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut data = vec![];
+    let mut bytes = text.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            data.push(b);
+            continue;
+        }
+        match bytes.next()? {
+            b'r' => data.push(b'\r'),
+            b'n' => data.push(b'\n'),
+            b't' => data.push(b'\t'),
+            b'\\' => data.push(b'\\'),
+            b'x' => {
+                let hex = [bytes.next()?, bytes.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                data.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{escape, unescape, Replay};
+    use crate::prelude::*;
+
+    #[test]
+    fn parse() {
+        let replay = Replay::parse("# 注释\n\n0 SN0001\n  1500\tSN 0002\\r\\n\n20 \\\\x").unwrap();
+        assert_eq!(
+            replay.entries(),
+            &[
+                (Duration::ZERO, b"SN0001".to_vec()),
+                (Duration::from_millis(1500), b"SN 0002\r\n".to_vec()),
+                (Duration::from_millis(20), b"\\x".to_vec()),
+            ]
+        );
+        assert!(Replay::parse("1.5 SN0001").is_err());
+        assert!(Replay::parse("0 SN\\x1").is_err());
+        assert!(Replay::parse("0 SN\\q").is_err());
+        let replay = Replay::parse_capture(
+            "# 100 连接 MOCK\n100 < \x20SN\\r\n150 > LON\\r\n170 <\n# 900 连接 MOCK\n1000 < SN2",
+        )
+        .unwrap();
+        assert_eq!(
+            replay.entries(),
+            &[
+                (Duration::ZERO, b" SN\r".to_vec()),
+                (Duration::from_millis(70), vec![]),
+                (Duration::from_millis(830), b"SN2".to_vec()),
+            ]
+        );
+        assert!(Replay::parse_capture("100 SN0001").is_err());
+        assert!(Replay::parse_capture("100 < SN\\q").is_err());
+        let data = b"SN 0001\\\x1d\xff\r\n";
+        assert_eq!(escape(data), "SN 0001\\\\\\x1D\\xFF\\r\\n");
+        assert_eq!(unescape(&escape(data)).unwrap(), data);
+        assert!(Replay::new([(Duration::ZERO, "SN")])
+            .with_speed(0.0)
+            .check()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        use std::time::Duration;
+
+        let replay = Replay::parse("0 SN0001\n1000 SN0002")
+            .unwrap()
+            .with_speed(100.0);
+        let scanner = Scanner::new(replay);
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        let ev = tokio::time::timeout(Duration::from_millis(500), scanner.recv())
+            .await
+            .unwrap();
+        assert_eq!(ev.unwrap().as_str_lossy().unwrap(), "SN0002");
+        // 回放结束后不重连
+        let ev = tokio::time::timeout(Duration::from_millis(100), scanner.recv()).await;
+        assert!(ev.is_err());
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_commands() {
+        let scanner = Scanner::new(Replay::parse("0 SN0001").unwrap());
+        assert!(!scanner.connector.is_writable());
+        // 只能接收数据的连接方式不把指令放入队列，不会一直等待
+        let r = scanner.send_message("BEEP".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+        let r = scanner.send_confirmed("BEEP".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+        assert!(scanner.reconnect().await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ScannerError;
+
+/// 重连间隔
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Backoff {
+    /// 固定间隔
+    Fixed(Duration),
+    /// 指数退避，每次失败后间隔翻倍，不超过`max`
+    Exponential {
+        /// 首次重连间隔
+        initial: Duration,
+        /// 最大间隔
+        max: Duration,
+        /// 是否加入随机抖动(实际间隔为计算值的 50%~100%)，避免大量扫码枪同时重连
+        jitter: bool,
+    },
+}
+
+/// 放弃重连时的回调
+type GiveUp = Arc<dyn Fn(&ScannerError) + Send + Sync>;
+
+/// 重连策略，串口、网络等所有连接方式通用
+///
+/// 默认固定间隔3秒，无限重试。连接成功后失败次数清零。
+/// 超过最大重试次数或连续失败超过`give_up_after`后放弃重连，扫码枪进入失败状态并发出[`crate::ScanEvent::Failed`]事件
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// let retry = RetryPolicy::exponential(Duration::from_millis(500), Duration::from_secs(30))
+///     .with_jitter(true)
+///     .max_retries(10)
+///     .give_up_after(Duration::from_secs(600))
+///     .on_give_up(|err| eprintln!("扫码枪已放弃重连: {}", err));
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).retry(retry);
+///
+/// let retry = RetryPolicy::fixed(Duration::from_secs(1));
+/// assert_eq!(retry.delay(5), Duration::from_secs(1));
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    backoff: Backoff,
+    max_retries: Option<u32>,
+    give_up_after: Option<Duration>,
+    give_up: Option<GiveUp>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::fixed(Duration::from_secs(3))
+    }
+}
+
+impl Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("backoff", &self.backoff)
+            .field("max_retries", &self.max_retries)
+            .field("give_up_after", &self.give_up_after)
+            .field("give_up", &self.give_up.is_some())
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// 固定间隔重连
+    pub fn fixed(delay: Duration) -> Self {
+        RetryPolicy {
+            backoff: Backoff::Fixed(delay),
+            max_retries: None,
+            give_up_after: None,
+            give_up: None,
+        }
+    }
+
+    /// 指数退避重连
+    ///
+    /// * `initial` 首次重连间隔
+    /// * `max` 最大间隔
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        RetryPolicy {
+            backoff: Backoff::Exponential {
+                initial,
+                max,
+                jitter: false,
+            },
+            max_retries: None,
+            give_up_after: None,
+            give_up: None,
+        }
+    }
+
+    /// 指数退避时是否加入随机抖动
+    pub fn with_jitter(mut self, enable: bool) -> Self {
+        if let Backoff::Exponential { jitter, .. } = &mut self.backoff {
+            *jitter = enable;
+        }
+        self
+    }
+
+    /// 连续失败的最大重试次数，超过后停止重连
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// 从第一次失败开始连续失败的最长时间，超过后停止重连，例如扫码枪已被拆除
+    pub fn give_up_after(mut self, duration: Duration) -> Self {
+        self.give_up_after = Some(duration);
+        self
+    }
+
+    /// 放弃重连时的回调，参数为最后一次的错误
+    pub fn on_give_up(mut self, f: impl Fn(&ScannerError) + Send + Sync + 'static) -> Self {
+        self.give_up = Some(Arc::new(f));
+        self
+    }
+
+    /// 获取重连间隔配置
+    pub fn backoff(&self) -> &Backoff {
+        &self.backoff
+    }
+
+    /// 计算第`failures`次连续失败后的重连间隔，0 表示连接正常断开
+    pub fn delay(&self, failures: u32) -> Duration {
+        match &self.backoff {
+            Backoff::Fixed(delay) => *delay,
+            Backoff::Exponential {
+                initial,
+                max,
+                jitter,
+            } => {
+                let exp = failures.saturating_sub(1).min(31);
+                let delay = initial.saturating_mul(1 << exp).min(*max);
+                if *jitter {
+                    // 50%~100% 之间随机
+                    let random = RandomState::new().build_hasher().finish();
+                    let ratio = 0.5 + (random % 1000) as f64 / 2000.0;
+                    delay.mul_f64(ratio)
+                } else {
+                    delay
+                }
+            }
+        }
+    }
+
+    /// 连续失败`failures`次后是否应该放弃
+    pub(crate) fn exhausted(&self, failures: u32) -> bool {
+        self.max_retries.is_some_and(|max| failures > max)
+    }
+
+    /// 从第一次失败开始已经过`elapsed`后是否应该放弃
+    pub(crate) fn expired(&self, elapsed: Duration) -> bool {
+        self.give_up_after.is_some_and(|max| elapsed >= max)
+    }
+
+    /// 放弃重连，调用回调
+    pub(crate) fn give_up(&self, err: &ScannerError) {
+        if let Some(f) = &self.give_up {
+            f(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+    use crate::prelude::*;
+
+    #[test]
+    fn exponential() {
+        let retry = RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(10));
+        assert_eq!(retry.delay(0), Duration::from_secs(1));
+        assert_eq!(retry.delay(1), Duration::from_secs(1));
+        assert_eq!(retry.delay(2), Duration::from_secs(2));
+        assert_eq!(retry.delay(4), Duration::from_secs(8));
+        assert_eq!(retry.delay(5), Duration::from_secs(10));
+        assert_eq!(retry.delay(100), Duration::from_secs(10));
+
+        let retry = retry.with_jitter(true);
+        for failures in 1..10 {
+            let delay = retry.delay(failures);
+            let max = RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(10))
+                .delay(failures);
+            assert!(delay >= max / 2 && delay <= max);
+        }
+    }
+
+    #[test]
+    fn max_retries() {
+        let retry = RetryPolicy::default().max_retries(2);
+        assert!(!retry.exhausted(2));
+        assert!(retry.exhausted(3));
+        assert!(!RetryPolicy::default().exhausted(u32::MAX));
+    }
+
+    #[test]
+    fn give_up_after() {
+        let retry = RetryPolicy::default().give_up_after(Duration::from_secs(60));
+        assert!(!retry.expired(Duration::from_secs(59)));
+        assert!(retry.expired(Duration::from_secs(60)));
+        assert!(!RetryPolicy::default().expired(Duration::MAX));
+    }
+
+    #[tokio::test]
+    async fn retry_give_up() {
+        use std::time::Duration;
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let retry = RetryPolicy::fixed(Duration::from_millis(10))
+            .max_retries(2)
+            .on_give_up(move |err| {
+                let _ = tx.send(err.to_string());
+            });
+        // 没有服务监听的端口，连接必定失败
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6007)).retry(retry);
+        scanner.start().await.unwrap().unwrap();
+        let err = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap();
+        assert!(err.is_some());
+        // 每次连接失败发出错误事件，放弃时发出失败事件
+        let ev = loop {
+            match scanner.recv().await.unwrap() {
+                ScanEvent::Error(ScannerError::Comm(_)) => continue,
+                ev => break ev,
+            }
+        };
+        assert!(matches!(ev, ScanEvent::Failed(ScannerError::Comm(_))));
+        assert!(scanner.is_failed());
+
+        // 连续失败超过指定时长后放弃
+        let retry = RetryPolicy::fixed(Duration::from_millis(50)).give_up_after(Duration::ZERO);
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6007)).retry(retry);
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Error(_))));
+        let ev = tokio::time::timeout(Duration::from_secs(5), scanner.recv())
+            .await
+            .unwrap();
+        assert!(matches!(ev, Some(ScanEvent::Failed(_))));
+        assert!(scanner.is_failed());
+        scanner.start().await.unwrap().unwrap();
+        assert!(!scanner.is_failed());
+    }
+}
@@ -0,0 +1,449 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
+use tracing::{event, Instrument, Level};
+
+use crate::connector::command::{Command, Queued};
+use crate::frame::split::{complete, split, DELIMITERS, FRAME_GAP, MAX_FRAME};
+use crate::telemetry;
+use crate::{Online, RetryPolicy, ScanEvent, Scanner, ScannerError, Serial, TaskGuard};
+
+/// RS-485 多机总线
+///
+/// 多把扫码枪共用一个串口，每把扫码枪有自己的地址：
+/// * 接收：每帧数据(默认以 CR/LF 分隔，见[`Rs485Bus::delimiters`])以地址开头，去掉地址后交给对应的扫码枪处理
+/// * 发送：扫码枪的指令前自动加上地址
+///
+/// 每个地址对应一个独立的[`Scanner`]，过滤器、校验等配置各自生效。
+/// 串口的连接、断开和放弃重连事件发给总线上的每把扫码枪
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() {
+/// let serial = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::None);
+/// let line1 = Scanner::new(serial.clone());
+/// let line2 = Scanner::new(serial.clone()).trim_end([b'\t']);
+/// let bus = Rs485Bus::new(serial)
+///     .device("01", line1.clone())
+///     .device("02", line2.clone());
+/// bus.start().await.unwrap();
+///
+/// while let Some(ev) = line1.recv().await {
+///     println!("1号线: {:?}", ev);
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Rs485Bus {
+    serial: Serial,
+    devices: Vec<(Vec<u8>, Scanner)>,
+    delimiters: Vec<u8>,
+    retry: RetryPolicy,
+    task: Arc<std::sync::Mutex<Option<AbortHandle>>>,
+}
+
+impl Rs485Bus {
+    /// 创建 RS-485 总线
+    ///
+    /// * `serial` 总线使用的串口
+    pub fn new(serial: Serial) -> Self {
+        Rs485Bus {
+            serial,
+            devices: vec![],
+            delimiters: DELIMITERS.to_vec(),
+            retry: RetryPolicy::default(),
+            task: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    /// 设置总线的帧分隔符，默认为回车和换行
+    ///
+    /// 总线先拆帧再按地址分发，各扫码枪的[`Scanner::delimiters`]在总线上不生效
+    pub fn delimiters(mut self, delimiters: &[u8]) -> Self {
+        self.delimiters = delimiters.to_vec();
+        self
+    }
+
+    /// 串口断开或打开失败后的重连策略，默认固定间隔3秒一直重连，见[`RetryPolicy`]
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 添加一个总线上的扫码枪
+    ///
+    /// * `address` 扫码枪地址，即每帧数据的前缀
+    /// * `scanner` 接收该地址数据的扫码枪
+    pub fn device(mut self, address: impl Into<Vec<u8>>, scanner: Scanner) -> Self {
+        self.devices.push((address.into(), scanner));
+        // 地址长的优先匹配，避免 `1` 抢走 `12` 的数据
+        self.devices
+            .sort_by_key(|(address, _)| std::cmp::Reverse(address.len()));
+        self
+    }
+
+    /// 获取总线使用的串口
+    pub fn serial(&self) -> &Serial {
+        &self.serial
+    }
+
+    /// 启动总线，串口断开后按重连策略重新打开。已经启动且没有停止时不重复启动
+    pub async fn start(&self) -> Result<(), ScannerError> {
+        self.serial.check()?;
+        if self.devices.is_empty() {
+            return Err(ScannerError::Param(format!(
+                "RS-485总线上没有扫码枪,name={}",
+                self.serial.name()
+            )));
+        }
+        if self.is_running() {
+            event!(Level::WARN, scanner.addr = %self.serial.name(), "RS485总线已启动,忽略重复启动");
+            return Ok(());
+        }
+        let handle = tokio::spawn(self.clone().supervise());
+        if let Some(old) = self.task.lock().unwrap().replace(handle.abort_handle()) {
+            old.abort();
+        }
+        Ok(())
+    }
+
+    /// 停止总线，关闭串口并不再重连，之后可以重新调用[`Rs485Bus::start`]
+    pub fn stop(&self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+            event!(Level::INFO, scanner.addr = %self.serial.name(), "RS485总线已停止");
+        }
+    }
+
+    /// 总线是否已启动且没有停止(包括重连等待中)
+    pub fn is_running(&self) -> bool {
+        self.task
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// 打开串口，断开后按重连策略重新打开，超过限制后放弃
+    async fn supervise(self) {
+        let name = self.serial.name();
+        let mut failures = 0u32;
+        // 第一次失败的时间，打开成功后清除
+        let mut since = None;
+        for (_, scanner) in &self.devices {
+            scanner.failed.store(false, Ordering::Relaxed);
+        }
+        loop {
+            match self.run().instrument(telemetry::span::connection()).await {
+                Ok(()) => {
+                    failures = 0;
+                    since = None;
+                }
+                Err(err) => {
+                    self.broadcast(|_| ScanEvent::Error(err.clone()));
+                    failures += 1;
+                    let since = *since.get_or_insert_with(tokio::time::Instant::now);
+                    if self.retry.exhausted(failures) || self.retry.expired(since.elapsed()) {
+                        event!(
+                            Level::ERROR,
+                            scanner.addr = %name,
+                            failures,
+                            error = %err,
+                            error.kind = err.kind(),
+                            "连续失败,放弃重连",
+                        );
+                        self.retry.give_up(&err);
+                        self.broadcast(|scanner| {
+                            scanner.failed.store(true, Ordering::Relaxed);
+                            ScanEvent::Failed(err.clone())
+                        });
+                        break;
+                    }
+                }
+            }
+            self.serial.wait_present(self.retry.delay(failures)).await;
+            event!(Level::INFO, scanner.addr = %name, failures, "重新启动串口");
+        }
+    }
+
+    /// 把串口的连接事件发给总线上的每把扫码枪
+    fn broadcast(&self, ev: impl Fn(&Scanner) -> ScanEvent) {
+        let name = self.serial.name();
+        for (address, scanner) in &self.devices {
+            scanner.emit(&label(name, address), ev(scanner));
+        }
+    }
+
+    /// 打开串口并收发数据，直到串口断开。串口打开失败时返回错误
+    async fn run(&self) -> Result<(), ScannerError> {
+        let name = self.serial.name();
+        let com = match self.serial.open(None).await {
+            Ok(com) => com,
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %name,
+                    error = %err,
+                    error.kind = "io",
+                    params = ?self.serial,
+                    "串口连接错误",
+                );
+                return Err(ScannerError::Comm(err.to_string()));
+            }
+        };
+        event!(Level::INFO, scanner.addr = %name, "串口连接成功");
+        // 串口打开期间总线上的扫码枪都视为已连接
+        let online: Vec<_> = self
+            .devices
+            .iter()
+            .map(|(_, scanner)| Online::new(&scanner.online))
+            .collect();
+        self.broadcast(|_| ScanEvent::Connected);
+        let (mut rx, tx) = tokio::io::split(com);
+        let tx = Arc::new(Mutex::new(tx));
+        // ! 每个扫码枪一个发送线程，指令前加上地址，总线停止时随之结束
+        let mut write_handles = TaskGuard(vec![]);
+        for (address, scanner) in &self.devices {
+            let addr = label(name, address);
+            let address = address.clone();
+            let scanner = scanner.clone();
+            let tx = Arc::clone(&tx);
+            let handle = telemetry::span::spawn(async move {
+                let mut receiver = scanner.receiver.lock().await;
+                while let Some(queued) = receiver.recv().await {
+                    let Some(Queued { cmd, confirm, .. }) = scanner.fresh(&addr, queued) else {
+                        continue;
+                    };
+                    let r = match cmd {
+                        Command::Write(data) => {
+                            let mut buf = address.clone();
+                            buf.extend_from_slice(&data);
+                            let mut tx = tx.lock().await;
+                            match tx.write_all(&buf).await {
+                                Ok(()) => tx.flush().await.map_err(|err| ("刷新发送缓冲错误", err)),
+                                Err(err) => Err(("发送数据错误", err)),
+                            }
+                        }
+                        Command::Flush => tx
+                            .lock()
+                            .await
+                            .flush()
+                            .await
+                            .map_err(|err| ("刷新发送缓冲错误", err)),
+                        // 总线上的扫码枪共用串口，不能单独断开
+                        Command::Reconnect | Command::Shutdown => {
+                            event!(Level::WARN, scanner.addr = %addr, cmd = %cmd, "RS485总线不支持该指令,忽略");
+                            continue;
+                        }
+                    };
+                    if let Err((reason, err)) = r {
+                        confirm.done(Err(scanner.send_failed(&addr, reason, "io", err)));
+                        break;
+                    }
+                    confirm.done(Ok(()));
+                }
+            });
+            write_handles.0.push(handle.abort_handle());
+        }
+        // ! 读取串口数据，按地址分发
+        // 与单独连接的扫码枪相同，没有收完的帧留在缓冲中与后续数据拼接
+        let mut buf = BytesMut::with_capacity(1024);
+        let mut gap = None;
+        loop {
+            buf.reserve(1024);
+            let r = tokio::select! {
+                r = rx.read_buf(&mut buf) => r,
+                _ = tokio::time::sleep_until(gap.unwrap_or_else(tokio::time::Instant::now)), if gap.is_some() => {
+                    gap = None;
+                    self.receive(buf.split().freeze());
+                    continue;
+                }
+            };
+            match r {
+                Ok(0) => {
+                    event!(Level::ERROR, scanner.addr = %name, "接收数据为空,关闭连接");
+                    self.receive(buf.split().freeze());
+                    break;
+                }
+                Ok(_) => {
+                    let mut end = complete(&buf, &self.delimiters);
+                    if buf.len() - end >= MAX_FRAME {
+                        end = buf.len();
+                    }
+                    self.receive(buf.split_to(end).freeze());
+                    gap = (!buf.is_empty()).then(|| tokio::time::Instant::now() + FRAME_GAP);
+                }
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %name,
+                        error = %err,
+                        error.kind = "io",
+                        "接收数据错误",
+                    );
+                    break;
+                }
+            }
+        }
+        drop(write_handles);
+        drop(online);
+        self.broadcast(|_| ScanEvent::Disconnected);
+        Ok(())
+    }
+
+    /// 按总线的分隔符拆帧后逐帧分发
+    fn receive(&self, data: Bytes) {
+        for frame in split(&data, &self.delimiters) {
+            self.route(data.slice_ref(frame));
+        }
+    }
+
+    /// 根据地址把一帧数据交给对应的扫码枪
+    fn route(&self, frame: Bytes) {
+        let name = self.serial.name();
+        let device = self
+            .devices
+            .iter()
+            .find(|(address, _)| frame.starts_with(address));
+        match device {
+            Some((address, scanner)) => {
+                scanner.dispatch(&label(name, address), frame.slice(address.len()..));
+            }
+            None => {
+                event!(
+                    Level::WARN,
+                    scanner.addr = %name,
+                    data = ?format_args!("{:02X?}", &frame[..]),
+                    "未知地址的数据,已丢弃",
+                );
+            }
+        }
+    }
+}
+
+/// 日志中使用的扫码枪名称，例如`COM3#01`
+fn label(name: &str, address: &[u8]) -> String {
+    format!("{}#{}", name, String::from_utf8_lossy(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn route() {
+        let serial = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::None);
+        let s1 = Scanner::new(serial.clone());
+        let s12 = Scanner::new(serial.clone());
+        let bus = Rs485Bus::new(serial)
+            .device("1", s1.clone())
+            .device("12", s12.clone());
+        bus.route(Bytes::from_static(b"12SN0001"));
+        bus.route(Bytes::from_static(b"1SN0002"));
+        bus.route(Bytes::from_static(b"3SN0003"));
+        assert_eq!(s12.recv().await.unwrap().as_str_lossy().unwrap(), "SN0001");
+        assert_eq!(s1.recv().await.unwrap().as_str_lossy().unwrap(), "SN0002");
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn split_reads() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut device, path) = crate::tests::pty();
+        let serial = Serial::new(&path, 9600, 8, StopBits::One, Parity::None);
+        let s1 = Scanner::new(serial.clone());
+        let bus = Rs485Bus::new(serial)
+            .device("01", s1.clone())
+            .delimiters(b";");
+        bus.start().await.unwrap();
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Connected)));
+        // 一帧分两次读取时拼接后再分发
+        device.write_all(b"01SN").await.unwrap();
+        device.flush().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        device.write_all(b"0001;01SN0002;").await.unwrap();
+        assert_eq!(s1.recv().await.unwrap().as_str_lossy().unwrap(), "SN0001");
+        assert_eq!(s1.recv().await.unwrap().as_str_lossy().unwrap(), "SN0002");
+        bus.stop();
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn connection_events() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut device, path) = crate::tests::pty();
+        let serial = Serial::new(&path, 9600, 8, StopBits::One, Parity::None);
+        let s1 = Scanner::new(serial.clone());
+        let s2 = Scanner::new(serial.clone());
+        let bus = Rs485Bus::new(serial)
+            .device("01", s1.clone())
+            .device("02", s2.clone());
+        bus.start().await.unwrap();
+        assert!(bus.is_running());
+        // 串口的连接事件发给每把扫码枪
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Connected)));
+        assert!(matches!(s2.recv().await, Some(ScanEvent::Connected)));
+        assert!(s1.is_connected() && s2.is_connected());
+        device.write_all(b"02SN0001\r").await.unwrap();
+        assert_eq!(s2.recv().await.unwrap().as_str_lossy().unwrap(), "SN0001");
+        drop(device);
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Disconnected)));
+        assert!(matches!(s2.recv().await, Some(ScanEvent::Disconnected)));
+        assert!(!s1.is_connected());
+        bus.stop();
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn give_up() {
+        use std::time::Duration;
+
+        // 文件存在但不是串口，每次打开都失败
+        let path = std::env::temp_dir().join(format!("rs485-give-up-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+        let serial = Serial::new(path.to_str().unwrap(), 9600, 8, StopBits::One, Parity::None);
+        let s1 = Scanner::new(serial.clone());
+        let s2 = Scanner::new(serial.clone());
+        let bus = Rs485Bus::new(serial)
+            .device("01", s1.clone())
+            .device("02", s2.clone())
+            .retry(RetryPolicy::fixed(Duration::from_millis(10)).max_retries(1));
+        bus.start().await.unwrap();
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Error(_))));
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Error(_))));
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Failed(_))));
+        assert!(matches!(s2.recv().await, Some(ScanEvent::Error(_))));
+        assert!(matches!(s2.recv().await, Some(ScanEvent::Error(_))));
+        assert!(matches!(s2.recv().await, Some(ScanEvent::Failed(_))));
+        assert!(s1.is_failed() && s2.is_failed());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!bus.is_running());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn stop() {
+        let (_device, path) = crate::tests::pty();
+        let serial = Serial::new(&path, 9600, 8, StopBits::One, Parity::None);
+        let s1 = Scanner::new(serial.clone());
+        let bus = Rs485Bus::new(serial).device("01", s1.clone());
+        bus.start().await.unwrap();
+        assert!(matches!(s1.recv().await, Some(ScanEvent::Connected)));
+        bus.stop();
+        assert!(!bus.is_running());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        // 停止后串口关闭，不再视为已连接
+        assert!(!s1.is_connected());
+    }
+}
@@ -1,11 +1,29 @@
+use std::time::Duration;
+
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+use tracing::{event, Level};
+
+use crate::{AutoBaud, Connecting, PortFilter, PortInfo, ScannerError, Transport};
+
 /// 串口连接器
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Serial {
     name: String,
     baudrate: u32,
     databits: u8,
     stopbits: StopBits,
     parity: Parity,
+    #[cfg_attr(feature = "serde", serde(default = "flow_control"))]
+    flow_control: FlowControl,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    auto_baud: Option<AutoBaud>,
+    /// 是否独占串口
+    #[cfg_attr(feature = "serde", serde(default = "exclusive"))]
+    exclusive: bool,
+    /// 串口被占用时的重试次数及间隔
+    #[cfg_attr(feature = "serde", serde(skip, default = "open_retry"))]
+    open_retry: (u32, Duration),
 }
 
 impl Serial {
@@ -16,6 +34,17 @@ impl Serial {
     /// * `databits` 数据位
     /// * `stopbits` 停止位
     /// * `parity` 奇偶校验
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// // Windows
+    /// let conn = Serial::new("COM3", 9600, 8, StopBits::One, Parity::None);
+    /// // Linux / macOS
+    /// let conn = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::None);
+    /// let conn = Serial::new("/dev/tty.usbserial-1410", 9600, 8, StopBits::One, Parity::None);
+    /// ```
     pub fn new(
         name: &str,
         baudrate: u32,
@@ -29,14 +58,288 @@ impl Serial {
             databits,
             stopbits,
             parity,
+            flow_control: FlowControl::None,
+            auto_baud: None,
+            exclusive: true,
+            open_retry: open_retry(),
         }
     }
 
+    /// 设置流控制，默认不使用
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn = Serial::new("COM3", 115200, 8, StopBits::One, Parity::None)
+    ///     .with_flow_control(FlowControl::Hardware);
+    /// assert_eq!(conn.flow_control(), &FlowControl::Hardware);
+    /// ```
+    pub fn with_flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// 设置是否独占串口，默认独占
+    ///
+    /// 独占时其它程序(包括串口监视工具)无法再打开该串口；
+    /// 需要同时用监视工具抓取数据时设置为`false`。
+    /// Windows 的串口总是独占的，该设置只在 Linux / macOS 下生效
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::None)
+    ///     .with_exclusive(false)
+    ///     .with_open_retry(5, Duration::from_millis(500));
+    /// assert!(!conn.exclusive());
+    /// assert_eq!(conn.open_retry(), (5, Duration::from_millis(500)));
+    /// ```
+    pub fn with_exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
+
+    /// 是否独占串口
+    pub fn exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// 设置串口被其它程序占用时的重试次数及间隔，默认不重试，直接按重连策略处理
+    pub fn with_open_retry(mut self, attempts: u32, interval: Duration) -> Self {
+        self.open_retry = (attempts, interval);
+        self
+    }
+
+    /// 获取串口被占用时的重试次数及间隔
+    pub fn open_retry(&self) -> (u32, Duration) {
+        self.open_retry
+    }
+
+    /// 查找本机所有串口
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// for port in Serial::discover().unwrap() {
+    ///     println!("{} {:?} {:04X?}:{:04X?}", port.name(), port.port_type(), port.vid(), port.pid());
+    /// }
+    /// ```
+    pub fn discover() -> Result<Vec<PortInfo>, ScannerError> {
+        let ports =
+            tokio_serial::available_ports().map_err(|err| ScannerError::Comm(err.to_string()))?;
+        Ok(ports.into_iter().map(PortInfo::from).collect())
+    }
+
+    /// 查找第一个满足条件的串口
+    pub fn find(filter: &PortFilter) -> Result<Option<PortInfo>, ScannerError> {
+        let ports = Serial::discover()?;
+        Ok(ports.into_iter().find(|port| filter.matches(port)))
+    }
+
+    /// 启用自动波特率检测，`baudrate`作为第一个检测的波特率
+    pub fn with_auto_baud(mut self, auto_baud: AutoBaud) -> Self {
+        self.auto_baud = Some(auto_baud);
+        self
+    }
+
+    /// 获取自动波特率检测配置
+    pub fn auto_baud(&self) -> Option<&AutoBaud> {
+        self.auto_baud.as_ref()
+    }
+
     /// 获取串口名称
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// 检查串口名称是否符合当前平台的格式
+    ///
+    /// * Windows: `COMn`或`\\.\COMn`
+    /// * 其它平台: 设备文件的绝对路径，例如`/dev/ttyUSB0`
+    ///
+    /// 串口是否存在、能否打开以打开时操作系统返回的错误为准
+    pub(crate) fn check_name(&self) -> Result<(), ScannerError> {
+        let name = self.name.trim();
+        let valid = if cfg!(windows) {
+            let name = name.to_uppercase();
+            name.starts_with("COM") || name.starts_with(r"\\.\")
+        } else {
+            name.starts_with('/')
+        };
+        if !valid {
+            return Err(ScannerError::Param(format!(
+                "无效的串口名称,name={}",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// 获取打开串口时使用的设备路径
+    ///
+    /// Windows 下`COM10`及以上的串口必须使用`\\.\COM10`的写法才能打开，
+    /// 这里自动转换，调用方直接传`COM12`即可
+    pub(crate) fn path(&self) -> String {
+        let name = self.name.trim();
+        if cfg!(windows) {
+            com_path(name)
+        } else {
+            name.to_owned()
+        }
+    }
+
+    /// 检查串口名称及数据位、停止位、奇偶校验的组合是否受支持
+    pub(crate) fn check(&self) -> Result<(), ScannerError> {
+        self.check_name()?;
+        self.settings()?;
+        Ok(())
+    }
+
+    /// 转换为 tokio_serial 的数据位、停止位、奇偶校验
+    fn settings(
+        &self,
+    ) -> Result<
+        (
+            tokio_serial::DataBits,
+            tokio_serial::StopBits,
+            tokio_serial::Parity,
+        ),
+        ScannerError,
+    > {
+        let databits = match self.databits {
+            5 => tokio_serial::DataBits::Five,
+            6 => tokio_serial::DataBits::Six,
+            7 => tokio_serial::DataBits::Seven,
+            8 => tokio_serial::DataBits::Eight,
+            n => {
+                return Err(ScannerError::Param(format!(
+                    "不支持的数据位,databits={}",
+                    n
+                )))
+            }
+        };
+        let stopbits = match self.stopbits {
+            StopBits::One => tokio_serial::StopBits::One,
+            StopBits::Two => tokio_serial::StopBits::Two,
+            ref stopbits => {
+                return Err(ScannerError::Param(format!(
+                    "不支持的停止位,stopbits={:?}",
+                    stopbits
+                )))
+            }
+        };
+        let parity = match self.parity {
+            Parity::None => tokio_serial::Parity::None,
+            Parity::Odd => tokio_serial::Parity::Odd,
+            Parity::Even => tokio_serial::Parity::Even,
+            ref parity => {
+                return Err(ScannerError::Param(format!(
+                    "不支持的奇偶校验,parity={:?}",
+                    parity
+                )))
+            }
+        };
+        Ok((databits, stopbits, parity))
+    }
+
+    /// 打开串口，串口被占用时按设置重试，`timeout`为串口驱动的超时，`None`时使用驱动的默认值
+    pub(crate) async fn open(
+        &self,
+        timeout: Option<Duration>,
+    ) -> tokio_serial::Result<SerialStream> {
+        let (attempts, interval) = self.open_retry;
+        let mut attempt = 0;
+        loop {
+            match self.open_at(self.baudrate, timeout) {
+                Err(err) if self.is_busy(&err) && attempt < attempts => {
+                    attempt += 1;
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %self.name,
+                        attempts = attempt,
+                        "串口被占用,等待重试",
+                    );
+                    tokio::time::sleep(interval).await;
+                }
+                r => return r,
+            }
+        }
+    }
+
+    /// 以指定的波特率打开串口
+    pub(crate) fn open_at(
+        &self,
+        baudrate: u32,
+        timeout: Option<Duration>,
+    ) -> tokio_serial::Result<SerialStream> {
+        let (databits, stopbits, parity) = self.settings().map_err(|err| {
+            tokio_serial::Error::new(tokio_serial::ErrorKind::InvalidInput, err.to_string())
+        })?;
+        let builder = tokio_serial::new(self.path(), baudrate)
+            .data_bits(databits)
+            .stop_bits(stopbits)
+            .parity(parity)
+            .flow_control(self.flow_control.clone().into());
+        let builder = match timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        };
+        #[cfg(unix)]
+        let builder = builder.exclusive(self.exclusive);
+        builder.open_native_async().map_err(|err| {
+            if self.is_busy(&err) {
+                tokio_serial::Error::new(
+                    tokio_serial::ErrorKind::NoDevice,
+                    format!("串口被其它程序占用,name={}", self.name),
+                )
+            } else {
+                err
+            }
+        })
+    }
+
+    /// 打开失败是否因为串口被其它程序占用
+    ///
+    /// 设备存在但返回`NoDevice`时视为被占用(Linux 下为 EBUSY，Windows 下为拒绝访问)
+    fn is_busy(&self, err: &tokio_serial::Error) -> bool {
+        err.kind == tokio_serial::ErrorKind::NoDevice && self.is_present()
+    }
+
+    /// 串口设备是否存在，用于检测 USB 串口是否已插入
+    pub(crate) fn is_present(&self) -> bool {
+        let name = self.name.trim();
+        if cfg!(windows) {
+            let name = name.trim_start_matches(r"\\.\");
+            Serial::discover()
+                .map(|ports| ports.iter().any(|p| p.name().eq_ignore_ascii_case(name)))
+                .unwrap_or(true)
+        } else {
+            std::path::Path::new(name).exists()
+        }
+    }
+
+    /// 等待串口可以重新打开
+    ///
+    /// 设备存在时(打开失败或读取出错)等待`delay`；设备不存在时(USB 串口被拔出)每秒检测一次，插入后立即返回
+    pub(crate) async fn wait_present(&self, delay: Duration) {
+        if self.is_present() {
+            tokio::time::sleep(delay).await;
+            return;
+        }
+        event!(Level::WARN, scanner.addr = %self.name, "串口不存在,等待插入");
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if self.is_present() {
+                event!(Level::INFO, scanner.addr = %self.name, "检测到串口插入");
+                return;
+            }
+        }
+    }
+
     /// 获取波特率
     pub fn baudrate(&self) -> u32 {
         self.baudrate
@@ -56,10 +359,44 @@ impl Serial {
     pub fn parity(&self) -> &Parity {
         &self.parity
     }
+
+    /// 获取流控制
+    pub fn flow_control(&self) -> &FlowControl {
+        &self.flow_control
+    }
+}
+
+/// 默认不重试打开串口
+fn open_retry() -> (u32, Duration) {
+    (0, Duration::from_secs(1))
+}
+
+#[cfg(feature = "serde")]
+fn flow_control() -> FlowControl {
+    FlowControl::None
+}
+
+#[cfg(feature = "serde")]
+fn exclusive() -> bool {
+    true
+}
+
+/// 将 Windows 串口名称`COMn`(n >= 10)转换为`\\.\COMn`
+fn com_path(name: &str) -> String {
+    let upper = name.to_uppercase();
+    let num = upper
+        .strip_prefix("COM")
+        .and_then(|n| n.parse::<u32>().ok());
+    match num {
+        Some(n) if n >= 10 => format!(r"\\.\COM{}", n),
+        _ => name.to_owned(),
+    }
 }
 
 /// 奇偶校验
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Parity {
     /// 不发生奇偶校验检查。
     None = 0,
@@ -94,7 +431,9 @@ impl From<tokio_serial::Parity> for Parity {
 // }
 
 /// 停止位
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum StopBits {
     /// 不使用停止位。
     None = 0,
@@ -114,3 +453,161 @@ impl From<tokio_serial::StopBits> for StopBits {
         }
     }
 }
+
+/// 流控制
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FlowControl {
+    /// 不使用流控制。
+    None,
+    /// 软件流控制(XON/XOFF)。
+    Software,
+    /// 硬件流控制(RTS/CTS)。
+    Hardware,
+}
+
+impl From<tokio_serial::FlowControl> for FlowControl {
+    fn from(value: tokio_serial::FlowControl) -> Self {
+        match value {
+            tokio_serial::FlowControl::None => FlowControl::None,
+            tokio_serial::FlowControl::Software => FlowControl::Software,
+            tokio_serial::FlowControl::Hardware => FlowControl::Hardware,
+        }
+    }
+}
+
+impl From<FlowControl> for tokio_serial::FlowControl {
+    fn from(value: FlowControl) -> Self {
+        match value {
+            FlowControl::None => tokio_serial::FlowControl::None,
+            FlowControl::Software => tokio_serial::FlowControl::Software,
+            FlowControl::Hardware => tokio_serial::FlowControl::Hardware,
+        }
+    }
+}
+
+/// 直接打开串口，不进行自动波特率检测
+impl Transport for Serial {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn connect(&self) -> Connecting<'_> {
+        self.connect_with_timeout(None)
+    }
+
+    /// 串口驱动的超时对异步读取不起作用，读取超时由扫码枪计时处理
+    fn connect_with_timeout(&self, timeout: Option<Duration>) -> Connecting<'_> {
+        Box::pin(async move {
+            self.check()?;
+            let com = self
+                .open(timeout)
+                .await
+                .map_err(|err| ScannerError::Comm(err.to_string()))?;
+            event!(Level::INFO, scanner.addr = %self.name, "串口连接成功");
+            Ok(Box::new(com) as Box<dyn crate::TransportStream>)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::com_path;
+    use crate::prelude::*;
+
+    #[test]
+    fn windows_com_path() {
+        assert_eq!(com_path("COM3"), "COM3");
+        assert_eq!(com_path("COM9"), "COM9");
+        assert_eq!(com_path("COM10"), r"\\.\COM10");
+        assert_eq!(com_path("com12"), r"\\.\COM12");
+        assert_eq!(com_path(r"\\.\COM12"), r"\\.\COM12");
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn serial_session() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (mut device, path) = crate::tests::pty();
+        let scanner = Scanner::new(Serial::new(&path, 9600, 8, StopBits::One, Parity::None));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        device.write_all(b"SN0001\r\n").await.unwrap();
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        // 与网络连接相同，指令由发送线程写入串口
+        scanner.send_message("BEEP".into()).await.unwrap().unwrap();
+        let mut buf = [0u8; 4];
+        device.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"BEEP");
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn serial_send_confirmed() {
+        use tokio::io::AsyncReadExt;
+
+        let (mut device, path) = crate::tests::pty();
+        let scanner = Scanner::new(Serial::new(&path, 9600, 8, StopBits::One, Parity::None))
+            .queue_policy(QueuePolicy::FailFast);
+        // 串口打开前按策略立即返回错误
+        let r = scanner.send_confirmed("OK\r".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        // 写入串口后才返回
+        scanner
+            .send_confirmed("OK\r".into())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut buf = [0u8; 3];
+        device.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"OK\r");
+        // 关闭指令在之前的指令写入后停止扫码枪
+        scanner.send_message("NG\r".into()).await.unwrap().unwrap();
+        scanner.shutdown().await.unwrap().unwrap();
+        device.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"NG\r");
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!scanner.is_running());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn serial_name() {
+        let conn = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::None);
+        assert!(conn.check_name().is_ok());
+        let conn = Serial::new("ttyUSB0", 9600, 8, StopBits::One, Parity::None);
+        assert!(conn.check_name().is_err());
+        // 热插拔检测
+        let conn = Serial::new("/dev/null", 9600, 8, StopBits::One, Parity::None);
+        assert!(conn.is_present());
+        let conn = Serial::new("/dev/ttyNOTEXIST", 9600, 8, StopBits::One, Parity::None);
+        assert!(!conn.is_present());
+    }
+
+    #[tokio::test]
+    async fn serial_settings() {
+        let conn = Serial::new("/dev/ttyUSB0", 9600, 7, StopBits::Two, Parity::Even);
+        assert!(conn.check().is_ok());
+        let conn = Serial::new(
+            "/dev/ttyUSB0",
+            9600,
+            8,
+            StopBits::OnePointFive,
+            Parity::None,
+        );
+        let err = Scanner::new(conn).start().await.unwrap_err();
+        assert!(matches!(err, ScannerError::Param(_)));
+        let conn = Serial::new("/dev/ttyUSB0", 9600, 9, StopBits::One, Parity::None);
+        assert!(conn.check().is_err());
+        let conn = Serial::new("/dev/ttyUSB0", 9600, 8, StopBits::One, Parity::Mark);
+        assert!(conn.check().is_err());
+    }
+}
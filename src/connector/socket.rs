@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use socket2::{SockRef, TcpKeepalive};
+use tokio::net::TcpStream;
+
+/// TCP 连接参数
+///
+/// 嵌入式扫码枪断电或网线被拔出时不会发送 FIN，
+/// 开启 keepalive 后可以较快发现半开连接并重新连接
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// let conn = Network::new_client("192.168.1.10", 9004).with_socket_options(
+///     SocketOptions::new()
+///         .with_keepalive(Duration::from_secs(10), Duration::from_secs(3))
+///         .with_nodelay(true)
+///         .with_linger(Duration::from_secs(0)),
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SocketOptions {
+    keepalive: Option<(Duration, Duration)>,
+    nodelay: Option<bool>,
+    linger: Option<Duration>,
+}
+
+impl SocketOptions {
+    /// 创建 TCP 连接参数，未设置的参数保持系统默认值
+    pub fn new() -> Self {
+        SocketOptions::default()
+    }
+
+    /// 开启 SO_KEEPALIVE
+    ///
+    /// * `time` 连接空闲多久后开始发送探测包
+    /// * `interval` 探测包发送间隔
+    pub fn with_keepalive(mut self, time: Duration, interval: Duration) -> Self {
+        self.keepalive = Some((time, interval));
+        self
+    }
+
+    /// 设置 TCP_NODELAY，开启后指令立即发送，不等待合并
+    pub fn with_nodelay(mut self, nodelay: bool) -> Self {
+        self.nodelay = Some(nodelay);
+        self
+    }
+
+    /// 设置 SO_LINGER，关闭连接时等待未发送数据的时长，0 表示直接复位连接
+    pub fn with_linger(mut self, linger: Duration) -> Self {
+        self.linger = Some(linger);
+        self
+    }
+
+    /// 获取 keepalive 参数(空闲时长，探测间隔)
+    pub fn keepalive(&self) -> Option<(Duration, Duration)> {
+        self.keepalive
+    }
+
+    /// 获取 TCP_NODELAY
+    pub fn nodelay(&self) -> Option<bool> {
+        self.nodelay
+    }
+
+    /// 获取 SO_LINGER
+    pub fn linger(&self) -> Option<Duration> {
+        self.linger
+    }
+
+    /// 应用到已建立的连接
+    pub(crate) fn apply(&self, stream: &TcpStream) -> std::io::Result<()> {
+        let socket = SockRef::from(stream);
+        if let Some((time, _interval)) = self.keepalive {
+            let keepalive = TcpKeepalive::new().with_time(time);
+            #[cfg(any(target_os = "linux", target_os = "windows", target_os = "macos"))]
+            let keepalive = keepalive.with_interval(_interval);
+            socket.set_tcp_keepalive(&keepalive)?;
+        }
+        if let Some(nodelay) = self.nodelay {
+            socket.set_nodelay(nodelay)?;
+        }
+        if let Some(linger) = self.linger {
+            socket.set_linger(Some(linger))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn socket_options() {
+        use std::time::Duration;
+
+        let options = SocketOptions::new()
+            .with_keepalive(Duration::from_secs(10), Duration::from_secs(3))
+            .with_nodelay(true)
+            .with_linger(Duration::from_secs(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        options.apply(&client).unwrap();
+        assert!(client.nodelay().unwrap());
+        let socket = socket2::SockRef::from(&client);
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(0)));
+    }
+}
@@ -0,0 +1,48 @@
+use std::future::pending;
+use std::time::Duration;
+
+use tokio::time::{sleep_until, Instant};
+
+/// 读取超时的处理方式，见[`crate::Scanner::timeout`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimeoutAction {
+    /// 发出[`crate::ScannerError::Timeout`]错误事件，继续等待数据
+    #[default]
+    Event,
+    /// 断开连接，按重连策略重新连接
+    Reconnect,
+}
+
+/// 读取超时计时，收到数据后重新计时，每段没有数据的时间只超时一次
+pub(crate) struct ReadTimer {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl ReadTimer {
+    /// 创建计时，`timeout`为`None`时不超时
+    pub(crate) fn new(timeout: Option<Duration>) -> Self {
+        ReadTimer {
+            timeout,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    /// 收到数据，重新计时
+    pub(crate) fn reset(&mut self) {
+        self.deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+    }
+
+    /// 等待超时，没有设置超时或已经超时过一次时一直等待
+    pub(crate) async fn elapsed(&mut self) {
+        match self.deadline {
+            Some(deadline) => {
+                sleep_until(deadline).await;
+                self.deadline = None;
+            }
+            None => pending().await,
+        }
+    }
+}
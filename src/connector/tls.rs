@@ -0,0 +1,162 @@
+use std::{path::PathBuf, sync::Arc};
+
+use tokio_rustls::rustls::{
+    self,
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, ServerName},
+    server::WebPkiClientVerifier,
+    ClientConfig, RootCertStore, ServerConfig,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+use crate::ScannerError;
+
+/// TLS 加密参数(需要启用`tls`特性)
+///
+/// 证书和私钥均为 PEM 格式文件
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tls {
+    /// 本端证书链
+    cert: Option<PathBuf>,
+    /// 本端私钥
+    key: Option<PathBuf>,
+    /// 信任的 CA 证书
+    ca: Option<PathBuf>,
+    /// 客户端模式下校验的服务器名称，默认使用连接的 IP 地址
+    server_name: Option<String>,
+}
+
+impl Tls {
+    /// 创建客户端模式的 TLS 参数
+    ///
+    /// * `ca` 用于校验扫码枪证书的 CA 证书
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn = Network::new_client("192.168.1.10", 9004)
+    ///     .with_tls(Tls::client("certs/ca.pem").server_name("scanner-01.local"));
+    /// assert!(conn.tls().is_some());
+    /// ```
+    pub fn client(ca: impl Into<PathBuf>) -> Self {
+        Tls {
+            cert: None,
+            key: None,
+            ca: Some(ca.into()),
+            server_name: None,
+        }
+    }
+
+    /// 创建服务器模式的 TLS 参数
+    ///
+    /// * `cert` 服务器证书链
+    /// * `key` 服务器私钥
+    pub fn server(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        Tls {
+            cert: Some(cert.into()),
+            key: Some(key.into()),
+            ca: None,
+            server_name: None,
+        }
+    }
+
+    /// 设置信任的 CA 证书，服务器模式下设置后要求扫码枪提供客户端证书(双向认证)
+    pub fn ca(mut self, ca: impl Into<PathBuf>) -> Self {
+        self.ca = Some(ca.into());
+        self
+    }
+
+    /// 设置客户端证书和私钥(双向认证)
+    pub fn identity(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.cert = Some(cert.into());
+        self.key = Some(key.into());
+        self
+    }
+
+    /// 设置客户端模式下校验的服务器名称
+    pub fn server_name(mut self, name: &str) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// 获取校验的服务器名称
+    pub(crate) fn name(&self, ip: &str) -> Result<ServerName<'static>, ScannerError> {
+        let name = self.server_name.as_deref().unwrap_or(ip);
+        ServerName::try_from(name.to_owned())
+            .map_err(|e| ScannerError::Param(format!("无效的TLS服务器名称,name={},{}", name, e)))
+    }
+
+    /// 创建客户端连接器
+    pub(crate) fn connector(&self) -> Result<TlsConnector, ScannerError> {
+        let ca = self
+            .ca
+            .as_ref()
+            .ok_or_else(|| ScannerError::Param("TLS客户端缺少CA证书".into()))?;
+        let builder = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?
+            .with_root_certificates(load_roots(ca)?);
+        let config = match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+                .map_err(tls_error)?,
+            _ => builder.with_no_client_auth(),
+        };
+        Ok(TlsConnector::from(Arc::new(config)))
+    }
+
+    /// 创建服务器接收器
+    pub(crate) fn acceptor(&self) -> Result<TlsAcceptor, ScannerError> {
+        let (Some(cert), Some(key)) = (&self.cert, &self.key) else {
+            return Err(ScannerError::Param("TLS服务器缺少证书或私钥".into()));
+        };
+        let builder = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?;
+        let builder = match &self.ca {
+            Some(ca) => {
+                let verifier = WebPkiClientVerifier::builder_with_provider(
+                    Arc::new(load_roots(ca)?),
+                    provider(),
+                )
+                .build()
+                .map_err(|e| ScannerError::Param(format!("TLS参数错误,{}", e)))?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let config = builder
+            .with_single_cert(load_certs(cert)?, load_key(key)?)
+            .map_err(tls_error)?;
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn tls_error(err: rustls::Error) -> ScannerError {
+    ScannerError::Param(format!("TLS参数错误,{}", err))
+}
+
+fn load_certs(path: &PathBuf) -> Result<Vec<CertificateDer<'static>>, ScannerError> {
+    CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| ScannerError::Param(format!("读取证书失败,path={:?},{}", path, e)))
+}
+
+fn load_key(path: &PathBuf) -> Result<PrivateKeyDer<'static>, ScannerError> {
+    PrivateKeyDer::from_pem_file(path)
+        .map_err(|e| ScannerError::Param(format!("读取私钥失败,path={:?},{}", path, e)))
+}
+
+fn load_roots(path: &PathBuf) -> Result<RootCertStore, ScannerError> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert).map_err(tls_error)?;
+    }
+    Ok(roots)
+}
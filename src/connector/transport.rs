@@ -0,0 +1,117 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::ScannerError;
+
+/// 传输层建立的双向字节流
+pub trait TransportStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> TransportStream for T {}
+
+/// 传输层连接结果
+pub type Connecting<'a> =
+    Pin<Box<dyn Future<Output = Result<Box<dyn TransportStream>, ScannerError>> + Send + 'a>>;
+
+/// 自定义传输层
+///
+/// 实现该接口即可接入 CAN 网关、厂商 SDK 等特殊的连接方式，
+/// 重连策略、空闲检测、帧处理和事件等逻辑仍由[`crate::Scanner`]负责：
+/// * `connect` 建立连接并返回双向字节流，读到的数据作为条码处理，
+///   [`crate::Scanner::send_message`]发送的指令写入该字节流
+/// * 读取结束(返回 0 或出错)视为连接断开，按重连策略重新调用`connect`
+/// * `connect`返回[`ScannerError::Param`]视为参数错误，不再重连，其它错误按重连策略重试
+///
+/// 内置的串口和网络客户端连接器也实现了该接口，扫码枪通过同一流程连接和收发数据
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// /// 通过网关转发的扫码枪
+/// #[derive(Debug)]
+/// struct Gateway {
+///     addr: String,
+/// }
+///
+/// impl Transport for Gateway {
+///     fn name(&self) -> String {
+///         format!("GW:{}", self.addr)
+///     }
+///
+///     fn connect(&self) -> Connecting<'_> {
+///         Box::pin(async move {
+///             let stream = tokio::net::TcpStream::connect(&self.addr)
+///                 .await
+///                 .map_err(ScannerError::Io)?;
+///             Ok(Box::new(stream) as Box<dyn TransportStream>)
+///         })
+///     }
+/// }
+///
+/// let conn = Connector::transport(Gateway { addr: "10.0.0.5:7000".into() });
+/// assert_eq!(conn.to_string(), "GW:10.0.0.5:7000");
+/// let scanner = Scanner::new(conn);
+/// ```
+pub trait Transport: Debug + Send + Sync {
+    /// 日志中显示的名称
+    fn name(&self) -> String;
+
+    /// 建立连接
+    fn connect(&self) -> Connecting<'_>;
+
+    /// 按扫码枪的读取超时([`crate::Scanner::timeout`])建立连接，扫码枪连接时调用该方法
+    ///
+    /// 默认忽略超时直接调用[`Transport::connect`]，打开时需要设置超时的传输层(例如串口)重写该方法
+    fn connect_with_timeout(&self, timeout: Option<Duration>) -> Connecting<'_> {
+        let _ = timeout;
+        self.connect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn custom_transport() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+
+        #[derive(Debug)]
+        struct Pipe(std::sync::Mutex<Option<DuplexStream>>);
+
+        impl Transport for Pipe {
+            fn name(&self) -> String {
+                "PIPE".into()
+            }
+
+            fn connect(&self) -> Connecting<'_> {
+                Box::pin(async move {
+                    match self.0.lock().unwrap().take() {
+                        Some(stream) => Ok(Box::new(stream) as Box<dyn TransportStream>),
+                        None => Err(ScannerError::Comm("已断开".into())),
+                    }
+                })
+            }
+        }
+
+        let (device, stream) = tokio::io::duplex(64);
+        let (mut rx, mut tx) = tokio::io::split(device);
+        let conn = Connector::transport(Pipe(std::sync::Mutex::new(Some(stream))));
+        let scanner = Scanner::new(conn);
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        tx.write_all(b"SN0001\r\n").await.unwrap();
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        scanner.send_message("BEEP".into()).await.unwrap().unwrap();
+        let mut buf = [0u8; 4];
+        rx.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"BEEP");
+    }
+}
@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+#[cfg(feature = "bluetooth")]
+use crate::Bluetooth;
+#[cfg(feature = "websocket")]
+use crate::WebSocket;
+use crate::{Connector, FlowControl, Network, Parity, ScannerError, Serial, StopBits};
+
+/// 从 URL 字符串创建连接器，便于把连接配置写在配置文件或环境变量中
+///
+/// * `tcp://host:port` 网络客户端模式
+/// * `tcp-server://ip:port` 网络服务器模式
+/// * `serial://COM3?baud=115200&databits=8&stopbits=1&parity=even&flow=none` 串口，
+///   Linux 下写作`serial:///dev/ttyUSB0?baud=9600`，未指定的参数默认为 9600、8、1、none、none
+/// * `ws://...`、`wss://...` WebSocket 客户端，`ws-server://ip:port` WebSocket 服务器(需要启用`websocket`特性)
+/// * `bt://AA:BB:CC:DD:EE:FF?channel=1` 蓝牙(需要启用`bluetooth`特性)
+///
+/// # Examples
+/// ```
+/// use std::str::FromStr;
+/// use kim_scanner::prelude::*;
+///
+/// let conn = Connector::from_str("tcp://192.168.1.10:9004").unwrap();
+/// assert_eq!(conn.to_string(), "192.168.1.10:9004");
+///
+/// let conn: Connector = "tcp-server://[::]:6000".parse().unwrap();
+/// assert!(matches!(conn, Connector::Network(ref nw) if nw.is_server()));
+///
+/// let conn: Connector = "serial://COM3?baud=115200&parity=even".parse().unwrap();
+/// if let Connector::Serial(serial) = conn {
+///     assert_eq!(serial.name(), "COM3");
+///     assert_eq!(serial.baudrate(), 115200);
+///     assert_eq!(serial.parity(), &Parity::Even);
+/// }
+///
+/// assert!("ftp://192.168.1.10".parse::<Connector>().is_err());
+/// ```
+impl FromStr for Connector {
+    type Err = ScannerError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let url = url.trim();
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| invalid(url, "缺少协议"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let params = query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| param.split_once('=').unwrap_or((param, "")))
+            .collect::<Vec<_>>();
+        match scheme.to_lowercase().as_str() {
+            "tcp" | "tcp-server" => {
+                no_params(url, &params)?;
+                let (ip, port) = host_port(url, path)?;
+                if scheme.eq_ignore_ascii_case("tcp") {
+                    Ok(Network::new_client(ip, port).into())
+                } else {
+                    Ok(Network::new_server(ip, port).into())
+                }
+            }
+            "serial" => serial(url, path, &params).map(Connector::from),
+            #[cfg(feature = "websocket")]
+            "ws" | "wss" => Ok(WebSocket::new_client(url).into()),
+            #[cfg(feature = "websocket")]
+            "ws-server" => {
+                no_params(url, &params)?;
+                let (ip, port) = host_port(url, path)?;
+                Ok(WebSocket::new_server(ip, port).into())
+            }
+            #[cfg(feature = "bluetooth")]
+            "bt" => {
+                let mut channel = 1;
+                for (key, value) in &params {
+                    match *key {
+                        "channel" => channel = number(url, key, value)?,
+                        _ => return Err(invalid(url, &format!("不支持的参数{}", key))),
+                    }
+                }
+                Ok(Bluetooth::new(path, channel).into())
+            }
+            _ => Err(invalid(url, &format!("不支持的协议{}", scheme))),
+        }
+    }
+}
+
+/// 解析串口参数
+fn serial(url: &str, name: &str, params: &[(&str, &str)]) -> Result<Serial, ScannerError> {
+    if name.is_empty() {
+        return Err(invalid(url, "缺少串口名称"));
+    }
+    let mut baudrate = 9600;
+    let mut databits = 8;
+    let mut stopbits = StopBits::One;
+    let mut parity = Parity::None;
+    let mut flow_control = FlowControl::None;
+    for (key, value) in params {
+        match *key {
+            "baud" | "baudrate" => baudrate = number(url, key, value)?,
+            "databits" => databits = number(url, key, value)?,
+            "stopbits" => {
+                stopbits = match *value {
+                    "1" => StopBits::One,
+                    "2" => StopBits::Two,
+                    "1.5" => StopBits::OnePointFive,
+                    _ => return Err(invalid(url, &format!("无效的停止位{}", value))),
+                }
+            }
+            "parity" => {
+                parity = match value.to_lowercase().as_str() {
+                    "none" | "n" => Parity::None,
+                    "odd" | "o" => Parity::Odd,
+                    "even" | "e" => Parity::Even,
+                    "mark" | "m" => Parity::Mark,
+                    "space" | "s" => Parity::Space,
+                    _ => return Err(invalid(url, &format!("无效的奇偶校验{}", value))),
+                }
+            }
+            "flow" => {
+                flow_control = match value.to_lowercase().as_str() {
+                    "none" => FlowControl::None,
+                    "software" | "xonxoff" => FlowControl::Software,
+                    "hardware" | "rtscts" => FlowControl::Hardware,
+                    _ => return Err(invalid(url, &format!("无效的流控制{}", value))),
+                }
+            }
+            _ => return Err(invalid(url, &format!("不支持的参数{}", key))),
+        }
+    }
+    Ok(Serial::new(name, baudrate, databits, stopbits, parity).with_flow_control(flow_control))
+}
+
+/// 拆分`host:port`，IPv6 地址需要带方括号
+fn host_port<'a>(url: &str, addr: &'a str) -> Result<(&'a str, u16), ScannerError> {
+    let addr = addr.trim_end_matches('/');
+    let (host, port) = addr
+        .rsplit_once(':')
+        .ok_or_else(|| invalid(url, "缺少端口"))?;
+    if host.is_empty() {
+        return Err(invalid(url, "缺少地址"));
+    }
+    Ok((host, number(url, "port", port)?))
+}
+
+/// 解析数字参数
+fn number<T: FromStr>(url: &str, key: &str, value: &str) -> Result<T, ScannerError> {
+    value
+        .parse()
+        .map_err(|_| invalid(url, &format!("无效的{}={}", key, value)))
+}
+
+/// 网络连接器不支持参数
+fn no_params(url: &str, params: &[(&str, &str)]) -> Result<(), ScannerError> {
+    match params.first() {
+        Some((key, _)) => Err(invalid(url, &format!("不支持的参数{}", key))),
+        None => Ok(()),
+    }
+}
+
+fn invalid(url: &str, reason: &str) -> ScannerError {
+    ScannerError::Param(format!("无效的连接地址,{},url={}", reason, url))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn from_str() {
+        let conn: Connector = "serial:///dev/ttyUSB0?baud=19200&stopbits=2&flow=hardware"
+            .parse()
+            .unwrap();
+        let Connector::Serial(serial) = conn else {
+            panic!("connector is not serial");
+        };
+        assert_eq!(serial.name(), "/dev/ttyUSB0");
+        assert_eq!(serial.baudrate(), 19200);
+        assert_eq!(serial.stopbits(), &StopBits::Two);
+        assert_eq!(serial.flow_control(), &FlowControl::Hardware);
+
+        let conn: Connector = "TCP://scanner-01.local:9004".parse().unwrap();
+        assert_eq!(conn.to_string(), "scanner-01.local:9004");
+        assert!("tcp://192.168.1.10".parse::<Connector>().is_err());
+        assert!("tcp://192.168.1.10:99999".parse::<Connector>().is_err());
+        assert!("tcp://192.168.1.10:9004?x=1".parse::<Connector>().is_err());
+        assert!("serial://COM3?baud=fast".parse::<Connector>().is_err());
+        assert!("serial://?baud=9600".parse::<Connector>().is_err());
+        assert!("192.168.1.10:9004".parse::<Connector>().is_err());
+    }
+}
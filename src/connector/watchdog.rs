@@ -0,0 +1,149 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tracing::{event, Level};
+
+/// 空闲检测
+///
+/// 扫码枪断电、死机时连接可能不会断开，和没有扫码时一样收不到数据。
+/// 超过`idle`没有收到数据时发送心跳指令，再等待`grace`仍然没有任何数据则断开并重新连接。
+/// 未设置心跳指令时，空闲超时后直接重新连接
+///
+/// 适用于串口、网络、蓝牙和 WebSocket 连接
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// // 30 秒没有数据时发送 Honeywell 查询指令，5 秒内没有应答则重连
+/// let watchdog = Watchdog::new(Duration::from_secs(30))
+///     .with_heartbeat("\x16M\rREVINF.")
+///     .with_grace(Duration::from_secs(5));
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).watchdog(watchdog);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Watchdog {
+    idle: Duration,
+    heartbeat: Option<String>,
+    grace: Duration,
+}
+
+/// 带空闲检测的读取结果
+pub(crate) enum Idle<T> {
+    /// 读取完成
+    Ready(T),
+    /// 空闲超时，需要发送心跳指令
+    Heartbeat(String),
+    /// 扫码枪无响应，需要重新连接
+    Dead,
+}
+
+impl Watchdog {
+    /// 创建空闲检测
+    ///
+    /// * `idle` 多久没有收到数据视为空闲
+    pub fn new(idle: Duration) -> Self {
+        Watchdog {
+            idle,
+            heartbeat: None,
+            grace: Duration::from_secs(5),
+        }
+    }
+
+    /// 设置空闲时发送的心跳指令，扫码枪收到后应有应答
+    pub fn with_heartbeat(mut self, cmd: &str) -> Self {
+        self.heartbeat = Some(cmd.into());
+        self
+    }
+
+    /// 设置发送心跳后等待应答的时长，默认5秒
+    pub fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// 获取空闲时长
+    pub fn idle(&self) -> Duration {
+        self.idle
+    }
+
+    /// 获取心跳指令
+    pub fn heartbeat(&self) -> Option<&str> {
+        self.heartbeat.as_deref()
+    }
+
+    /// 获取等待应答的时长
+    pub fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// 等待读取完成，超时后根据是否已发送心跳决定下一步
+    ///
+    /// * `pinged` 是否已发送心跳，收到数据后由调用方清除
+    pub(crate) async fn watch<T>(
+        watchdog: Option<&Watchdog>,
+        addr: &str,
+        pinged: &mut bool,
+        read: impl Future<Output = T>,
+    ) -> Idle<T> {
+        let Some(watchdog) = watchdog else {
+            return Idle::Ready(read.await);
+        };
+        let timeout = if *pinged {
+            watchdog.grace
+        } else {
+            watchdog.idle
+        };
+        if let Ok(r) = tokio::time::timeout(timeout, read).await {
+            return Idle::Ready(r);
+        }
+        match (&watchdog.heartbeat, *pinged) {
+            (Some(cmd), false) => {
+                event!(Level::DEBUG, scanner.addr = %addr, "空闲超时,发送心跳指令");
+                *pinged = true;
+                Idle::Heartbeat(cmd.clone())
+            }
+            _ => {
+                event!(Level::WARN, scanner.addr = %addr, "长时间没有收到数据,重新连接");
+                Idle::Dead
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn watchdog_heartbeat() {
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let watchdog = Watchdog::new(Duration::from_millis(200))
+            .with_heartbeat("PING")
+            .with_grace(Duration::from_millis(200));
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6008)).watchdog(watchdog);
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let mut client = tokio::net::TcpStream::connect("127.0.0.1:6008")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 8];
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"PING");
+        // 应答后连接保持
+        client.write_all(b"PONG\r\n").await.unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "PONG"
+        );
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"PING");
+        // 不应答则断开
+        assert_eq!(client.read(&mut buf).await.unwrap_or(0), 0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+use crate::connector::network::host_port;
+
+/// WebSocket 连接器
+///
+/// 用于手机扫码 App、物联网网关等通过 WebSocket 发送条码的设备，
+/// 每条文本或二进制消息作为一帧数据处理，[`crate::Scanner::send_message`]发送的指令以文本消息发出
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WebSocket {
+    /// 服务器模式为监听地址`ip:port`，客户端模式为`ws://`地址
+    addr: String,
+    /// 是否为服务器模式
+    #[cfg_attr(feature = "serde", serde(default))]
+    is_server: bool,
+}
+
+impl WebSocket {
+    /// 创建一个 WebSocket 服务器连接器，等待扫码设备连接
+    ///
+    /// * `ip` 监听的 ip 地址，IPv6 地址可以带或不带方括号
+    /// * `port` 端口
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn: Connector = WebSocket::new_server("0.0.0.0", 9001).into();
+    /// assert_eq!(conn.to_string(), "ws://0.0.0.0:9001");
+    /// assert_eq!(WebSocket::new_server("::", 9001).addr(), "[::]:9001");
+    /// assert_eq!(WebSocket::new_server("[::1]", 9001).addr(), "[::1]:9001");
+    /// ```
+    pub fn new_server(ip: &str, port: u16) -> Self {
+        WebSocket {
+            addr: host_port(ip, port),
+            is_server: true,
+        }
+    }
+
+    /// 创建一个 WebSocket 客户端连接器，连接扫码设备或网关
+    ///
+    /// * `url` WebSocket 地址，例如`ws://192.168.1.20:8080/scan`
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let conn: Connector = WebSocket::new_client("ws://192.168.1.20:8080/scan").into();
+    /// assert_eq!(conn.to_string(), "ws://192.168.1.20:8080/scan");
+    /// ```
+    pub fn new_client(url: &str) -> Self {
+        WebSocket {
+            addr: url.into(),
+            is_server: false,
+        }
+    }
+
+    /// 是否为服务器模式
+    pub fn is_server(&self) -> bool {
+        self.is_server
+    }
+
+    /// 获取地址，服务器模式为监听地址`ip:port`，客户端模式为`ws://`地址
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+}
+
+impl Display for WebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_server {
+            write!(f, "ws://{}", self.addr)
+        } else {
+            write!(f, "{}", self.addr)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    #[cfg(feature = "websocket")]
+    async fn websocket_server_session() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let scanner = Scanner::new(WebSocket::new_server("127.0.0.1", 6003));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let (mut client, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:6003")
+            .await
+            .unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        client.send(Message::text("SN0001")).await.unwrap();
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+        // 每条消息作为一帧，二进制数据中的回车换行不拆分
+        client
+            .send(Message::binary(&b"\x02A\rB\n\x03"[..]))
+            .await
+            .unwrap();
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.payload().unwrap().as_ref(), b"\x02A\rB\n\x03");
+        scanner.send_message("BEEP".into()).await.unwrap().unwrap();
+        let msg = client.next().await.unwrap().unwrap();
+        assert_eq!(msg, Message::text("BEEP"));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "websocket")]
+    async fn websocket_server_silent_client() {
+        use futures_util::SinkExt;
+        use std::time::Duration;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let scanner = Scanner::new(WebSocket::new_server("127.0.0.1", 6013))
+            .timeout(Duration::from_millis(200));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // 不发送握手的客户端超时后被放弃，之后的扫码枪可以正常连接
+        let _silent = tokio::net::TcpStream::connect("127.0.0.1:6013")
+            .await
+            .unwrap();
+        let (mut client, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:6013")
+            .await
+            .unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        client.send(Message::text("SN0001")).await.unwrap();
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+    }
+}
@@ -0,0 +1,97 @@
+use std::net::IpAddr;
+
+use crate::Network;
+
+/// 发现方式
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DiscoverySource {
+    /// mDNS/Bonjour，值为服务类型，例如`_scanner._tcp.local.`
+    Mdns(String),
+    /// UDP 广播
+    Udp,
+}
+
+/// 发现的网络扫码枪
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Discovered {
+    name: String,
+    model: Option<String>,
+    serial_number: Option<String>,
+    ip: IpAddr,
+    port: u16,
+    source: DiscoverySource,
+}
+
+impl Discovered {
+    /// 创建发现结果，用于 UDP 广播应答的解析函数
+    ///
+    /// * `name` 设备名称
+    /// * `ip` 设备地址
+    /// * `port` 条码输出端口
+    pub fn new(name: &str, ip: IpAddr, port: u16) -> Self {
+        Discovered {
+            name: name.into(),
+            model: None,
+            serial_number: None,
+            ip,
+            port,
+            source: DiscoverySource::Udp,
+        }
+    }
+
+    /// 设置型号
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// 设置序列号
+    pub fn with_serial_number(mut self, serial_number: &str) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    /// 设置发现方式
+    pub(crate) fn with_source(mut self, source: DiscoverySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// 获取设备名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 获取型号
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// 获取序列号
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+
+    /// 获取设备地址
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// 获取条码输出端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 获取发现方式
+    pub fn source(&self) -> &DiscoverySource {
+        &self.source
+    }
+
+    /// 创建连接该扫码枪的网络连接器(客户端模式)
+    pub fn network(&self) -> Network {
+        Network::new_client(&self.ip.to_string(), self.port)
+    }
+}
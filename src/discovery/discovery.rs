@@ -0,0 +1,277 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ipnet::IpNet;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{event, Level};
+
+use crate::discovery::mdns;
+use crate::{Discovered, ScannerError, UdpProbe};
+
+/// 局域网扫码枪发现(需要启用`discovery`特性)
+///
+/// 同时进行 mDNS/Bonjour 查询和 UDP 广播，在超时时间内收集应答，
+/// 相同地址和端口的设备只保留一个
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let devices = Discovery::new()
+///     .mdns("_scanner._tcp.local.")
+///     .timeout(Duration::from_secs(2))
+///     .run()
+///     .await?;
+/// for device in devices {
+///     println!("{} {:?} {}:{}", device.name(), device.model(), device.ip(), device.port());
+///     let scanner = Scanner::new(device.network());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Discovery {
+    services: Vec<String>,
+    probes: Vec<UdpProbe>,
+    timeout: Duration,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Discovery {
+            services: vec![],
+            probes: vec![],
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+impl Discovery {
+    /// 创建扫码枪发现，默认等待3秒
+    pub fn new() -> Self {
+        Discovery::default()
+    }
+
+    /// 添加 mDNS 服务类型，例如`_scanner._tcp.local.`
+    pub fn mdns(mut self, service: &str) -> Self {
+        self.services.push(service.into());
+        self
+    }
+
+    /// 添加 UDP 广播发现
+    pub fn probe(mut self, probe: UdpProbe) -> Self {
+        self.probes.push(probe);
+        self
+    }
+
+    /// 设置等待应答的时长
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 开始发现，返回找到的扫码枪
+    pub async fn run(&self) -> Result<Vec<Discovered>, ScannerError> {
+        let mut tasks = JoinSet::new();
+        for service in self.services.clone() {
+            let timeout = self.timeout;
+            tasks.spawn(async move { mdns::browse(&service, timeout).await });
+        }
+        for probe in self.probes.clone() {
+            let timeout = self.timeout;
+            tasks.spawn(async move { probe.run(timeout).await.map_err(ScannerError::Io) });
+        }
+        let mut found: Vec<Discovered> = vec![];
+        while let Some(r) = tasks.join_next().await {
+            let devices = match r {
+                Ok(Ok(devices)) => devices,
+                // 参数错误直接返回
+                Ok(Err(ScannerError::Param(err))) => return Err(ScannerError::Param(err)),
+                Ok(Err(err)) => {
+                    event!(Level::WARN, error = %err, error.kind = err.kind(), "扫码枪发现失败");
+                    continue;
+                }
+                Err(err) => return Err(ScannerError::Comm(err.to_string())),
+            };
+            for device in devices {
+                let exists = found
+                    .iter()
+                    .any(|d| d.ip() == device.ip() && d.port() == device.port());
+                if !exists {
+                    event!(
+                        Level::INFO,
+                        scanner.ip = %device.ip(),
+                        scanner.port = device.port(),
+                        scanner.name = device.name(),
+                        scanner.model = ?device.model(),
+                        "发现扫码枪",
+                    );
+                    found.push(device);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// 扫描网段内在扫码枪端口上可以连接的设备，用于产线调试时批量查找扫码枪
+    ///
+    /// 并发连接网段内的每个地址，连接成功即视为发现；
+    /// 设置`probe`时连接后发送该识别指令，应答内容(去掉首尾空白)作为型号，没有应答的设备不计入结果
+    ///
+    /// * `subnet` 网段，例如`192.168.1.0/24`
+    /// * `port` 扫码枪端口
+    /// * `probe` 识别指令
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// // Honeywell 查询固件版本
+    /// let devices = Discovery::sweep("192.168.1.0/24", 55256, Some(b"\x16M\rREVINF.")).await?;
+    /// for device in devices {
+    ///     println!("{} {:?}", device.ip(), device.model());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sweep(
+        subnet: &str,
+        port: u16,
+        probe: Option<&[u8]>,
+    ) -> Result<Vec<Discovered>, ScannerError> {
+        let net = IpNet::from_str(subnet)
+            .or_else(|_| IpAddr::from_str(subnet).map(IpNet::from))
+            .map_err(|_| ScannerError::Param(format!("无效的网段,subnet={}", subnet)))?;
+        // 同时连接的数量，避免耗尽文件句柄
+        let limit = Arc::new(Semaphore::new(SWEEP_CONCURRENCY));
+        let probe = probe.map(|probe| Arc::new(probe.to_vec()));
+        let mut tasks = JoinSet::new();
+        for ip in net.hosts() {
+            let limit = Arc::clone(&limit);
+            let probe = probe.clone();
+            tasks.spawn(async move {
+                let _permit = limit.acquire().await.ok()?;
+                identify(ip, port, probe.as_deref().map(|p| p.as_slice())).await
+            });
+        }
+        let mut found = vec![];
+        while let Some(r) = tasks.join_next().await {
+            if let Ok(Some(device)) = r {
+                event!(
+                    Level::INFO,
+                    scanner.ip = %device.ip(),
+                    scanner.port = device.port(),
+                    scanner.model = ?device.model(),
+                    "发现扫码枪",
+                );
+                found.push(device);
+            }
+        }
+        found.sort_by_key(|device| device.ip());
+        Ok(found)
+    }
+}
+
+/// 网段扫描的并发数量
+const SWEEP_CONCURRENCY: usize = 128;
+
+/// 连接设备并发送识别指令
+async fn identify(ip: IpAddr, port: u16, probe: Option<&[u8]>) -> Option<Discovered> {
+    let connect = TcpStream::connect((ip, port));
+    let mut stream = tokio::time::timeout(Duration::from_millis(500), connect)
+        .await
+        .ok()?
+        .ok()?;
+    let device = Discovered::new(&ip.to_string(), ip, port);
+    let Some(probe) = probe else {
+        return Some(device);
+    };
+    stream.write_all(probe).await.ok()?;
+    let mut buf = [0u8; 256];
+    let read = tokio::time::timeout(Duration::from_secs(1), stream.read(&mut buf));
+    match read.await {
+        Ok(Ok(n)) if n > 0 => {
+            let model = String::from_utf8_lossy(&buf[..n]);
+            Some(device.with_model(model.trim()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use tokio::net::UdpSocket;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn udp_probe() {
+        // 模拟扫码枪
+        let device = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = device.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let (n, addr) = device.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..n], b"DISCOVER");
+            device.send_to(b"HS-360,SN0001,9004", addr).await.unwrap();
+            device.send_to(b"HS-360,SN0001,9004", addr).await.unwrap();
+            device.send_to(b"garbage", addr).await.unwrap();
+        });
+        let probe = UdpProbe::new(port, b"DISCOVER", |ip, data| {
+            let text = std::str::from_utf8(data).ok()?;
+            let mut fields = text.split(',');
+            let model = fields.next()?;
+            let sn = fields.next()?;
+            let port = fields.next()?.parse().ok()?;
+            Some(Discovered::new(sn, ip, port).with_model(model))
+        })
+        .with_target(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let devices = Discovery::new()
+            .probe(probe)
+            .timeout(Duration::from_millis(300))
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].model(), Some("HS-360"));
+        assert_eq!(devices[0].network().addr(), "127.0.0.1:9004");
+    }
+
+    #[tokio::test]
+    async fn sweep() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (mut s, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 8];
+                if let Ok(n) = s.read(&mut buf).await {
+                    if &buf[..n] == b"ID?" {
+                        s.write_all(b"HS-360\r\n").await.unwrap();
+                    }
+                }
+            }
+        });
+        let devices = Discovery::sweep("127.0.0.1/32", port, Some(b"ID?"))
+            .await
+            .unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].model(), Some("HS-360"));
+        let devices = Discovery::sweep("127.0.0.1", port, None).await.unwrap();
+        assert_eq!(devices[0].ip().to_string(), "127.0.0.1");
+        assert!(Discovery::sweep("127.0.0.1/33", port, None).await.is_err());
+    }
+}
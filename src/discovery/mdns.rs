@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use crate::discovery::discovered::DiscoverySource;
+use crate::{Discovered, ScannerError};
+
+/// 通过 mDNS/Bonjour 查找服务
+///
+/// * `service` 服务类型，例如`_scanner._tcp.local.`
+pub(crate) async fn browse(
+    service: &str,
+    timeout: Duration,
+) -> Result<Vec<Discovered>, ScannerError> {
+    let daemon = ServiceDaemon::new().map_err(|err| ScannerError::Comm(err.to_string()))?;
+    let receiver = daemon.browse(service).map_err(|err| {
+        ScannerError::Param(format!("无效的mDNS服务类型,service={},{}", service, err))
+    })?;
+    let mut found = vec![];
+    let deadline = tokio::time::Instant::now() + timeout;
+    while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, receiver.recv_async()).await {
+        if let ServiceEvent::ServiceResolved(info) = event {
+            if let Some(device) = resolve(service, &info) {
+                found.push(device);
+            }
+        }
+    }
+    let _ = daemon.shutdown();
+    Ok(found)
+}
+
+/// 转换为发现结果，优先使用 IPv4 地址
+fn resolve(service: &str, info: &ServiceInfo) -> Option<Discovered> {
+    let addrs = info.get_addresses();
+    let ip = addrs
+        .iter()
+        .find(|ip| ip.is_ipv4())
+        .or_else(|| addrs.iter().next())?;
+    let name = info
+        .get_fullname()
+        .strip_suffix(info.get_type())
+        .unwrap_or(info.get_fullname())
+        .trim_end_matches('.');
+    let mut device = Discovered::new(name, *ip, info.get_port())
+        .with_source(DiscoverySource::Mdns(service.into()));
+    // TXT 记录中常见的型号、序列号字段
+    if let Some(model) = ["model", "md", "product"]
+        .iter()
+        .find_map(|key| info.get_property_val_str(key))
+    {
+        device = device.with_model(model);
+    }
+    if let Some(sn) = ["serial", "sn", "serialnumber"]
+        .iter()
+        .find_map(|key| info.get_property_val_str(key))
+    {
+        device = device.with_serial_number(sn);
+    }
+    Some(device)
+}
@@ -0,0 +1,5 @@
+pub mod discovered;
+#[allow(clippy::module_inception)]
+pub mod discovery;
+pub mod mdns;
+pub mod udp;
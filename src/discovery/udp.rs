@@ -0,0 +1,98 @@
+use std::fmt::Debug;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::UdpSocket;
+
+use crate::Discovered;
+
+/// 应答解析函数
+type Parse = Arc<dyn Fn(IpAddr, &[u8]) -> Option<Discovered> + Send + Sync>;
+
+/// UDP 广播发现
+///
+/// 向局域网广播厂商的发现报文，由`parse`解析设备的应答。
+/// 各厂商(Cognex、Keyence 等)的发现报文格式和端口请参考设备的通讯手册
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// // 应答格式: 型号,序列号,端口
+/// let probe = UdpProbe::new(30718, b"DISCOVER", |ip, data| {
+///     let text = std::str::from_utf8(data).ok()?;
+///     let mut fields = text.trim().split(',');
+///     let model = fields.next()?;
+///     let sn = fields.next()?;
+///     let port = fields.next()?.parse().ok()?;
+///     Some(Discovered::new(sn, ip, port).with_model(model).with_serial_number(sn))
+/// });
+/// ```
+#[derive(Clone)]
+pub struct UdpProbe {
+    port: u16,
+    payload: Vec<u8>,
+    target: IpAddr,
+    parse: Parse,
+}
+
+impl Debug for UdpProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdpProbe")
+            .field("port", &self.port)
+            .field("payload", &self.payload)
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl UdpProbe {
+    /// 创建 UDP 广播发现
+    ///
+    /// * `port` 设备监听发现报文的端口
+    /// * `payload` 发现报文
+    /// * `parse` 解析应答，参数为应答设备的地址和应答内容，不是扫码枪的应答返回`None`
+    pub fn new(
+        port: u16,
+        payload: &[u8],
+        parse: impl Fn(IpAddr, &[u8]) -> Option<Discovered> + Send + Sync + 'static,
+    ) -> Self {
+        UdpProbe {
+            port,
+            payload: payload.to_vec(),
+            target: IpAddr::V4(Ipv4Addr::BROADCAST),
+            parse: Arc::new(parse),
+        }
+    }
+
+    /// 设置发送地址，默认`255.255.255.255`，跨网段时可以使用定向广播地址，例如`192.168.1.255`
+    pub fn with_target(mut self, target: IpAddr) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// 获取设备监听的端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 发送发现报文并在`timeout`内收集应答
+    pub(crate) async fn run(&self, timeout: Duration) -> std::io::Result<Vec<Discovered>> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        socket.set_broadcast(true)?;
+        socket
+            .send_to(&self.payload, SocketAddr::new(self.target, self.port))
+            .await?;
+        let mut found = vec![];
+        let mut buf = [0u8; 2048];
+        let deadline = tokio::time::Instant::now() + timeout;
+        while let Ok(r) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            let (n, addr) = r?;
+            if let Some(device) = (self.parse)(addr.ip(), &buf[..n]) {
+                found.push(device);
+            }
+        }
+        Ok(found)
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{event, Level};
+
+use crate::ScannerError;
+
+/// 模拟器注入的故障
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// 输出未读内容，见[`Emulator::with_no_read`]
+    NoRead,
+    /// 输出损坏的条码(去掉后一半内容并随机替换一个字符为`?`)
+    Corrupt,
+    /// 不应答
+    Silent,
+    /// 断开连接
+    Disconnect,
+    /// 延迟指定时长后再输出条码
+    Slow(Duration),
+}
+
+/// 扫码枪模拟器，像 TCP 或串口扫码枪一样收到触发指令后输出预设的条码，
+/// 可以设置解码延时和故障注入，用于端到端测试应用程序
+///
+/// 收到的数据按回车或换行拆分为指令：
+///
+/// * 触发指令(默认`LON`)：等待解码延时后按顺序输出下一个条码，条码用完后从头开始
+/// * 停止指令(默认`LOFF`)：忽略
+/// * [`Emulator::with_reply`]设置的指令：输出对应的应答
+///
+/// 设置了[`Emulator::with_interval`]时为连续模式，不需要触发，按间隔输出条码
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// Emulator::new(["SN0001", "SN0002"])
+///     .with_delay(Duration::from_millis(50))
+///     .with_fault(Fault::NoRead, 0.1)
+///     .with_reply("?", "OK")
+///     .serve(([127, 0, 0, 1], 9004))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Emulator {
+    barcodes: Vec<String>,
+    trigger: String,
+    stop: String,
+    terminator: String,
+    no_read: String,
+    delay: Duration,
+    interval: Option<Duration>,
+    replies: BTreeMap<String, String>,
+    faults: Vec<(Fault, f64)>,
+    seed: Option<u64>,
+}
+
+impl Emulator {
+    /// 创建模拟器
+    ///
+    /// * `barcodes` 按顺序输出的条码
+    pub fn new<T: Into<String>>(barcodes: impl IntoIterator<Item = T>) -> Self {
+        Emulator {
+            barcodes: barcodes.into_iter().map(Into::into).collect(),
+            trigger: "LON".into(),
+            stop: "LOFF".into(),
+            terminator: "\r\n".into(),
+            no_read: "NoRead".into(),
+            delay: Duration::from_millis(100),
+            interval: None,
+            replies: BTreeMap::new(),
+            faults: vec![],
+            seed: None,
+        }
+    }
+
+    /// 设置触发指令和停止指令(不包括回车换行)，默认为`LON`、`LOFF`
+    pub fn with_trigger(mut self, trigger: &str, stop: &str) -> Self {
+        self.trigger = trigger.into();
+        self.stop = stop.into();
+        self
+    }
+
+    /// 获取触发指令
+    pub fn trigger(&self) -> &str {
+        &self.trigger
+    }
+
+    /// 设置输出的帧尾，默认为`\r\n`
+    pub fn with_terminator(mut self, terminator: &str) -> Self {
+        self.terminator = terminator.into();
+        self
+    }
+
+    /// 设置未读时输出的内容，默认为`NoRead`
+    pub fn with_no_read(mut self, no_read: &str) -> Self {
+        self.no_read = no_read.into();
+        self
+    }
+
+    /// 设置收到触发指令到输出条码的解码延时，默认 100 毫秒
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// 获取解码延时
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// 设置连续模式，每隔`interval`输出一个条码，不需要触发
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// 设置指令的应答，应答后加上帧尾
+    pub fn with_reply(mut self, command: &str, reply: &str) -> Self {
+        self.replies.insert(command.into(), reply.into());
+        self
+    }
+
+    /// 注入故障，每次输出条码时按概率发生，多个故障的概率之和不超过 1
+    ///
+    /// * `rate` 发生概率，0 到 1
+    pub fn with_fault(mut self, fault: Fault, rate: f64) -> Self {
+        self.faults.push((fault, rate.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// 设置故障注入的随机数种子，相同种子的故障序列相同，默认按当前时间
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// 作为网络扫码枪(服务器模式)监听，应用程序以客户端模式连接，每个连接独立应答，条码顺序共享
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        self.check()?;
+        let addr = addr.into();
+        let listener = TcpListener::bind(addr).await.map_err(ScannerError::Io)?;
+        event!(Level::INFO, server.addr = %addr, "扫码枪模拟器启动成功");
+        Emulating::new(self).accept(listener).await
+    }
+
+    /// 在任意数据流上模拟扫码枪(例如打开的串口、虚拟串口)，直到对方断开或注入断开故障
+    pub async fn run<S>(self, stream: S) -> Result<(), ScannerError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        self.check()?;
+        Emulating::new(self).session(stream).await
+    }
+
+    /// 创建虚拟串口并在后台模拟扫码枪，返回应用程序要打开的串口路径(例如`/dev/pts/3`)
+    ///
+    /// 需要在 tokio 运行时中调用
+    #[cfg(unix)]
+    pub fn virtual_serial(self) -> Result<String, ScannerError> {
+        use tokio_serial::SerialPort;
+
+        self.check()?;
+        let (master, slave) = tokio_serial::SerialStream::pair()
+            .map_err(|err| ScannerError::Comm(err.to_string()))?;
+        let name = slave
+            .name()
+            .ok_or_else(|| ScannerError::Comm("无法获取虚拟串口路径".into()))?;
+        event!(Level::INFO, server.addr = %name, "虚拟串口扫码枪模拟器启动成功");
+        let emulating = Emulating::new(self);
+        tokio::spawn(async move {
+            // 保持从端打开，应用程序关闭串口后主端仍可读写
+            let _slave = slave;
+            let _ = emulating.session(master).await;
+        });
+        Ok(name)
+    }
+
+    fn check(&self) -> Result<(), ScannerError> {
+        if self.barcodes.is_empty() {
+            return Err(ScannerError::Param("没有配置模拟条码".into()));
+        }
+        if self.trigger.is_empty() {
+            return Err(ScannerError::Param("触发指令不能为空".into()));
+        }
+        if self.faults.iter().map(|(_, rate)| rate).sum::<f64>() > 1.0 {
+            return Err(ScannerError::Param("故障概率之和不能超过1".into()));
+        }
+        Ok(())
+    }
+}
+
+/// 一次输出的结果
+enum Output {
+    Data(String),
+    Silent,
+    Disconnect,
+}
+
+/// 运行中的模拟器，各连接共享条码顺序和随机数
+#[derive(Clone)]
+struct Emulating {
+    emulator: Arc<Emulator>,
+    next: Arc<AtomicUsize>,
+    rng: Arc<Mutex<u64>>,
+}
+
+impl Emulating {
+    fn new(emulator: Emulator) -> Self {
+        let seed = emulator.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        Emulating {
+            emulator: Arc::new(emulator),
+            next: Arc::new(AtomicUsize::new(0)),
+            // xorshift 的状态不能为 0
+            rng: Arc::new(Mutex::new(seed | 1)),
+        }
+    }
+
+    /// 接受连接，每个连接一个任务
+    async fn accept(self, listener: TcpListener) -> Result<(), ScannerError> {
+        loop {
+            let (client, peer) = listener.accept().await.map_err(ScannerError::Io)?;
+            event!(Level::INFO, client.addr = %peer, "模拟器客户端已连接");
+            let emulating = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = emulating.session(client).await {
+                    event!(
+                        Level::WARN,
+                        client.addr = %peer,
+                        error = %err,
+                        error.kind = err.kind(),
+                        "模拟器连接异常",
+                    );
+                }
+                event!(Level::INFO, client.addr = %peer, "模拟器客户端已断开");
+            });
+        }
+    }
+
+    /// 处理一个连接的指令，直到对方断开或注入断开故障
+    async fn session<S>(&self, mut stream: S) -> Result<(), ScannerError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let emulator = &self.emulator;
+        let mut buf = [0u8; 1024];
+        let mut line = Vec::new();
+        let mut interval = emulator.interval.map(|interval| {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker
+        });
+        loop {
+            let tick = async {
+                match interval.as_mut() {
+                    Some(ticker) => ticker.tick().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let n = tokio::select! {
+                n = stream.read(&mut buf) => n.map_err(ScannerError::Io)?,
+                _ = tick => {
+                    if !self.scan(&mut stream).await? {
+                        return Ok(());
+                    }
+                    continue;
+                }
+            };
+            if n == 0 {
+                return Ok(());
+            }
+            for &b in &buf[..n] {
+                if b != b'\r' && b != b'\n' {
+                    line.push(b);
+                    continue;
+                }
+                let command = String::from_utf8_lossy(&line).into_owned();
+                line.clear();
+                if command.is_empty() || command == emulator.stop {
+                    continue;
+                }
+                if command == emulator.trigger {
+                    tokio::time::sleep(emulator.delay).await;
+                    if !self.scan(&mut stream).await? {
+                        return Ok(());
+                    }
+                } else if let Some(reply) = emulator.replies.get(&command) {
+                    self.write(&mut stream, reply).await?;
+                }
+            }
+        }
+    }
+
+    /// 输出一次扫码结果，注入断开故障时返回`false`
+    async fn scan<S>(&self, stream: &mut S) -> Result<bool, ScannerError>
+    where
+        S: AsyncWrite + Unpin,
+    {
+        match self.output().await {
+            Output::Data(data) => self.write(stream, &data).await?,
+            Output::Silent => {}
+            Output::Disconnect => {
+                let _ = stream.shutdown().await;
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 取下一个条码并按概率注入故障
+    async fn output(&self) -> Output {
+        let emulator = &self.emulator;
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % emulator.barcodes.len();
+        let barcode = emulator.barcodes[index].clone();
+        let roll = self.random();
+        let mut cumulative = 0.0;
+        let fault = emulator.faults.iter().find(|(_, rate)| {
+            cumulative += rate;
+            roll < cumulative
+        });
+        match fault.map(|(fault, _)| fault) {
+            None => Output::Data(barcode),
+            Some(Fault::NoRead) => Output::Data(emulator.no_read.clone()),
+            Some(Fault::Corrupt) => Output::Data(self.corrupt(&barcode)),
+            Some(Fault::Silent) => Output::Silent,
+            Some(Fault::Disconnect) => Output::Disconnect,
+            Some(Fault::Slow(delay)) => {
+                tokio::time::sleep(*delay).await;
+                Output::Data(barcode)
+            }
+        }
+    }
+
+    /// 去掉后一半并随机替换一个字符
+    fn corrupt(&self, barcode: &str) -> String {
+        let mut chars = barcode.chars().collect::<Vec<_>>();
+        chars.truncate(chars.len().div_ceil(2));
+        if chars.is_empty() {
+            return "?".into();
+        }
+        let index = (self.random() * chars.len() as f64) as usize % chars.len();
+        chars[index] = '?';
+        chars.into_iter().collect()
+    }
+
+    /// 0 到 1 之间的随机数(xorshift64)
+    fn random(&self) -> f64 {
+        let mut state = self.rng.lock().unwrap();
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        (*state >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    async fn write<S>(&self, stream: &mut S, data: &str) -> Result<(), ScannerError>
+    where
+        S: AsyncWrite + Unpin,
+    {
+        let data = format!("{}{}", data, self.emulator.terminator);
+        stream
+            .write_all(data.as_bytes())
+            .await
+            .map_err(ScannerError::Io)?;
+        stream.flush().await.map_err(ScannerError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn emulator() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let emulator = Emulator::new(["SN0001", "SN0002"])
+            .with_delay(Duration::ZERO)
+            .with_reply("?", "OK");
+        tokio::spawn(Emulating::new(emulator).accept(listener));
+
+        let mut client = BufReader::new(TcpStream::connect(addr).await.unwrap());
+        let mut line = String::new();
+        for expected in ["SN0001\r\n", "OK\r\n", "SN0002\r\n", "SN0001\r\n"] {
+            let command = if expected == "OK\r\n" {
+                "LOFF\r?\r"
+            } else {
+                "LON\r"
+            };
+            client
+                .get_mut()
+                .write_all(command.as_bytes())
+                .await
+                .unwrap();
+            line.clear();
+            client.read_line(&mut line).await.unwrap();
+            assert_eq!(line, expected);
+        }
+
+        // 故障注入
+        let emulator = Emulator::new(["SN0001"])
+            .with_delay(Duration::ZERO)
+            .with_fault(Fault::NoRead, 1.0);
+        let (local, remote) = tokio::io::duplex(64);
+        tokio::spawn(emulator.run(remote));
+        let mut client = BufReader::new(local);
+        client.get_mut().write_all(b"LON\n").await.unwrap();
+        line.clear();
+        client.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "NoRead\r\n");
+
+        let emulator = Emulator::new(["SN0001"]).with_fault(Fault::Disconnect, 1.0);
+        let (mut local, remote) = tokio::io::duplex(64);
+        let session = tokio::spawn(emulator.with_delay(Duration::ZERO).run(remote));
+        local.write_all(b"LON\r").await.unwrap();
+        assert!(session.await.unwrap().is_ok());
+
+        assert!(Emulator::new(Vec::<String>::new()).check().is_err());
+        assert!(Emulator::new(["SN0001"])
+            .with_fault(Fault::Silent, 0.6)
+            .with_fault(Fault::Corrupt, 0.6)
+            .check()
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn virtual_serial() {
+        use crate::prelude::*;
+
+        // 连续模式，不需要触发
+        let name = Emulator::new(["SN0001"])
+            .with_interval(Duration::from_millis(50))
+            .virtual_serial()
+            .unwrap();
+        let scanner = Scanner::new(Serial::new(&name, 9600, 8, StopBits::One, Parity::None))
+            .trim_end([b'\r', b'\n']);
+        scanner.start().await.unwrap().unwrap();
+        let barcode = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(ScanEvent::Barcode(barcode)) = scanner.recv().await {
+                    return barcode;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(barcode.text(), "SN0001");
+        scanner.stop();
+    }
+
+    #[test]
+    fn corrupt() {
+        let emulating = Emulating::new(Emulator::new(["SN0001"]).with_seed(7));
+        let corrupted = emulating.corrupt("SN0001");
+        assert_eq!(corrupted.len(), 3);
+        assert!(corrupted.contains('?'));
+        let random = emulating.random();
+        assert!((0.0..1.0).contains(&random));
+    }
+}
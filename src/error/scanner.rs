@@ -1,7 +1,15 @@
 use std::{error::Error, fmt::Display};
 
 /// 扫码枪返回致命错误
+///
+/// 启用`serde`功能后序列化为`{"kind": "param", "message": "..."}`，
+/// IO 错误只保留错误信息，反序列化为[`std::io::ErrorKind::Other`]
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "ErrorRepr", from = "ErrorRepr")
+)]
 pub enum ScannerError {
     /// IO 错误
     Io(std::io::Error),
@@ -9,6 +17,43 @@ pub enum ScannerError {
     Param(String),
     /// 通讯错误(Communicate Error)
     Comm(String),
+    /// 校验错误(Checksum Error)
+    Checksum(String),
+    /// 条码解析错误(Parse Error)
+    Parse(String),
+    /// 扫码枪执行指令失败(Device Error)
+    Device(String),
+    /// 等待扫码枪应答超时(Timeout Error)
+    Timeout(String),
+}
+
+impl Clone for ScannerError {
+    fn clone(&self) -> Self {
+        match self {
+            ScannerError::Io(e) => ScannerError::Io(std::io::Error::new(e.kind(), e.to_string())),
+            ScannerError::Param(e) => ScannerError::Param(e.clone()),
+            ScannerError::Comm(e) => ScannerError::Comm(e.clone()),
+            ScannerError::Checksum(e) => ScannerError::Checksum(e.clone()),
+            ScannerError::Parse(e) => ScannerError::Parse(e.clone()),
+            ScannerError::Device(e) => ScannerError::Device(e.clone()),
+            ScannerError::Timeout(e) => ScannerError::Timeout(e.clone()),
+        }
+    }
+}
+
+impl ScannerError {
+    /// 错误类型名称，与序列化的`kind`字段一致，日志中记为`error.kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScannerError::Io(_) => "io",
+            ScannerError::Param(_) => "param",
+            ScannerError::Comm(_) => "comm",
+            ScannerError::Checksum(_) => "checksum",
+            ScannerError::Parse(_) => "parse",
+            ScannerError::Device(_) => "device",
+            ScannerError::Timeout(_) => "timeout",
+        }
+    }
 }
 
 impl Display for ScannerError {
@@ -17,6 +62,10 @@ impl Display for ScannerError {
             ScannerError::Io(e) => e.fmt(f),
             ScannerError::Param(e) => write!(f, "扫码枪参数错误:{}", e),
             ScannerError::Comm(e) => write!(f, "扫码枪通讯错误:{}", e),
+            ScannerError::Checksum(e) => write!(f, "扫码枪校验错误:{}", e),
+            ScannerError::Parse(e) => write!(f, "扫码枪解析错误:{}", e),
+            ScannerError::Device(e) => write!(f, "扫码枪指令错误:{}", e),
+            ScannerError::Timeout(e) => write!(f, "扫码枪应答超时:{}", e),
         }
     }
 }
@@ -26,3 +75,47 @@ impl Error for ScannerError {
         None
     }
 }
+
+/// 序列化格式
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+enum ErrorRepr {
+    Io(String),
+    Param(String),
+    Comm(String),
+    Checksum(String),
+    Parse(String),
+    Device(String),
+    Timeout(String),
+}
+
+#[cfg(feature = "serde")]
+impl From<ScannerError> for ErrorRepr {
+    fn from(err: ScannerError) -> Self {
+        match err {
+            ScannerError::Io(e) => ErrorRepr::Io(e.to_string()),
+            ScannerError::Param(e) => ErrorRepr::Param(e),
+            ScannerError::Comm(e) => ErrorRepr::Comm(e),
+            ScannerError::Checksum(e) => ErrorRepr::Checksum(e),
+            ScannerError::Parse(e) => ErrorRepr::Parse(e),
+            ScannerError::Device(e) => ErrorRepr::Device(e),
+            ScannerError::Timeout(e) => ErrorRepr::Timeout(e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ErrorRepr> for ScannerError {
+    fn from(repr: ErrorRepr) -> Self {
+        match repr {
+            ErrorRepr::Io(e) => ScannerError::Io(std::io::Error::other(e)),
+            ErrorRepr::Param(e) => ScannerError::Param(e),
+            ErrorRepr::Comm(e) => ScannerError::Comm(e),
+            ErrorRepr::Checksum(e) => ScannerError::Checksum(e),
+            ErrorRepr::Parse(e) => ScannerError::Parse(e),
+            ErrorRepr::Device(e) => ScannerError::Device(e),
+            ErrorRepr::Timeout(e) => ScannerError::Timeout(e),
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Connector, Failover, Scanner, ScannerError};
+
+/// 单台扫码枪的配置
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let config = ScannerConfig::new("line1-in", Network::new_client("192.168.1.10", 9004))
+///     .with_label("line", "1");
+/// let scanner = config.scanner();
+/// assert_eq!(scanner.get_id(), Some("line1-in"));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScannerConfig {
+    id: String,
+    connector: Connector,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    labels: BTreeMap<String, String>,
+}
+
+impl ScannerConfig {
+    /// 创建扫码枪配置
+    ///
+    /// * `id` 扫码枪编号
+    /// * `connector` 连接器
+    pub fn new(id: &str, connector: impl Into<Connector>) -> Self {
+        ScannerConfig {
+            id: id.into(),
+            connector: connector.into(),
+            labels: BTreeMap::new(),
+        }
+    }
+
+    /// 添加标签
+    pub fn with_label(mut self, key: &str, value: &str) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// 获取扫码枪编号
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 获取连接器
+    pub fn connector(&self) -> &Connector {
+        &self.connector
+    }
+
+    /// 获取标签
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// 按配置创建扫码枪
+    pub fn scanner(&self) -> Scanner {
+        let scanner = Scanner::new(self.connector.clone()).id(&self.id);
+        self.labels
+            .iter()
+            .fold(scanner, |scanner, (key, value)| scanner.label(key, value))
+    }
+}
+
+/// 扫码枪管理器的配置，描述整条产线的扫码枪
+///
+/// 支持 TOML、YAML、JSON 格式，按文件扩展名识别：
+///
+/// ```toml
+/// [[scanners]]
+/// id = "st1-a"
+/// connector = "tcp://192.168.1.10:9004"
+/// labels = { line = "1" }
+///
+/// [[scanners]]
+/// id = "st1-b"
+/// connector = { type = "serial", name = "COM3", baudrate = 9600, databits = 8, stopbits = "one", parity = "none" }
+///
+/// [[failover]]
+/// station = "st1"
+/// primary = "st1-a"
+/// backup = "st1-b"
+/// ```
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = ScannerManager::from_config("scanners.toml")?;
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FleetConfig {
+    #[serde(default)]
+    scanners: Vec<ScannerConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    failover: Vec<Failover>,
+}
+
+impl FleetConfig {
+    /// 创建空的配置
+    pub fn new() -> Self {
+        FleetConfig::default()
+    }
+
+    /// 添加扫码枪
+    pub fn with_scanner(mut self, scanner: ScannerConfig) -> Self {
+        self.scanners.push(scanner);
+        self
+    }
+
+    /// 添加主备扫码枪工位
+    pub fn with_failover(mut self, failover: Failover) -> Self {
+        self.failover.push(failover);
+        self
+    }
+
+    /// 获取扫码枪配置
+    pub fn scanners(&self) -> &[ScannerConfig] {
+        &self.scanners
+    }
+
+    /// 获取主备扫码枪工位
+    pub fn failover(&self) -> &[Failover] {
+        &self.failover
+    }
+
+    /// 读取配置文件，按扩展名`toml`、`yaml`/`yml`、`json`识别格式
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScannerError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+        let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        match ext.to_lowercase().as_str() {
+            "toml" => toml::from_str(&text).map_err(invalid),
+            "yaml" | "yml" => serde_yaml::from_str(&text).map_err(invalid),
+            "json" => serde_json::from_str(&text).map_err(invalid),
+            _ => Err(ScannerError::Param(format!(
+                "不支持的配置文件格式,path={}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// 保存配置文件，格式同[`FleetConfig::load`]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScannerError> {
+        let path = path.as_ref();
+        let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        let text = match ext.to_lowercase().as_str() {
+            "toml" => toml::to_string(self).map_err(invalid)?,
+            "yaml" | "yml" => serde_yaml::to_string(self).map_err(invalid)?,
+            "json" => serde_json::to_string_pretty(self).map_err(invalid)?,
+            _ => {
+                return Err(ScannerError::Param(format!(
+                    "不支持的配置文件格式,path={}",
+                    path.display()
+                )))
+            }
+        };
+        std::fs::write(path, text).map_err(ScannerError::Io)
+    }
+}
+
+fn invalid(err: impl std::fmt::Display) -> ScannerError {
+    ScannerError::Param(format!("配置文件格式错误,{}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FleetConfig;
+    use crate::prelude::*;
+
+    #[test]
+    fn load() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("kim_scanner_fleet.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [[scanners]]
+            id = "a"
+            connector = "tcp://192.168.1.10:9004"
+            labels = { line = "1" }
+
+            [[scanners]]
+            id = "b"
+            connector = { type = "serial", name = "COM3", baudrate = 9600, databits = 8, stopbits = "one", parity = "even" }
+
+            [[failover]]
+            station = "st1"
+            primary = "a"
+            backup = "b"
+            "#,
+        )
+        .unwrap();
+        let config = FleetConfig::load(&path).unwrap();
+        assert_eq!(config.scanners().len(), 2);
+        assert_eq!(config.scanners()[0].labels()["line"], "1");
+        let Connector::Serial(serial) = config.scanners()[1].connector() else {
+            panic!("connector is not serial");
+        };
+        assert_eq!(serial.parity(), &Parity::Even);
+        assert_eq!(config.failover()[0], Failover::new("st1", "a", "b"));
+
+        // 各格式互相转换
+        let toml = dir.join("kim_scanner_fleet2.toml");
+        config.save(&toml).unwrap();
+        let config = FleetConfig::load(&toml).unwrap();
+        let json = dir.join("kim_scanner_fleet.json");
+        config.save(&json).unwrap();
+        let yaml = dir.join("kim_scanner_fleet.yaml");
+        FleetConfig::load(&json).unwrap().save(&yaml).unwrap();
+        let config = FleetConfig::load(&yaml).unwrap();
+        assert_eq!(
+            config.scanners()[0].connector().to_string(),
+            "192.168.1.10:9004"
+        );
+        assert_eq!(config.scanners()[1].connector().to_string(), "COM3");
+
+        let manager = ScannerManager::from_config(&path).unwrap();
+        assert_eq!(manager.ids(), ["a", "b"]);
+        assert_eq!(manager.active("st1").as_deref(), Some("a"));
+        assert!(FleetConfig::load(dir.join("kim_scanner_fleet.ini")).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::ScanEvent;
+
+/// 带扫码枪编号和标签的事件
+///
+/// 由[`crate::ScannerManager`]合并各扫码枪的事件后发出，或通过[`crate::Scanner::recv_tagged`]接收
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FleetEvent {
+    id: String,
+    labels: Arc<BTreeMap<String, String>>,
+    event: ScanEvent,
+}
+
+impl FleetEvent {
+    /// 创建带编号的事件
+    pub fn new(id: &str, event: ScanEvent) -> Self {
+        FleetEvent::tagged(id.into(), Arc::default(), event)
+    }
+
+    /// 创建带编号和标签的事件，标签由同一台扫码枪的事件共享
+    pub(crate) fn tagged(
+        id: String,
+        labels: Arc<BTreeMap<String, String>>,
+        event: ScanEvent,
+    ) -> Self {
+        FleetEvent { id, labels, event }
+    }
+
+    /// 获取扫码枪编号
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 获取扫码枪标签
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// 获取标签值
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels.get(key).map(String::as_str)
+    }
+
+    /// 获取事件
+    pub fn event(&self) -> &ScanEvent {
+        &self.event
+    }
+
+    /// 转为事件
+    pub fn into_event(self) -> ScanEvent {
+        self.event
+    }
+}
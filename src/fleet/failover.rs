@@ -0,0 +1,318 @@
+use tracing::{event, Level};
+
+use crate::ScanEvent;
+
+/// 主备扫码枪，同一工位配置两台扫码枪
+///
+/// 主扫码枪断开、无响应或连接失败时切换到备用扫码枪，发出[`ScanEvent::Failover`]事件；
+/// 主扫码枪重新连接后自动切回，发出[`ScanEvent::Failback`]事件。
+/// 切换事件的编号为工位名称，内容为切换后使用的扫码枪编号。
+/// 通过[`crate::ScannerManager::trigger`]触发工位时使用当前的扫码枪
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let trigger = SoftTrigger::new("LON\r").with_stop("LOFF\r");
+/// let manager = ScannerManager::new()
+///     .with_scanner("st1-a", Scanner::new(Network::new_client("192.168.1.10", 9004)).soft_trigger(trigger.clone()))
+///     .with_scanner("st1-b", Scanner::new(Network::new_client("192.168.1.11", 9004)).soft_trigger(trigger))
+///     .with_failover(Failover::new("st1", "st1-a", "st1-b"));
+/// manager.start().await?;
+/// manager.trigger("st1", None).await?;
+/// println!("当前使用{:?}", manager.active("st1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Failover {
+    station: String,
+    primary: String,
+    backup: String,
+}
+
+impl Failover {
+    /// 创建主备扫码枪
+    ///
+    /// * `station` 工位名称
+    /// * `primary` 主扫码枪编号
+    /// * `backup` 备用扫码枪编号
+    pub fn new(station: &str, primary: &str, backup: &str) -> Self {
+        Failover {
+            station: station.into(),
+            primary: primary.into(),
+            backup: backup.into(),
+        }
+    }
+
+    /// 获取工位名称
+    pub fn station(&self) -> &str {
+        &self.station
+    }
+
+    /// 获取主扫码枪编号
+    pub fn primary(&self) -> &str {
+        &self.primary
+    }
+
+    /// 获取备用扫码枪编号
+    pub fn backup(&self) -> &str {
+        &self.backup
+    }
+}
+
+/// 工位当前使用的扫码枪
+#[derive(Debug)]
+pub(crate) struct Station {
+    failover: Failover,
+    /// 是否已切换到备用扫码枪
+    on_backup: bool,
+    /// 主扫码枪是否已连接
+    connected: bool,
+}
+
+impl Station {
+    pub(crate) fn new(failover: Failover) -> Self {
+        Station {
+            failover,
+            on_backup: false,
+            connected: false,
+        }
+    }
+
+    pub(crate) fn failover(&self) -> &Failover {
+        &self.failover
+    }
+
+    /// 当前使用的扫码枪编号
+    pub(crate) fn active(&self) -> &str {
+        match self.on_backup {
+            true => &self.failover.backup,
+            false => &self.failover.primary,
+        }
+    }
+
+    /// 根据主扫码枪的连接事件切换，返回需要发出的切换事件
+    ///
+    /// 未连接时的错误事件是连接失败，主扫码枪启动时就连接不上也会切换；
+    /// 已连接时的错误(如读取超时、帧校验失败)不切换
+    pub(crate) fn observe(&mut self, id: &str, ev: &ScanEvent) -> Option<ScanEvent> {
+        if id != self.failover.primary {
+            return None;
+        }
+        match ev {
+            ScanEvent::Connected => self.connected = true,
+            ScanEvent::Disconnected | ScanEvent::Failed(_) => self.connected = false,
+            _ => {}
+        }
+        let station = &self.failover.station;
+        match ev {
+            ScanEvent::Disconnected | ScanEvent::Unhealthy(_) | ScanEvent::Failed(_)
+                if !self.on_backup =>
+            {
+                self.on_backup = true;
+                event!(
+                    Level::WARN,
+                    station = %station,
+                    primary = %self.failover.primary,
+                    backup = %self.failover.backup,
+                    "主扫码枪断开,切换到备用扫码枪",
+                );
+                Some(ScanEvent::Failover(self.failover.backup.clone()))
+            }
+            ScanEvent::Error(err) if !self.on_backup && !self.connected => {
+                self.on_backup = true;
+                event!(
+                    Level::WARN,
+                    station = %station,
+                    primary = %self.failover.primary,
+                    backup = %self.failover.backup,
+                    error = %err,
+                    "主扫码枪连接失败,切换到备用扫码枪",
+                );
+                Some(ScanEvent::Failover(self.failover.backup.clone()))
+            }
+            ScanEvent::Connected if self.on_backup => {
+                self.on_backup = false;
+                event!(
+                    Level::INFO,
+                    station = %station,
+                    primary = %self.failover.primary,
+                    "主扫码枪恢复,切回主扫码枪",
+                );
+                Some(ScanEvent::Failback(self.failover.primary.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Failover, Station};
+    use crate::prelude::*;
+    use crate::{ScanEvent, ScannerError};
+
+    #[test]
+    fn observe() {
+        let mut station = Station::new(Failover::new("st1", "a", "b"));
+        assert_eq!(station.active(), "a");
+        assert!(station.observe("b", &ScanEvent::Disconnected).is_none());
+        assert!(station.observe("a", &ScanEvent::Connected).is_none());
+        let ev = station.observe("a", &ScanEvent::Unhealthy("超时".into()));
+        assert!(matches!(ev, Some(ScanEvent::Failover(ref id)) if id == "b"));
+        assert_eq!(station.active(), "b");
+        // 已经切换后不重复发出事件
+        assert!(station.observe("a", &ScanEvent::Disconnected).is_none());
+        let ev = station.observe("a", &ScanEvent::Connected);
+        assert!(matches!(ev, Some(ScanEvent::Failback(ref id)) if id == "a"));
+        assert_eq!(station.active(), "a");
+    }
+
+    #[test]
+    fn observe_connect_error() {
+        let mut station = Station::new(Failover::new("st1", "a", "b"));
+        // 启动时就连接不上
+        let err = ScannerError::Comm("连接被拒绝".into());
+        let ev = station.observe("a", &ScanEvent::Error(err.clone()));
+        assert!(matches!(ev, Some(ScanEvent::Failover(ref id)) if id == "b"));
+        assert!(station.observe("a", &ScanEvent::Connected).is_some());
+        // 已连接时的错误不切换
+        let timeout = ScannerError::Timeout("没有收到数据".into());
+        assert!(station.observe("a", &ScanEvent::Error(timeout)).is_none());
+        assert_eq!(station.active(), "a");
+        assert!(station.observe("a", &ScanEvent::Disconnected).is_some());
+        assert!(station.observe("a", &ScanEvent::Connected).is_some());
+        assert!(station.observe("a", &ScanEvent::Disconnected).is_some());
+        // 切换后重连失败不重复发出事件
+        assert!(station.observe("a", &ScanEvent::Error(err)).is_none());
+    }
+
+    #[tokio::test]
+    async fn failover() {
+        use std::time::Duration;
+
+        let (a, b) = (MockConnector::new(), MockConnector::new());
+        let retry = RetryPolicy::fixed(Duration::from_millis(50));
+        let manager = ScannerManager::new()
+            .with_scanner(
+                "A",
+                Scanner::new(a.clone())
+                    .retry(retry.clone())
+                    .soft_trigger(SoftTrigger::new("LON")),
+            )
+            .with_scanner(
+                "B",
+                Scanner::new(b.clone()).soft_trigger(SoftTrigger::new("TRG")),
+            )
+            .with_failover(Failover::new("ST1", "A", "B"));
+        manager.start().await.unwrap();
+        for _ in 0..2 {
+            manager.recv().await.unwrap();
+        }
+        assert!(manager.trigger("ST1", None).await.unwrap());
+        assert_eq!(a.next_command().await.unwrap(), "LON");
+        a.disconnect();
+        assert!(matches!(
+            manager.recv().await.unwrap().event(),
+            ScanEvent::Disconnected
+        ));
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "ST1");
+        assert!(matches!(ev.event(), ScanEvent::Failover(id) if id == "B"));
+        assert_eq!(manager.active("ST1").as_deref(), Some("B"));
+        assert!(manager.trigger("ST1", None).await.unwrap());
+        assert_eq!(b.next_command().await.unwrap(), "TRG");
+        // 主扫码枪重新连接后切回
+        assert!(matches!(
+            manager.recv().await.unwrap().event(),
+            ScanEvent::Connected
+        ));
+        let ev = manager.recv().await.unwrap();
+        assert!(matches!(ev.event(), ScanEvent::Failback(id) if id == "A"));
+        assert_eq!(manager.active("ST1").as_deref(), Some("A"));
+        assert!(manager.trigger("ST2", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failover_network() {
+        use std::time::Duration;
+
+        let primary = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let backup = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = |listener: &tokio::net::TcpListener| {
+            let port = listener.local_addr().unwrap().port();
+            Scanner::new(Network::new_client("127.0.0.1", port))
+                .retry(RetryPolicy::fixed(Duration::from_millis(50)))
+        };
+        let manager = ScannerManager::new()
+            .with_scanner("A", client(&primary))
+            .with_scanner("B", client(&backup))
+            .with_failover(Failover::new("ST1", "A", "B"));
+        manager.start().await.unwrap();
+        let (device, _) = primary.accept().await.unwrap();
+        let (_backup, _) = backup.accept().await.unwrap();
+        for _ in 0..2 {
+            assert!(matches!(
+                manager.recv().await.unwrap().event(),
+                ScanEvent::Connected
+            ));
+        }
+        // 主扫码枪的 TCP 连接断开后切换到备用扫码枪
+        drop(device);
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "A");
+        assert!(matches!(ev.event(), ScanEvent::Disconnected));
+        let ev = manager.recv().await.unwrap();
+        assert!(matches!(ev.event(), ScanEvent::Failover(id) if id == "B"));
+        assert_eq!(manager.active("ST1").as_deref(), Some("B"));
+        // 主扫码枪重新连接后切回
+        let (_device, _) = primary.accept().await.unwrap();
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "A");
+        assert!(matches!(ev.event(), ScanEvent::Connected));
+        let ev = manager.recv().await.unwrap();
+        assert!(matches!(ev.event(), ScanEvent::Failback(id) if id == "A"));
+        manager.stop();
+    }
+
+    #[tokio::test]
+    async fn failover_primary_never_connects() {
+        use std::time::Duration;
+
+        // 主扫码枪的地址没有监听，启动时就连接不上
+        let port = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let backup = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = |port| {
+            Scanner::new(Network::new_client("127.0.0.1", port))
+                .retry(RetryPolicy::fixed(Duration::from_millis(50)))
+        };
+        let manager = ScannerManager::new()
+            .with_scanner("A", client(port))
+            .with_scanner("B", client(backup.local_addr().unwrap().port()))
+            .with_failover(Failover::new("ST1", "A", "B"));
+        manager.start().await.unwrap();
+        let (_backup, _) = backup.accept().await.unwrap();
+        let failover = loop {
+            let ev = manager.recv().await.unwrap();
+            if ev.id() == "ST1" {
+                break ev;
+            }
+        };
+        assert!(matches!(failover.event(), ScanEvent::Failover(id) if id == "B"));
+        assert_eq!(manager.active("ST1").as_deref(), Some("B"));
+        // 主扫码枪一直连接不上，不重复切换
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        while let Ok(Some(ev)) =
+            tokio::time::timeout(Duration::from_millis(10), manager.recv()).await
+        {
+            assert_ne!(ev.id(), "ST1");
+        }
+        manager.stop();
+    }
+}
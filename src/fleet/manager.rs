@@ -0,0 +1,679 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{broadcast, watch, Mutex};
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+use tracing::{event, Level};
+
+use crate::fleet::event::FleetEvent;
+use crate::fleet::failover::{Failover, Station};
+use crate::fleet::route::{Route, Routing};
+use crate::fleet::stats::{ScannerStats, Stats};
+use crate::fleet::supervisor::{Restart, Supervisor, Verdict};
+use crate::{ScanEvent, Scanner, ScannerError};
+
+/// 受管理的扫码枪
+struct Managed {
+    scanner: Scanner,
+    /// 事件转发任务
+    forwarder: Option<AbortHandle>,
+}
+
+/// 扫码枪管理器，统一启动和停止多台扫码枪，并把各扫码枪的事件合并为一个事件流
+///
+/// 每个事件都带有添加扫码枪时指定的编号和扫码枪的标签。
+/// 启动后由管理器接收各扫码枪的事件，不要再调用[`Scanner::recv`]。
+/// 运行中可以通过[`ScannerManager::add`]和[`ScannerManager::remove`]增减扫码枪，不影响其它扫码枪。
+/// 设置[`Supervisor`]后自动重启已退出的扫码枪任务；设置[`Route`]后按规则把条码事件发送到不同的输出
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_scanner("line1-out", Scanner::new(Network::new_client("192.168.1.11", 9004)));
+/// manager.start().await?;
+/// while let Some(ev) = manager.recv().await {
+///     println!("{} {}", ev.id(), ev.event().printable());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ScannerManager {
+    scanners: Arc<std::sync::Mutex<BTreeMap<String, Managed>>>,
+    /// 是否已启动，启动后添加的扫码枪立即启动
+    running: AtomicBool,
+    /// 主备扫码枪工位
+    stations: Arc<std::sync::Mutex<Vec<Station>>>,
+    /// 事件路由
+    routing: Arc<std::sync::RwLock<Routing>>,
+    /// 统计和最近的条码
+    stats: Arc<std::sync::Mutex<Stats>>,
+    /// 所有事件的订阅
+    subscribers: broadcast::Sender<FleetEvent>,
+    /// 任务监督
+    supervisor: Option<Supervisor>,
+    /// 任务监督的后台任务
+    watch: std::sync::Mutex<Option<AbortHandle>>,
+    /// 正在停止，事件转发任务转发完队列中的事件后结束
+    closing: watch::Sender<bool>,
+    sender: Sender<FleetEvent>,
+    receiver: Arc<Mutex<Receiver<FleetEvent>>>,
+}
+
+impl Default for ScannerManager {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel(1000);
+        ScannerManager {
+            scanners: Arc::new(std::sync::Mutex::new(BTreeMap::new())),
+            running: AtomicBool::new(false),
+            stations: Arc::new(std::sync::Mutex::new(vec![])),
+            routing: Arc::default(),
+            stats: Arc::default(),
+            subscribers: broadcast::Sender::new(1000),
+            supervisor: None,
+            watch: std::sync::Mutex::new(None),
+            closing: watch::Sender::new(false),
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+        }
+    }
+}
+
+impl ScannerManager {
+    /// 创建扫码枪管理器
+    pub fn new() -> Self {
+        ScannerManager::default()
+    }
+
+    /// 添加扫码枪，编号相同时替换原来的扫码枪
+    ///
+    /// * `id` 扫码枪编号，例如工位名称，同时设置为扫码枪的编号(见[`Scanner::id`])
+    pub fn with_scanner(self, id: &str, scanner: Scanner) -> Self {
+        self.scanners.lock().unwrap().insert(
+            id.into(),
+            Managed {
+                scanner: scanner.id(id),
+                forwarder: None,
+            },
+        );
+        self
+    }
+
+    /// 按配置文件创建扫码枪管理器，详见[`crate::FleetConfig`]
+    #[cfg(feature = "serde")]
+    pub fn from_config(path: impl AsRef<std::path::Path>) -> Result<Self, ScannerError> {
+        crate::FleetConfig::load(path).map(ScannerManager::from)
+    }
+
+    /// 设置主备扫码枪工位，详见[`Failover`]
+    pub fn with_failover(self, failover: Failover) -> Self {
+        self.stations.lock().unwrap().push(Station::new(failover));
+        self
+    }
+
+    /// 设置任务监督，启动后自动重启已退出的扫码枪任务，详见[`Supervisor`]
+    pub fn with_supervisor(mut self, supervisor: Supervisor) -> Self {
+        self.supervisor = Some(supervisor);
+        self
+    }
+
+    /// 添加事件输出，同名的输出会被替换
+    ///
+    /// 路由到输出的事件不再进入合并的事件流；输出已满时等待，输出关闭后事件改为进入合并的事件流
+    ///
+    /// * `name` 输出名称，见[`Route::new`]
+    /// * `sink` 接收路由到该输出的事件
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    /// use regex::Regex;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let (wms, mut pallets) = tokio::sync::mpsc::channel(100);
+    /// let (mes, mut serials) = tokio::sync::mpsc::channel(100);
+    /// let manager = ScannerManager::new()
+    ///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+    ///     .with_sink("wms", wms)
+    ///     .with_sink("mes", mes)
+    ///     .with_route(Route::new("wms").with_pattern(Regex::new(r"^00\d{18}$").unwrap()))
+    ///     .with_route(Route::new("mes").with_symbology(Symbology::DataMatrix));
+    /// manager.start().await?;
+    /// while let Some(ev) = pallets.recv().await {
+    ///     println!("托盘标签 {}", ev.event().printable());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_sink(self, name: &str, sink: Sender<FleetEvent>) -> Self {
+        self.add_sink(name, sink);
+        self
+    }
+
+    /// 添加路由规则，按添加顺序匹配，详见[`Route`]
+    pub fn with_route(self, route: Route) -> Self {
+        self.routing.write().unwrap().routes.push(route);
+        self
+    }
+
+    /// 运行中添加事件输出，同名的输出会被替换
+    pub fn add_sink(&self, name: &str, sink: Sender<FleetEvent>) {
+        self.routing
+            .write()
+            .unwrap()
+            .sinks
+            .insert(name.into(), sink);
+    }
+
+    /// 运行中移除事件输出，路由到该输出的事件改为进入合并的事件流
+    pub fn remove_sink(&self, name: &str) -> Option<Sender<FleetEvent>> {
+        self.routing.write().unwrap().sinks.remove(name)
+    }
+
+    /// 运行中替换全部路由规则
+    pub fn set_routes(&self, routes: Vec<Route>) {
+        self.routing.write().unwrap().routes = routes;
+    }
+
+    /// 获取路由规则
+    pub fn routes(&self) -> Vec<Route> {
+        self.routing.read().unwrap().routes.clone()
+    }
+
+    /// 获取工位当前使用的扫码枪编号
+    pub fn active(&self, station: &str) -> Option<String> {
+        let stations = self.stations.lock().unwrap();
+        stations
+            .iter()
+            .find(|s| s.failover().station() == station)
+            .map(|s| s.active().to_owned())
+    }
+
+    /// 软件触发工位当前使用的扫码枪，见[`Scanner::trigger`]
+    pub async fn trigger(
+        &self,
+        station: &str,
+        duration: Option<Duration>,
+    ) -> Result<bool, ScannerError> {
+        let Some(id) = self.active(station) else {
+            return Err(ScannerError::Param(format!(
+                "没有配置工位,station={}",
+                station
+            )));
+        };
+        let Some(scanner) = self.get(&id) else {
+            return Err(ScannerError::Param(format!(
+                "工位的扫码枪不存在,station={},id={}",
+                station, id
+            )));
+        };
+        scanner.trigger(duration).await
+    }
+
+    /// 运行中添加扫码枪，管理器已启动时立即启动该扫码枪
+    ///
+    /// 编号已存在或扫码枪参数错误时返回错误
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run(manager: &ScannerManager) -> Result<(), ScannerError> {
+    /// manager
+    ///     .add("line2-in", Scanner::new(Network::new_client("192.168.2.10", 9004)))
+    ///     .await?;
+    /// if let Some(scanner) = manager.remove("line1-in") {
+    ///     println!("已移除{}", scanner.connector);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add(&self, id: &str, scanner: Scanner) -> Result<(), ScannerError> {
+        let scanner = scanner.id(id);
+        {
+            let mut scanners = self.scanners.lock().unwrap();
+            if scanners.contains_key(id) {
+                return Err(ScannerError::Param(format!("扫码枪编号已存在,id={}", id)));
+            }
+            scanners.insert(
+                id.into(),
+                Managed {
+                    scanner: scanner.clone(),
+                    forwarder: None,
+                },
+            );
+        }
+        if self.running.load(Ordering::Acquire) {
+            if let Err(err) = scanner.start().await.and_then(|r| r) {
+                self.scanners.lock().unwrap().remove(id);
+                return Err(err);
+            }
+            self.forward(id, scanner);
+        }
+        event!(Level::INFO, scanner.id = %id, "添加扫码枪");
+        Ok(())
+    }
+
+    /// 运行中移除扫码枪，停止扫码枪并结束事件转发，返回被移除的扫码枪
+    ///
+    /// 移除前已经转发的事件仍可以通过[`ScannerManager::recv`]接收
+    pub fn remove(&self, id: &str) -> Option<Scanner> {
+        let mut managed = self.scanners.lock().unwrap().remove(id)?;
+        self.stats.lock().unwrap().remove(id);
+        managed.scanner.stop();
+        if let Some(forwarder) = managed.forwarder.take() {
+            forwarder.abort();
+        }
+        event!(Level::INFO, scanner.id = %id, "移除扫码枪");
+        Some(managed.scanner)
+    }
+
+    /// 获取扫码枪的统计，扫码枪不存在时返回 None
+    pub fn stats(&self, id: &str) -> Option<ScannerStats> {
+        if !self.scanners.lock().unwrap().contains_key(id) {
+            return None;
+        }
+        Some(self.stats.lock().unwrap().get(id))
+    }
+
+    /// 获取最近的条码事件(最多100个)，新的在前
+    pub fn recent(&self, limit: usize) -> Vec<FleetEvent> {
+        self.stats.lock().unwrap().recent(limit)
+    }
+
+    /// 获取扫码枪
+    pub fn get(&self, id: &str) -> Option<Scanner> {
+        let scanners = self.scanners.lock().unwrap();
+        scanners.get(id).map(|managed| managed.scanner.clone())
+    }
+
+    /// 获取所有扫码枪编号
+    pub fn ids(&self) -> Vec<String> {
+        self.scanners.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// 扫码枪数量
+    pub fn len(&self) -> usize {
+        self.scanners.lock().unwrap().len()
+    }
+
+    /// 是否没有扫码枪
+    pub fn is_empty(&self) -> bool {
+        self.scanners.lock().unwrap().is_empty()
+    }
+
+    /// 启动所有扫码枪
+    ///
+    /// 任意一台扫码枪参数错误时停止已启动的扫码枪并返回错误
+    pub async fn start(&self) -> Result<(), ScannerError> {
+        self.closing.send_replace(false);
+        let scanners = self
+            .scanners
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, managed)| (id.clone(), managed.scanner.clone()))
+            .collect::<Vec<_>>();
+        for (id, scanner) in scanners {
+            if let Err(err) = scanner.start().await.and_then(|r| r) {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %scanner.connector,
+                    scanner.id = %id,
+                    error = %err,
+                    error.kind = err.kind(),
+                    "扫码枪启动失败",
+                );
+                self.stop();
+                return Err(err);
+            }
+            self.forward(&id, scanner);
+        }
+        self.running.store(true, Ordering::Release);
+        if let Some(supervisor) = &self.supervisor {
+            let handle = tokio::spawn(supervise(supervisor.clone(), Arc::clone(&self.scanners)));
+            if let Some(old) = self.watch.lock().unwrap().replace(handle.abort_handle()) {
+                old.abort();
+            }
+        }
+        event!(Level::INFO, count = self.len(), "扫码枪管理器启动成功");
+        Ok(())
+    }
+
+    /// 停止所有扫码枪
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(watch) = self.watch.lock().unwrap().take() {
+            watch.abort();
+        }
+        let mut scanners = self.scanners.lock().unwrap();
+        for managed in scanners.values_mut() {
+            managed.scanner.stop();
+            if let Some(forwarder) = managed.forwarder.take() {
+                forwarder.abort();
+            }
+        }
+    }
+
+    /// 优雅停止，停止所有扫码枪，并把已经产生的事件全部转发到合并的事件流后返回，
+    /// 用于服务重启前清理
+    ///
+    /// 全部结束返回 true；超过`timeout`时强制结束剩余的任务并返回 false，未转发的事件被丢弃。
+    /// 合并的事件流已满时转发会等待，需要继续调用[`ScannerManager::recv`]接收事件
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run(manager: ScannerManager) {
+    /// tokio::signal::ctrl_c().await.unwrap();
+    /// if !manager.shutdown(Duration::from_secs(5)).await {
+    ///     eprintln!("扫码枪管理器停止超时");
+    /// }
+    /// while let Some(ev) = manager.try_recv() {
+    ///     println!("{} {}", ev.id(), ev.event().printable());
+    /// }
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.running.store(false, Ordering::Release);
+        if let Some(watch) = self.watch.lock().unwrap().take() {
+            watch.abort();
+        }
+        let mut tasks = vec![];
+        let mut forwarders = vec![];
+        for managed in self.scanners.lock().unwrap().values_mut() {
+            tasks.extend(managed.scanner.task.lock().unwrap().clone());
+            managed.scanner.stop();
+            forwarders.extend(managed.forwarder.take());
+        }
+        self.closing.send_replace(true);
+        let finished = tokio::time::timeout(timeout, async {
+            while tasks
+                .iter()
+                .chain(&forwarders)
+                .any(|task| !task.is_finished())
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .is_ok();
+        if finished {
+            event!(Level::INFO, "扫码枪管理器已停止");
+        } else {
+            forwarders.iter().for_each(AbortHandle::abort);
+            event!(
+                Level::WARN,
+                timeout = ?timeout,
+                "扫码枪管理器停止超时,强制停止",
+            );
+        }
+        finished
+    }
+
+    /// 接收所有扫码枪的事件
+    pub async fn recv(&self) -> Option<FleetEvent> {
+        let mut receiver = self.receiver.lock().await;
+        receiver.recv().await
+    }
+
+    /// 订阅所有扫码枪的事件(包括路由到输出的条码)，不影响合并的事件流，
+    /// 每个订阅者都收到全部事件，接收过慢时丢弃最早的事件并返回[`broadcast::error::RecvError::Lagged`]
+    pub fn subscribe(&self) -> broadcast::Receiver<FleetEvent> {
+        self.subscribers.subscribe()
+    }
+
+    /// 不等待地接收事件，没有事件或正在被其它任务接收时返回 None
+    pub fn try_recv(&self) -> Option<FleetEvent> {
+        self.receiver.try_lock().ok()?.try_recv().ok()
+    }
+
+    /// 把扫码枪的事件加上编号后按路由规则转发到输出或合并的事件流，停止时转发完队列中的事件后结束
+    fn forward(&self, id: &str, scanner: Scanner) {
+        let mut scanners = self.scanners.lock().unwrap();
+        let Some(managed) = scanners.get_mut(id) else {
+            return;
+        };
+        let sender = self.sender.clone();
+        let stations = Arc::clone(&self.stations);
+        let routing = Arc::clone(&self.routing);
+        let stats = Arc::clone(&self.stats);
+        let subscribers = self.subscribers.clone();
+        let mut closing = self.closing.subscribe();
+        let handle = tokio::spawn(async move {
+            loop {
+                let ev = tokio::select! {
+                    ev = scanner.recv_tagged() => Some(ev),
+                    _ = closing.wait_for(|closing| *closing) => None,
+                };
+                let ev = match ev {
+                    Some(ev) => ev,
+                    None => scanner.try_recv_tagged(),
+                };
+                let Some(ev) = ev else {
+                    break;
+                };
+                let switched = stations
+                    .lock()
+                    .unwrap()
+                    .iter_mut()
+                    .filter_map(|station| {
+                        let switch = station.observe(ev.id(), ev.event())?;
+                        Some(FleetEvent::new(station.failover().station(), switch))
+                    })
+                    .collect::<Vec<_>>();
+                stats.lock().unwrap().record(&ev);
+                if subscribers.receiver_count() > 0 {
+                    let _ = subscribers.send(ev.clone());
+                }
+                let routed = routing.read().unwrap().route(&ev);
+                let ev = match routed {
+                    Some((_, Some(sink))) => match sink.send(ev).await {
+                        Ok(()) => None,
+                        Err(err) => {
+                            event!(
+                                Level::WARN,
+                                scanner.id = %err.0.id(),
+                                "事件输出已关闭,转入合并的事件流",
+                            );
+                            Some(err.0)
+                        }
+                    },
+                    Some((name, None)) => {
+                        event!(
+                            Level::WARN,
+                            scanner.id = %ev.id(),
+                            output = %name,
+                            "事件输出不存在,转入合并的事件流",
+                        );
+                        Some(ev)
+                    }
+                    None => Some(ev),
+                };
+                if let Some(ev) = ev {
+                    if sender.send(ev).await.is_err() {
+                        break;
+                    }
+                }
+                for ev in switched {
+                    let _ = subscribers.send(ev.clone());
+                    let _ = sender.send(ev).await;
+                }
+            }
+        });
+        if let Some(old) = managed.forwarder.replace(handle.abort_handle()) {
+            old.abort();
+        }
+    }
+}
+
+/// 定期检查扫码枪任务，冷却后重新启动已退出的任务
+async fn supervise(
+    supervisor: Supervisor,
+    scanners: Arc<std::sync::Mutex<BTreeMap<String, Managed>>>,
+) {
+    let mut restarts: HashMap<String, Restart> = HashMap::new();
+    let mut interval = tokio::time::interval(supervisor.interval());
+    loop {
+        interval.tick().await;
+        let dead = {
+            let scanners = scanners.lock().unwrap();
+            restarts.retain(|id, _| scanners.contains_key(id));
+            scanners
+                .iter()
+                .filter(|(_, managed)| managed.scanner.is_dead())
+                .map(|(id, managed)| (id.clone(), managed.scanner.clone()))
+                .collect::<Vec<_>>()
+        };
+        let now = Instant::now();
+        for (id, scanner) in dead {
+            let addr = scanner.tag(&scanner.connector);
+            match supervisor.check(restarts.entry(id).or_default(), now) {
+                Verdict::Wait => {}
+                Verdict::Restart(count) => {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        restarts = count,
+                        "扫码枪任务已退出,重新启动",
+                    );
+                    match scanner.start().await.and_then(|r| r) {
+                        Ok(()) => scanner.emit(&addr, ScanEvent::Restarted(count)),
+                        Err(err) => {
+                            event!(
+                                Level::ERROR,
+                                scanner.addr = %addr,
+                                error = %err,
+                                error.kind = err.kind(),
+                                "扫码枪重新启动失败",
+                            )
+                        }
+                    }
+                }
+                Verdict::GiveUp(count) => event!(
+                    Level::ERROR,
+                    scanner.addr = %addr,
+                    restarts = count,
+                    "连续重启,放弃重启",
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<crate::FleetConfig> for ScannerManager {
+    fn from(config: crate::FleetConfig) -> Self {
+        let manager = config
+            .scanners()
+            .iter()
+            .fold(ScannerManager::new(), |manager, scanner| {
+                manager.with_scanner(scanner.id(), scanner.scanner())
+            });
+        config.failover().iter().fold(manager, |manager, failover| {
+            manager.with_failover(failover.clone())
+        })
+    }
+}
+
+impl Drop for ScannerManager {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn manager() {
+        let (a, b) = (MockConnector::new(), MockConnector::new());
+        let manager = ScannerManager::new()
+            .with_scanner("A", Scanner::new(a.clone()).label("line", "1"))
+            .with_scanner("B", Scanner::new(b.clone()));
+        assert_eq!(manager.ids(), ["A", "B"]);
+        manager.start().await.unwrap();
+        let mut connected = vec![];
+        for _ in 0..2 {
+            let ev = manager.recv().await.unwrap();
+            assert!(matches!(ev.event(), ScanEvent::Connected));
+            connected.push(ev.id().to_owned());
+        }
+        connected.sort();
+        assert_eq!(connected, ["A", "B"]);
+        b.push("SN0002");
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "B");
+        assert_eq!(ev.label("line"), None);
+        assert_eq!(ev.event().as_str_lossy().unwrap(), "SN0002");
+        a.push("SN0001");
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "A");
+        assert_eq!(ev.label("line"), Some("1"));
+        assert_eq!(manager.get("A").unwrap().get_id(), Some("A"));
+        // 运行中增减扫码枪
+        let c = MockConnector::new();
+        manager.add("C", Scanner::new(c.clone())).await.unwrap();
+        assert!(manager.add("C", Scanner::new(c.clone())).await.is_err());
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.id(), "C");
+        assert!(matches!(ev.event(), ScanEvent::Connected));
+        let removed = manager.remove("B").unwrap();
+        assert!(!removed.is_running());
+        assert!(manager.remove("B").is_none());
+        assert_eq!(manager.ids(), ["A", "C"]);
+        c.push("SN0004");
+        assert_eq!(manager.recv().await.unwrap().id(), "C");
+        // 停止后不再转发事件
+        manager.stop();
+        assert!(!manager.get("A").unwrap().is_running());
+        a.push("SN0003");
+        let ev = tokio::time::timeout(std::time::Duration::from_millis(100), manager.recv()).await;
+        assert!(ev.is_err());
+    }
+
+    #[tokio::test]
+    async fn manager_shutdown() {
+        use std::time::Duration;
+
+        let mock = MockConnector::new();
+        let manager = ScannerManager::new()
+            .with_scanner("A", Scanner::new(mock.clone()))
+            .with_scanner("B", Scanner::new(MockConnector::new()));
+        manager.start().await.unwrap();
+        for i in 0..3 {
+            mock.push(format!("SN000{}", i));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(manager.shutdown(Duration::from_secs(1)).await);
+        assert!(!manager.get("A").unwrap().is_running());
+        // 停止前产生的事件全部转发
+        let events = std::iter::from_fn(|| manager.try_recv()).collect::<Vec<_>>();
+        let barcodes = events
+            .iter()
+            .filter_map(|ev| ev.event().as_str_lossy())
+            .collect::<Vec<_>>();
+        assert_eq!(barcodes, ["SN0000", "SN0001", "SN0002"]);
+        assert_eq!(events.len(), 5);
+        // 停止后可以重新启动
+        manager.start().await.unwrap();
+        mock.push("SN0003");
+        loop {
+            let ev = manager.recv().await.unwrap();
+            if let Some(text) = ev.event().as_str_lossy() {
+                assert_eq!(text, "SN0003");
+                break;
+            }
+        }
+    }
+}
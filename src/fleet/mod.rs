@@ -0,0 +1,8 @@
+#[cfg(feature = "serde")]
+pub mod config;
+pub mod event;
+pub mod failover;
+pub mod manager;
+pub mod route;
+pub mod stats;
+pub mod supervisor;
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use tokio::sync::mpsc::Sender;
+
+use crate::{FleetEvent, Symbology};
+
+/// 事件路由规则，把符合条件的条码事件发送到指定名称的输出
+///
+/// 同时设置多个条件时需要全部满足，同一条件的多个值满足其一即可。
+/// 只路由条码事件(包括未读、比对结果)，连接状态等其它事件仍进入合并的事件流
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+/// use regex::Regex;
+///
+/// // 托盘标签(SSCC)发送到 WMS，产线扫码枪的 DataMatrix 序列号发送到 MES
+/// let pallet = Route::new("wms").with_pattern(Regex::new(r"^00\d{18}$").unwrap());
+/// let serial = Route::new("mes")
+///     .with_scanner("line1-in")
+///     .with_scanner("line1-out")
+///     .with_symbology(Symbology::DataMatrix);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Route {
+    sink: String,
+    pattern: Option<Regex>,
+    scanners: Vec<String>,
+    symbologies: Vec<Symbology>,
+}
+
+impl Route {
+    /// 创建路由规则，没有条件时匹配所有条码
+    ///
+    /// * `sink` 输出名称，见[`crate::ScannerManager::with_sink`]
+    pub fn new(sink: &str) -> Self {
+        Route {
+            sink: sink.into(),
+            pattern: None,
+            scanners: vec![],
+            symbologies: vec![],
+        }
+    }
+
+    /// 按条码内容匹配
+    pub fn with_pattern(mut self, pattern: Regex) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// 按扫码枪编号匹配
+    pub fn with_scanner(mut self, id: &str) -> Self {
+        self.scanners.push(id.into());
+        self
+    }
+
+    /// 按码制匹配，需要扫码枪输出 AIM 符号标识，见[`Symbology::from_aim`]
+    pub fn with_symbology(mut self, symbology: Symbology) -> Self {
+        self.symbologies.push(symbology);
+        self
+    }
+
+    /// 获取输出名称
+    pub fn sink(&self) -> &str {
+        &self.sink
+    }
+
+    /// 事件是否符合规则
+    pub fn matches(&self, ev: &FleetEvent) -> bool {
+        let Some(barcode) = ev.event().barcode() else {
+            return false;
+        };
+        if !self.scanners.is_empty() && !self.scanners.iter().any(|id| id == ev.id()) {
+            return false;
+        }
+        if !self.symbologies.is_empty()
+            && !barcode
+                .symbology()
+                .is_some_and(|symbology| self.symbologies.contains(&symbology))
+        {
+            return false;
+        }
+        match &self.pattern {
+            Some(pattern) => pattern.is_match(&barcode.text()),
+            None => true,
+        }
+    }
+}
+
+/// 路由规则和输出
+#[derive(Debug, Default)]
+pub(crate) struct Routing {
+    pub(crate) routes: Vec<Route>,
+    pub(crate) sinks: HashMap<String, Sender<FleetEvent>>,
+}
+
+impl Routing {
+    /// 按第一条符合的规则查找输出，返回(输出名称，输出)
+    pub(crate) fn route(&self, ev: &FleetEvent) -> Option<(String, Option<Sender<FleetEvent>>)> {
+        let route = self.routes.iter().find(|route| route.matches(ev))?;
+        Some((route.sink.clone(), self.sinks.get(&route.sink).cloned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn matches() {
+        let ev = |id: &str, text: &str| {
+            FleetEvent::new(
+                id,
+                ScanEvent::Barcode(Barcode::from_text(text, Encoding::Utf8)),
+            )
+        };
+        let route = Route::new("wms").with_pattern(Regex::new(r"^00\d{18}$").unwrap());
+        assert!(route.matches(&ev("a", "00123456789012345678")));
+        assert!(!route.matches(&ev("a", "SN0001")));
+        assert!(!route.matches(&FleetEvent::new("a", ScanEvent::Connected)));
+
+        let route = Route::new("mes")
+            .with_scanner("a")
+            .with_symbology(Symbology::DataMatrix);
+        assert!(route.matches(&ev("a", "]d2SN0001")));
+        assert!(!route.matches(&ev("b", "]d2SN0001")));
+        assert!(!route.matches(&ev("a", "]C1SN0001")));
+        assert!(!route.matches(&ev("a", "SN0001")));
+        assert!(Route::new("all").matches(&ev("b", "SN0001")));
+    }
+
+    #[tokio::test]
+    async fn manager_route() {
+        use regex::Regex;
+
+        let mock = MockConnector::new();
+        let (wms, mut pallets) = tokio::sync::mpsc::channel(10);
+        let manager = ScannerManager::new()
+            .with_scanner("A", Scanner::new(mock.clone()))
+            .with_sink("wms", wms)
+            .with_route(Route::new("wms").with_pattern(Regex::new(r"^00\d{18}$").unwrap()));
+        manager.start().await.unwrap();
+        assert!(matches!(
+            manager.recv().await.unwrap().event(),
+            ScanEvent::Connected
+        ));
+        mock.push("00123456789012345678");
+        mock.push("SN0001");
+        let ev = pallets.recv().await.unwrap();
+        assert_eq!(ev.id(), "A");
+        assert_eq!(ev.event().as_str_lossy().unwrap(), "00123456789012345678");
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.event().as_str_lossy().unwrap(), "SN0001");
+
+        // 运行中修改规则，输出不存在时进入合并的事件流
+        manager.set_routes(vec![Route::new("mes").with_scanner("A")]);
+        mock.push("SN0002");
+        let ev = manager.recv().await.unwrap();
+        assert_eq!(ev.event().as_str_lossy().unwrap(), "SN0002");
+        let (mes, mut serials) = tokio::sync::mpsc::channel(10);
+        manager.add_sink("mes", mes);
+        mock.push("SN0003");
+        let ev = serials.recv().await.unwrap();
+        assert_eq!(ev.event().as_str_lossy().unwrap(), "SN0003");
+        assert_eq!(manager.routes().len(), 1);
+        assert!(pallets.try_recv().is_err());
+    }
+}
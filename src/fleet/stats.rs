@@ -0,0 +1,156 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+
+use crate::{Barcode, FleetEvent, ScanEvent};
+
+/// 最近条码的保存数量
+const RECENT: usize = 100;
+
+/// 扫码枪统计，由[`crate::ScannerManager`]按转发的事件统计
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScannerStats {
+    scans: u64,
+    no_reads: u64,
+    mismatches: u64,
+    errors: u64,
+    disconnects: u64,
+    connected: Option<bool>,
+    last_scan: Option<SystemTime>,
+    last_barcode: Option<Barcode>,
+}
+
+impl ScannerStats {
+    /// 条码数量(包括比对结果)
+    pub fn scans(&self) -> u64 {
+        self.scans
+    }
+
+    /// 未读次数
+    pub fn no_reads(&self) -> u64 {
+        self.no_reads
+    }
+
+    /// 比对不一致次数
+    pub fn mismatches(&self) -> u64 {
+        self.mismatches
+    }
+
+    /// 错误次数
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// 断开次数(包括无响应)
+    pub fn disconnects(&self) -> u64 {
+        self.disconnects
+    }
+
+    /// 是否已连接，没有收到连接事件时为`None`
+    pub fn connected(&self) -> Option<bool> {
+        self.connected
+    }
+
+    /// 最后一次扫码的时间
+    pub fn last_scan(&self) -> Option<SystemTime> {
+        self.last_scan
+    }
+
+    /// 最后一次扫到的条码(不包括未读)
+    pub fn last_barcode(&self) -> Option<&Barcode> {
+        self.last_barcode.as_ref()
+    }
+
+    fn record(&mut self, ev: &ScanEvent) {
+        match ev {
+            ScanEvent::Barcode(_) | ScanEvent::Match(_) => self.scans += 1,
+            ScanEvent::Mismatch(_) => {
+                self.scans += 1;
+                self.mismatches += 1;
+            }
+            ScanEvent::NoRead(_) => self.no_reads += 1,
+            ScanEvent::Error(_) => self.errors += 1,
+            ScanEvent::Connected | ScanEvent::Restarted(_) => self.connected = Some(true),
+            ScanEvent::Disconnected | ScanEvent::Unhealthy(_) => {
+                self.disconnects += 1;
+                self.connected = Some(false);
+            }
+            ScanEvent::Failed(_) => self.connected = Some(false),
+            ScanEvent::Failover(_) | ScanEvent::Failback(_) => {}
+        }
+        if let Some(barcode) = ev.barcode() {
+            self.last_scan = Some(SystemTime::now());
+            if !matches!(ev, ScanEvent::NoRead(_)) {
+                self.last_barcode = Some(barcode.clone());
+            }
+        }
+    }
+}
+
+/// 所有扫码枪的统计和最近的条码
+#[derive(Debug, Default)]
+pub(crate) struct Stats {
+    scanners: HashMap<String, ScannerStats>,
+    recent: VecDeque<FleetEvent>,
+}
+
+impl Stats {
+    pub(crate) fn record(&mut self, ev: &FleetEvent) {
+        self.scanners
+            .entry(ev.id().to_owned())
+            .or_default()
+            .record(ev.event());
+        if ev.event().barcode().is_some() {
+            if self.recent.len() >= RECENT {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(ev.clone());
+        }
+    }
+
+    pub(crate) fn get(&self, id: &str) -> ScannerStats {
+        self.scanners.get(id).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn remove(&mut self, id: &str) {
+        self.scanners.remove(id);
+    }
+
+    /// 最近的条码，新的在前
+    pub(crate) fn recent(&self, limit: usize) -> Vec<FleetEvent> {
+        self.recent.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use crate::prelude::*;
+
+    #[test]
+    fn record() {
+        let mut stats = Stats::default();
+        let barcode = |text| Barcode::from_text(text, Encoding::Utf8);
+        stats.record(&FleetEvent::new("a", ScanEvent::Connected));
+        stats.record(&FleetEvent::new("a", ScanEvent::Barcode(barcode("SN0001"))));
+        stats.record(&FleetEvent::new(
+            "a",
+            ScanEvent::Mismatch(barcode("SN0002")),
+        ));
+        stats.record(&FleetEvent::new("a", ScanEvent::Disconnected));
+        stats.record(&FleetEvent::new("b", ScanEvent::NoRead(barcode("NG"))));
+        let a = stats.get("a");
+        assert_eq!(a.scans(), 2);
+        assert_eq!(a.mismatches(), 1);
+        assert_eq!(a.disconnects(), 1);
+        assert_eq!(a.connected(), Some(false));
+        assert!(a.last_scan().is_some());
+        assert_eq!(a.last_barcode().unwrap().text(), "SN0002");
+        assert_eq!(stats.get("b").no_reads(), 1);
+        assert!(stats.get("b").last_barcode().is_none());
+        assert_eq!(stats.get("c"), ScannerStats::default());
+        let recent = stats.recent(2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].id(), "b");
+        assert_eq!(recent[1].event().as_str_lossy().unwrap(), "SN0002");
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::RetryPolicy;
+
+/// 扫码枪任务监督，由[`crate::ScannerManager`]定期检查各扫码枪的后台任务
+///
+/// 扫码枪出现致命错误、超过最大重试次数或任务崩溃(panic)后会停止重连，
+/// 监督发现任务已退出后等待冷却时间再重新启动，并发出[`crate::ScanEvent::Restarted`]事件。
+/// 冷却时间按`cooldown`策略计算，默认从1秒开始指数增长，最长1分钟；
+/// 重启后持续运行超过`reset`时长则冷却时间清零。
+/// 冷却策略设置了最大重试次数时，连续重启超过该次数后放弃
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let supervisor = Supervisor::new()
+///     .with_interval(Duration::from_millis(500))
+///     .with_cooldown(RetryPolicy::exponential(Duration::from_secs(2), Duration::from_secs(120)).max_retries(10));
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_supervisor(supervisor);
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Supervisor {
+    interval: Duration,
+    cooldown: RetryPolicy,
+    reset: Duration,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Supervisor {
+            interval: Duration::from_secs(1),
+            cooldown: RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)),
+            reset: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Supervisor {
+    /// 创建任务监督，每秒检查一次
+    pub fn new() -> Self {
+        Supervisor::default()
+    }
+
+    /// 设置检查间隔
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 设置重启前的冷却策略
+    pub fn with_cooldown(mut self, cooldown: RetryPolicy) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// 设置重启后持续运行多久冷却时间清零
+    pub fn with_reset(mut self, reset: Duration) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    /// 获取检查间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 获取冷却策略
+    pub fn cooldown(&self) -> &RetryPolicy {
+        &self.cooldown
+    }
+
+    /// 获取冷却时间清零的运行时长
+    pub fn reset(&self) -> Duration {
+        self.reset
+    }
+
+    /// 发现任务已退出，返回下一步动作
+    pub(crate) fn check(&self, restart: &mut Restart, now: Instant) -> Verdict {
+        if restart.given_up {
+            return Verdict::Wait;
+        }
+        let due = match restart.due {
+            Some(due) => due,
+            None => {
+                if restart
+                    .last
+                    .is_some_and(|last| now.duration_since(last) >= self.reset)
+                {
+                    restart.count = 0;
+                }
+                if self.cooldown.exhausted(restart.count + 1) {
+                    restart.given_up = true;
+                    return Verdict::GiveUp(restart.count);
+                }
+                let due = now + self.cooldown.delay(restart.count + 1);
+                restart.due = Some(due);
+                due
+            }
+        };
+        if now < due {
+            return Verdict::Wait;
+        }
+        restart.due = None;
+        restart.count += 1;
+        restart.last = Some(now);
+        Verdict::Restart(restart.count)
+    }
+}
+
+/// 一台扫码枪的重启状态
+#[derive(Debug, Default)]
+pub(crate) struct Restart {
+    /// 连续重启次数
+    count: u32,
+    /// 上次重启时间
+    last: Option<Instant>,
+    /// 冷却结束时间
+    due: Option<Instant>,
+    /// 是否已放弃重启
+    given_up: bool,
+}
+
+/// 监督检查结果
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    /// 冷却中
+    Wait,
+    /// 重新启动，内容为连续重启次数
+    Restart(u32),
+    /// 超过最大重启次数，放弃重启
+    GiveUp(u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::{Restart, Supervisor, Verdict};
+    use crate::prelude::*;
+    use crate::RetryPolicy;
+
+    #[test]
+    fn check() {
+        let secs = Duration::from_secs;
+        let supervisor = Supervisor::new()
+            .with_cooldown(RetryPolicy::exponential(secs(1), secs(4)).max_retries(3))
+            .with_reset(secs(10));
+        let start = Instant::now();
+        let mut restart = Restart::default();
+        assert_eq!(supervisor.check(&mut restart, start), Verdict::Wait);
+        assert_eq!(
+            supervisor.check(&mut restart, start + secs(1)),
+            Verdict::Restart(1)
+        );
+        // 第二次冷却2秒
+        let dead = start + secs(2);
+        assert_eq!(supervisor.check(&mut restart, dead), Verdict::Wait);
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(1)),
+            Verdict::Wait
+        );
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(2)),
+            Verdict::Restart(2)
+        );
+        // 持续运行超过10秒后清零
+        let dead = dead + secs(20);
+        assert_eq!(supervisor.check(&mut restart, dead), Verdict::Wait);
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(1)),
+            Verdict::Restart(1)
+        );
+        let dead = dead + secs(1);
+        assert_eq!(supervisor.check(&mut restart, dead), Verdict::Wait);
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(2)),
+            Verdict::Restart(2)
+        );
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(2)),
+            Verdict::Wait
+        );
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(6)),
+            Verdict::Restart(3)
+        );
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(7)),
+            Verdict::GiveUp(3)
+        );
+        assert_eq!(
+            supervisor.check(&mut restart, dead + secs(60)),
+            Verdict::Wait
+        );
+    }
+
+    #[tokio::test]
+    async fn supervisor() {
+        use std::time::Duration;
+
+        // 没有服务监听的端口，第一次连接失败后任务退出
+        let retry = RetryPolicy::fixed(Duration::from_millis(10)).max_retries(0);
+        let supervisor = Supervisor::new()
+            .with_interval(Duration::from_millis(20))
+            .with_cooldown(RetryPolicy::fixed(Duration::from_millis(10)).max_retries(2));
+        let manager = ScannerManager::new()
+            .with_scanner(
+                "A",
+                Scanner::new(Network::new_client("127.0.0.1", 6007)).retry(retry),
+            )
+            .with_supervisor(supervisor);
+        manager.start().await.unwrap();
+        for count in 1..=2 {
+            // 连接失败发出错误事件，放弃重连发出失败事件，之后由监督重新启动
+            let ev = tokio::time::timeout(Duration::from_secs(5), manager.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(matches!(ev.event(), ScanEvent::Error(_)));
+            let ev = tokio::time::timeout(Duration::from_secs(5), manager.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(matches!(ev.event(), ScanEvent::Failed(_)));
+            let ev = tokio::time::timeout(Duration::from_secs(5), manager.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(ev.id(), "A");
+            assert!(matches!(ev.event(), ScanEvent::Restarted(n) if *n == count));
+        }
+        // 超过最大重启次数后放弃
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!manager.get("A").unwrap().is_running());
+        manager.stop();
+    }
+}
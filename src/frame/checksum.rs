@@ -0,0 +1,135 @@
+use crate::ScannerError;
+
+/// 校验算法
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChecksumAlgorithm {
+    /// 异或校验(BCC)，1 字节
+    Xor,
+    /// 纵向冗余校验(LRC)，所有字节求和后取补码，1 字节
+    Lrc,
+    /// CRC-16/MODBUS，2 字节，低字节在前
+    Crc16,
+}
+
+impl ChecksumAlgorithm {
+    /// 校验码长度(字节)
+    pub fn size(&self) -> usize {
+        match self {
+            ChecksumAlgorithm::Xor | ChecksumAlgorithm::Lrc => 1,
+            ChecksumAlgorithm::Crc16 => 2,
+        }
+    }
+
+    /// 计算校验码
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert_eq!(ChecksumAlgorithm::Xor.calculate(b"\x01\x02\x04"), vec![0x07]);
+    /// assert_eq!(ChecksumAlgorithm::Lrc.calculate(b"\x01\x02\x04"), vec![0xF9]);
+    /// assert_eq!(ChecksumAlgorithm::Crc16.calculate(b"123456789"), vec![0x37, 0x4B]);
+    /// ```
+    pub fn calculate(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            ChecksumAlgorithm::Xor => vec![data.iter().fold(0u8, |acc, b| acc ^ b)],
+            ChecksumAlgorithm::Lrc => {
+                let sum = data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+                vec![sum.wrapping_neg()]
+            }
+            ChecksumAlgorithm::Crc16 => {
+                let mut crc = 0xFFFFu16;
+                for b in data {
+                    crc ^= *b as u16;
+                    for _ in 0..8 {
+                        if crc & 0x0001 != 0 {
+                            crc = (crc >> 1) ^ 0xA001;
+                        } else {
+                            crc >>= 1;
+                        }
+                    }
+                }
+                crc.to_le_bytes().to_vec()
+            }
+        }
+    }
+}
+
+/// 校验码位置
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChecksumPosition {
+    /// 校验码位于帧头
+    Head,
+    /// 校验码位于帧尾
+    Tail,
+}
+
+/// 帧校验器
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checksum {
+    algorithm: ChecksumAlgorithm,
+    position: ChecksumPosition,
+}
+
+impl Checksum {
+    /// 创建帧校验器
+    ///
+    /// * `algorithm` 校验算法
+    /// * `position` 校验码位置
+    pub fn new(algorithm: ChecksumAlgorithm, position: ChecksumPosition) -> Self {
+        Checksum {
+            algorithm,
+            position,
+        }
+    }
+
+    /// 获取校验算法
+    pub fn algorithm(&self) -> &ChecksumAlgorithm {
+        &self.algorithm
+    }
+
+    /// 获取校验码位置
+    pub fn position(&self) -> &ChecksumPosition {
+        &self.position
+    }
+
+    /// 校验一帧数据，成功后返回去掉校验码的数据
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let checksum = Checksum::new(ChecksumAlgorithm::Xor, ChecksumPosition::Tail);
+    /// assert_eq!(checksum.verify(b"\x01\x02\x04\x07").unwrap(), b"\x01\x02\x04");
+    /// assert!(checksum.verify(b"\x01\x02\x04\x08").is_err());
+    /// ```
+    pub fn verify<'a>(&self, frame: &'a [u8]) -> Result<&'a [u8], ScannerError> {
+        let len = self.algorithm.size();
+        if frame.len() <= len {
+            return Err(ScannerError::Checksum(format!(
+                "帧长度不足,len={}",
+                frame.len()
+            )));
+        }
+        let (data, code) = match self.position {
+            ChecksumPosition::Head => {
+                let (code, data) = frame.split_at(len);
+                (data, code)
+            }
+            ChecksumPosition::Tail => frame.split_at(frame.len() - len),
+        };
+        let expected = self.algorithm.calculate(data);
+        if expected != code {
+            return Err(ScannerError::Checksum(format!(
+                "{:?}校验失败,期望={:02X?},实际={:02X?}",
+                self.algorithm, expected, code
+            )));
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod checksum;
+pub(crate) mod split;
@@ -0,0 +1,55 @@
+/// 默认的帧分隔符：回车、换行
+pub(crate) const DELIMITERS: &[u8] = b"\r\n";
+
+use std::time::Duration;
+
+/// 没有收到分隔符的数据最多缓存的字节数，超过后作为一帧处理
+pub(crate) const MAX_FRAME: usize = 64 * 1024;
+
+/// 没有以分隔符结尾的数据等待后续数据的时间，超过后作为一帧处理(例如菜单指令的应答)
+pub(crate) const FRAME_GAP: Duration = Duration::from_millis(100);
+
+/// 数据中以分隔符结尾的完整部分的长度，之后的数据是还没有收完的帧
+///
+/// 分隔符为空时整块数据都是完整的
+pub(crate) fn complete(data: &[u8], delimiters: &[u8]) -> usize {
+    if delimiters.is_empty() {
+        return data.len();
+    }
+    data.iter()
+        .rposition(|b| delimiters.contains(b))
+        .map_or(0, |i| i + 1)
+}
+
+/// 按分隔符把一次读取到的数据拆成帧，丢弃空帧，所有连接方式共用
+///
+/// 分隔符为空时整块数据作为一帧
+pub(crate) fn split<'a>(data: &'a [u8], delimiters: &'a [u8]) -> impl Iterator<Item = &'a [u8]> {
+    let whole = delimiters.is_empty();
+    data.split(move |b| !whole && delimiters.contains(b))
+        .filter(|frame| !frame.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_frames() {
+        let frames = split(b"SN0001\r\nSN0002\r\n\r\nSN0003", DELIMITERS).collect::<Vec<_>>();
+        assert_eq!(frames, [&b"SN0001"[..], b"SN0002", b"SN0003"]);
+        assert_eq!(split(b"\r\n", DELIMITERS).count(), 0);
+        let frames = split(b"A;B\r\n", b";").collect::<Vec<_>>();
+        assert_eq!(frames, [&b"A"[..], b"B\r\n"]);
+        let frames = split(b"A\rB", b"").collect::<Vec<_>>();
+        assert_eq!(frames, [&b"A\rB"[..]]);
+    }
+
+    #[test]
+    fn complete_frames() {
+        assert_eq!(complete(b"SN0001\r\nSN00", DELIMITERS), 8);
+        assert_eq!(complete(b"SN00", DELIMITERS), 0);
+        assert_eq!(complete(b"SN0001\r", DELIMITERS), 7);
+        assert_eq!(complete(b"A\rB", b""), 3);
+    }
+}
@@ -0,0 +1,150 @@
+/// 应用标识符数据长度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AiLength {
+    /// 定长(纯数字)
+    Fixed(usize),
+    /// 变长，最大长度
+    Variable(usize),
+}
+
+/// 应用标识符(AI)定义
+#[derive(Clone, Copy, Debug)]
+pub struct AiSpec {
+    /// AI 前缀，例如 `310` 对应 `3100`~`3109`
+    pub prefix: &'static str,
+    /// AI 位数
+    pub digits: usize,
+    /// 数据长度
+    pub length: AiLength,
+    /// 数据名称
+    pub title: &'static str,
+}
+
+const fn spec(
+    prefix: &'static str,
+    digits: usize,
+    length: AiLength,
+    title: &'static str,
+) -> AiSpec {
+    AiSpec {
+        prefix,
+        digits,
+        length,
+        title,
+    }
+}
+
+use AiLength::{Fixed, Variable};
+
+/// 常用 GS1 应用标识符
+const SPECS: &[AiSpec] = &[
+    spec("00", 2, Fixed(18), "SSCC"),
+    spec("01", 2, Fixed(14), "GTIN"),
+    spec("02", 2, Fixed(14), "CONTENT"),
+    spec("10", 2, Variable(20), "BATCH/LOT"),
+    spec("11", 2, Fixed(6), "PROD DATE"),
+    spec("12", 2, Fixed(6), "DUE DATE"),
+    spec("13", 2, Fixed(6), "PACK DATE"),
+    spec("15", 2, Fixed(6), "BEST BEFORE"),
+    spec("16", 2, Fixed(6), "SELL BY"),
+    spec("17", 2, Fixed(6), "USE BY"),
+    spec("20", 2, Fixed(2), "VARIANT"),
+    spec("21", 2, Variable(20), "SERIAL"),
+    spec("22", 2, Variable(20), "CPV"),
+    spec("235", 3, Variable(28), "TPX"),
+    spec("240", 3, Variable(30), "ADDITIONAL ID"),
+    spec("241", 3, Variable(30), "CUST. PART No."),
+    spec("242", 3, Variable(6), "MTO VARIANT"),
+    spec("243", 3, Variable(20), "PCN"),
+    spec("250", 3, Variable(30), "SECONDARY SERIAL"),
+    spec("251", 3, Variable(30), "REF. TO SOURCE"),
+    spec("253", 3, Variable(30), "GDTI"),
+    spec("254", 3, Variable(20), "GLN EXTENSION COMPONENT"),
+    spec("255", 3, Variable(25), "GCN"),
+    spec("30", 2, Variable(8), "VAR. COUNT"),
+    spec("31", 4, Fixed(6), "MEASURE (METRIC)"),
+    spec("32", 4, Fixed(6), "MEASURE (IMPERIAL)"),
+    spec("33", 4, Fixed(6), "MEASURE (LOGISTIC, METRIC)"),
+    spec("34", 4, Fixed(6), "MEASURE (LOGISTIC, IMPERIAL)"),
+    spec("35", 4, Fixed(6), "AREA/VOLUME (IMPERIAL)"),
+    spec("36", 4, Fixed(6), "VOLUME (IMPERIAL)"),
+    spec("37", 2, Variable(8), "COUNT"),
+    spec("390", 4, Variable(15), "AMOUNT"),
+    spec("391", 4, Variable(18), "AMOUNT (ISO)"),
+    spec("392", 4, Variable(15), "PRICE"),
+    spec("393", 4, Variable(18), "PRICE (ISO)"),
+    spec("394", 4, Fixed(4), "PRCNT OFF"),
+    spec("395", 4, Fixed(6), "PRICE/UoM"),
+    spec("400", 3, Variable(30), "ORDER NUMBER"),
+    spec("401", 3, Variable(30), "GINC"),
+    spec("402", 3, Fixed(17), "GSIN"),
+    spec("403", 3, Variable(30), "ROUTE"),
+    spec("41", 3, Fixed(13), "GLN"),
+    spec("420", 3, Variable(20), "SHIP TO POST"),
+    spec("421", 3, Variable(12), "SHIP TO POST (ISO)"),
+    spec("422", 3, Fixed(3), "ORIGIN"),
+    spec("423", 3, Variable(15), "COUNTRY - INITIAL PROCESS"),
+    spec("424", 3, Fixed(3), "COUNTRY - PROCESS"),
+    spec("425", 3, Variable(15), "COUNTRY - DISASSEMBLY"),
+    spec("426", 3, Fixed(3), "COUNTRY - FULL PROCESS"),
+    spec("427", 3, Variable(3), "ORIGIN SUBDIVISION"),
+    spec("7001", 4, Fixed(13), "NSN"),
+    spec("7002", 4, Variable(30), "MEAT CUT"),
+    spec("7003", 4, Fixed(10), "EXPIRY TIME"),
+    spec("7004", 4, Variable(4), "ACTIVE POTENCY"),
+    spec("7005", 4, Variable(12), "CATCH AREA"),
+    spec("7006", 4, Fixed(6), "FIRST FREEZE DATE"),
+    spec("7007", 4, Variable(12), "HARVEST DATE"),
+    spec("7008", 4, Variable(3), "AQUATIC SPECIES"),
+    spec("7009", 4, Variable(10), "FISHING GEAR TYPE"),
+    spec("7010", 4, Variable(2), "PROD METHOD"),
+    spec("7020", 4, Variable(20), "REFURB LOT"),
+    spec("7021", 4, Variable(20), "FUNC STAT"),
+    spec("7022", 4, Variable(20), "REV STAT"),
+    spec("7023", 4, Variable(30), "GIAI - ASSEMBLY"),
+    spec("71", 3, Variable(20), "NHRN"),
+    spec("7240", 4, Variable(20), "PROTOCOL"),
+    spec("8001", 4, Fixed(14), "DIMENSIONS"),
+    spec("8002", 4, Variable(20), "CMT No."),
+    spec("8003", 4, Variable(30), "GRAI"),
+    spec("8004", 4, Variable(30), "GIAI"),
+    spec("8005", 4, Fixed(6), "PRICE PER UNIT"),
+    spec("8006", 4, Fixed(18), "ITIP"),
+    spec("8007", 4, Variable(34), "IBAN"),
+    spec("8008", 4, Variable(12), "PROD TIME"),
+    spec("8009", 4, Variable(50), "OPTSEN"),
+    spec("8010", 4, Variable(30), "CPID"),
+    spec("8011", 4, Variable(12), "CPID SERIAL"),
+    spec("8012", 4, Variable(20), "VERSION"),
+    spec("8013", 4, Variable(25), "GMN"),
+    spec("8017", 4, Fixed(18), "GSRN - PROVIDER"),
+    spec("8018", 4, Fixed(18), "GSRN - RECIPIENT"),
+    spec("8019", 4, Variable(10), "SRIN"),
+    spec("8020", 4, Variable(25), "REF No."),
+    spec("8026", 4, Fixed(18), "ITIP CONTENT"),
+    spec("8200", 4, Variable(70), "PRODUCT URL"),
+    spec("90", 2, Variable(30), "INTERNAL"),
+    spec("91", 2, Variable(90), "INTERNAL"),
+    spec("92", 2, Variable(90), "INTERNAL"),
+    spec("93", 2, Variable(90), "INTERNAL"),
+    spec("94", 2, Variable(90), "INTERNAL"),
+    spec("95", 2, Variable(90), "INTERNAL"),
+    spec("96", 2, Variable(90), "INTERNAL"),
+    spec("97", 2, Variable(90), "INTERNAL"),
+    spec("98", 2, Variable(90), "INTERNAL"),
+    spec("99", 2, Variable(90), "INTERNAL"),
+];
+
+/// 根据数据开头查找应用标识符定义
+pub(crate) fn lookup(data: &str) -> Option<&'static AiSpec> {
+    SPECS
+        .iter()
+        .find(|spec| data.starts_with(spec.prefix) && data.len() >= spec.digits)
+}
+
+/// 根据完整的 AI 查找定义
+pub(crate) fn find(ai: &str) -> Option<&'static AiSpec> {
+    lookup(ai).filter(|spec| spec.digits == ai.len())
+}
@@ -0,0 +1,218 @@
+use std::fmt::Display;
+
+use super::ai::{self, AiLength};
+use super::separator::GS;
+use crate::{GsNormalizer, ScannerError};
+
+/// GS1 数据元素(AI + 数据)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gs1Element {
+    ai: String,
+    value: String,
+}
+
+impl Gs1Element {
+    /// 获取应用标识符
+    pub fn ai(&self) -> &str {
+        &self.ai
+    }
+
+    /// 获取数据
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// 获取数据名称，例如 `01` 对应 `GTIN`
+    pub fn title(&self) -> &'static str {
+        ai::find(&self.ai)
+            .map(|spec| spec.title)
+            .unwrap_or_default()
+    }
+}
+
+/// GS1 条码数据(GS1-128 / GS1 DataMatrix / GS1 QR)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gs1 {
+    elements: Vec<Gs1Element>,
+}
+
+impl Gs1 {
+    /// 解析 GS1 条码数据
+    ///
+    /// 支持以下格式:
+    /// * 扫码枪原始输出，变长数据以 GS(0x1D) 分隔，可带 `]C1`、`]d2`、`]Q3`、`]e0` 符号标识，
+    ///   `<GS>`、`[FNC1]`等常见替代写法会先按[`GsNormalizer::default`]规范化
+    /// * 人工可读格式，例如 `(01)09521234543213(10)ABC123`
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let gs1 = Gs1::parse("]d201095212345432131725123110ABC123\x1d21SN0001").unwrap();
+    /// assert_eq!(gs1.get("01"), Some("09521234543213"));
+    /// assert_eq!(gs1.get("17"), Some("251231"));
+    /// assert_eq!(gs1.get("10"), Some("ABC123"));
+    /// assert_eq!(gs1.get("21"), Some("SN0001"));
+    ///
+    /// let gs1 = Gs1::parse("(01)09521234543213(10)ABC123(21)SN0001").unwrap();
+    /// assert_eq!(gs1.get("10"), Some("ABC123"));
+    /// assert_eq!(gs1.elements().len(), 3);
+    /// ```
+    pub fn parse(data: &str) -> Result<Gs1, ScannerError> {
+        let data = GsNormalizer::default().normalize(data);
+        let data = data.as_str();
+        let elements = if data.starts_with('(') {
+            parse_bracketed(data)?
+        } else {
+            parse_raw(data)?
+        };
+        if elements.is_empty() {
+            return Err(ScannerError::Parse("GS1数据为空".into()));
+        }
+        Ok(Gs1 { elements })
+    }
+
+    /// 获取所有数据元素
+    pub fn elements(&self) -> &[Gs1Element] {
+        &self.elements
+    }
+
+    /// 根据应用标识符获取数据
+    pub fn get(&self, ai: &str) -> Option<&str> {
+        self.elements
+            .iter()
+            .find(|el| el.ai == ai)
+            .map(|el| el.value.as_str())
+    }
+
+    /// 转为扫码枪原始输出格式，变长数据后(最后一个除外)添加 GS 分隔符
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let gs1 = Gs1::parse("(10)ABC(01)09521234543213(21)SN1").unwrap();
+    /// assert_eq!(gs1.to_raw(), "10ABC\x1d010952123454321321SN1");
+    /// assert_eq!(gs1.to_string(), "(10)ABC(01)09521234543213(21)SN1");
+    /// ```
+    pub fn to_raw(&self) -> String {
+        let mut raw = String::new();
+        for (i, el) in self.elements.iter().enumerate() {
+            raw.push_str(&el.ai);
+            raw.push_str(&el.value);
+            let variable = matches!(
+                ai::find(&el.ai).map(|spec| spec.length),
+                Some(AiLength::Variable(_))
+            );
+            if variable && i + 1 < self.elements.len() {
+                raw.push(GS);
+            }
+        }
+        raw
+    }
+}
+
+/// 输出人工可读格式，例如`(01)09521234543213(10)ABC123`
+impl Display for Gs1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for el in &self.elements {
+            write!(f, "({}){}", el.ai, el.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验并创建数据元素
+fn element(ai: &str, value: &str) -> Result<Gs1Element, ScannerError> {
+    let spec = ai::find(ai).ok_or_else(|| ScannerError::Parse(format!("未知的AI,ai={}", ai)))?;
+    match spec.length {
+        AiLength::Fixed(n) => {
+            if value.len() != n || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ScannerError::Parse(format!(
+                    "AI({})应为{}位数字,value={}",
+                    ai, n, value
+                )));
+            }
+        }
+        AiLength::Variable(max) => {
+            if value.is_empty() || value.len() > max {
+                return Err(ScannerError::Parse(format!(
+                    "AI({})长度应为1~{},value={}",
+                    ai, max, value
+                )));
+            }
+        }
+    }
+    Ok(Gs1Element {
+        ai: ai.into(),
+        value: value.into(),
+    })
+}
+
+/// 解析扫码枪原始输出
+fn parse_raw(mut data: &str) -> Result<Vec<Gs1Element>, ScannerError> {
+    let mut elements = vec![];
+    while !data.is_empty() {
+        let spec = ai::lookup(data)
+            .filter(|spec| {
+                data.as_bytes()[..spec.digits]
+                    .iter()
+                    .all(u8::is_ascii_digit)
+            })
+            .ok_or_else(|| ScannerError::Parse(format!("无法识别的AI,data={}", data)))?;
+        let (ai, rest) = data.split_at(spec.digits);
+        let end = match spec.length {
+            AiLength::Fixed(n) => n.min(rest.len()),
+            AiLength::Variable(_) => rest.find(GS).unwrap_or(rest.len()),
+        };
+        let value = rest
+            .get(..end)
+            .ok_or_else(|| ScannerError::Parse(format!("AI({})数据无效,data={}", ai, rest)))?;
+        elements.push(element(ai, value)?);
+        // 定长数据后面也可能跟着多余的分隔符
+        data = rest[end..].trim_start_matches(GS);
+    }
+    Ok(elements)
+}
+
+/// 解析人工可读格式
+fn parse_bracketed(data: &str) -> Result<Vec<Gs1Element>, ScannerError> {
+    let mut elements = vec![];
+    for part in data.split('(').skip(1) {
+        let (ai, value) = part
+            .split_once(')')
+            .ok_or_else(|| ScannerError::Parse(format!("缺少右括号,data={}", part)))?;
+        elements.push(element(ai, value.trim_end_matches(GS))?);
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn fixed_length_validation() {
+        assert!(Gs1::parse("01123").is_err());
+        assert!(Gs1::parse("(17)25AB31").is_err());
+        assert!(Gs1::parse("(10)").is_err());
+        assert!(Gs1::parse("17条码条码").is_err());
+    }
+
+    #[test]
+    fn unknown_ai() {
+        let err = Gs1::parse("(05)123").unwrap_err();
+        assert_eq!(err.to_string(), "扫码枪解析错误:未知的AI,ai=05");
+    }
+
+    #[test]
+    fn four_digit_ai() {
+        let gs1 = Gs1::parse("]C1310200150010ABC\x1d3922199").unwrap();
+        assert_eq!(gs1.get("3102"), Some("001500"));
+        assert_eq!(gs1.get("10"), Some("ABC"));
+        assert_eq!(gs1.get("3922"), Some("199"));
+        assert_eq!(gs1.elements()[0].title(), "MEASURE (METRIC)");
+    }
+}
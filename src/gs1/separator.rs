@@ -0,0 +1,80 @@
+use crate::{Layer, ScanEvent};
+
+/// GS1 分隔符(FNC1 在数据中的表示)
+pub const GS: char = '\x1d';
+
+/// 符号标识(Symbology Identifier)，部分扫码枪配置会在数据开头输出
+const SYMBOLOGY_IDS: [&str; 5] = ["]C1", "]d2", "]Q3", "]e0", "]J1"];
+
+/// 扫码枪常见的分隔符替代写法
+const DEFAULT_ALIASES: [&str; 8] = [
+    "<GS>", "[GS]", "{GS}", "<FNC1>", "[FNC1]", "{FNC1}", "\\x1d", "\\x1D",
+];
+
+/// GS 分隔符 / FNC1 规范化
+///
+/// 不同的扫码枪配置会把 FNC1 输出为 0x1D、`<GS>`、`[FNC1]`等不同的形式，
+/// 也可能带或不带符号标识，规范化后统一为：不带符号标识，变长数据之间以 0x1D 分隔
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let normalizer = GsNormalizer::default().alias("|");
+/// assert_eq!(normalizer.normalize("]C1<FNC1>0109521234543213<GS>10ABC\r\n"), "0109521234543213\x1d10ABC");
+/// assert_eq!(normalizer.normalize("010952123454321310ABC|21SN1"), "010952123454321310ABC\x1d21SN1");
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GsNormalizer {
+    aliases: Vec<String>,
+}
+
+impl Default for GsNormalizer {
+    /// 包含常见替代写法(`<GS>`、`[FNC1]`、`\x1d`等)的规范化器
+    fn default() -> Self {
+        GsNormalizer {
+            aliases: DEFAULT_ALIASES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl GsNormalizer {
+    /// 创建不包含任何替代写法的规范化器
+    pub fn new() -> Self {
+        GsNormalizer { aliases: vec![] }
+    }
+
+    /// 添加分隔符替代写法，例如扫码枪配置为用`|`代替 GS
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// 规范化条码数据
+    pub fn normalize(&self, data: &str) -> String {
+        let mut data = strip_symbology(data.trim_end_matches(['\r', '\n'])).to_owned();
+        for alias in self.aliases.iter().filter(|a| !a.is_empty()) {
+            data = data.replace(alias.as_str(), &GS.to_string());
+        }
+        data.trim_matches(GS).to_owned()
+    }
+}
+
+/// 作为中间件使用时规范化带条码的事件(包括未解码和比对结果事件)的文本
+impl Layer for GsNormalizer {
+    fn call(&self, event: ScanEvent) -> Option<ScanEvent> {
+        Some(event.map_barcode(|barcode| {
+            let text = self.normalize(&barcode.text());
+            barcode.with_text(&text)
+        }))
+    }
+}
+
+/// 去掉符号标识
+pub(crate) fn strip_symbology(data: &str) -> &str {
+    SYMBOLOGY_IDS
+        .iter()
+        .find_map(|id| data.strip_prefix(id))
+        .unwrap_or(data)
+}
@@ -1,42 +1,153 @@
-use std::net::Ipv4Addr;
-use std::str::FromStr;
+use bytes::{Bytes, BytesMut};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 
+#[cfg(feature = "capi")]
+mod capi;
 mod connector;
+#[cfg(feature = "discovery")]
+mod discovery;
+mod emulator;
 mod error;
+mod fleet;
+mod frame;
+mod gs1;
+mod middleware;
 pub mod prelude;
+mod protocols;
+#[cfg(feature = "python")]
+mod python;
+mod scan;
+mod server;
+mod sink;
+mod telemetry;
+mod udi;
+mod validate;
+use connector::capture::Capturing;
+use connector::command::{Command, Queued};
+use connector::keepalive::{Beat, Keepaliving, Pulse};
+use connector::timeout::ReadTimer;
+use connector::watchdog::Idle;
 use prelude::*;
-use tokio_serial::SerialPortBuilderExt;
-use tracing::{event, Level};
+use tracing::{event, info_span, Instrument, Level};
+use validate::match_code::Matched;
 
 /// 扫码枪
 #[derive(Clone)]
 pub struct Scanner {
     /// 连接器参数
     pub connector: Connector,
-    /// 超时时长
+    /// 扫码枪编号
+    id: Option<String>,
+    /// 扫码枪标签(例如产线、工位)
+    labels: Arc<BTreeMap<String, String>>,
+    /// 读取超时时长
     timeout: Option<Duration>,
+    /// 读取超时的处理方式
+    on_timeout: TimeoutAction,
     /// 用于发送指令给扫码枪
-    sender: Arc<Mutex<Sender<String>>>,
+    sender: Arc<Mutex<Sender<Queued>>>,
     /// 用于接收扫码枪指令
-    receiver: Arc<Mutex<Receiver<String>>>,
+    receiver: Arc<Mutex<Receiver<Queued>>>,
+    /// 未连接时发送指令的处理方式
+    queue_policy: QueuePolicy,
+    /// 用于发送扫码枪事件
+    event_sender: Sender<ScanEvent>,
+    /// 用于接收扫码枪事件
+    event_receiver: Arc<Mutex<Receiver<ScanEvent>>>,
+    /// 帧分隔符
+    delimiters: Vec<u8>,
+    /// 需要去掉的帧尾字符
+    trim_end: Vec<u8>,
+    /// 帧校验
+    checksum: Option<Checksum>,
+    /// 条码字符编码
+    encoding: Encoding,
+    /// 日志中是否以助记符显示控制字符
+    printable: bool,
+    /// 条码格式过滤器，条码至少需要匹配其中一个
+    filters: Vec<Regex>,
+    /// 用于发送被过滤器拒绝的条码
+    rejected: Option<Sender<Rejected>>,
+    /// 事件中间件，按添加顺序调用
+    layers: Vec<Arc<dyn Layer>>,
+    /// 生命周期钩子，按添加顺序调用
+    hooks: Vec<Arc<dyn ScannerHooks>>,
+    /// 事件输出
+    sinks: Vec<Arc<dyn BarcodeSink>>,
+    /// 各事件输出的队列，启动时创建，停止时关闭
+    sink_senders: Arc<std::sync::Mutex<Vec<Sender<ScanEvent>>>>,
+    /// 未读到条码时扫码枪输出的内容
+    no_reads: Vec<String>,
+    /// 多字段拆分配置
+    fields: Option<Fields>,
+    /// 读码质量数据格式
+    quality: Option<QualityFormat>,
+    /// 条码校验器
+    validator: Option<Arc<dyn Validator>>,
+    /// 条码比对
+    match_code: Option<MatchCode>,
+    /// 重连策略
+    retry: RetryPolicy,
+    /// 空闲检测
+    watchdog: Option<Watchdog>,
+    /// 保活指令
+    keepalive: Option<Arc<Keepaliving>>,
+    /// 指令应答路由
+    responses: Option<Arc<protocols::response::Responses>>,
+    /// 软件触发指令
+    soft_trigger: Option<SoftTrigger>,
+    /// 蜂鸣和提示灯指令
+    feedback: Feedback,
+    /// 指令模板库
+    commands: CommandTemplates,
+    /// 厂商协议模块
+    protocol: Option<Arc<dyn ScannerProtocol>>,
+    /// 设备信息缓存，重新连接后清空
+    device_info: Arc<Mutex<Option<DeviceInfo>>>,
+    /// 指令应答握手
+    handshake: Option<Arc<protocols::handshake::Handshaking>>,
+    /// 当前读取模式，切换成功后记录
+    read_mode: Arc<std::sync::Mutex<Option<ReadMode>>>,
+    /// 原始数据抓包
+    capture: Option<Arc<Capturing>>,
+    /// 服务器模式的监听，第一次连接时创建，扫码枪的所有克隆共用
+    listener: Arc<std::sync::Mutex<Option<Arc<TcpListener>>>>,
+    /// 后台连接任务，用于停止扫码枪
+    task: Arc<std::sync::Mutex<Option<AbortHandle>>>,
+    /// 是否已放弃重连，重新启动后清除
+    failed: Arc<AtomicBool>,
+    /// 是否已连接，会话开始时设置，会话结束或被中止时清除
+    online: Arc<AtomicBool>,
 }
 unsafe impl Send for Scanner {}
 
 type ScannerResult = Result<Result<(), ScannerError>, ScannerError>;
 
+/// 读取缓冲每次预留的大小
+const READ_BUFFER: usize = 1024;
+
+/// 服务器模式接入连接时握手的默认超时时间，设置了读取超时时使用读取超时
+#[cfg(any(feature = "tls", feature = "websocket"))]
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl Scanner {
     /// 创建扫码枪
     ///
     /// * `connector` 连接器
-    /// #Examples
+    ///
+    /// # Examples
     /// ```
-    /// use scanner::prelude::*;
+    /// use kim_scanner::prelude::*;
     /// let scanner = Scanner::new(Network::new_server("192.168.1.1", 6000));
     ///
     /// if let Connector::Network(nw) = scanner.connector {
@@ -46,406 +157,2271 @@ impl Scanner {
     /// }
     /// ```
     pub fn new(connector: impl Into<Connector>) -> Self {
-        let (tx, rx) = mpsc::channel::<String>(100);
+        let (tx, rx) = mpsc::channel::<Queued>(100);
+        let (event_tx, event_rx) = mpsc::channel::<ScanEvent>(100);
         Scanner {
             connector: connector.into(),
+            id: None,
+            labels: Arc::new(BTreeMap::new()),
             sender: Arc::new(Mutex::new(tx)),
             receiver: Arc::new(Mutex::new(rx)),
+            event_sender: event_tx,
+            event_receiver: Arc::new(Mutex::new(event_rx)),
             timeout: None,
+            on_timeout: TimeoutAction::default(),
+            queue_policy: QueuePolicy::default(),
+            delimiters: frame::split::DELIMITERS.to_vec(),
+            trim_end: vec![],
+            checksum: None,
+            encoding: Encoding::default(),
+            printable: false,
+            filters: vec![],
+            rejected: None,
+            layers: vec![],
+            hooks: vec![],
+            sinks: vec![],
+            sink_senders: Arc::new(std::sync::Mutex::new(vec![])),
+            no_reads: vec![],
+            fields: None,
+            quality: None,
+            validator: None,
+            match_code: None,
+            retry: RetryPolicy::default(),
+            watchdog: None,
+            keepalive: None,
+            responses: None,
+            soft_trigger: None,
+            feedback: Feedback::default(),
+            commands: CommandTemplates::default(),
+            protocol: None,
+            device_info: Arc::new(Mutex::new(None)),
+            handshake: None,
+            read_mode: Arc::new(std::sync::Mutex::new(None)),
+            capture: None,
+            listener: Arc::new(std::sync::Mutex::new(None)),
+            task: Arc::new(std::sync::Mutex::new(None)),
+            failed: Arc::new(AtomicBool::new(false)),
+            online: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 设置扫码枪编号，日志中显示为`编号@连接地址`，[`Scanner::recv_tagged`]接收的事件带有该编号
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .id("line1-in")
+    ///     .label("line", "1")
+    ///     .label("station", "入口");
+    /// assert_eq!(scanner.get_id(), Some("line1-in"));
+    /// assert_eq!(scanner.labels()["station"], "入口");
+    /// ```
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// 添加扫码枪标签，同名标签会被替换
+    pub fn label(mut self, key: &str, value: &str) -> Self {
+        Arc::make_mut(&mut self.labels).insert(key.into(), value.into());
+        self
+    }
+
+    /// 获取扫码枪编号
+    pub fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// 获取扫码枪标签
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        &self.labels
+    }
+
+    /// 日志中显示的名称，设置了编号时为`编号@连接地址`
+    pub(crate) fn tag(&self, addr: impl Display) -> String {
+        match &self.id {
+            Some(id) => format!("{}@{}", id, addr),
+            None => addr.to_string(),
         }
     }
 
-    /// 设置超时时长
+    /// 设置读取超时时长，超过该时长没有收到数据时按[`Scanner::on_timeout`]处理，收到数据后重新计时
+    ///
+    /// 适用于串口、网络、蓝牙、WebSocket、HID 和自定义传输层。与[`Watchdog`]同时使用时，
+    /// 超时后空闲检测重新计时
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// // 产线节拍 10 秒，30 秒没有条码说明扫码枪或来料异常
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .timeout(Duration::from_secs(30))
+    ///     .on_timeout(TimeoutAction::Event);
+    /// ```
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
     }
 
+    /// 设置读取超时的处理方式，默认发出超时错误事件，详见[`TimeoutAction`]
+    pub fn on_timeout(mut self, action: TimeoutAction) -> Self {
+        self.on_timeout = action;
+        self
+    }
+
+    /// 设置未连接时发送指令的处理方式，默认缓存到重新连接后发送，详见[`QueuePolicy`]
+    pub fn queue_policy(mut self, policy: QueuePolicy) -> Self {
+        self.queue_policy = policy;
+        self
+    }
+
+    /// 设置重连策略，默认固定间隔3秒无限重试
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .retry(RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)).with_jitter(true));
+    /// ```
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 设置厂商协议模块
+    pub(crate) fn protocol(mut self, protocol: Arc<dyn ScannerProtocol>) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// 设置指令应答路由，应答不再作为条码发出
+    pub(crate) fn responses(mut self, responses: Arc<protocols::response::Responses>) -> Self {
+        self.responses = Some(responses);
+        self
+    }
+
+    /// 设置软件触发指令，详见[`SoftTrigger`]
+    ///
+    /// 厂商协议模块会自动设置对应的触发指令
+    pub fn soft_trigger(mut self, soft_trigger: SoftTrigger) -> Self {
+        self.soft_trigger = Some(soft_trigger);
+        self
+    }
+
+    /// 设置蜂鸣和提示灯指令，详见[`Feedback`]
+    pub fn feedback(mut self, feedback: Feedback) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// 设置指令模板库，通过[`Scanner::command`]按名称发送指令，详见[`CommandTemplates`]
+    pub fn commands(mut self, commands: CommandTemplates) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    /// 设置指令应答握手，发送指令后等待扫码枪回复 ACK/NAK，详见[`Handshake`]
+    pub fn handshake(mut self, handshake: Handshake) -> Self {
+        self.handshake = Some(Arc::new(protocols::handshake::Handshaking::new(handshake)));
+        self
+    }
+
+    /// 设置空闲检测，长时间没有数据时发送心跳，仍无响应则重新连接
+    ///
+    /// 详见[`Watchdog`]
+    pub fn watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// 设置保活指令，按固定间隔发送，连续没有应答则重新连接
+    ///
+    /// 详见[`Keepalive`]
+    pub fn keepalive(mut self, keepalive: Keepalive) -> Self {
+        self.keepalive = Some(Arc::new(Keepaliving::new(keepalive)));
+        self
+    }
+
+    /// 设置原始数据抓包，记录每次连接收发的原始字节
+    ///
+    /// 详见[`Capture`]
+    pub fn capture(mut self, capture: Capture) -> Self {
+        self.capture = Some(Arc::new(Capturing::new(capture)));
+        self
+    }
+
+    /// 设置帧分隔符，默认为回车和换行，收到的数据按分隔符拆成多个条码，空帧被丢弃
+    ///
+    /// 所有按字节流读取的连接方式(网络、串口、蓝牙等)使用相同的拆帧规则，WebSocket 的每条消息直接作为一帧。
+    /// 一次读取到的数据不完整时，最后一个分隔符之后的数据与后续数据拼接，
+    /// 100 毫秒内没有收到后续数据或连接关闭时作为一帧处理。
+    /// 设置为空时不拆帧，一次读取到的数据作为一帧，适用于校验位等二进制数据中可能出现回车换行的协议
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).delimiters(b";");
+    /// ```
+    pub fn delimiters(mut self, delimiters: &[u8]) -> Self {
+        self.delimiters = delimiters.to_vec();
+        self
+    }
+
+    /// 设置需要去掉的帧尾字符(例如 CR、LF、TAB、NUL)，在帧校验之前处理
+    ///
+    /// 回车换行默认作为帧分隔符不会出现在帧中，见[`Scanner::delimiters`]
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .trim_end([b'\r', b'\n', b'\t', b'\0']);
+    /// ```
+    pub fn trim_end(mut self, chars: impl IntoIterator<Item = u8>) -> Self {
+        self.trim_end = chars.into_iter().collect();
+        self
+    }
+
+    /// 设置帧校验，校验失败的帧会被丢弃并产生`ScanEvent::Error`事件
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Serial::new("COM1", 9600, 8, StopBits::One, Parity::None))
+    ///     .checksum(Checksum::new(ChecksumAlgorithm::Crc16, ChecksumPosition::Tail));
+    /// ```
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// 设置条码字符编码，默认为 UTF-8
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).encoding(Encoding::Gbk);
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// 设置日志中是否以`<STX>`、`<GS>`等助记符显示控制字符，用于排查帧格式、结束符等问题
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).printable(true);
+    /// ```
+    pub fn printable(mut self, printable: bool) -> Self {
+        self.printable = printable;
+        self
+    }
+
+    /// 添加条码格式过滤器，可多次调用
+    ///
+    /// 设置过滤器后，条码至少需要匹配其中一个，否则会被拒绝
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .filter(Regex::new(r"^SN\d{8}$").unwrap())
+    ///     .filter(Regex::new(r"^PL\d{6}$").unwrap());
+    /// ```
+    pub fn filter(mut self, pattern: Regex) -> Self {
+        self.filters.push(pattern);
+        self
+    }
+
+    /// 设置被拒绝条码的接收通道，未设置时被拒绝的条码只记录日志
+    pub fn rejected(mut self, sender: Sender<Rejected>) -> Self {
+        self.rejected = Some(sender);
+        self
+    }
+
+    /// 添加未读到条码时扫码枪输出的内容(例如`NoRead`、`NG`、`ERROR`)，可多次调用
+    ///
+    /// 去掉首尾空白字符后与之完全相同的数据会产生`ScanEvent::NoRead`事件，而不是条码事件
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .no_read("NoRead")
+    ///     .no_read("NG");
+    /// ```
+    pub fn no_read(mut self, pattern: impl Into<String>) -> Self {
+        self.no_reads.push(pattern.into());
+        self
+    }
+
+    /// 设置多字段拆分方式，拆分结果通过[`Barcode::record`]获取
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .fields(Fields::delimited(";").names(["code", "grade", "x", "y"]));
+    /// ```
+    pub fn fields(mut self, fields: Fields) -> Self {
+        self.fields = Some(fields);
+        self
+    }
+
+    /// 设置条码比对，比对结果作为`Match`/`Mismatch`事件发出，详见[`MatchCode`]
+    pub fn match_code(mut self, match_code: MatchCode) -> Self {
+        self.match_code = Some(match_code);
+        self
+    }
+
+    /// 设置读码质量数据格式，拆出条码后追加的质量数据，通过[`Barcode::quality`]获取
+    ///
+    /// 详见[`QualityFormat`]
+    pub fn quality(mut self, quality: QualityFormat) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// 设置校验位算法，等同于`validator(check_digit)`
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).check_digit(CheckDigit::Ean13);
+    /// ```
+    pub fn check_digit(self, check_digit: CheckDigit) -> Self {
+        self.validator(check_digit)
+    }
+
+    /// 设置条码校验器，校验结果通过[`Barcode::verified`]获取，校验失败的条码仍会发送
+    pub fn validator(mut self, validator: impl Validator + 'static) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// 添加事件中间件，事件在发送给使用者之前按添加顺序依次经过中间件
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+    ///     .layer(Transform::Trim)
+    ///     .layer(Transform::Prefix("L01-".into()));
+    /// ```
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// 添加由闭包实现的事件中间件
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).map(|ev| match ev {
+    ///     ScanEvent::Barcode(barcode) => {
+    ///         let text = barcode.text().replace('-', "");
+    ///         ScanEvent::Barcode(Barcode::from_text(&text, Encoding::Utf8))
+    ///     }
+    ///     ev => ev,
+    /// });
+    /// ```
+    pub fn map<F>(self, f: F) -> Self
+    where
+        F: Fn(ScanEvent) -> ScanEvent + Send + Sync + 'static,
+    {
+        self.layer(MapLayer(f))
+    }
+
+    /// 添加生命周期钩子，连接、断开、出错、扫码时按添加顺序调用，可多次调用
+    ///
+    /// 详见[`ScannerHooks`]
+    pub fn hooks(mut self, hooks: impl ScannerHooks + 'static) -> Self {
+        self.hooks.push(Arc::new(hooks));
+        self
+    }
+
+    /// 添加事件输出，可多次调用，每个输出都会收到所有事件
+    ///
+    /// 内置[`ChannelSink`]、[`LogSink`]和[`CallbackSink`]，详见[`BarcodeSink`]
+    pub fn sink(mut self, sink: impl BarcodeSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// 接收扫码枪事件(条码、错误等)
+    pub async fn recv(&self) -> Option<ScanEvent> {
+        let mut receiver = self.event_receiver.lock().await;
+        receiver.recv().await
+    }
+
+    /// 接收扫码枪事件，事件带有扫码枪编号和标签，未设置编号时以连接地址作为编号
+    pub async fn recv_tagged(&self) -> Option<FleetEvent> {
+        let ev = self.recv().await?;
+        Some(self.tagged(ev))
+    }
+
+    /// 不等待地接收事件队列中的带编号事件，队列为空或正在被其它任务接收时返回 None
+    pub(crate) fn try_recv_tagged(&self) -> Option<FleetEvent> {
+        let ev = self.event_receiver.try_lock().ok()?.try_recv().ok()?;
+        Some(self.tagged(ev))
+    }
+
+    /// 事件加上扫码枪编号和标签
+    fn tagged(&self, ev: ScanEvent) -> FleetEvent {
+        let id = self
+            .id
+            .clone()
+            .unwrap_or_else(|| self.connector.to_string());
+        FleetEvent::tagged(id, Arc::clone(&self.labels), ev)
+    }
+
     /// 给扫码枪发送指令（数据），一般用于反控
+    ///
+    /// 设置了[`Handshake`]时等待扫码枪应答 ACK，收到 NAK 或超时返回内层错误；
+    /// USB HID、脚本回放等不能发送指令的连接方式返回内层通讯错误，见[`Connector::is_writable`]
     pub async fn send_message(&self, cmd: String) -> ScannerResult {
-        let sender = self.sender.lock().await;
-        let r = sender.send(cmd).await;
-        if let Err(e) = r {
-            return Err(ScannerError::Comm(e.0));
+        match &self.handshake {
+            Some(handshake) => handshake.send(self, cmd).await,
+            None => self.enqueue(cmd).await,
         }
-        Ok(Ok(()))
     }
 
-    // 启动扫码枪
-    pub async fn start(&self) -> ScannerResult {
-        let conn: &Connector = &self.connector;
-        let self_arc = Arc::new(self.clone());
+    /// 把指令放入发送队列，不等待握手应答
+    pub(crate) async fn enqueue(&self, cmd: String) -> ScannerResult {
+        self.control(cmd.into()).await
+    }
 
-        match conn {
-            Connector::Serial(conn) => {
-                if !conn.name().to_lowercase().starts_with("com") {
-                    return Err(ScannerError::Param(format!(
-                        "无效的串口名称,name={}",
-                        conn.name()
-                    )));
-                }
-                tokio::spawn(async move {
-                    loop {
-                        let self_arc = Arc::clone(&self_arc);
-                        let conn: &Connector = &self_arc.connector;
-                        if let Err(err) = self_arc.start_serial().await {
-                            event!(
-                                Level::ERROR,
-                                "\t{}\t致命错误❌❌❌\t错误原因={:?}",
-                                conn.to_string(),
-                                err
-                            );
-                            break;
-                        }
-                        // 非致命错误等待3秒重启
-                        tokio::time::sleep(Duration::from_secs(3)).await;
-                        event!(Level::INFO, "\t{}\t重新启动串口🔃", conn.to_string());
-                    }
-                });
-            }
-            Connector::Network(conn) => {
-                if Ipv4Addr::from_str(conn.ip()).is_err() {
-                    return Err(ScannerError::Param(format!(
-                        "无效的IP地址,ip={}",
-                        conn.ip()
-                    )));
-                }
-                // 创建线程启动扫码枪
-                tokio::spawn(async move {
-                    loop {
-                        let self_arc = Arc::clone(&self_arc);
-                        let conn: &Connector = &self_arc.connector;
-                        let is_server = match conn {
-                            Connector::Serial(_) => false,
-                            Connector::Network(conn) => conn.is_server(),
-                        };
-                        let r = if is_server {
-                            self_arc.start_network_server().await
-                        } else {
-                            self_arc.start_network_client().await
-                        };
-                        // 出现致命错误后，返回给主线程，否则重启服务
-                        if let Err(err) = r {
-                            event!(
-                                Level::ERROR,
-                                "\t{}\t致命错误❌❌❌\t错误原因={:?}",
-                                conn.to_string(),
-                                err
-                            );
-                            break;
-                        }
-                    }
-                });
-            }
+    /// 给扫码枪发送指令，等到指令写入连接后才返回
+    ///
+    /// [`Scanner::send_message`]放入发送队列就返回；未连接时指令缓存在队列中，
+    /// 本方法会一直等到重新连接后写入，可通过[`QueuePolicy`]限制等待。
+    /// 写入失败、指令缓存超时被丢弃或写入前连接被中止时返回内层通讯错误，不等待[`Handshake`]应答
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004));
+    /// scanner.start().await??;
+    /// if let Err(err) = scanner.send_confirmed("OK\r".into()).await? {
+    ///     println!("指令未送达: {}", err);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_confirmed(&self, cmd: String) -> ScannerResult {
+        let cmd = Command::from(cmd);
+        if let Some(err) = self.refuse(&cmd) {
+            return Ok(Err(err));
+        }
+        let (queued, rx) = Queued::confirmed(cmd);
+        self.push(queued).await?;
+        match rx.await {
+            Ok(r) => Ok(r),
+            Err(_) => Ok(Err(ScannerError::Comm(
+                "指令未发送，连接已断开或扫码枪已停止".into(),
+            ))),
+        }
+    }
+
+    /// 刷新发送缓冲，在之前的指令写入后执行
+    pub async fn flush(&self) -> ScannerResult {
+        self.control(Command::Flush).await
+    }
+
+    /// 断开当前连接，之后按重连策略重新连接，例如更换扫码枪设置后重新建立会话
+    ///
+    /// 服务器模式下关闭扫码枪的连接，等待扫码枪重新连接
+    pub async fn reconnect(&self) -> ScannerResult {
+        self.control(Command::Reconnect).await
+    }
+
+    /// 发送完队列中的指令后关闭连接并停止扫码枪
+    ///
+    /// 与立即中止的[`Scanner::stop`]不同，之前放入队列的指令会先写入扫码枪。
+    /// 未连接时在重新连接后执行
+    pub async fn shutdown(&self) -> ScannerResult {
+        self.control(Command::Shutdown).await
+    }
+
+    /// 把指令放入发送队列，由发送线程按顺序执行
+    ///
+    /// 不能发送指令的连接方式、[`QueuePolicy::FailFast`]时未连接的写入指令不放入队列，返回内层通讯错误
+    async fn control(&self, cmd: Command) -> ScannerResult {
+        if let Some(err) = self.refuse(&cmd) {
+            return Ok(Err(err));
         }
+        self.push(Queued::new(cmd)).await?;
         Ok(Ok(()))
     }
 
-    /// 启动网络扫码枪`服务器模式`
-    async fn start_network_server(&self) -> ScannerResult {
-        // 检查参数是否一致
-        let conn = match &self.connector {
-            Connector::Serial(conn) => {
-                let err = format!("此处应该是网络参数，但是却收到了串口参数({})", conn.name());
-                return Err(ScannerError::Param(err));
-            }
-            Connector::Network(conn) => conn,
+    /// 不等待地发送指令，用于接收数据时自动发送的指令(例如比对结果)，与[`Scanner::control`]的检查相同
+    ///
+    /// 尽量按条码顺序直接放入发送队列，发送队列忙时再交给后台任务；不能发送时只记录日志
+    fn try_control(&self, addr: &str, cmd: Command) {
+        if let Some(err) = self.refuse(&cmd) {
+            event!(
+                Level::WARN,
+                scanner.addr = %addr,
+                error = %err,
+                error.kind = err.kind(),
+                "指令未发送",
+            );
+            return;
+        }
+        let queued = match self.sender.try_lock() {
+            Ok(sender) => match sender.try_send(Queued::new(cmd)) {
+                Ok(()) => return,
+                Err(err) => err.into_inner(),
+            },
+            Err(_) => Queued::new(cmd),
+        };
+        let scanner = self.clone();
+        tokio::spawn(async move { scanner.push(queued).await });
+    }
+
+    /// 不放入队列的指令返回错误：连接方式不能发送指令([`Connector::is_writable`])，
+    /// 或[`QueuePolicy::FailFast`]时未连接的写入指令
+    fn refuse(&self, cmd: &Command) -> Option<ScannerError> {
+        if !self.connector.is_writable() {
+            return Some(ScannerError::Comm(format!(
+                "该连接方式不支持发送指令({}): {}",
+                self.connector, cmd
+            )));
+        }
+        if self.queue_policy == QueuePolicy::FailFast
+            && matches!(cmd, Command::Write(_))
+            && !self.is_connected()
+        {
+            return Some(ScannerError::Comm(format!(
+                "扫码枪未连接，指令未发送: {}",
+                cmd
+            )));
+        }
+        None
+    }
+
+    /// 放入发送队列，队列已关闭时返回错误
+    ///
+    /// 队列已满时等待，等待期间不占用发送端的锁
+    async fn push(&self, queued: Queued) -> Result<(), ScannerError> {
+        let sender = self.sender.lock().await.clone();
+        if let Err(e) = sender.send(queued).await {
+            return Err(ScannerError::Comm(e.0.cmd.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 检查取出的指令是否已超过[`QueuePolicy`]的最长缓存时长，超过时记录日志并丢弃，返回`None`
+    pub(crate) fn fresh(&self, addr: &str, queued: Queued) -> Option<Queued> {
+        if !self.queue_policy.expired(&queued) {
+            return Some(queued);
+        }
+        let err = format!("指令缓存超时，已丢弃: {}", queued.cmd);
+        event!(Level::WARN, scanner.addr = addr, "{}", err);
+        queued.confirm.done(Err(ScannerError::Comm(err)));
+        None
+    }
+
+    /// 软件触发扫码，发送[`SoftTrigger`]的开始指令
+    ///
+    /// * `duration` 扫码时长，到时自动发送停止指令；`None`表示不自动停止
+    ///
+    /// 返回`false`表示触发在防抖时长内被忽略，没有配置触发指令时返回参数错误
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004))
+    ///     .soft_trigger(SoftTrigger::new("LON\r").with_stop("LOFF\r"));
+    /// scanner.start().await??;
+    /// scanner.trigger(Some(Duration::from_secs(2))).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn trigger(&self, duration: Option<Duration>) -> Result<bool, ScannerError> {
+        let addr = self.tag(&self.connector);
+        let Some(trigger) = &self.soft_trigger else {
+            return Err(ScannerError::Param(format!(
+                "没有配置软件触发指令,addr={}",
+                addr
+            )));
+        };
+        if !trigger.arm() {
+            event!(Level::DEBUG, scanner.addr = %addr, "软件触发防抖,忽略");
+            return Ok(false);
+        }
+        self.send_message(trigger.start().into()).await??;
+        if let (Some(duration), Some(stop)) = (duration, trigger.stop()) {
+            let scanner = self.clone();
+            let stop = stop.to_owned();
+            let handle = tokio::spawn(async move {
+                tokio::time::sleep(duration).await;
+                let _ = scanner.send_message(stop).await;
+            });
+            trigger.schedule_stop(handle.abort_handle());
+        }
+        Ok(true)
+    }
+
+    /// 按名称发送[`CommandTemplates`]中的指令
+    pub async fn command(&self, name: &str) -> Result<(), ScannerError> {
+        let Some(cmd) = self.commands.get(name) else {
+            return Err(ScannerError::Param(format!(
+                "没有配置指令模板,addr={},name={}",
+                self.connector, name
+            )));
+        };
+        self.send_message(cmd.into()).await?
+    }
+
+    /// 扫码枪蜂鸣，发送[`Feedback`]中配置的指令，用于给操作员提示
+    ///
+    /// * `pattern` 蜂鸣方式编号
+    pub async fn beep(&self, pattern: u8) -> Result<(), ScannerError> {
+        let Some(cmd) = self.feedback.beep(pattern) else {
+            return Err(ScannerError::Param(format!(
+                "没有配置蜂鸣指令,addr={},pattern={}",
+                self.connector, pattern
+            )));
         };
+        self.send_message(cmd.into()).await?
+    }
+
+    /// 点亮扫码枪的合格/不合格提示灯，发送[`Feedback`]中配置的指令
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let scanner = CognexDataMan::new(Scanner::new(Network::new_client("192.168.0.50", 23)))
+    ///     .scanner()
+    ///     .clone();
+    /// scanner.start().await??;
+    /// scanner.set_indicator(Indicator::Bad).await?;
+    /// scanner.beep(3).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_indicator(&self, indicator: Indicator) -> Result<(), ScannerError> {
+        let Some(cmd) = self.feedback.indicator(indicator) else {
+            return Err(ScannerError::Param(format!(
+                "没有配置提示灯指令,addr={},indicator={:?}",
+                self.connector, indicator
+            )));
+        };
+        self.send_message(cmd.into()).await?
+    }
+
+    /// 查询设备信息(型号、固件版本、序列号)，使用厂商协议模块的查询指令
+    ///
+    /// 查询结果会缓存，重新连接后再次查询。没有使用厂商协议模块时返回参数错误
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let dm = CognexDataMan::new(Scanner::new(Network::new_client("192.168.0.50", 23)));
+    /// let scanner = dm.scanner();
+    /// scanner.start().await??;
+    /// let info = scanner.device_info().await?;
+    /// println!("{:?} {:?}", info.model(), info.firmware());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn device_info(&self) -> Result<DeviceInfo, ScannerError> {
+        let Some(protocol) = &self.protocol else {
+            return Err(ScannerError::Param(format!(
+                "没有使用厂商协议模块,无法查询设备信息,addr={}",
+                self.connector
+            )));
+        };
+        let mut cache = self.device_info.lock().await;
+        if let Some(info) = cache.as_ref() {
+            return Ok(info.clone());
+        }
+        let info = protocol.query_info().await?;
+        event!(
+            Level::INFO,
+            scanner.addr = %self.connector,
+            model = ?info.model(),
+            firmware = ?info.firmware(),
+            serial_number = ?info.serial_number(),
+            "设备信息",
+        );
+        *cache = Some(info.clone());
+        Ok(info)
+    }
+
+    /// 切换读取模式(连续、触发、感应)，使用厂商协议模块的指令
+    ///
+    /// 例如维护时切换为连续读取，生产时切换为触发读取。没有使用厂商协议模块时返回参数错误
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let dm = CognexDataMan::new(Scanner::new(Network::new_client("192.168.0.50", 23)));
+    /// let scanner = dm.scanner();
+    /// scanner.start().await??;
+    /// scanner.set_read_mode(ReadMode::Continuous).await?;
+    /// assert_eq!(scanner.read_mode(), Some(ReadMode::Continuous));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_read_mode(&self, mode: ReadMode) -> Result<(), ScannerError> {
+        let Some(protocol) = &self.protocol else {
+            return Err(ScannerError::Param(format!(
+                "没有使用厂商协议模块,无法切换读取模式,addr={}",
+                self.connector
+            )));
+        };
+        protocol.set_read_mode(mode).await?;
+        *self.read_mode.lock().unwrap() = Some(mode);
+        event!(Level::INFO, scanner.addr = %self.connector, mode = ?mode, "切换读取模式");
+        Ok(())
+    }
+
+    /// 启用或禁用码制，使用厂商协议模块的指令
+    pub async fn enable_symbology(
+        &self,
+        symbology: Symbology,
+        enable: bool,
+    ) -> Result<(), ScannerError> {
+        let Some(protocol) = &self.protocol else {
+            return Err(ScannerError::Param(format!(
+                "没有使用厂商协议模块,无法设置码制,addr={}",
+                self.connector
+            )));
+        };
+        protocol.enable_symbology(symbology, enable).await
+    }
+
+    /// 只启用指定的码制，禁用其它码制，用于部署时限定扫码枪只读取预期的条码类型
+    ///
+    /// 需要禁用的码制扫码枪不支持时跳过
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use kim_scanner::prelude::*;
+    ///
+    /// # async fn run() -> Result<(), ScannerError> {
+    /// let serial = Serial::new("COM3", 115200, 8, StopBits::One, Parity::None);
+    /// let hw = Honeywell::new(Scanner::new(serial)).with_save(true);
+    /// hw.scanner().start().await??;
+    /// hw.scanner().restrict_symbologies(&[Symbology::DataMatrix, Symbology::Code128]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn restrict_symbologies(
+        &self,
+        symbologies: &[Symbology],
+    ) -> Result<(), ScannerError> {
+        for symbology in Symbology::ALL {
+            let enable = symbologies.contains(&symbology);
+            match self.enable_symbology(symbology, enable).await {
+                Ok(()) => {}
+                Err(ScannerError::Device(err)) if !enable => {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %self.connector,
+                        symbology = ?symbology,
+                        error = %err,
+                        error.kind = "device",
+                        "禁用码制失败,跳过",
+                    );
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取当前读取模式，没有切换过时返回`None`
+    pub fn read_mode(&self) -> Option<ReadMode> {
+        *self.read_mode.lock().unwrap()
+    }
+
+    // 启动扫码枪，已经启动且没有停止时不重复启动
+    pub async fn start(&self) -> ScannerResult {
+        if self.is_running() {
+            event!(Level::WARN, scanner.addr = %self.connector, "扫码枪已启动,忽略重复启动");
+            return Ok(Ok(()));
+        }
+        // 检查参数，参数错误直接返回
+        match &self.connector {
+            Connector::Serial(conn) => conn.check()?,
+            Connector::Network(conn) => conn.check()?,
+            Connector::Replay(conn) => conn.check()?,
+            #[cfg(feature = "bluetooth")]
+            Connector::Bluetooth(conn) => {
+                conn.bdaddr()?;
+            }
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+        self.failed.store(false, Ordering::Relaxed);
+        // 每个事件输出一个投递任务
+        let addr = self.tag(&self.connector);
+        *self.sink_senders.lock().unwrap() = self
+            .sinks
+            .iter()
+            .map(|sink| {
+                let (sender, receiver) = mpsc::channel(100);
+                tokio::spawn(sink::barcode::pump(
+                    addr.clone(),
+                    Arc::clone(sink),
+                    receiver,
+                ));
+                sender
+            })
+            .collect();
+        // 创建线程启动扫码枪，后台任务的日志都带上扫码枪地址
+        let self_arc = Arc::new(self.clone());
+        let span = info_span!("scanner", scanner.addr = %addr);
+        let handle = tokio::spawn(self_arc.supervise().instrument(span));
+        // 同时启动时可能已有任务，结束旧任务避免重复连接
+        if let Some(old) = self.task.lock().unwrap().replace(handle.abort_handle()) {
+            old.abort();
+        }
+        Ok(Ok(()))
+    }
+
+    /// 停止扫码枪，断开连接并不再重连，之后可以重新调用[`Scanner::start`]
+    ///
+    /// 停止后不再产生事件，已经在事件队列中的事件仍可以接收。
+    /// 服务器模式的监听端口不会释放，扫码枪(包括所有克隆)被丢弃后才释放
+    pub fn stop(&self) {
+        // 关闭队列后投递任务处理完剩余的事件后结束
+        self.sink_senders.lock().unwrap().clear();
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+            event!(Level::INFO, scanner.addr = %self.connector, "扫码枪已停止");
+        }
+    }
+
+    /// 扫码枪是否已启动且没有停止(包括重连等待中)
+    pub fn is_running(&self) -> bool {
+        self.task
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// 扫码枪当前是否已连接
+    pub fn is_connected(&self) -> bool {
+        self.online.load(Ordering::Relaxed)
+    }
+
+    /// 是否已超过重连策略的限制而放弃重连，之后不再产生事件，重新调用[`Scanner::start`]后清除
+    ///
+    /// 详见[`RetryPolicy::max_retries`]和[`RetryPolicy::give_up_after`]
+    pub fn is_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// 后台任务是否已自行退出(致命错误、放弃重连或崩溃)，调用[`Scanner::stop`]停止的不算
+    pub(crate) fn is_dead(&self) -> bool {
+        self.task
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+    }
+
+    /// 是否为服务器模式，等待扫码枪连接
+    fn is_server(&self) -> bool {
+        match &self.connector {
+            Connector::Network(conn) => conn.is_server(),
+            #[cfg(feature = "websocket")]
+            Connector::WebSocket(conn) => conn.is_server(),
+            _ => false,
+        }
+    }
+
+    /// 连接扫码枪并收发数据，直到连接断开
+    async fn connect(&self) -> ScannerResult {
+        match &self.connector {
+            Connector::Serial(_) => self.start_serial().await,
+            Connector::Network(conn) if conn.is_server() => self.start_network_server().await,
+            Connector::Network(_) => self.start_network_client().await,
+            #[cfg(feature = "hid")]
+            Connector::Hid(_) => self.start_hid().await,
+            #[cfg(feature = "bluetooth")]
+            Connector::Bluetooth(_) => self.start_bluetooth().await,
+            #[cfg(feature = "websocket")]
+            Connector::WebSocket(conn) if conn.is_server() => self.start_websocket_server().await,
+            #[cfg(feature = "websocket")]
+            Connector::WebSocket(_) => self.start_websocket_client().await,
+            Connector::Mock(_) => self.start_mock().await,
+            Connector::Replay(_) => self.start_replay().await,
+            Connector::Custom(transport) => self.start_transport(transport.as_ref()).await,
+        }
+    }
+
+    /// 断开或非致命错误后按重连策略重新连接，出现致命错误或超过最大重试次数后停止
+    async fn supervise(self: Arc<Self>) {
+        let addr = self.tag(&self.connector);
+        let mut failures = 0u32;
+        // 第一次失败的时间，连接成功后清除
+        let mut since = None;
+        loop {
+            // 每次连接尝试一个跨度，收发日志带上连接编号；服务器模式每个接入的会话各自一个跨度
+            let span = match self.is_server() {
+                true => tracing::Span::none(),
+                false => telemetry::span::connection(),
+            };
+            let r = self.connect().instrument(span).await;
+            // 重新连接后可能已经更换了扫码枪
+            self.device_info.lock().await.take();
+            match r {
+                Ok(Ok(())) => {
+                    failures = 0;
+                    since = None;
+                }
+                // 连接失败也要发出事件，主扫码枪启动时就连接不上才能切换到备用扫码枪
+                Ok(Err(err)) => {
+                    self.emit(&addr, ScanEvent::Error(err.clone()));
+                    failures += 1;
+                    let since = *since.get_or_insert_with(tokio::time::Instant::now);
+                    if self.retry.exhausted(failures) || self.retry.expired(since.elapsed()) {
+                        event!(
+                            Level::ERROR,
+                            scanner.addr = %addr,
+                            failures,
+                            error = %err,
+                            error.kind = err.kind(),
+                            "连续失败,放弃重连",
+                        );
+                        self.retry.give_up(&err);
+                        self.failed.store(true, Ordering::Relaxed);
+                        self.emit(&addr, ScanEvent::Failed(err));
+                        break;
+                    }
+                }
+                // 出现致命错误后停止，否则重启服务
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = err.kind(),
+                        "致命错误",
+                    );
+                    self.notify(&addr, &ScanEvent::Error(err));
+                    break;
+                }
+            }
+            let delay = self.retry.delay(failures);
+            match &self.connector {
+                // USB 串口拔出后等待重新插入
+                Connector::Serial(serial) => serial.wait_present(delay).await,
+                _ => tokio::time::sleep(delay).await,
+            }
+            telemetry::metrics::reconnect(&self);
+            event!(Level::INFO, scanner.addr = %addr, failures, "重新连接");
+        }
+    }
+
+    /// 处理读取缓冲中已经收完(以分隔符结尾)的帧，没有收完的数据留在缓冲中与下次读取到的数据拼接
+    ///
+    /// 没有收完的数据超过[`frame::split::MAX_FRAME`]时作为一帧处理，避免缓冲无限增长
+    fn receive_buffered(&self, addr: &str, buf: &mut BytesMut) {
+        let mut end = frame::split::complete(buf, &self.delimiters);
+        if buf.len() - end >= frame::split::MAX_FRAME {
+            event!(Level::WARN, scanner.addr = %addr, len = buf.len() - end, "长时间没有收到分隔符,作为一帧处理");
+            end = buf.len();
+        }
+        if end > 0 {
+            let data = buf.split_to(end).freeze();
+            self.receive(addr, data);
+        }
+    }
+
+    /// 处理缓冲中剩余的没有以分隔符结尾的数据，连接正常关闭或一段时间没有收到后续数据时调用
+    fn receive_rest(&self, addr: &str, buf: &mut BytesMut) {
+        if !buf.is_empty() {
+            let data = buf.split().freeze();
+            self.receive(addr, data);
+        }
+    }
+
+    /// 按分隔符拆帧后逐帧处理，所有按字节流读取的连接方式读取到的数据都经过这里
+    ///
+    /// 每帧都是`data`的切片，与读取缓冲共用内存，不复制数据
+    fn receive(&self, addr: &str, data: Bytes) {
+        for frame in frame::split::split(&data, &self.delimiters) {
+            self.dispatch(addr, data.slice_ref(frame));
+        }
+    }
+
+    /// 处理接收到的一帧数据
+    fn dispatch(&self, addr: &str, frame: Bytes) {
+        telemetry::metrics::frame(self, frame.len());
+        let end = frame
+            .iter()
+            .rposition(|b| !self.trim_end.contains(b))
+            .map_or(0, |i| i + 1);
+        let frame = frame.slice(..end);
+        if frame.is_empty() {
+            return;
+        }
+        if let Some(handshake) = &self.handshake {
+            if handshake.intercept(&frame) {
+                event!(Level::DEBUG, scanner.addr = %addr, response = ?frame, "握手应答");
+                return;
+            }
+        }
+        let frame = match &self.checksum {
+            Some(checksum) => match checksum.verify(&frame) {
+                Ok(data) => frame.slice_ref(data),
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = err.kind(),
+                        "帧校验失败",
+                    );
+                    self.emit(addr, ScanEvent::Error(err));
+                    return;
+                }
+            },
+            None => frame,
+        };
+        if let Some(keepalive) = &self.keepalive {
+            if keepalive.intercept(&frame) {
+                event!(Level::DEBUG, scanner.addr = %addr, response = ?frame, "保活应答");
+                return;
+            }
+        }
+        if let Some(responses) = &self.responses {
+            if responses.intercept(&frame) {
+                event!(
+                    Level::DEBUG,
+                    scanner.addr = %addr,
+                    response = ?String::from_utf8_lossy(&frame),
+                    "指令应答",
+                );
+                return;
+            }
+        }
+        let barcode = Barcode::new(frame, self.encoding.clone());
+        if !self.no_reads.is_empty() {
+            let text = barcode.text();
+            if self.no_reads.iter().any(|p| p == text.trim()) {
+                event!(
+                    Level::WARN,
+                    scanner.addr = %addr,
+                    barcode = %self.render(&barcode),
+                    "未读到条码",
+                );
+                self.emit(addr, ScanEvent::NoRead(barcode));
+                return;
+            }
+        }
+        let barcode = match &self.quality {
+            Some(format) => match format.parse(&barcode.text()) {
+                Some((data, quality)) => barcode.with_text(&data).with_quality(quality),
+                None => {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        barcode = %self.render(&barcode),
+                        "读码质量数据无效",
+                    );
+                    barcode
+                }
+            },
+            None => barcode,
+        };
+        if !self.filters.is_empty() {
+            let text = barcode.text();
+            if !self.filters.iter().any(|re| re.is_match(&text)) {
+                let reason = format!("条码格式不匹配,过滤器={:?}", self.filters);
+                self.reject(addr, Rejected::new(barcode, reason));
+                return;
+            }
+        }
+        let barcode = match &self.fields {
+            Some(fields) => {
+                let record = fields.split(&barcode.text());
+                barcode.with_record(record)
+            }
+            None => barcode,
+        };
+        let barcode = match &self.validator {
+            Some(validator) => {
+                let verified = validator.validate(barcode.text().trim());
+                if !verified {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        barcode = %self.render(&barcode),
+                        "条码校验失败",
+                    );
+                }
+                barcode.with_verified(verified)
+            }
+            None => barcode,
+        };
+        event!(Level::INFO, scanner.addr = %addr, barcode = %self.render(&barcode), "接收条码");
+        let ev = match &self.match_code {
+            Some(match_code) => self.compare(addr, match_code, barcode),
+            None => ScanEvent::Barcode(barcode),
+        };
+        self.emit(addr, ev);
+    }
+
+    /// 比对条码，并发送 OK/NG 指令
+    fn compare(&self, addr: &str, match_code: &MatchCode, barcode: Barcode) -> ScanEvent {
+        let (ev, cmd) = match match_code.check(barcode.text().trim()) {
+            Matched::Taught => {
+                event!(
+                    Level::INFO,
+                    scanner.addr = %addr,
+                    barcode = %self.render(&barcode),
+                    "设置基准条码",
+                );
+                return ScanEvent::Barcode(barcode);
+            }
+            Matched::Match => (ScanEvent::Match(barcode), match_code.ok()),
+            Matched::Mismatch => {
+                event!(
+                    Level::WARN,
+                    scanner.addr = %addr,
+                    master = ?match_code.get_master(),
+                    barcode = %self.render(&barcode),
+                    "条码比对不一致",
+                );
+                (ScanEvent::Mismatch(barcode), match_code.ng())
+            }
+        };
+        if let Some(cmd) = cmd {
+            self.try_control(addr, Command::from(cmd.to_owned()));
+        }
+        ev
+    }
+
+    /// 条码在日志中的显示形式
+    fn render(&self, barcode: &Barcode) -> String {
+        if self.printable {
+            barcode.printable()
+        } else {
+            barcode.to_string()
+        }
+    }
+
+    /// 拒绝条码
+    fn reject(&self, addr: &str, rejected: Rejected) {
+        event!(
+            Level::WARN,
+            scanner.addr = %addr,
+            barcode = %self.render(rejected.barcode()),
+            reason = %rejected.reason(),
+            "条码被拒绝",
+        );
+        if let Some(sender) = &self.rejected {
+            if let Err(mpsc::error::TrySendError::Full(_)) = sender.try_send(rejected) {
+                event!(Level::WARN, scanner.addr = %addr, "拒绝队列已满,丢弃条码");
+            }
+        }
+    }
+
+    /// 发送扫码枪事件，事件队列已满时丢弃事件，避免阻塞读取线程
+    fn emit(&self, addr: &str, ev: ScanEvent) {
+        telemetry::metrics::scan(self, &ev);
+        let ev = match self.layers.iter().try_fold(ev, |ev, layer| layer.call(ev)) {
+            Some(ev) => ev,
+            None => return,
+        };
+        self.notify(addr, &ev);
+        for sender in self.sink_senders.lock().unwrap().iter() {
+            if let Err(mpsc::error::TrySendError::Full(_)) = sender.try_send(ev.clone()) {
+                event!(Level::WARN, scanner.addr = %addr, "事件输出队列已满,丢弃事件");
+            }
+        }
+        if let Err(mpsc::error::TrySendError::Full(ev)) = self.event_sender.try_send(ev) {
+            event!(Level::WARN, scanner.addr = %addr, event = ?ev, "事件队列已满,丢弃事件");
+        }
+    }
+
+    /// 按事件类型调用生命周期钩子
+    fn notify(&self, addr: &str, ev: &ScanEvent) {
+        if self.hooks.is_empty() {
+            return;
+        }
+        let ctx = HookContext::new(self, addr);
+        for hooks in &self.hooks {
+            match ev {
+                ScanEvent::Connected => hooks.on_connect(&ctx),
+                ScanEvent::Disconnected => hooks.on_disconnect(&ctx),
+                ScanEvent::Error(err) => hooks.on_error(&ctx, err),
+                ev if ev.barcode().is_some() => hooks.on_scan(&ctx, ev),
+                _ => {}
+            }
+        }
+    }
+
+    /// 抓包记录收到的原始数据
+    fn tap(&self, data: &[u8]) {
+        if let Some(capture) = &self.capture {
+            capture.received(data);
+        }
+    }
+
+    /// 抓包记录发送的原始数据
+    fn tap_sent(&self, data: &[u8]) {
+        if let Some(capture) = &self.capture {
+            capture.sent(data);
+        }
+    }
+
+    /// 抓包记录一次连接
+    fn tap_connected(&self, addr: &str) {
+        if let Some(capture) = &self.capture {
+            capture.connected(addr);
+        }
+    }
+
+    /// 读取超时，返回`true`表示需要断开连接
+    fn timed_out(&self, addr: &str) -> bool {
+        let timeout = self.timeout.unwrap_or_default();
+        match self.on_timeout {
+            TimeoutAction::Event => {
+                event!(Level::WARN, scanner.addr = %addr, timeout = ?timeout, "读取超时");
+                let err = ScannerError::Timeout(format!("{:?}内没有收到数据", timeout));
+                self.emit(addr, ScanEvent::Error(err));
+                false
+            }
+            TimeoutAction::Reconnect => {
+                event!(Level::ERROR, scanner.addr = %addr, timeout = ?timeout, "读取超时,重新连接");
+                true
+            }
+        }
+    }
+
+    /// 扫码枪无响应，发出健康事件后由调用方断开连接
+    fn unhealthy(&self, addr: &str, reason: String) {
+        event!(Level::ERROR, scanner.addr = %addr, reason = %reason, "扫码枪无响应,重新连接");
+        self.emit(addr, ScanEvent::Unhealthy(reason));
+    }
+
+    /// 发送失败，记录日志并发出通讯错误事件，返回的错误用于通知等待写入的调用方
+    pub(crate) fn send_failed(
+        &self,
+        addr: &str,
+        reason: &str,
+        kind: &str,
+        err: impl Display,
+    ) -> ScannerError {
+        event!(
+            Level::ERROR,
+            scanner.addr = %addr,
+            error = %err,
+            error.kind = kind,
+            "{}",
+            reason,
+        );
+        let err = ScannerError::Comm(format!("{}: {}", reason, err));
+        self.emit(addr, ScanEvent::Error(err.clone()));
+        err
+    }
+
+    /// 启动网络扫码枪`服务器模式`
+    async fn start_network_server(&self) -> ScannerResult {
+        // 检查参数是否一致
+        let conn = match &self.connector {
+            Connector::Network(conn) => conn,
+            conn => {
+                let err = format!("此处应该是网络参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let addr = self.tag(conn.addr());
+        #[cfg(feature = "tls")]
+        let acceptor = match conn.tls() {
+            Some(tls) => Some(tls.acceptor()?),
+            None => None,
+        };
+        // 创建服务，扫码枪重启、重连后复用
+        let server = match self.listener(&conn.addr()).await {
+            Ok(server) => server,
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %addr,
+                    error = %err,
+                    error.kind = "io",
+                    "扫码枪服务创建失败",
+                );
+                return Ok(Err(ScannerError::Io(err)));
+            }
+        };
+        let mut next = None;
+        loop {
+            let (client, peer) = match next.take() {
+                Some(accepted) => accepted,
+                None => {
+                    event!(Level::INFO, scanner.addr = %addr, "等待扫码枪连接");
+                    self.accept(&addr, &server, conn.ip_filter()).await
+                }
+            };
+            let span = telemetry::span::connection();
+            span.in_scope(|| {
+                event!(Level::INFO, scanner.addr = %addr, peer.addr = %peer, "扫码枪连接成功");
+            });
+            if let Some(options) = conn.socket_options() {
+                if let Err(err) = options.apply(&client) {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "TCP连接参数设置失败",
+                    );
+                }
+            }
+            let stream: Box<dyn TransportStream> = {
+                #[cfg(feature = "tls")]
+                match &acceptor {
+                    // 连接后不发送数据的客户端不能一直占用监听
+                    Some(acceptor) => match self
+                        .accept_handshake(acceptor.accept(client))
+                        .instrument(span.clone())
+                        .await
+                    {
+                        Ok(stream) => Box::new(stream),
+                        Err(err) => {
+                            event!(
+                                Level::ERROR,
+                                scanner.addr = %addr,
+                                peer.addr = %peer,
+                                error = %err,
+                                error.kind = "io",
+                                "TLS握手失败",
+                            );
+                            continue;
+                        }
+                    },
+                    None => Box::new(client),
+                }
+                #[cfg(not(feature = "tls"))]
+                Box::new(client)
+            };
+            // 会话期间继续监听：扫码枪断电重启后旧连接可能不会断开，同一地址的新连接替换旧连接
+            tokio::select! {
+                _ = self.session(&addr, stream).instrument(span) => {}
+                accepted = self.accept_same_peer(&addr, &server, conn.ip_filter(), peer) => {
+                    self.replaced(&addr, accepted.1);
+                    next = Some(accepted);
+                }
+            }
+        }
+    }
+
+    /// 等待接入连接的握手完成，超时返回`TimedOut`错误
+    #[cfg(any(feature = "tls", feature = "websocket"))]
+    async fn accept_handshake<T, E>(
+        &self,
+        handshake: impl std::future::Future<Output = Result<T, E>>,
+    ) -> std::io::Result<T>
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let timeout = self.timeout.unwrap_or(HANDSHAKE_TIMEOUT);
+        match tokio::time::timeout(timeout, handshake).await {
+            Ok(r) => r.map_err(std::io::Error::other),
+            Err(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("握手超时({:?})", timeout),
+            )),
+        }
+    }
+
+    /// 获取服务器模式的监听，第一次调用时创建
+    ///
+    /// 监听在扫码枪的整个生命周期内保持，重连、停止后重新启动都不再重新绑定端口
+    async fn listener(&self, bind: &str) -> std::io::Result<Arc<TcpListener>> {
+        if let Some(listener) = self.listener.lock().unwrap().clone() {
+            return Ok(listener);
+        }
+        let listener = Arc::new(TcpListener::bind(bind).await?);
+        event!(Level::INFO, scanner.addr = %self.tag(bind), "扫码枪服务创建成功");
+        *self.listener.lock().unwrap() = Some(Arc::clone(&listener));
+        Ok(listener)
+    }
+
+    /// 等待下一个允许的扫码枪连接，出错时稍后重试
+    async fn accept(
+        &self,
+        addr: &str,
+        server: &TcpListener,
+        filter: Option<&IpFilter>,
+    ) -> (TcpStream, SocketAddr) {
+        loop {
+            let (client, peer) = match server.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "扫码枪连接错误",
+                    );
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            };
+            if filter.is_some_and(|filter| !filter.is_allowed(peer.ip())) {
+                event!(
+                    target: "kim_scanner::security",
+                    Level::WARN,
+                    scanner.addr = %addr,
+                    peer.addr = %peer,
+                    "拒绝未授权的连接",
+                );
+                continue;
+            }
+            return (client, peer);
+        }
+    }
+
+    /// 会话期间等待当前扫码枪重新连接(与`current`的 IP 地址相同)，其他地址的连接直接关闭
+    ///
+    /// 避免端口扫描、监控探测或其他设备的连接断开正在使用的扫码枪
+    async fn accept_same_peer(
+        &self,
+        addr: &str,
+        server: &TcpListener,
+        filter: Option<&IpFilter>,
+        current: SocketAddr,
+    ) -> (TcpStream, SocketAddr) {
+        loop {
+            let (client, peer) = self.accept(addr, server, filter).await;
+            if peer.ip() == current.ip() {
+                return (client, peer);
+            }
+            event!(
+                target: "kim_scanner::security",
+                Level::WARN,
+                scanner.addr = %addr,
+                peer.addr = %peer,
+                current.addr = %current,
+                "已有扫码枪连接,拒绝其他地址的连接",
+            );
+        }
+    }
+
+    /// 新的连接替换正在进行的会话
+    fn replaced(&self, addr: &str, peer: SocketAddr) {
+        event!(Level::WARN, scanner.addr = %addr, peer.addr = %peer, "扫码枪重新连接,关闭旧连接");
+        self.emit(addr, ScanEvent::Disconnected);
+    }
+
+    /// 启动网络扫码枪`客户端模式`
+    async fn start_network_client(&self) -> ScannerResult {
+        // 检查参数是否一致
+        let conn = match &self.connector {
+            Connector::Network(conn) => conn,
+            conn => {
+                let err = format!("此处应该是网络参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        self.start_transport(conn).await
+    }
+
+    /// 通过传输层连接扫码枪并处理会话
+    async fn start_transport(&self, transport: &dyn Transport) -> ScannerResult {
+        let addr = self.tag(transport.name());
+        let stream = match transport.connect_with_timeout(self.timeout).await {
+            Ok(stream) => stream,
+            // 参数错误直接返回，不再重连
+            Err(ScannerError::Param(err)) => return Err(ScannerError::Param(err)),
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %addr,
+                    error = %err,
+                    error.kind = err.kind(),
+                    "连接错误",
+                );
+                return Ok(Err(err));
+            }
+        };
+        self.session(&addr, stream).await;
+        Ok(Ok(()))
+    }
+
+    /// 处理一次连接会话(网络、串口、自定义传输层)，读取线程结束后自动关闭发送线程
+    async fn session<S>(&self, addr: &str, stream: S)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let online = Online::new(&self.online);
+        self.tap_connected(addr);
+        self.emit(addr, ScanEvent::Connected);
+        let receiver = Arc::clone(&self.receiver);
+        let capture = self.capture.clone();
+        let (mut rx, mut tx) = tokio::io::split(stream);
+        // ! 读取条码线程
+        let addr1 = addr.to_owned();
+        let scanner = self.clone();
+        let read_handle = telemetry::span::spawn(async move {
+            let mut buf = BytesMut::with_capacity(READ_BUFFER);
+            let mut pinged = false;
+            let mut beat = scanner.keepalive.as_ref().map(|k| k.beat());
+            let mut timer = ReadTimer::new(scanner.timeout);
+            // 缓冲中有没有收完的帧时，等待后续数据的截止时间
+            let mut gap = None;
+            loop {
+                // 之前的条码都已释放时重用同一块内存，没有收完的帧留在缓冲开头
+                buf.reserve(READ_BUFFER);
+                let start = buf.len();
+                let watchdog = scanner.watchdog.as_ref();
+                let idle = tokio::select! {
+                    idle = Watchdog::watch(watchdog, &addr1, &mut pinged, rx.read_buf(&mut buf)) => idle,
+                    pulse = Beat::tick(&mut beat, &addr1) => match pulse {
+                        Pulse::Ping(cmd) => Idle::Heartbeat(cmd),
+                        Pulse::Dead(reason) => {
+                            scanner.unhealthy(&addr1, reason);
+                            break;
+                        }
+                    },
+                    _ = timer.elapsed() => {
+                        if scanner.timed_out(&addr1) {
+                            break;
+                        }
+                        continue;
+                    }
+                    _ = tokio::time::sleep_until(gap.unwrap_or_else(tokio::time::Instant::now)), if gap.is_some() => {
+                        gap = None;
+                        scanner.receive_rest(&addr1, &mut buf);
+                        continue;
+                    }
+                };
+                let r = match idle {
+                    Idle::Ready(r) => r,
+                    Idle::Heartbeat(cmd) => {
+                        let _ = scanner.enqueue(cmd).await;
+                        continue;
+                    }
+                    Idle::Dead => break,
+                };
+                match r {
+                    Ok(0) => {
+                        event!(Level::ERROR, scanner.addr = %addr1, "接收数据为空,关闭连接");
+                        scanner.receive_rest(&addr1, &mut buf);
+                        break;
+                    }
+                    Ok(_) => {
+                        pinged = false;
+                        timer.reset();
+                        scanner.tap(&buf[start..]);
+                        scanner.receive_buffered(&addr1, &mut buf);
+                        gap = (!buf.is_empty())
+                            .then(|| tokio::time::Instant::now() + frame::split::FRAME_GAP);
+                    }
+                    Err(err) => {
+                        event!(
+                            Level::ERROR,
+                            scanner.addr = %addr1,
+                            error = %err,
+                            error.kind = "io",
+                            "接收数据错误",
+                        );
+                        break;
+                    }
+                }
+            }
+        });
+        // ! 发送命令线程
+        let addr2 = addr.to_owned();
+        let scanner = self.clone();
+        let write_handle = telemetry::span::spawn(async move {
+            let mut receiver = receiver.lock().await;
+            // 返回值表示结束后是否停止扫码枪
+            let stop = loop {
+                let Some(queued) = receiver.recv().await else {
+                    event!(Level::INFO, scanner.addr = %addr2, "发送队列已关闭,关闭连接");
+                    break false;
+                };
+                let Some(Queued { cmd, confirm, .. }) = scanner.fresh(&addr2, queued) else {
+                    continue;
+                };
+                let r = match &cmd {
+                    // 写入全部数据后立即刷新，写入不完整时返回 WriteZero 错误
+                    Command::Write(buf) => match tx.write_all(buf).await {
+                        Ok(()) => tx.flush().await.map_err(|err| ("刷新发送缓冲错误", err)),
+                        Err(err) => Err(("发送数据错误", err)),
+                    },
+                    Command::Flush => tx.flush().await.map_err(|err| ("刷新发送缓冲错误", err)),
+                    Command::Reconnect => {
+                        event!(Level::INFO, scanner.addr = %addr2, "收到重新连接指令,关闭连接");
+                        break false;
+                    }
+                    Command::Shutdown => {
+                        event!(Level::INFO, scanner.addr = %addr2, "收到关闭指令,关闭连接");
+                        break true;
+                    }
+                };
+                if let Err((reason, err)) = r {
+                    let err = scanner.send_failed(&addr2, reason, "io", err);
+                    confirm.done(Err(err));
+                    return;
+                }
+                if let (Some(capture), Command::Write(buf)) = (&capture, &cmd) {
+                    capture.sent(buf);
+                }
+                confirm.done(Ok(()));
+            };
+            // 正常结束时把缓冲中的数据发完再关闭连接
+            if let Err(err) = tx.shutdown().await {
+                event!(
+                    Level::WARN,
+                    scanner.addr = %addr2,
+                    error = %err,
+                    error.kind = "io",
+                    "关闭连接错误",
+                );
+            }
+            if stop {
+                scanner.stop();
+            }
+        });
+        // 扫码枪停止时同时结束读写线程
+        let _guard = TaskGuard(vec![
+            read_handle.abort_handle(),
+            write_handle.abort_handle(),
+        ]);
+        self.join(addr, read_handle, write_handle).await;
+        drop(online);
+        self.emit(addr, ScanEvent::Disconnected);
+    }
+
+    /// 等待会话的读写线程，读取线程结束(连接断开)或发送线程结束(发送错误、重新连接)时关闭另一个线程
+    async fn join(
+        &self,
+        addr: &str,
+        mut read_handle: tokio::task::JoinHandle<()>,
+        mut write_handle: tokio::task::JoinHandle<()>,
+    ) {
+        tokio::select! {
+            r = &mut read_handle => {
+                if let Err(err) = r {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        "接收线程错误",
+                    );
+                }
+                event!(Level::INFO, scanner.addr = %addr, "接收线程关闭");
+                write_handle.abort(); // 👈 读取线程关闭后,自动关闭写入线程
+                event!(Level::INFO, scanner.addr = %addr, "发送线程关闭");
+            }
+            r = &mut write_handle => {
+                if let Err(err) = r {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        "发送线程错误",
+                    );
+                }
+                event!(Level::INFO, scanner.addr = %addr, "发送线程关闭");
+                read_handle.abort();
+                event!(Level::INFO, scanner.addr = %addr, "接收线程关闭");
+            }
+        }
+    }
+
+    /// 启动 WebSocket 扫码枪`服务器模式`
+    #[cfg(feature = "websocket")]
+    async fn start_websocket_server(&self) -> ScannerResult {
+        let conn = match &self.connector {
+            Connector::WebSocket(conn) => conn,
+            conn => {
+                let err = format!("此处应该是WebSocket参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let addr = self.tag(conn);
+        let server = match self.listener(conn.addr()).await {
+            Ok(server) => server,
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %addr,
+                    error = %err,
+                    error.kind = "io",
+                    "扫码枪服务创建失败",
+                );
+                return Ok(Err(ScannerError::Io(err)));
+            }
+        };
+        let mut next = None;
+        loop {
+            let (client, peer) = match next.take() {
+                Some(accepted) => accepted,
+                None => {
+                    event!(Level::INFO, scanner.addr = %addr, "等待扫码枪连接");
+                    self.accept(&addr, &server, None).await
+                }
+            };
+            let span = telemetry::span::connection();
+            // 连接后不发送数据的客户端不能一直占用监听
+            let stream = match self
+                .accept_handshake(tokio_tungstenite::accept_async(client))
+                .instrument(span.clone())
+                .await
+            {
+                Ok(stream) => stream,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        peer.addr = %peer,
+                        error = %err,
+                        error.kind = "comm",
+                        "WebSocket握手失败",
+                    );
+                    continue;
+                }
+            };
+            span.in_scope(|| {
+                event!(Level::INFO, scanner.addr = %addr, peer.addr = %peer, "扫码枪连接成功");
+            });
+            // 会话期间继续监听，同一地址的新连接替换旧连接
+            tokio::select! {
+                _ = self.websocket_session(&addr, stream).instrument(span) => {}
+                accepted = self.accept_same_peer(&addr, &server, None, peer) => {
+                    self.replaced(&addr, accepted.1);
+                    next = Some(accepted);
+                }
+            }
+        }
+    }
+
+    /// 启动 WebSocket 扫码枪`客户端模式`
+    #[cfg(feature = "websocket")]
+    async fn start_websocket_client(&self) -> ScannerResult {
+        let conn = match &self.connector {
+            Connector::WebSocket(conn) => conn,
+            conn => {
+                let err = format!("此处应该是WebSocket参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let addr = self.tag(conn);
+        let stream = match tokio_tungstenite::connect_async(conn.addr()).await {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    scanner.addr = %addr,
+                    error = %err,
+                    error.kind = "comm",
+                    "扫码枪连接错误",
+                );
+                return Ok(Err(ScannerError::Comm(err.to_string())));
+            }
+        };
+        event!(Level::INFO, scanner.addr = %addr, "扫码枪连接成功");
+        self.websocket_session(&addr, stream).await;
+        Ok(Ok(()))
+    }
+
+    /// 处理一次 WebSocket 会话，每条消息作为一帧数据，读取线程结束后自动关闭发送线程
+    #[cfg(feature = "websocket")]
+    async fn websocket_session<S>(&self, addr: &str, stream: tokio_tungstenite::WebSocketStream<S>)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let online = Online::new(&self.online);
+        self.tap_connected(addr);
+        self.emit(addr, ScanEvent::Connected);
         let receiver = Arc::clone(&self.receiver);
-        let addr = format!("{}:{}", conn.ip(), conn.port());
-        // 创建服务
-        let server = TcpListener::bind(&addr).await;
-        if let Err(err) = server {
-            event!(
-                Level::ERROR,
-                "\t{}\t扫码枪服务创建失败❌\t失败原因={}",
-                &addr,
-                err
-            );
-            return Ok(Err(ScannerError::Io(err)));
-        }
-        event!(Level::INFO, "\t{}\t扫码枪服务创建成功✅", &addr);
-        let server = server.unwrap();
-        // 等待客户端连接
-        event!(Level::INFO, "\t{}\t等待扫码枪连接⌛⌛⌛", &addr);
-        let client = server.accept().await;
-        if let Err(err) = client {
-            event!(
-                Level::ERROR,
-                "\t{}\t扫码枪连接错误❌\t错误原因={}",
-                &addr,
-                err
-            );
-            return Err(ScannerError::Comm(err.to_string()));
-        }
-        let (client, _) = client.unwrap();
-        event!(
-            Level::INFO,
-            "\t{}\t扫码枪连接成功✅\t扫码枪地址={:?}",
-            &addr,
-            &client.peer_addr().unwrap()
-        );
-        let (mut rx, mut tx) = client.into_split();
+        let capture = self.capture.clone();
+        let (mut tx, mut rx) = stream.split();
         // ! 读取条码线程
         let addr1 = addr.to_owned();
-        let read_handle = tokio::spawn(async move {
-            let mut buf = [0u8; 1024];
+        let scanner = self.clone();
+        let read_handle = telemetry::span::spawn(async move {
+            let mut pinged = false;
+            let mut beat = scanner.keepalive.as_ref().map(|k| k.beat());
+            let mut timer = ReadTimer::new(scanner.timeout);
             loop {
-                let r = rx.read(&mut buf).await;
-                match r {
-                    Ok(n) if n == 0 => {
-                        event!(Level::ERROR, "\t{}\t接收数据为空,关闭连接❌", &addr1);
-                        break;
+                let watchdog = scanner.watchdog.as_ref();
+                let idle = tokio::select! {
+                    idle = Watchdog::watch(watchdog, &addr1, &mut pinged, rx.next()) => idle,
+                    pulse = Beat::tick(&mut beat, &addr1) => match pulse {
+                        Pulse::Ping(cmd) => Idle::Heartbeat(cmd),
+                        Pulse::Dead(reason) => {
+                            scanner.unhealthy(&addr1, reason);
+                            break;
+                        }
+                    },
+                    _ = timer.elapsed() => {
+                        if scanner.timed_out(&addr1) {
+                            break;
+                        }
+                        continue;
                     }
-                    Ok(n) => {
-                        let s = String::from_utf8_lossy(&buf[0..n]);
-                        event!(Level::INFO, "\t{}\t接收条码={}", &addr1, s);
+                };
+                let msg = match idle {
+                    Idle::Ready(Some(msg)) => msg,
+                    Idle::Ready(None) | Idle::Dead => break,
+                    Idle::Heartbeat(cmd) => {
+                        let _ = scanner.enqueue(cmd).await;
+                        continue;
+                    }
+                };
+                // 任何消息(包括 Pong)都说明连接正常
+                pinged = false;
+                timer.reset();
+                match msg {
+                    // 每条消息就是一帧，不再按分隔符拆分
+                    Ok(Message::Text(text)) => {
+                        scanner.tap(text.as_bytes());
+                        scanner.dispatch(&addr1, text.into());
                     }
+                    Ok(Message::Binary(data)) => {
+                        scanner.tap(&data);
+                        scanner.dispatch(&addr1, data);
+                    }
+                    Ok(Message::Close(_)) => break,
+                    Ok(_) => {}
                     Err(err) => {
                         event!(
                             Level::ERROR,
-                            "\t{}\t接收数据错误❌\t错误原因={:?}",
-                            &addr1,
-                            err
+                            scanner.addr = %addr1,
+                            error = %err,
+                            error.kind = "comm",
+                            "接收数据错误",
                         );
-                        break;
+                        return;
                     }
                 }
             }
+            event!(Level::ERROR, scanner.addr = %addr1, "WebSocket连接关闭");
         });
         // ! 发送命令线程
         let addr2 = addr.to_owned();
-        let write_handle = tokio::spawn(async move {
+        let scanner = self.clone();
+        let write_handle = telemetry::span::spawn(async move {
             let mut receiver = receiver.lock().await;
-            loop {
-                let cmd = receiver.recv().await;
-                if let Some(cmd) = cmd {
-                    let buf = cmd.as_bytes();
-                    let r = tx.write(buf).await;
-                    if let Err(err) = r {
-                        event!(
-                            Level::ERROR,
-                            "\t{}\t发送数据错误❌\t错误原因={:?}",
-                            &addr2,
-                            err
-                        );
-                        break;
+            // 返回值表示结束后是否停止扫码枪
+            let stop = loop {
+                let Some(queued) = receiver.recv().await else {
+                    event!(Level::INFO, scanner.addr = %addr2, "发送队列已关闭,关闭连接");
+                    break false;
+                };
+                let Some(Queued { cmd, confirm, .. }) = scanner.fresh(&addr2, queued) else {
+                    continue;
+                };
+                let r = match &cmd {
+                    // 文本指令作为文本消息发送，其它数据作为二进制消息，发送后自动刷新
+                    Command::Write(buf) => match std::str::from_utf8(buf) {
+                        Ok(text) => tx.send(Message::text(text)).await,
+                        Err(_) => tx.send(Message::binary(buf.clone())).await,
+                    }
+                    .map_err(|err| ("发送数据错误", err)),
+                    Command::Flush => tx.flush().await.map_err(|err| ("刷新发送缓冲错误", err)),
+                    Command::Reconnect => {
+                        event!(Level::INFO, scanner.addr = %addr2, "收到重新连接指令,关闭连接");
+                        break false;
                     }
+                    Command::Shutdown => {
+                        event!(Level::INFO, scanner.addr = %addr2, "收到关闭指令,关闭连接");
+                        break true;
+                    }
+                };
+                if let Err((reason, err)) = r {
+                    let err = scanner.send_failed(&addr2, reason, "comm", err);
+                    confirm.done(Err(err));
+                    return;
+                }
+                if let (Some(capture), Command::Write(buf)) = (&capture, &cmd) {
+                    capture.sent(buf);
                 }
+                confirm.done(Ok(()));
+            };
+            // 正常结束时发送关闭帧，把缓冲中的消息发完再关闭连接
+            if let Err(err) = tx.close().await {
+                event!(
+                    Level::WARN,
+                    scanner.addr = %addr2,
+                    error = %err,
+                    error.kind = "comm",
+                    "关闭连接错误",
+                );
+            }
+            if stop {
+                scanner.stop();
             }
         });
-        if let Err(err) = read_handle.await {
-            event!(
-                Level::ERROR,
-                "\t{}\t接收线程错误❌\t错误原因={:?}",
-                &addr,
-                err
-            )
-        }
-        event!(Level::INFO, "\t{}\t接收线程关闭❌", &addr);
-        write_handle.abort(); // 👈 读取线程关闭后,自动关闭写入线程
-        event!(Level::INFO, "\t{}\t发送线程关闭❌", &addr);
-        Ok(Ok(()))
+        // 扫码枪停止时同时结束读写线程
+        let _guard = TaskGuard(vec![
+            read_handle.abort_handle(),
+            write_handle.abort_handle(),
+        ]);
+        self.join(addr, read_handle, write_handle).await;
+        drop(online);
+        self.emit(addr, ScanEvent::Disconnected);
     }
 
-    /// 启动网络扫码枪`客户端模式`
-    async fn start_network_client(&self) -> ScannerResult {
-        // 检查参数是否一致
+    /// 启动 USB HID 扫码枪
+    ///
+    /// hidapi 为阻塞接口，在独立线程中读取按键报告
+    #[cfg(feature = "hid")]
+    async fn start_hid(&self) -> ScannerResult {
         let conn = match &self.connector {
-            Connector::Serial(conn) => {
-                let err = format!("此处应该是网络参数，但是却收到了串口参数({})", conn.name());
+            Connector::Hid(conn) => conn.clone(),
+            conn => {
+                let err = format!("此处应该是HID参数，但是却收到了其它参数({})", conn);
                 return Err(ScannerError::Param(err));
             }
-            Connector::Network(conn) => conn,
         };
-        let receiver = Arc::clone(&self.receiver);
-        let addr = format!("{}:{}", conn.ip(), conn.port());
-        // 连接扫码枪服务
-        let client = TcpStream::connect(&addr).await;
-        if let Err(err) = client {
-            event!(
-                Level::ERROR,
-                "\t{}\t扫码枪连接错误❌\t错误原因={}",
-                &addr,
-                err
-            );
-            return Ok(Err(ScannerError::Comm(err.to_string())));
-        }
-        let client = client.unwrap();
-        event!(
-            Level::INFO,
-            "\t{}\t扫码枪连接成功✅\t扫码枪地址={:?}",
-            &addr,
-            &client.peer_addr().unwrap()
-        );
-        let (mut rx, mut tx) = client.into_split();
-        // ! 读取条码线程
-        let addr1 = addr.to_owned();
-        let read_handle = tokio::spawn(async move {
-            let mut buf = [0u8; 1024];
-            loop {
-                let r = rx.read(&mut buf).await;
+        // 读取在阻塞线程中进行，扫码枪停止(本任务被取消)时通过标记通知阻塞线程退出
+        let cancel = HidCancel(Arc::new(AtomicBool::new(false)));
+        let cancelled = Arc::clone(&cancel.0);
+        let scanner = self.clone();
+        let span = tracing::Span::current();
+        let handle = tokio::task::spawn_blocking(move || {
+            let _span = span.enter();
+            let addr = scanner.tag(&conn);
+            let device = match conn.open() {
+                Ok(device) => device,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = err.kind(),
+                        "HID设备打开失败",
+                    );
+                    return Err(ScannerError::Comm(err.to_string()));
+                }
+            };
+            event!(Level::INFO, scanner.addr = %addr, "HID设备打开成功");
+            let online = Online::new(&scanner.online);
+            scanner.tap_connected(&addr);
+            scanner.emit(&addr, ScanEvent::Connected);
+            let mut decoder = connector::hid::KeyboardDecoder::default();
+            let mut buf = [0u8; 64];
+            // 收到数据的时间，用于读取超时计时
+            let mut last = std::time::Instant::now();
+            let mut timed_out = false;
+            let r = loop {
+                // 每次读取最多等待一个检查周期，到时检查是否已停止
+                let r = device.read_timeout(&mut buf, HID_POLL);
+                if cancelled.load(Ordering::Relaxed) {
+                    event!(Level::INFO, scanner.addr = %addr, "扫码枪已停止,关闭HID设备");
+                    return Ok(());
+                }
                 match r {
-                    Ok(n) if n == 0 => {
-                        event!(Level::ERROR, "\t{}\t接收数据为空,关闭连接❌", &addr1);
-                        break;
+                    Ok(0) => {
+                        // 每段没有数据的时间只超时一次
+                        let expired = scanner
+                            .timeout
+                            .is_some_and(|timeout| last.elapsed() >= timeout);
+                        if expired && !timed_out {
+                            timed_out = true;
+                            if scanner.timed_out(&addr) {
+                                break Ok(());
+                            }
+                        }
                     }
                     Ok(n) => {
-                        let s = String::from_utf8_lossy(&buf[0..n]);
-                        event!(Level::INFO, "\t{}\t接收条码={}", &addr1, s);
+                        last = std::time::Instant::now();
+                        timed_out = false;
+                        scanner.tap(&buf[..n]);
+                        if let Some(frame) = decoder.feed(&buf[..n]) {
+                            scanner.dispatch(&addr, frame.into());
+                        }
                     }
                     Err(err) => {
                         event!(
                             Level::ERROR,
-                            "\t{}\t接收数据错误❌\t错误原因={:?}",
-                            &addr1,
-                            err
-                        );
-                        break;
-                    }
-                }
-            }
-        });
-        // ! 发送命令线程
-        let addr2 = addr.to_owned();
-        let write_handle = tokio::spawn(async move {
-            let mut receiver = receiver.lock().await;
-            loop {
-                let cmd = receiver.recv().await;
-                if let Some(cmd) = cmd {
-                    let buf = cmd.as_bytes();
-                    let r = tx.write(buf).await;
-                    if let Err(err) = r {
-                        event!(
-                            Level::ERROR,
-                            "\t{}\t发送数据错误❌\t错误原因={:?}",
-                            &addr2,
-                            err
+                            scanner.addr = %addr,
+                            error = %err,
+                            error.kind = err.kind(),
+                            "HID读取错误",
                         );
-                        break;
+                        break Err(ScannerError::Comm(err.to_string()));
                     }
                 }
-            }
+            };
+            drop(online);
+            scanner.emit(&addr, ScanEvent::Disconnected);
+            r
         });
-        if let Err(err) = read_handle.await {
-            event!(
-                Level::ERROR,
-                "\t{}\t接收线程错误❌\t错误原因={:?}",
-                &addr,
-                err
-            )
-        }
-        event!(Level::INFO, "\t{}\t接收线程关闭❌", &addr);
-        write_handle.abort(); // 👈 读取线程关闭后,自动关闭写入线程
-        event!(Level::INFO, "\t{}\t发送线程关闭❌", &addr);
-        Ok(Ok(()))
+        let r = match handle.await {
+            Ok(r) => Ok(r),
+            Err(err) => Err(ScannerError::Comm(err.to_string())),
+        };
+        drop(cancel);
+        r
     }
 
-    /// 启动串口扫码枪
-    async fn start_serial(&self) -> ScannerResult {
-        // 检查参数是否一致
+    /// 启动蓝牙扫码枪
+    #[cfg(feature = "bluetooth")]
+    async fn start_bluetooth(&self) -> ScannerResult {
         let conn = match &self.connector {
-            Connector::Serial(conn) => conn,
-            Connector::Network(conn) => {
-                let err = format!(
-                    "此处应该是串口参数，但是却收到了网络参数({}:{})",
-                    conn.ip(),
-                    conn.port()
-                );
+            Connector::Bluetooth(conn) => conn,
+            conn => {
+                let err = format!("此处应该是蓝牙参数，但是却收到了其它参数({})", conn);
                 return Err(ScannerError::Param(err));
             }
         };
-        let addr = conn.name().to_owned();
-        // let receiver = Arc::clone(&self.receiver);
-
-        // let ports = tokio_serial::available_ports();
-        // if let Err(err) = ports {
-        //     event!(
-        //         Level::ERROR,
-        //         "\t{}\t串口查询错误❌\t错误原因={}",
-        //         &addr,
-        //         err
-        //     );
-        //     return Ok(Err(ScannerError::Comm(err.to_string())));
-        // }
-        // let ports = ports.unwrap();
-        // println!("{:?}", ports);
-
-        // 串口连接
-        // TODO timeout 实测不起作用
-        let timeout = self.timeout.unwrap_or(Duration::from_secs(60)); // 不能将下面这行拆开用条件判断，所以只能这样了
-        let com = tokio_serial::new(&addr, conn.baudrate())
-            .stop_bits(tokio_serial::StopBits::One)
-            .parity(tokio_serial::Parity::None)
-            .timeout(timeout)
-            .open_native_async();
-        if let Err(err) = com {
+        let addr = self.tag(conn);
+        #[cfg(target_os = "linux")]
+        {
+            let stream = match conn.connect().await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    // 未配对、超出范围或扫码枪关机
+                    event!(
+                        Level::ERROR,
+                        scanner.addr = %addr,
+                        error = %err,
+                        error.kind = "io",
+                        "蓝牙连接错误",
+                    );
+                    return Ok(Err(ScannerError::Comm(err.to_string())));
+                }
+            };
+            event!(Level::INFO, scanner.addr = %addr, "蓝牙连接成功");
+            self.session(&addr, stream).await;
+            event!(Level::WARN, scanner.addr = %addr, "蓝牙连接断开,可能超出范围或已取消配对");
+            Ok(Ok(()))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let err = format!(
+                "当前平台不支持RFCOMM,请使用系统映射的虚拟串口{:?}",
+                Bluetooth::ports().unwrap_or_default()
+            );
             event!(
                 Level::ERROR,
-                "\t{}\t串口连接错误❌\t错误原因={}\t参数={:?}",
-                &addr,
-                err,
-                &conn
+                scanner.addr = %addr,
+                error = %err,
+                error.kind = "param",
+                "蓝牙连接失败",
             );
-            return Ok(Err(ScannerError::Comm(err.to_string())));
-        }
-        let mut com = com.unwrap();
-        event!(Level::INFO, "\t{}\t串口连接成功✅", &conn.name());
-        // 测试写入串口数据
-        // let mut buf = "123456789".as_bytes();
-        // let r = com.write_buf(&mut buf).await;
-        // println!("串口写入：{:?}", r);
-        // ! 读取串口数据
-        // tokio::spawn(async move {
+            Err(ScannerError::Param(err))
+        }
+    }
+
+    /// 启动模拟扫码枪
+    async fn start_mock(&self) -> ScannerResult {
+        let conn = match &self.connector {
+            Connector::Mock(conn) => conn,
+            conn => {
+                let err = format!("此处应该是模拟参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let addr = self.tag(conn);
+        let mut input = conn.input_receiver.lock().await;
+        let mut receiver = self.receiver.lock().await;
+        let online = Online::new(&self.online);
+        self.tap_connected(&addr);
+        self.emit(&addr, ScanEvent::Connected);
+        let mut beat = self.keepalive.as_ref().map(|k| k.beat());
         loop {
-            let mut buf = [0u8; 1024];
-            let r = com.read(&mut buf).await;
-            match r {
-                Ok(n) if n == 0 => {
-                    event!(Level::ERROR, "\t{}\t接收数据为空,关闭连接❌", &addr);
-                    break;
-                }
-                Ok(n) => {
-                    let barcodes = String::from_utf8_lossy(&buf[0..n]);
-                    let arr: Vec<&str> = barcodes.split(&['\r', '\n'][..]).collect();
-                    for barcode in arr {
-                        if barcode.len() > 0 {
-                            event!(Level::INFO, "\t{}\t接收条码={}", &addr, barcode);
+            tokio::select! {
+                data = input.recv() => match data {
+                    Some(connector::mock::MockInput::Data(data)) => {
+                        self.tap(&data);
+                        self.dispatch(&addr, data.into());
+                    }
+                    Some(connector::mock::MockInput::Disconnect) | None => break,
+                },
+                Some(queued) = receiver.recv() => {
+                    let Some(Queued { cmd, confirm, .. }) = self.fresh(&addr, queued) else {
+                        continue;
+                    };
+                    match cmd {
+                        Command::Write(buf) => {
+                            self.tap_sent(&buf);
+                            let _ = conn.command_sender.send(String::from_utf8_lossy(&buf).into_owned());
+                            confirm.done(Ok(()));
+                        }
+                        Command::Flush => {}
+                        Command::Reconnect => break,
+                        Command::Shutdown => {
+                            self.stop();
+                            break;
                         }
                     }
                 }
-                Err(err) => {
-                    event!(
-                        Level::ERROR,
-                        "\t{}\t接收数据错误❌\t错误原因={:?}",
-                        &addr,
-                        err
-                    );
-                    break;
-                }
+                pulse = Beat::tick(&mut beat, &addr) => match pulse {
+                    Pulse::Ping(cmd) => {
+                        self.tap_sent(cmd.as_bytes());
+                        let _ = conn.command_sender.send(cmd);
+                    }
+                    Pulse::Dead(reason) => {
+                        self.unhealthy(&addr, reason);
+                        break;
+                    }
+                },
             }
         }
-        // });
+        drop(online);
+        self.emit(&addr, ScanEvent::Disconnected);
+        Ok(Ok(()))
+    }
+
+    /// 启动脚本回放
+    async fn start_replay(&self) -> ScannerResult {
+        let conn = match &self.connector {
+            Connector::Replay(conn) => conn,
+            conn => {
+                let err = format!("此处应该是回放参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let addr = self.tag(conn);
+        event!(Level::INFO, scanner.addr = %addr, count = conn.entries().len(), "开始回放");
+        self.emit(&addr, ScanEvent::Connected);
+        loop {
+            for (delay, data) in conn.entries() {
+                tokio::time::sleep(conn.delay(*delay)).await;
+                self.receive(&addr, Bytes::copy_from_slice(data));
+            }
+            if !conn.repeat() || conn.entries().is_empty() {
+                break;
+            }
+        }
+        event!(Level::INFO, scanner.addr = %addr, "回放结束");
+        // 回放结束后保持连接，不触发重连
+        std::future::pending::<()>().await;
+        Ok(Ok(()))
+    }
+
+    /// 启动串口扫码枪
+    ///
+    /// 与网络客户端相同，通过[`Transport`]打开串口后交给[`Scanner::session`]收发数据；
+    /// 启用自动波特率检测时先检测波特率，检测期间收到的条码在会话开始前处理
+    async fn start_serial(&self) -> ScannerResult {
+        // 检查参数是否一致
+        let conn = match &self.connector {
+            Connector::Serial(conn) => conn,
+            conn => {
+                let err = format!("此处应该是串口参数，但是却收到了其它参数({})", conn);
+                return Err(ScannerError::Param(err));
+            }
+        };
+        let Some(auto_baud) = conn.auto_baud() else {
+            return self.start_transport(conn).await;
+        };
+        let addr = self.tag(conn.name());
+        let Some((com, data)) = auto_baud.detect(conn, self.timeout).await else {
+            return Ok(Err(ScannerError::Comm("波特率检测失败".into())));
+        };
+        event!(Level::INFO, scanner.addr = %addr, "串口连接成功");
+        self.receive(&addr, data.into());
+        self.session(&addr, com).await;
         Ok(Ok(()))
     }
 }
 
+/// 会话被取消(例如扫码枪停止)时结束读写线程
+struct TaskGuard(Vec<AbortHandle>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        for task in &self.0 {
+            task.abort();
+        }
+    }
+}
+
+/// HID 读取的检查周期(毫秒)，阻塞读取最多等待该时长后检查扫码枪是否已停止
+#[cfg(feature = "hid")]
+const HID_POLL: i32 = 100;
+
+/// HID 读取任务被取消(例如扫码枪停止)时通知阻塞线程退出
+#[cfg(feature = "hid")]
+struct HidCancel(Arc<AtomicBool>);
+
+#[cfg(feature = "hid")]
+impl Drop for HidCancel {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 会话期间标记扫码枪已连接，会话结束或被取消时清除
+struct Online(Arc<AtomicBool>);
+
+impl Online {
+    fn new(flag: &Arc<AtomicBool>) -> Self {
+        flag.store(true, Ordering::Relaxed);
+        Online(Arc::clone(flag))
+    }
+}
+
+impl Drop for Online {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use bytes::Bytes;
+
     use crate::prelude::*;
 
     #[test]
     fn new_network() {
-        let conn = Connector::Network(Network::new_server("192.168.1.1", 6000).into());
+        let conn = Connector::Network(Network::new_server("192.168.1.1", 6000));
         assert_eq!(conn.to_string(), "192.168.1.1:6000");
         if let Connector::Network(nw) = conn {
             assert_eq!(nw.ip(), "192.168.1.1");
         } else {
-            assert!(false, "connector is not network");
+            panic!("connector is not network");
         }
     }
 
     #[test]
+    #[allow(clippy::unnecessary_literal_unwrap)]
     fn scanner_error() {
         let err: Result<(), ScannerError> = Err(ScannerError::Param("无效的IP地址".into()));
         assert!(err.is_err(), "err is not a scanner error");
@@ -460,4 +2436,481 @@ mod tests {
         let r = scanner.start().await;
         assert!(r.is_ok());
     }
+
+    #[tokio::test]
+    async fn filter_rejected() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6001))
+            .filter(Regex::new(r"^SN\d{4}$").unwrap())
+            .rejected(tx);
+        scanner.dispatch("test", Bytes::from_static(b"XX1234"));
+        scanner.dispatch("test", Bytes::from_static(b"SN1234"));
+        let rejected = rx.recv().await.unwrap();
+        assert_eq!(rejected.barcode().text(), "XX1234");
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN1234");
+    }
+
+    #[tokio::test]
+    async fn no_read() {
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6001)).no_read("NoRead");
+        scanner.dispatch("test", Bytes::from_static(b"NoRead\r\n"));
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::NoRead(_))));
+    }
+
+    #[tokio::test]
+    async fn trim_end() {
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6001)).trim_end([b'\r', b'\n']);
+        scanner.dispatch("test", Bytes::from_static(b"\r\n"));
+        scanner.dispatch("test", Bytes::from_static(b"SN0001\r\n"));
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.payload().unwrap().as_ref(), b"SN0001");
+    }
+
+    #[tokio::test]
+    async fn split_read() {
+        use std::time::Duration;
+        use tokio::io::AsyncWriteExt;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port));
+        scanner.start().await.unwrap().unwrap();
+        let (mut device, _) = listener.accept().await.unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        // 一个条码分两次写入，拼接后作为一个条码
+        device.write_all(b"SN00").await.unwrap();
+        device.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        device.write_all(b"01\r\nSN0002\r\n").await.unwrap();
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0002"
+        );
+        // 没有分隔符的数据在连接关闭时作为一帧处理
+        device.write_all(b"SN0003").await.unwrap();
+        drop(device);
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0003"
+        );
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Disconnected)
+        ));
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    async fn network_server_keeps_session() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6011));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let mut client = tokio::net::TcpStream::connect("127.0.0.1:6011")
+            .await
+            .unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        // 其他地址的连接(例如端口扫描)被关闭，不影响正在使用的扫码枪
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.2:0".parse().unwrap()).unwrap();
+        let mut other = socket
+            .connect("127.0.0.1:6011".parse().unwrap())
+            .await
+            .unwrap();
+        let mut buf = [0u8; 8];
+        assert_eq!(other.read(&mut buf).await.unwrap_or(0), 0);
+        client.write_all(b"SN0001\r\n").await.unwrap();
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    #[cfg(any(feature = "tls", feature = "websocket"))]
+    async fn accept_handshake_timeout() {
+        use std::time::Duration;
+
+        // 连接后不发送数据的客户端在超时后被放弃，不会一直占用监听
+        let scanner =
+            Scanner::new(Network::new_server("127.0.0.1", 6012)).timeout(Duration::from_millis(50));
+        let handshake = std::future::pending::<std::io::Result<()>>();
+        let err = scanner.accept_handshake(handshake).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn receive_zero_copy() {
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 6001));
+        let data = Bytes::from_static(b"SN0001\r\nSN0002\r\n");
+        scanner.receive("test", data.clone());
+        // 条码是读取缓冲的切片，不复制数据
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.payload().unwrap().as_ptr(), data.as_ptr());
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.payload().unwrap().as_ref(), b"SN0002");
+        assert_eq!(ev.payload().unwrap().as_ptr(), data[8..].as_ptr());
+    }
+
+    #[tokio::test]
+    async fn network_server_session() {
+        use tokio::io::AsyncWriteExt;
+
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6002));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let mut client = tokio::net::TcpStream::connect("127.0.0.1:6002")
+            .await
+            .unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        client.write_all(b"SN0001\r\n").await.unwrap();
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+        // 与串口相同，一次收到的多个条码按回车换行拆开
+        client.write_all(b"SN0002\r\nSN0003\r\n").await.unwrap();
+        for sn in ["SN0002", "SN0003"] {
+            assert_eq!(scanner.recv().await.unwrap().as_str_lossy().unwrap(), sn);
+        }
+    }
+
+    #[tokio::test]
+    async fn network_server_reconnect() {
+        use tokio::io::AsyncWriteExt;
+
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6005));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        for sn in ["SN0001", "SN0002"] {
+            let mut client = tokio::net::TcpStream::connect("127.0.0.1:6005")
+                .await
+                .unwrap();
+            assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+            client
+                .write_all(format!("{}\r\n", sn).as_bytes())
+                .await
+                .unwrap();
+            let ev = scanner.recv().await.unwrap();
+            assert_eq!(ev.as_str_lossy().unwrap(), sn);
+            // 断开后监听不关闭，可以立即重新连接
+            drop(client);
+            assert!(matches!(
+                scanner.recv().await,
+                Some(ScanEvent::Disconnected)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn network_server_power_cycle() {
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6009));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // 扫码枪断电重启时旧连接没有关闭，新的连接替换旧连接
+        let mut old: Option<tokio::net::TcpStream> = None;
+        for sn in ["SN0001", "SN0002", "SN0003", "SN0004"] {
+            let mut client = tokio::net::TcpStream::connect("127.0.0.1:6009")
+                .await
+                .unwrap();
+            client
+                .write_all(format!("{}\r\n", sn).as_bytes())
+                .await
+                .unwrap();
+            // 新的连接替换旧连接时先发出旧连接的断开事件
+            if old.is_some() {
+                let ev = scanner.recv().await.unwrap();
+                assert!(matches!(ev, ScanEvent::Disconnected));
+            }
+            let ev = scanner.recv().await.unwrap();
+            assert!(matches!(ev, ScanEvent::Connected));
+            let ev = tokio::time::timeout(Duration::from_secs(2), scanner.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(ev.as_str_lossy().unwrap(), sn);
+            if let Some(mut old) = old.replace(client) {
+                let mut buf = [0u8; 8];
+                assert_eq!(old.read(&mut buf).await.unwrap_or(0), 0);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn network_server_restart_keeps_listener() {
+        use std::time::Duration;
+        use tokio::io::AsyncWriteExt;
+
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6010));
+        for sn in ["SN0001", "SN0002", "SN0003"] {
+            scanner.start().await.unwrap().unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let mut client = tokio::net::TcpStream::connect("127.0.0.1:6010")
+                .await
+                .unwrap();
+            client
+                .write_all(format!("{}\r\n", sn).as_bytes())
+                .await
+                .unwrap();
+            assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+            assert_eq!(scanner.recv().await.unwrap().as_str_lossy().unwrap(), sn);
+            // 停止后监听仍由扫码枪持有，重新启动不会绑定失败
+            scanner.stop();
+            assert!(tokio::net::TcpListener::bind("127.0.0.1:6010")
+                .await
+                .is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn network_ip_filter() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let filter = IpFilter::new().deny("127.0.0.0/8");
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6006).with_ip_filter(filter));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let mut client = tokio::net::TcpStream::connect("127.0.0.1:6006")
+            .await
+            .unwrap();
+        // 被拒绝的连接直接关闭
+        let _ = client.write_all(b"SN0001").await;
+        let mut buf = [0u8; 8];
+        assert_eq!(client.read(&mut buf).await.unwrap_or(0), 0);
+
+        let filter = IpFilter::new().allow("10.0.0.0/8").allow("not-an-ip");
+        let scanner = Scanner::new(Network::new_server("127.0.0.1", 6006).with_ip_filter(filter));
+        assert!(scanner.start().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn network_connection_tagged() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .id("st1")
+            .label("line", "1");
+        scanner.start().await.unwrap().unwrap();
+        let (device, _) = listener.accept().await.unwrap();
+        // 网络连接的连接、断开事件同样带有编号和标签
+        let ev = scanner.recv_tagged().await.unwrap();
+        assert!(matches!(ev.event(), ScanEvent::Connected));
+        assert_eq!((ev.id(), ev.label("line")), ("st1", Some("1")));
+        drop(device);
+        let ev = scanner.recv_tagged().await.unwrap();
+        assert!(matches!(ev.event(), ScanEvent::Disconnected));
+        assert_eq!((ev.id(), ev.label("line")), ("st1", Some("1")));
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    async fn network_client_control() {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .retry(RetryPolicy::fixed(std::time::Duration::from_millis(10)));
+        scanner.start().await.unwrap().unwrap();
+        // 重新连接指令关闭当前连接，之后重新连接
+        let (mut first, _) = listener.accept().await.unwrap();
+        scanner.send_message("BEEP".into()).await.unwrap().unwrap();
+        scanner.reconnect().await.unwrap().unwrap();
+        let mut received = vec![];
+        first.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"BEEP");
+        let (mut second, _) = listener.accept().await.unwrap();
+        // 关闭指令在之前的指令写入后执行
+        scanner.send_message("BYE".into()).await.unwrap().unwrap();
+        scanner.shutdown().await.unwrap().unwrap();
+        let mut received = vec![];
+        second.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"BYE");
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!scanner.is_running());
+    }
+
+    #[tokio::test]
+    async fn send_confirmed() {
+        use std::time::Duration;
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .queue_policy(QueuePolicy::buffer(Duration::from_millis(50)));
+        // 未连接时一直等待，重新连接后发现缓存超时，返回错误
+        let pending = tokio::spawn({
+            let scanner = scanner.clone();
+            async move { scanner.send_confirmed("OLD".into()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!pending.is_finished());
+        scanner.start().await.unwrap().unwrap();
+        let (mut device, _) = listener.accept().await.unwrap();
+        let r = pending.await.unwrap().unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+        // 写入连接后返回
+        scanner.send_confirmed("NEW".into()).await.unwrap().unwrap();
+        let mut received = [0u8; 3];
+        device.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"NEW");
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    async fn short_write() {
+        use std::pin::Pin;
+        use std::task::{Context, Poll};
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        /// 不返回数据、每次只能写入 0 字节的连接
+        struct Stalled;
+
+        impl AsyncRead for Stalled {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                _: &mut ReadBuf<'_>,
+            ) -> Poll<std::io::Result<()>> {
+                Poll::Pending
+            }
+        }
+
+        impl AsyncWrite for Stalled {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                _: &[u8],
+            ) -> Poll<std::io::Result<usize>> {
+                Poll::Ready(Ok(0))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+            ) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        // 写入不完整时发出通讯错误事件并结束会话
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", 9));
+        let pending = tokio::spawn({
+            let scanner = scanner.clone();
+            async move { scanner.send_confirmed("BEEP".into()).await }
+        });
+        scanner.session("127.0.0.1:9", Stalled).await;
+        let r = pending.await.unwrap().unwrap();
+        assert!(matches!(r, Err(ScannerError::Comm(_))));
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        let ev = scanner.recv().await.unwrap();
+        assert!(
+            matches!(ev, ScanEvent::Error(ScannerError::Comm(msg)) if msg.starts_with("发送数据错误"))
+        );
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Disconnected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .timeout(Duration::from_millis(100));
+        scanner.start().await.unwrap().unwrap();
+        let (mut device, _) = listener.accept().await.unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        // 每段没有数据的时间只发出一次超时事件
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Error(ScannerError::Timeout(_))));
+        let ev = tokio::time::timeout(Duration::from_millis(300), scanner.recv()).await;
+        assert!(ev.is_err());
+        device.write_all(b"SN0001\r").await.unwrap();
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Error(ScannerError::Timeout(_))));
+        scanner.stop();
+
+        // 设置为重新连接时超时后断开
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port))
+            .timeout(Duration::from_millis(100))
+            .on_timeout(TimeoutAction::Reconnect);
+        scanner.start().await.unwrap().unwrap();
+        let (mut device, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 8];
+        assert_eq!(device.read(&mut buf).await.unwrap(), 0);
+        assert!(scanner.is_running());
+    }
+
+    /// 创建虚拟串口，返回模拟扫码枪的主端和给扫码枪打开的从端路径
+    #[cfg(unix)]
+    pub(crate) fn pty() -> (tokio_serial::SerialStream, String) {
+        use tokio_serial::SerialPort;
+
+        let (device, port) = tokio_serial::SerialStream::pair().unwrap();
+        let path = port.name().unwrap();
+        // 主端打开期间从端可以重新打开
+        drop(port);
+        (device, path)
+    }
+
+    #[tokio::test]
+    async fn start_twice() {
+        use std::time::Duration;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let scanner = Scanner::new(Network::new_client("127.0.0.1", port));
+        scanner.start().await.unwrap().unwrap();
+        // 已启动时再次启动不会建立第二个连接
+        scanner.start().await.unwrap().unwrap();
+        let (_device, _) = listener.accept().await.unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        let second = tokio::time::timeout(Duration::from_millis(300), listener.accept()).await;
+        assert!(second.is_err());
+        assert!(scanner.is_running());
+        // 停止后可以重新启动
+        scanner.stop();
+        scanner.start().await.unwrap().unwrap();
+        let (_device, _) = listener.accept().await.unwrap();
+        assert!(scanner.is_running());
+        scanner.stop();
+    }
+
+    #[tokio::test]
+    async fn network_ipv6_session() {
+        use tokio::io::AsyncWriteExt;
+
+        let scanner = Scanner::new(Network::new_server("::1", 6004));
+        scanner.start().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let mut client = tokio::net::TcpStream::connect("[::1]:6004").await.unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        client.write_all(b"SN0001\r\n").await.unwrap();
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+    }
 }
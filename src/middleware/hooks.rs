@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{ScanEvent, Scanner, ScannerError};
+
+/// 扫码枪生命周期钩子，在连接、断开、出错、扫码时调用，见[`Scanner::hooks`](crate::Scanner::hooks)
+///
+/// 所有方法都有空的默认实现，只需实现关心的方法。钩子在读取线程中同步调用，不要在其中阻塞，
+/// 耗时的处理请转交给其它任务
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// struct Andon;
+///
+/// impl ScannerHooks for Andon {
+///     fn on_disconnect(&self, ctx: &HookContext) {
+///         println!("{} 工位 {} 扫码枪断开", ctx.labels()["station"], ctx.addr());
+///     }
+///
+///     fn on_scan(&self, ctx: &HookContext, event: &ScanEvent) {
+///         println!("{} {}", ctx.addr(), event.printable());
+///     }
+/// }
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+///     .label("station", "OP10")
+///     .hooks(Andon);
+/// ```
+pub trait ScannerHooks: Send + Sync {
+    /// 连接成功
+    fn on_connect(&self, _ctx: &HookContext) {}
+
+    /// 连接断开
+    fn on_disconnect(&self, _ctx: &HookContext) {}
+
+    /// 出现错误，包括连接失败、帧校验失败等
+    fn on_error(&self, _ctx: &HookContext, _err: &ScannerError) {}
+
+    /// 收到条码，包括未读、比对一致和比对不一致，事件已经过中间件处理
+    fn on_scan(&self, _ctx: &HookContext, _event: &ScanEvent) {}
+}
+
+/// 共享的钩子，注册后调用方仍可以持有并读取钩子中记录的状态
+impl<T: ScannerHooks + ?Sized> ScannerHooks for Arc<T> {
+    fn on_connect(&self, ctx: &HookContext) {
+        (**self).on_connect(ctx)
+    }
+
+    fn on_disconnect(&self, ctx: &HookContext) {
+        (**self).on_disconnect(ctx)
+    }
+
+    fn on_error(&self, ctx: &HookContext, err: &ScannerError) {
+        (**self).on_error(ctx, err)
+    }
+
+    fn on_scan(&self, ctx: &HookContext, event: &ScanEvent) {
+        (**self).on_scan(ctx, event)
+    }
+}
+
+/// 钩子的调用上下文
+pub struct HookContext<'a> {
+    scanner: &'a Scanner,
+    addr: &'a str,
+}
+
+impl<'a> HookContext<'a> {
+    pub(crate) fn new(scanner: &'a Scanner, addr: &'a str) -> Self {
+        HookContext { scanner, addr }
+    }
+
+    /// 触发钩子的扫码枪，可以用于回复指令
+    pub fn scanner(&self) -> &Scanner {
+        self.scanner
+    }
+
+    /// 扫码枪编号，未设置时为`None`
+    pub fn id(&self) -> Option<&str> {
+        self.scanner.get_id()
+    }
+
+    /// 日志中显示的地址，设置了编号时为`编号@连接地址`
+    pub fn addr(&self) -> &str {
+        self.addr
+    }
+
+    /// 扫码枪标签
+    pub fn labels(&self) -> &BTreeMap<String, String> {
+        self.scanner.labels()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn hooks() {
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl ScannerHooks for Recorder {
+            fn on_connect(&self, ctx: &HookContext) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("connect {}", ctx.id().unwrap()));
+            }
+
+            fn on_disconnect(&self, ctx: &HookContext) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("disconnect {}", ctx.addr()));
+            }
+
+            fn on_scan(&self, ctx: &HookContext, event: &ScanEvent) {
+                let line = ctx.labels()["line"].clone();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", line, event.printable()));
+            }
+        }
+
+        let mock = MockConnector::new();
+        let recorder = Arc::new(Recorder::default());
+        let scanner = Scanner::new(mock.clone())
+            .id("A")
+            .label("line", "L1")
+            .retry(RetryPolicy::fixed(Duration::from_millis(10)))
+            .hooks(Arc::clone(&recorder));
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        mock.push("SN0001");
+        scanner.recv().await.unwrap();
+        mock.disconnect();
+        assert!(matches!(
+            scanner.recv().await,
+            Some(ScanEvent::Disconnected)
+        ));
+        scanner.stop();
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["connect A", "L1 SN0001", "disconnect A@MOCK"]
+        );
+    }
+}
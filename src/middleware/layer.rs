@@ -0,0 +1,19 @@
+use crate::ScanEvent;
+
+/// 事件中间件，在事件发送给使用者之前依次调用
+pub trait Layer: Send + Sync {
+    /// 处理事件，返回`None`时丢弃该事件
+    fn call(&self, event: ScanEvent) -> Option<ScanEvent>;
+}
+
+/// 由闭包实现的中间件，见[`Scanner::map`](crate::Scanner::map)
+pub struct MapLayer<F>(pub F);
+
+impl<F> Layer for MapLayer<F>
+where
+    F: Fn(ScanEvent) -> ScanEvent + Send + Sync,
+{
+    fn call(&self, event: ScanEvent) -> Option<ScanEvent> {
+        Some((self.0)(event))
+    }
+}
@@ -0,0 +1,3 @@
+pub mod hooks;
+pub mod layer;
+pub mod transform;
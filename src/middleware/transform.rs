@@ -0,0 +1,61 @@
+use crate::{Layer, ScanEvent};
+
+/// 常用的条码转换中间件，作用于带条码的事件(包括未解码和比对结果事件)
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let barcode = Barcode::new(&b" sn0001\r\n"[..], Encoding::Utf8);
+/// let ev = [Transform::Trim, Transform::Uppercase, Transform::Prefix("L01-".into())]
+///     .iter()
+///     .try_fold(ScanEvent::Barcode(barcode), |ev, layer| layer.call(ev))
+///     .unwrap();
+/// assert_eq!(ev.as_str_lossy().unwrap(), "L01-SN0001");
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Transform {
+    /// 去掉首尾空白字符
+    Trim,
+    /// 转为大写
+    Uppercase,
+    /// 转为小写
+    Lowercase,
+    /// 截取子串(按字符计算)，`(起始位置, 长度)`，长度为`None`时截取到末尾
+    Substring(usize, Option<usize>),
+    /// 添加前缀，例如站点代码
+    Prefix(String),
+    /// 添加后缀
+    Suffix(String),
+}
+
+impl Transform {
+    /// 转换文本
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Transform::Trim => text.trim().to_owned(),
+            Transform::Uppercase => text.to_uppercase(),
+            Transform::Lowercase => text.to_lowercase(),
+            Transform::Substring(start, len) => {
+                let chars = text.chars().skip(*start);
+                match len {
+                    Some(len) => chars.take(*len).collect(),
+                    None => chars.collect(),
+                }
+            }
+            Transform::Prefix(prefix) => format!("{}{}", prefix, text),
+            Transform::Suffix(suffix) => format!("{}{}", text, suffix),
+        }
+    }
+}
+
+impl Layer for Transform {
+    fn call(&self, event: ScanEvent) -> Option<ScanEvent> {
+        Some(event.map_barcode(|barcode| {
+            let text = self.apply(&barcode.text());
+            barcode.with_text(&text)
+        }))
+    }
+}
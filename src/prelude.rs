@@ -1,7 +1,133 @@
+pub use crate::connector::baud::AutoBaud;
+#[cfg(feature = "bluetooth")]
+pub use crate::connector::bluetooth::Bluetooth;
+pub use crate::connector::capture::Capture;
+pub use crate::connector::command::QueuePolicy;
 pub use crate::connector::connector::Connector;
+#[cfg(feature = "hid")]
+pub use crate::connector::hid::Hid;
+pub use crate::connector::ip_filter::IpFilter;
+pub use crate::connector::keepalive::Keepalive;
+pub use crate::connector::mock::MockConnector;
 pub use crate::connector::network::Network;
+pub use crate::connector::port::PortFilter;
+pub use crate::connector::port::PortInfo;
+pub use crate::connector::port::PortType;
+pub use crate::connector::proxy::Proxy;
+pub use crate::connector::proxy::ProxyKind;
+pub use crate::connector::replay::Replay;
+pub use crate::connector::retry::Backoff;
+pub use crate::connector::retry::RetryPolicy;
+pub use crate::connector::rs485::Rs485Bus;
+pub use crate::connector::serial::FlowControl;
 pub use crate::connector::serial::Parity;
 pub use crate::connector::serial::Serial;
 pub use crate::connector::serial::StopBits;
+pub use crate::connector::socket::SocketOptions;
+pub use crate::connector::timeout::TimeoutAction;
+#[cfg(feature = "tls")]
+pub use crate::connector::tls::Tls;
+pub use crate::connector::transport::Connecting;
+pub use crate::connector::transport::Transport;
+pub use crate::connector::transport::TransportStream;
+pub use crate::connector::watchdog::Watchdog;
+#[cfg(feature = "websocket")]
+pub use crate::connector::websocket::WebSocket;
+#[cfg(feature = "discovery")]
+pub use crate::discovery::discovered::Discovered;
+#[cfg(feature = "discovery")]
+pub use crate::discovery::discovered::DiscoverySource;
+#[cfg(feature = "discovery")]
+pub use crate::discovery::discovery::Discovery;
+#[cfg(feature = "discovery")]
+pub use crate::discovery::udp::UdpProbe;
+pub use crate::emulator::Emulator;
+pub use crate::emulator::Fault;
 pub use crate::error::scanner::ScannerError;
+#[cfg(feature = "serde")]
+pub use crate::fleet::config::FleetConfig;
+#[cfg(feature = "serde")]
+pub use crate::fleet::config::ScannerConfig;
+pub use crate::fleet::event::FleetEvent;
+pub use crate::fleet::failover::Failover;
+pub use crate::fleet::manager::ScannerManager;
+pub use crate::fleet::route::Route;
+pub use crate::fleet::stats::ScannerStats;
+pub use crate::fleet::supervisor::Supervisor;
+pub use crate::frame::checksum::Checksum;
+pub use crate::frame::checksum::ChecksumAlgorithm;
+pub use crate::frame::checksum::ChecksumPosition;
+pub use crate::gs1::parser::Gs1;
+pub use crate::gs1::parser::Gs1Element;
+pub use crate::gs1::separator::GsNormalizer;
+pub use crate::gs1::separator::GS;
+pub use crate::middleware::hooks::HookContext;
+pub use crate::middleware::hooks::ScannerHooks;
+pub use crate::middleware::layer::Layer;
+pub use crate::middleware::layer::MapLayer;
+pub use crate::middleware::transform::Transform;
+pub use crate::protocols::backup::ConfigBackup;
+pub use crate::protocols::cognex::CognexDataMan;
+pub use crate::protocols::feedback::Feedback;
+pub use crate::protocols::feedback::Indicator;
+pub use crate::protocols::handshake::Handshake;
+pub use crate::protocols::honeywell::Honeywell;
+pub use crate::protocols::keyence::KeyenceSr;
+pub use crate::protocols::protocol::DeviceInfo;
+pub use crate::protocols::protocol::ReadMode;
+pub use crate::protocols::protocol::Replying;
+pub use crate::protocols::protocol::ScannerProtocol;
+pub use crate::protocols::symbology::Symbology;
+pub use crate::protocols::template::CommandTemplates;
+pub use crate::protocols::trigger::SoftTrigger;
+pub use crate::protocols::zebra::ZebraSsi;
+pub use crate::scan::barcode::Barcode;
+pub use crate::scan::encoding::Encoding;
+pub use crate::scan::event::ScanEvent;
+pub use crate::scan::quality::Quality;
+pub use crate::scan::quality::QualityField;
+pub use crate::scan::quality::QualityFormat;
+pub use crate::scan::record::FieldSplitter;
+pub use crate::scan::record::Fields;
+pub use crate::scan::record::Record;
+pub use crate::scan::rejected::Rejected;
+#[cfg(feature = "grpc")]
+pub use crate::server::grpc::proto;
+#[cfg(feature = "grpc")]
+pub use crate::server::grpc::GrpcServer;
+#[cfg(feature = "modbus")]
+pub use crate::server::modbus::ModbusServer;
+#[cfg(feature = "opcua")]
+pub use crate::server::opcua::OpcUaServer;
+#[cfg(feature = "ws-push")]
+pub use crate::server::push::PushServer;
+#[cfg(feature = "api")]
+pub use crate::server::rest::RestApi;
+pub use crate::sink::barcode::BarcodeSink;
+pub use crate::sink::barcode::CallbackSink;
+pub use crate::sink::barcode::ChannelSink;
+pub use crate::sink::barcode::Delivering;
+pub use crate::sink::barcode::LogSink;
+#[cfg(feature = "sink-file")]
+pub use crate::sink::file::FileSink;
+#[cfg(feature = "sink-mqtt")]
+pub use crate::sink::mqtt::MqttSink;
+#[cfg(feature = "sink-plc")]
+pub use crate::sink::plc::PlcSink;
+#[cfg(feature = "sink-redis")]
+pub use crate::sink::redis::RedisSink;
+#[cfg(feature = "sink-webhook")]
+pub use crate::sink::webhook::WebhookSink;
+#[cfg(feature = "metrics")]
+pub use crate::telemetry::metrics::describe_metrics;
+#[cfg(feature = "prometheus")]
+pub use crate::telemetry::prometheus::serve_metrics;
+pub use crate::udi::parser::Udi;
+pub use crate::udi::parser::UdiDate;
+pub use crate::udi::parser::UdiIssuer;
+pub use crate::validate::check_digit::CheckDigit;
+pub use crate::validate::match_code::MatchCode;
+pub use crate::validate::validator::Validator;
+pub use crate::validate::vin::Vin;
 pub use crate::Scanner;
+pub use regex::Regex;
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use tracing::{event, Level};
+
+use crate::protocols::protocol::ScannerProtocol;
+use crate::ScannerError;
+
+/// 扫码枪设置备份，用于更换故障扫码枪时复制设置
+///
+/// 按设置名称逐项读取，恢复时逐项写入后保存到扫码枪。
+/// 备份文件每行一项，格式为`名称=值`，`#`开头的行为注释
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let old = KeyenceSr::new(Scanner::new(Network::new_client("192.168.100.100", 9004)));
+/// old.scanner().start().await??;
+/// let backup = ConfigBackup::read(&old, ["101", "102", "200"]).await?;
+/// backup.save("line1-sr1000.cfg")?;
+///
+/// let new = KeyenceSr::new(Scanner::new(Network::new_client("192.168.100.101", 9004)));
+/// new.scanner().start().await??;
+/// ConfigBackup::load("line1-sr1000.cfg")?.restore(&new).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigBackup {
+    entries: Vec<(String, String)>,
+}
+
+impl ConfigBackup {
+    /// 创建设置备份
+    ///
+    /// * `entries` (设置名称，设置值)
+    pub fn new<K: Into<String>, V: Into<String>>(
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        ConfigBackup {
+            entries: entries
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+
+    /// 从扫码枪读取设置
+    ///
+    /// * `device` 厂商协议模块
+    /// * `names` 需要备份的设置名称，格式见各厂商的协议模块
+    pub async fn read<S: AsRef<str>>(
+        device: &dyn ScannerProtocol,
+        names: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ScannerError> {
+        let mut entries = vec![];
+        for name in names {
+            let name = name.as_ref();
+            let value = device.get_parameter(name).await?;
+            entries.push((name.to_owned(), value));
+        }
+        Ok(ConfigBackup { entries })
+    }
+
+    /// 把设置写入扫码枪并保存
+    pub async fn restore(&self, device: &dyn ScannerProtocol) -> Result<(), ScannerError> {
+        let scanner = device.scanner();
+        let addr = scanner.tag(&scanner.connector);
+        for (name, value) in &self.entries {
+            device.set_parameter(name, value).await?;
+        }
+        device.save_parameters().await?;
+        event!(Level::INFO, scanner.addr = %addr, count = self.entries.len(), "恢复设置");
+        Ok(())
+    }
+
+    /// 解析备份文件内容
+    pub fn parse(text: &str) -> Result<Self, ScannerError> {
+        let mut entries = vec![];
+        for (no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once('=').ok_or_else(|| {
+                ScannerError::Param(format!("备份文件第{}行格式无效,line={}", no + 1, line))
+            })?;
+            entries.push((name.trim().to_owned(), value.trim().to_owned()));
+        }
+        Ok(ConfigBackup { entries })
+    }
+
+    /// 读取备份文件
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScannerError> {
+        let text = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+        ConfigBackup::parse(&text)
+    }
+
+    /// 保存到备份文件
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScannerError> {
+        std::fs::write(path, self.to_string()).map_err(ScannerError::Io)
+    }
+
+    /// 获取设置项
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    /// 获取设置值
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl std::fmt::Display for ConfigBackup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, value) in &self.entries {
+            writeln!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn backup_restore() {
+        let mock = MockConnector::new();
+        let sr = KeyenceSr::new(Scanner::new(mock.clone()));
+        sr.scanner().start().await.unwrap().unwrap();
+        let device = mock.clone();
+        let reader = tokio::spawn(async move {
+            assert_eq!(device.next_command().await.unwrap(), "RP,101\r");
+            device.push("OK,RP,1\r");
+            assert_eq!(device.next_command().await.unwrap(), "RP,200\r");
+            device.push("OK,RP,ABC\r");
+            assert_eq!(device.next_command().await.unwrap(), "WP,101,1\r");
+            device.push("OK,WP\r");
+            assert_eq!(device.next_command().await.unwrap(), "WP,200,ABC\r");
+            device.push("OK,WP\r");
+            assert_eq!(device.next_command().await.unwrap(), "SAVE\r");
+            device.push("OK,SAVE\r");
+        });
+        let backup = ConfigBackup::read(&sr, ["101", "200"]).await.unwrap();
+        assert_eq!(backup.to_string(), "101=1\n200=ABC\n");
+        let backup = ConfigBackup::parse(&format!("# SR-1000\n{}", backup)).unwrap();
+        assert_eq!(backup.get("200"), Some("ABC"));
+        backup.restore(&sr).await.unwrap();
+        reader.await.unwrap();
+        assert!(ConfigBackup::parse("101").is_err());
+    }
+}
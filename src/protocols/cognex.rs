@@ -0,0 +1,382 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::protocols::protocol::{DeviceInfo, ReadMode, Replying, ScannerProtocol};
+use crate::protocols::response::Responses;
+use crate::{Feedback, Indicator, Scanner, ScannerError, SoftTrigger, Symbology};
+
+/// Cognex DataMan 扫码枪(DMCC 指令)
+///
+/// 指令格式为`||校验:序号>指令 参数\r\n`，应答格式为`||校验:序号[状态]数据`，
+/// 按序号匹配指令和应答，状态为 0 表示成功。
+/// 启用校验后，指令和应答在 CR/LF 之前多一个字节，为之前所有字节的异或值
+///
+/// 自动设置[`Scanner::beep`]的蜂鸣方式 1~3(蜂鸣 1~3 次)，
+/// [`Scanner::set_indicator`]对应用户输出事件 1(合格)和 2(不合格)
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let dm = CognexDataMan::new(Scanner::new(Network::new_client("192.168.0.50", 23)));
+/// dm.scanner().start().await??;
+///
+/// println!("{}", dm.device_name().await?);
+/// dm.set("DATA.RESULT-TIMEOUT", "2000").await?;
+/// dm.trigger_on().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CognexDataMan {
+    scanner: Scanner,
+    responses: Arc<Responses>,
+    timeout: Duration,
+    checksum: bool,
+    sequence: Arc<AtomicU32>,
+}
+
+impl CognexDataMan {
+    /// 创建 Cognex DataMan 扫码枪
+    pub fn new(scanner: Scanner) -> Self {
+        let responses = Arc::new(Responses::new(|frame| frame.starts_with(b"||")));
+        CognexDataMan {
+            scanner: scanner
+                .soft_trigger(SoftTrigger::new("||>TRIGGER ON\r\n").with_stop("||>TRIGGER OFF\r\n"))
+                .feedback(
+                    Feedback::new()
+                        .with_beep(1, "||>BEEP 1 2\r\n")
+                        .with_beep(2, "||>BEEP 2 2\r\n")
+                        .with_beep(3, "||>BEEP 3 3\r\n")
+                        .with_indicator(Indicator::Good, "||>OUTPUT.USER1\r\n")
+                        .with_indicator(Indicator::Bad, "||>OUTPUT.USER2\r\n"),
+                )
+                .responses(Arc::clone(&responses)),
+            responses,
+            timeout: Duration::from_secs(3),
+            checksum: false,
+            sequence: Arc::new(AtomicU32::new(0)),
+        }
+        .install()
+    }
+
+    /// 设置等待指令应答的时长，默认3秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.install()
+    }
+
+    /// 启用 DMCC 校验
+    pub fn with_checksum(mut self, checksum: bool) -> Self {
+        self.checksum = checksum;
+        self.install()
+    }
+
+    /// 获取扫码枪，用于启动和接收条码
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// 把协议注册到扫码枪，供[`Scanner::device_info`]使用
+    fn install(mut self) -> Self {
+        self.scanner.protocol = None;
+        self.scanner = self.scanner.clone().protocol(Arc::new(self.clone()));
+        self
+    }
+
+    /// 开始读取(TRIGGER ON)，读取结果作为条码事件发出
+    pub async fn trigger_on(&self) -> Result<(), ScannerError> {
+        self.command("TRIGGER ON").await?;
+        Ok(())
+    }
+
+    /// 停止读取(TRIGGER OFF)
+    pub async fn trigger_off(&self) -> Result<(), ScannerError> {
+        self.command("TRIGGER OFF").await?;
+        Ok(())
+    }
+
+    /// 获取设备名称(GET DEVICE.NAME)
+    pub async fn device_name(&self) -> Result<String, ScannerError> {
+        self.get("DEVICE.NAME").await
+    }
+
+    /// 读取设置(GET)
+    ///
+    /// * `name` 设置名称，例如`DATA.RESULT-TIMEOUT`
+    pub async fn get(&self, name: &str) -> Result<String, ScannerError> {
+        self.command(&format!("GET {}", name)).await
+    }
+
+    /// 修改设置(SET)
+    pub async fn set(&self, name: &str, value: &str) -> Result<(), ScannerError> {
+        self.command(&format!("SET {} {}", name, value)).await?;
+        Ok(())
+    }
+
+    /// 发送 DMCC 指令并等待应答，返回应答数据
+    ///
+    /// * `cmd` 指令，不含`||>`前缀和结尾的 CR/LF，例如`GET DEVICE.NAME`
+    pub async fn command(&self, cmd: &str) -> Result<String, ScannerError> {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) % 1000;
+        let mut buf = format!("||{}:{}>{}", self.checksum as u8, sequence, cmd);
+        if self.checksum {
+            buf.push(xor(buf.as_bytes()) as char);
+        }
+        buf.push_str("\r\n");
+        let frame = self
+            .responses
+            .request(&self.scanner, buf, self.timeout, |frame| {
+                parse(frame).is_some_and(|r| r.sequence == Some(sequence))
+            })
+            .await?;
+        let response = parse(&frame).ok_or_else(|| {
+            ScannerError::Parse(format!(
+                "无效的应答,cmd={},应答={}",
+                cmd,
+                String::from_utf8_lossy(&frame)
+            ))
+        })?;
+        if response.checksum && !response.valid {
+            return Err(ScannerError::Checksum(format!(
+                "DMCC应答校验失败,cmd={}",
+                cmd
+            )));
+        }
+        match response.status {
+            0 => Ok(response.data),
+            status => Err(ScannerError::Device(format!(
+                "{},cmd={},状态={}",
+                status_message(status),
+                cmd,
+                status
+            ))),
+        }
+    }
+}
+
+impl ScannerProtocol for CognexDataMan {
+    fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    fn trigger_start(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_on())
+    }
+
+    fn trigger_stop(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_off())
+    }
+
+    /// 蜂鸣一次(BEEP 1 2)
+    fn beep(&self) -> Replying<'_, ()> {
+        Box::pin(async move {
+            self.command("BEEP 1 2").await?;
+            Ok(())
+        })
+    }
+
+    /// 查询型号、固件版本和序列号(GET DEVICE.TYPE/FIRMWARE-VER/SERIAL-NUMBER)
+    fn query_info(&self) -> Replying<'_, DeviceInfo> {
+        Box::pin(async move {
+            let model = self.get("DEVICE.TYPE").await?;
+            let firmware = self.get("DEVICE.FIRMWARE-VER").await?;
+            let serial_number = self.get("DEVICE.SERIAL-NUMBER").await?;
+            Ok(
+                DeviceInfo::new(format!("{} {} {}", model, firmware, serial_number))
+                    .with_model(model)
+                    .with_firmware(firmware)
+                    .with_serial_number(serial_number),
+            )
+        })
+    }
+
+    /// 修改触发方式(SET TRIGGER.TYPE)，连续读取为 5，触发读取为 0(单次)，感应模式为 1
+    fn set_read_mode(&self, mode: ReadMode) -> Replying<'_, ()> {
+        let value = match mode {
+            ReadMode::Continuous => "5",
+            ReadMode::Triggered => "0",
+            ReadMode::Presentation => "1",
+        };
+        Box::pin(self.set("TRIGGER.TYPE", value))
+    }
+
+    /// 修改码制设置(SET SYMBOL.*)，UPC-A、UPC-E、EAN-8、EAN-13 共用`SYMBOL.UPC-EAN`
+    fn enable_symbology(&self, symbology: Symbology, enable: bool) -> Replying<'_, ()> {
+        Box::pin(async move {
+            let name = match symbology {
+                Symbology::Code128 => "SYMBOL.C128",
+                Symbology::Code39 => "SYMBOL.C39",
+                Symbology::Code93 => "SYMBOL.C93",
+                Symbology::Codabar => "SYMBOL.CODABAR",
+                Symbology::Interleaved2of5 => "SYMBOL.I2O5",
+                Symbology::UpcA | Symbology::UpcE | Symbology::Ean8 | Symbology::Ean13 => {
+                    "SYMBOL.UPC-EAN"
+                }
+                Symbology::Pdf417 => "SYMBOL.PDF417",
+                Symbology::QrCode => "SYMBOL.QR",
+                Symbology::DataMatrix => "SYMBOL.DATAMATRIX",
+                Symbology::Aztec => "SYMBOL.AZTECCODE",
+                Symbology::Gs1DataBar => {
+                    return Err(ScannerError::Device(format!(
+                        "扫码枪不支持该码制,symbology={:?}",
+                        symbology
+                    )))
+                }
+            };
+            self.set(name, if enable { "ON" } else { "OFF" }).await
+        })
+    }
+
+    /// 等同于[`CognexDataMan::get`]
+    fn get_parameter<'a>(&'a self, name: &'a str) -> Replying<'a, String> {
+        Box::pin(self.get(name))
+    }
+
+    /// 等同于[`CognexDataMan::set`]
+    fn set_parameter<'a>(&'a self, name: &'a str, value: &'a str) -> Replying<'a, ()> {
+        Box::pin(self.set(name, value))
+    }
+
+    /// 保存设置(CONFIG.SAVE)
+    fn save_parameters(&self) -> Replying<'_, ()> {
+        Box::pin(async move {
+            self.command("CONFIG.SAVE").await?;
+            Ok(())
+        })
+    }
+}
+
+/// DMCC 应答
+#[derive(Debug, PartialEq)]
+struct Response {
+    checksum: bool,
+    /// 校验是否正确，未启用校验时为`true`
+    valid: bool,
+    sequence: Option<u32>,
+    status: u32,
+    data: String,
+}
+
+/// 解析应答，`||[状态]数据`或`||校验:序号[状态]数据`
+fn parse(frame: &[u8]) -> Option<Response> {
+    let end = frame
+        .iter()
+        .rposition(|b| *b != b'\r' && *b != b'\n')
+        .map_or(0, |i| i + 1);
+    let frame = &frame[..end];
+    let rest = frame.strip_prefix(b"||")?;
+    let open = rest.iter().position(|b| *b == b'[')?;
+    let header = std::str::from_utf8(&rest[..open]).ok()?;
+    let (checksum, sequence) = match header.split_once(':') {
+        Some((checksum, sequence)) => (checksum == "1", Some(sequence.parse().ok()?)),
+        None => (false, None),
+    };
+    let (frame, valid) = match (checksum, frame.split_last()) {
+        (true, Some((sum, body))) => (body, xor(body) == *sum),
+        _ => (frame, true),
+    };
+    let rest = &frame[2 + open + 1..];
+    let close = rest.iter().position(|b| *b == b']')?;
+    let status = std::str::from_utf8(&rest[..close]).ok()?.parse().ok()?;
+    Some(Response {
+        checksum,
+        valid,
+        sequence,
+        status,
+        data: String::from_utf8_lossy(&rest[close + 1..]).into_owned(),
+    })
+}
+
+/// 所有字节的异或值
+fn xor(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, b| sum ^ b)
+}
+
+/// 状态说明
+fn status_message(status: u32) -> &'static str {
+    match status {
+        1 => "未知错误",
+        100 => "未定义的指令",
+        101 => "参数无效",
+        102 => "校验错误",
+        103 => "当前状态下参数被拒绝",
+        104 => "扫码枪不可用",
+        _ => "指令执行失败",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, xor, Response};
+    use crate::prelude::*;
+
+    #[test]
+    fn parse_response() {
+        assert_eq!(
+            parse(b"||0:12[0]DM262\r\n"),
+            Some(Response {
+                checksum: false,
+                valid: true,
+                sequence: Some(12),
+                status: 0,
+                data: "DM262".into(),
+            })
+        );
+        assert_eq!(parse(b"||[100]").unwrap().status, 100);
+        let mut frame = b"||1:3[0]ON".to_vec();
+        frame.push(xor(&frame));
+        assert!(parse(&frame).unwrap().valid);
+        frame.pop();
+        frame.push(0);
+        assert!(!parse(&frame).unwrap().valid);
+        assert!(parse(b"4901234567894").is_none());
+    }
+
+    #[tokio::test]
+    async fn command() {
+        let mock = MockConnector::new();
+        let dm = CognexDataMan::new(Scanner::new(mock.clone()));
+        dm.scanner().start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:0>GET DEVICE.NAME\r\n"
+            );
+            mock.push("||0:0[0]Line1-DM262");
+            assert_eq!(mock.next_command().await.unwrap(), "||0:1>SET FOO 1\r\n");
+            mock.push("||0:1[100]");
+        });
+        assert_eq!(dm.device_name().await.unwrap(), "Line1-DM262");
+        let err = dm.set("FOO", "1").await.unwrap_err();
+        assert!(matches!(err, ScannerError::Device(_)));
+        device.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_mode() {
+        let mock = MockConnector::new();
+        let dm = CognexDataMan::new(Scanner::new(mock.clone()));
+        let scanner = dm.scanner();
+        scanner.start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:0>SET TRIGGER.TYPE 5\r\n"
+            );
+            mock.push("||0:0[0]");
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:1>SET TRIGGER.TYPE 0\r\n"
+            );
+            mock.push("||0:1[101]");
+        });
+        assert_eq!(scanner.read_mode(), None);
+        scanner.set_read_mode(ReadMode::Continuous).await.unwrap();
+        assert!(scanner.set_read_mode(ReadMode::Triggered).await.is_err());
+        assert_eq!(scanner.read_mode(), Some(ReadMode::Continuous));
+        device.await.unwrap();
+    }
+}
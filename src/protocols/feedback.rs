@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+/// 操作员提示灯
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Indicator {
+    /// 合格(通常为绿灯)
+    Good,
+    /// 不合格(通常为红灯)
+    Bad,
+}
+
+/// 蜂鸣和提示灯指令，用于[`crate::Scanner::beep`]和[`crate::Scanner::set_indicator`]
+///
+/// 厂商协议模块会自动设置支持的指令，其它扫码枪按通讯手册配置
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let feedback = Feedback::new()
+///     .with_beep(1, "BEEP1\r")
+///     .with_beep(2, "BEEP2\r")
+///     .with_indicator(Indicator::Good, "OUTON,1\r")
+///     .with_indicator(Indicator::Bad, "OUTON,2\r");
+/// assert_eq!(feedback.beep(2), Some("BEEP2\r"));
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).feedback(feedback);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feedback {
+    /// 蜂鸣方式对应的指令
+    beeps: HashMap<u8, String>,
+    /// 提示灯对应的指令
+    indicators: HashMap<Indicator, String>,
+}
+
+impl Feedback {
+    /// 创建空的指令配置
+    pub fn new() -> Self {
+        Feedback::default()
+    }
+
+    /// 设置蜂鸣指令
+    ///
+    /// * `pattern` 蜂鸣方式编号，由使用方约定，例如 1 表示短鸣
+    /// * `cmd` 对应的指令
+    pub fn with_beep(mut self, pattern: u8, cmd: &str) -> Self {
+        self.beeps.insert(pattern, cmd.into());
+        self
+    }
+
+    /// 设置提示灯指令
+    pub fn with_indicator(mut self, indicator: Indicator, cmd: &str) -> Self {
+        self.indicators.insert(indicator, cmd.into());
+        self
+    }
+
+    /// 获取蜂鸣指令
+    pub fn beep(&self, pattern: u8) -> Option<&str> {
+        self.beeps.get(&pattern).map(String::as_str)
+    }
+
+    /// 获取提示灯指令
+    pub fn indicator(&self, indicator: Indicator) -> Option<&str> {
+        self.indicators.get(&indicator).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn feedback() {
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone()).feedback(
+            Feedback::new()
+                .with_beep(1, "BEEP1")
+                .with_indicator(Indicator::Good, "GOOD"),
+        );
+        scanner.start().await.unwrap().unwrap();
+        scanner.beep(1).await.unwrap();
+        scanner.set_indicator(Indicator::Good).await.unwrap();
+        assert!(matches!(scanner.beep(2).await, Err(ScannerError::Param(_))));
+        assert!(scanner.set_indicator(Indicator::Bad).await.is_err());
+        assert_eq!(mock.next_command().await.unwrap(), "BEEP1");
+        assert_eq!(mock.next_command().await.unwrap(), "GOOD");
+    }
+}
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+use tracing::{event, Level};
+
+use crate::{Scanner, ScannerError};
+
+/// 指令应答握手，用于收到指令后回复 ACK/NAK 的扫码枪
+///
+/// 设置后[`crate::Scanner::send_message`]发送指令后等待扫码枪应答：
+/// 收到 ACK 返回成功，收到 NAK 重发指令，超过重试次数返回[`ScannerError::Device`]，
+/// 等待超时返回[`ScannerError::Timeout`]。ACK/NAK 不作为条码发出
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// let scanner = Scanner::new(Serial::new("COM3", 9600, 8, StopBits::One, Parity::None))
+///     .handshake(Handshake::new().with_retries(2).with_timeout(Duration::from_millis(500)));
+///
+/// // 厂商自定义的应答
+/// let handshake = Handshake::new().with_ack(b"OK").with_nak(b"NG");
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Handshake {
+    ack: Vec<u8>,
+    nak: Vec<u8>,
+    retries: u32,
+    timeout: Duration,
+}
+
+impl Default for Handshake {
+    fn default() -> Self {
+        Handshake {
+            ack: vec![0x06],
+            nak: vec![0x15],
+            retries: 3,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Handshake {
+    /// 创建握手配置，默认 ACK 为`0x06`，NAK 为`0x15`，NAK 后重试3次，等待应答1秒
+    pub fn new() -> Self {
+        Handshake::default()
+    }
+
+    /// 设置 ACK 应答
+    pub fn with_ack(mut self, ack: &[u8]) -> Self {
+        self.ack = ack.to_vec();
+        self
+    }
+
+    /// 设置 NAK 应答
+    pub fn with_nak(mut self, nak: &[u8]) -> Self {
+        self.nak = nak.to_vec();
+        self
+    }
+
+    /// 设置收到 NAK 后的重试次数
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// 设置等待应答的时长
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 获取 ACK 应答
+    pub fn ack(&self) -> &[u8] {
+        &self.ack
+    }
+
+    /// 获取 NAK 应答
+    pub fn nak(&self) -> &[u8] {
+        &self.nak
+    }
+
+    /// 获取重试次数
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// 获取等待应答的时长
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// 握手应答路由，`true`为 ACK
+pub(crate) struct Handshaking {
+    config: Handshake,
+    sender: UnboundedSender<bool>,
+    receiver: Mutex<UnboundedReceiver<bool>>,
+}
+
+impl Handshaking {
+    pub(crate) fn new(config: Handshake) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Handshaking {
+            config,
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    /// 截获 ACK/NAK，返回`true`表示该帧是握手应答
+    pub(crate) fn intercept(&self, frame: &[u8]) -> bool {
+        let ack = frame == self.config.ack.as_slice();
+        if !ack && frame != self.config.nak.as_slice() {
+            return false;
+        }
+        let _ = self.sender.send(ack);
+        true
+    }
+
+    /// 发送指令并等待 ACK，收到 NAK 时重发
+    pub(crate) async fn send(
+        &self,
+        scanner: &Scanner,
+        cmd: String,
+    ) -> Result<Result<(), ScannerError>, ScannerError> {
+        let addr = scanner.tag(&scanner.connector);
+        let mut receiver = self.receiver.lock().await;
+        // 丢弃之前超时指令的迟到应答
+        while receiver.try_recv().is_ok() {}
+        for attempt in 0..=self.config.retries {
+            if let Err(err) = scanner.enqueue(cmd.clone()).await? {
+                return Ok(Err(err));
+            }
+            match tokio::time::timeout(self.config.timeout, receiver.recv()).await {
+                Ok(Some(true)) => return Ok(Ok(())),
+                Ok(Some(false)) => {
+                    event!(
+                        Level::WARN,
+                        scanner.addr = %addr,
+                        cmd = ?cmd.trim(),
+                        retries = attempt,
+                        "扫码枪应答NAK",
+                    );
+                }
+                Ok(None) => {
+                    return Err(ScannerError::Comm(format!(
+                        "握手通道已关闭,cmd={}",
+                        cmd.trim()
+                    )))
+                }
+                Err(_) => {
+                    return Ok(Err(ScannerError::Timeout(format!(
+                        "等待ACK超时,cmd={}",
+                        cmd.trim()
+                    ))))
+                }
+            }
+        }
+        Ok(Err(ScannerError::Device(format!(
+            "扫码枪应答NAK,重试{}次后失败,cmd={}",
+            self.config.retries,
+            cmd.trim()
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn handshake() {
+        use std::time::Duration;
+
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone()).handshake(
+            Handshake::new()
+                .with_retries(1)
+                .with_timeout(Duration::from_millis(200)),
+        );
+        scanner.start().await.unwrap().unwrap();
+        let device = mock.clone();
+        let reader = tokio::spawn(async move {
+            assert_eq!(device.next_command().await.unwrap(), "OUT1");
+            device.push([0x15]);
+            assert_eq!(device.next_command().await.unwrap(), "OUT1");
+            device.push([0x06]);
+            for _ in 0..2 {
+                assert_eq!(device.next_command().await.unwrap(), "OUT2");
+                device.push([0x15]);
+            }
+            assert_eq!(device.next_command().await.unwrap(), "OUT3");
+        });
+        scanner.send_message("OUT1".into()).await.unwrap().unwrap();
+        let r = scanner.send_message("OUT2".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Device(_))));
+        let r = scanner.send_message("OUT3".into()).await.unwrap();
+        assert!(matches!(r, Err(ScannerError::Timeout(_))));
+        reader.await.unwrap();
+        // ACK/NAK 不作为条码发出
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        mock.push("SN0001");
+        assert_eq!(
+            scanner.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::protocols::protocol::{ReadMode, Replying, ScannerProtocol};
+use crate::protocols::response::Responses;
+use crate::{Scanner, ScannerError, SoftTrigger, Symbology};
+
+/// 菜单指令前缀`SYN M CR`
+const MENU: &str = "\x16M\r";
+const ACK: u8 = 0x06;
+const ENQ: u8 = 0x05;
+const NAK: u8 = 0x15;
+
+/// Honeywell 扫码枪(串口菜单指令)
+///
+/// 菜单指令格式为`SYN M CR 指令 结束符`，多条指令用`;`分隔，
+/// 结束符`!`表示临时修改，`.`表示保存到扫码枪。
+/// 扫码枪回显指令，并在每条指令后附加状态：ACK(0x06)成功，ENQ(0x05)指令无效，NAK(0x15)参数无效
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let serial = Serial::new("COM3", 115200, 8, StopBits::One, Parity::None);
+/// let hw = Honeywell::new(Scanner::new(serial)).with_save(true);
+/// hw.scanner().start().await??;
+///
+/// hw.enable_symbology(Symbology::Code39, false).await?;
+/// hw.set_suffix(b"\r\n").await?;
+/// hw.trigger_on().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Honeywell {
+    scanner: Scanner,
+    responses: Arc<Responses>,
+    timeout: Duration,
+    save: bool,
+}
+
+impl Honeywell {
+    /// 创建 Honeywell 扫码枪
+    pub fn new(scanner: Scanner) -> Self {
+        let responses = Arc::new(Responses::new(is_response));
+        Honeywell {
+            scanner: scanner
+                .soft_trigger(SoftTrigger::new("\x16T\r").with_stop("\x16U\r"))
+                .responses(Arc::clone(&responses)),
+            responses,
+            timeout: Duration::from_secs(3),
+            save: false,
+        }
+        .install()
+    }
+
+    /// 设置等待指令应答的时长，默认3秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.install()
+    }
+
+    /// 修改的设置是否保存到扫码枪，默认只临时修改，断电后恢复
+    pub fn with_save(mut self, save: bool) -> Self {
+        self.save = save;
+        self.install()
+    }
+
+    /// 获取扫码枪，用于启动和接收条码
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// 把协议注册到扫码枪，供[`Scanner::device_info`]使用
+    fn install(mut self) -> Self {
+        self.scanner.protocol = None;
+        self.scanner = self.scanner.clone().protocol(Arc::new(self.clone()));
+        self
+    }
+
+    /// 软件触发扫码(`SYN T CR`)，扫码枪不应答
+    pub async fn trigger_on(&self) -> Result<(), ScannerError> {
+        self.scanner.send_message("\x16T\r".into()).await?
+    }
+
+    /// 停止软件触发(`SYN U CR`)
+    pub async fn trigger_off(&self) -> Result<(), ScannerError> {
+        self.scanner.send_message("\x16U\r".into()).await?
+    }
+
+    /// 启用或禁用码制
+    pub async fn enable_symbology(
+        &self,
+        symbology: Symbology,
+        enable: bool,
+    ) -> Result<(), ScannerError> {
+        let cmd = format!("{}ENA{}", tag(symbology), enable as u8);
+        self.command(&cmd).await?;
+        Ok(())
+    }
+
+    /// 设置所有码制的后缀，空后缀表示清除后缀
+    pub async fn set_suffix(&self, suffix: &[u8]) -> Result<(), ScannerError> {
+        if suffix.is_empty() {
+            self.command("SUFCA2").await?;
+            return Ok(());
+        }
+        // 99 表示所有码制，后缀按十六进制表示
+        let hex = suffix
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<String>();
+        self.command(&format!("SUFBK299{}", hex)).await?;
+        Ok(())
+    }
+
+    /// 查询设置，返回设置值
+    ///
+    /// * `tag` 指令标签，例如`C39ENA`
+    pub async fn query(&self, tag: &str) -> Result<String, ScannerError> {
+        let echo = self.command(&format!("{}?", tag)).await?;
+        Ok(echo.strip_prefix(tag).unwrap_or(&echo).to_owned())
+    }
+
+    /// 发送菜单指令并等待应答，返回回显的指令(不含状态和结束符)
+    ///
+    /// * `cmd` 菜单指令，不含前缀和结束符，多条指令用`;`分隔，例如`C39ENA1;128ENA1`
+    pub async fn command(&self, cmd: &str) -> Result<String, ScannerError> {
+        let tag = cmd.get(..6).unwrap_or(cmd).to_owned();
+        let end = if self.save { '.' } else { '!' };
+        let frame = self
+            .responses
+            .request(
+                &self.scanner,
+                format!("{}{}{}", MENU, cmd, end),
+                self.timeout,
+                |frame| frame.starts_with(tag.as_bytes()),
+            )
+            .await?;
+        let mut echoes = vec![];
+        for (echo, status) in parse(&frame) {
+            match status {
+                ACK => echoes.push(echo),
+                ENQ => return Err(device_error("指令无效", cmd, &echo)),
+                NAK => return Err(device_error("参数超出范围", cmd, &echo)),
+                _ => {
+                    return Err(ScannerError::Parse(format!(
+                        "无效的应答,cmd={},应答={}",
+                        cmd,
+                        String::from_utf8_lossy(&frame)
+                    )))
+                }
+            }
+        }
+        Ok(echoes.join(";"))
+    }
+}
+
+impl ScannerProtocol for Honeywell {
+    fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    fn trigger_start(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_on())
+    }
+
+    fn trigger_stop(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_off())
+    }
+
+    /// 连续读取为流式感应模式(PAPSPN)，触发读取为手动触发(PAPHHF)，感应模式为 PAPPST
+    fn set_read_mode(&self, mode: ReadMode) -> Replying<'_, ()> {
+        let cmd = match mode {
+            ReadMode::Continuous => "PAPSPN",
+            ReadMode::Triggered => "PAPHHF",
+            ReadMode::Presentation => "PAPPST",
+        };
+        Box::pin(async move {
+            self.command(cmd).await?;
+            Ok(())
+        })
+    }
+
+    fn enable_symbology(&self, symbology: Symbology, enable: bool) -> Replying<'_, ()> {
+        Box::pin(Honeywell::enable_symbology(self, symbology, enable))
+    }
+
+    /// `name`为菜单指令标签，例如`C39ENA`，等同于[`Honeywell::query`]
+    fn get_parameter<'a>(&'a self, name: &'a str) -> Replying<'a, String> {
+        Box::pin(self.query(name))
+    }
+
+    /// `name`为菜单指令标签，例如`C39ENA`
+    fn set_parameter<'a>(&'a self, name: &'a str, value: &'a str) -> Replying<'a, ()> {
+        Box::pin(async move {
+            self.command(&format!("{}{}", name, value)).await?;
+            Ok(())
+        })
+    }
+}
+
+/// 码制的菜单指令标签
+fn tag(symbology: Symbology) -> &'static str {
+    match symbology {
+        Symbology::Code128 => "128",
+        Symbology::Code39 => "C39",
+        Symbology::Code93 => "C93",
+        Symbology::Codabar => "CBR",
+        Symbology::Interleaved2of5 => "I25",
+        Symbology::UpcA => "UPA",
+        Symbology::UpcE => "UPE",
+        Symbology::Ean8 => "EA8",
+        Symbology::Ean13 => "E13",
+        Symbology::Gs1DataBar => "RSS",
+        Symbology::Pdf417 => "PDF",
+        Symbology::QrCode => "QRC",
+        Symbology::DataMatrix => "IDM",
+        Symbology::Aztec => "AZT",
+    }
+}
+
+/// 是否为菜单指令应答，以状态和结束符结尾
+fn is_response(frame: &[u8]) -> bool {
+    match frame {
+        [.., status, b'.' | b'!'] => matches!(*status, ACK | ENQ | NAK),
+        _ => false,
+    }
+}
+
+/// 解析应答，返回每条指令的(回显，状态)
+fn parse(frame: &[u8]) -> Vec<(String, u8)> {
+    let frame = match frame {
+        [rest @ .., b'.' | b'!'] => rest,
+        _ => frame,
+    };
+    frame
+        .split(|b| *b == b';')
+        .map(|part| match part.split_last() {
+            Some((status, echo)) => (String::from_utf8_lossy(echo).into_owned(), *status),
+            None => (String::new(), 0),
+        })
+        .collect()
+}
+
+fn device_error(reason: &str, cmd: &str, echo: &str) -> ScannerError {
+    ScannerError::Device(format!("{},cmd={},应答={}", reason, cmd, echo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn command() {
+        let mock = MockConnector::new();
+        let hw = Honeywell::new(Scanner::new(mock.clone()));
+        hw.scanner().start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            assert_eq!(mock.next_command().await.unwrap(), "\x16M\rC39ENA0!");
+            mock.push("C39ENA0\x06!");
+            assert_eq!(mock.next_command().await.unwrap(), "\x16M\rSUFBK2990D0A!");
+            mock.push("SUFBK2990D0A\x15!");
+            assert_eq!(mock.next_command().await.unwrap(), "\x16M\r128ENA?!");
+            mock.push("128ENA1\x06!");
+        });
+        hw.enable_symbology(Symbology::Code39, false).await.unwrap();
+        let err = hw.set_suffix(b"\r\n").await.unwrap_err();
+        assert!(matches!(err, ScannerError::Device(_)));
+        assert_eq!(hw.query("128ENA").await.unwrap(), "1");
+        device.await.unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn serial_command() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // 菜单指令经串口写入，扫码枪的应答从串口读回
+        let (mut device, path) = crate::tests::pty();
+        let serial = Serial::new(&path, 115200, 8, StopBits::One, Parity::None);
+        let hw =
+            Honeywell::new(Scanner::new(serial)).with_timeout(std::time::Duration::from_secs(1));
+        hw.scanner().start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            for (cmd, reply) in [
+                (&b"\x16M\rC39ENA0!"[..], &b"C39ENA0\x06!"[..]),
+                (b"\x16M\r128ENA?!", b"128ENA1\x06!"),
+            ] {
+                let mut buf = vec![0u8; cmd.len()];
+                device.read_exact(&mut buf).await.unwrap();
+                assert_eq!(buf, cmd);
+                device.write_all(reply).await.unwrap();
+            }
+            // 主端关闭后从端读取结束，等应答读完再关闭
+            device
+        });
+        hw.enable_symbology(Symbology::Code39, false).await.unwrap();
+        assert_eq!(hw.query("128ENA").await.unwrap(), "1");
+        hw.scanner().stop();
+        drop(device.await.unwrap());
+    }
+
+    #[test]
+    fn parse_response() {
+        assert_eq!(
+            parse(b"C39ENA1\x06;XYZABC\x05."),
+            vec![("C39ENA1".into(), 0x06), ("XYZABC".into(), 0x05)]
+        );
+        assert!(super::is_response(b"C39ENA1\x06!"));
+        assert!(!super::is_response(b"SN0001."));
+    }
+
+    #[tokio::test]
+    async fn restrict_symbologies() {
+        let mock = MockConnector::new();
+        let hw = Honeywell::new(Scanner::new(mock.clone()));
+        hw.scanner().start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            let mut enabled = vec![];
+            for _ in Symbology::ALL {
+                let cmd = mock.next_command().await.unwrap();
+                let cmd = cmd.trim_start_matches("\x16M\r").trim_end_matches('!');
+                if cmd.ends_with('1') {
+                    enabled.push(cmd.to_owned());
+                }
+                mock.push(format!("{}\x06!", cmd));
+            }
+            enabled
+        });
+        hw.scanner()
+            .restrict_symbologies(&[Symbology::QrCode, Symbology::Code128])
+            .await
+            .unwrap();
+        assert_eq!(device.await.unwrap(), ["128ENA1", "QRCENA1"]);
+    }
+}
@@ -0,0 +1,245 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::protocols::protocol::{DeviceInfo, Replying, ScannerProtocol};
+use crate::protocols::response::Responses;
+use crate::{Scanner, ScannerError, SoftTrigger};
+
+/// Keyence SR 系列扫码枪(SR-1000、SR-700、SR-X 等)
+///
+/// 封装 SR 系列的串口/以太网指令，指令以 CR 结尾：
+/// * `LON`/`LOFF` 开始/停止读取
+/// * 其它指令应答`OK,指令[,数据]`或`ER,指令,错误码`
+///
+/// 读取失败时扫码枪输出`ERROR`，作为[`crate::ScanEvent::NoRead`]发出
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let sr = KeyenceSr::new(Scanner::new(Network::new_client("192.168.100.100", 9004)));
+/// sr.scanner().start().await??;
+///
+/// sr.trigger_on().await?;
+/// if let Some(ev) = sr.scanner().recv().await {
+///     println!("{:?}", ev);
+/// }
+/// sr.trigger_off().await?;
+///
+/// // 读取/修改设置
+/// let value = sr.read_setting("101").await?;
+/// sr.write_setting("101", "1").await?;
+/// sr.save().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct KeyenceSr {
+    scanner: Scanner,
+    responses: Arc<Responses>,
+    timeout: Duration,
+}
+
+impl KeyenceSr {
+    /// 创建 Keyence SR 系列扫码枪
+    pub fn new(scanner: Scanner) -> Self {
+        let responses = Arc::new(Responses::new(is_response));
+        KeyenceSr {
+            scanner: scanner
+                .no_read("ERROR")
+                .soft_trigger(SoftTrigger::new("LON\r").with_stop("LOFF\r"))
+                .responses(Arc::clone(&responses)),
+            responses,
+            timeout: Duration::from_secs(3),
+        }
+        .install()
+    }
+
+    /// 设置等待指令应答的时长，默认3秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.install()
+    }
+
+    /// 获取扫码枪，用于启动和接收条码
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// 把协议注册到扫码枪，供[`Scanner::device_info`]使用
+    fn install(mut self) -> Self {
+        self.scanner.protocol = None;
+        self.scanner = self.scanner.clone().protocol(Arc::new(self.clone()));
+        self
+    }
+
+    /// 开始读取(LON)，读取结果作为条码事件发出
+    pub async fn trigger_on(&self) -> Result<(), ScannerError> {
+        self.scanner.send_message("LON\r".into()).await?
+    }
+
+    /// 停止读取(LOFF)
+    pub async fn trigger_off(&self) -> Result<(), ScannerError> {
+        self.scanner.send_message("LOFF\r".into()).await?
+    }
+
+    /// 读取设置(RP)，返回设置值
+    ///
+    /// * `code` 设置代码，见 SR 系列通讯手册
+    pub async fn read_setting(&self, code: &str) -> Result<String, ScannerError> {
+        self.command(&format!("RP,{}", code)).await
+    }
+
+    /// 修改设置(WP)，需要调用[`KeyenceSr::save`]后才会在断电后保留
+    ///
+    /// * `code` 设置代码
+    /// * `value` 设置值
+    pub async fn write_setting(&self, code: &str, value: &str) -> Result<(), ScannerError> {
+        self.command(&format!("WP,{},{}", code, value)).await?;
+        Ok(())
+    }
+
+    /// 保存设置(SAVE)
+    pub async fn save(&self) -> Result<(), ScannerError> {
+        self.command("SAVE").await?;
+        Ok(())
+    }
+
+    /// 发送指令并等待应答，返回`OK,指令,`之后的数据
+    ///
+    /// * `cmd` 指令，不含结尾的 CR，例如`RP,101`
+    pub async fn command(&self, cmd: &str) -> Result<String, ScannerError> {
+        let head = cmd.split(',').next().unwrap_or_default().to_owned();
+        let frame = self
+            .responses
+            .request(&self.scanner, format!("{}\r", cmd), self.timeout, |frame| {
+                parse(frame).is_some_and(|(_, name, _)| name == head)
+            })
+            .await?;
+        match parse(&frame) {
+            Some((true, _, data)) => Ok(data),
+            Some((false, _, code)) => Err(ScannerError::Device(format!(
+                "{},cmd={},错误码={}",
+                error_message(&code),
+                cmd,
+                code
+            ))),
+            None => Err(ScannerError::Parse(format!(
+                "无效的应答,cmd={},应答={}",
+                cmd,
+                String::from_utf8_lossy(&frame)
+            ))),
+        }
+    }
+}
+
+impl ScannerProtocol for KeyenceSr {
+    fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    fn trigger_start(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_on())
+    }
+
+    fn trigger_stop(&self) -> Replying<'_, ()> {
+        Box::pin(self.trigger_off())
+    }
+
+    /// 查询型号(KEYENCE)
+    fn query_info(&self) -> Replying<'_, DeviceInfo> {
+        Box::pin(async move {
+            let model = self.command("KEYENCE").await?;
+            Ok(DeviceInfo::new(model.clone()).with_model(model))
+        })
+    }
+
+    /// `name`为设置代码，等同于[`KeyenceSr::read_setting`]
+    fn get_parameter<'a>(&'a self, name: &'a str) -> Replying<'a, String> {
+        Box::pin(self.read_setting(name))
+    }
+
+    /// `name`为设置代码，等同于[`KeyenceSr::write_setting`]
+    fn set_parameter<'a>(&'a self, name: &'a str, value: &'a str) -> Replying<'a, ()> {
+        Box::pin(self.write_setting(name, value))
+    }
+
+    fn save_parameters(&self) -> Replying<'_, ()> {
+        Box::pin(self.save())
+    }
+}
+
+/// 是否为指令应答
+fn is_response(frame: &[u8]) -> bool {
+    frame.starts_with(b"OK,") || frame.starts_with(b"ER,")
+}
+
+/// 解析应答，返回(是否成功，指令，数据或错误码)
+fn parse(frame: &[u8]) -> Option<(bool, String, String)> {
+    let text = String::from_utf8_lossy(frame);
+    let mut parts = text.trim_end().splitn(3, ',');
+    let ok = match parts.next()? {
+        "OK" => true,
+        "ER" => false,
+        _ => return None,
+    };
+    let name = parts.next()?.to_owned();
+    let data = parts.next().unwrap_or_default().to_owned();
+    Some((ok, name, data))
+}
+
+/// 错误码说明
+fn error_message(code: &str) -> &'static str {
+    match code {
+        "00" => "未定义的指令",
+        "01" => "指令格式错误",
+        "02" | "03" | "04" | "05" | "06" => "参数超出范围",
+        _ => "指令执行失败",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn command() {
+        let mock = MockConnector::new();
+        let sr = KeyenceSr::new(Scanner::new(mock.clone()));
+        sr.scanner().start().await.unwrap().unwrap();
+        let device = tokio::spawn(async move {
+            assert_eq!(mock.next_command().await.unwrap(), "RP,101\r");
+            mock.push("SN0001");
+            mock.push("OK,RP,1\r");
+            assert_eq!(mock.next_command().await.unwrap(), "WP,101,9\r");
+            mock.push("ER,WP,02\r");
+            assert_eq!(mock.next_command().await.unwrap(), "LON\r");
+            mock.push("ERROR");
+        });
+        assert_eq!(sr.read_setting("101").await.unwrap(), "1");
+        let err = sr.write_setting("101", "9").await.unwrap_err();
+        assert!(matches!(err, ScannerError::Device(_)));
+        sr.trigger_on().await.unwrap();
+        device.await.unwrap();
+        assert!(matches!(
+            sr.scanner().recv().await,
+            Some(ScanEvent::Connected)
+        ));
+        let ev = sr.scanner().recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+        assert!(matches!(
+            sr.scanner().recv().await,
+            Some(ScanEvent::NoRead(_))
+        ));
+    }
+
+    #[test]
+    fn parse_response() {
+        assert_eq!(parse(b"OK,RP,1\r"), Some((true, "RP".into(), "1".into())));
+        assert_eq!(parse(b"OK,SAVE"), Some((true, "SAVE".into(), "".into())));
+        assert_eq!(parse(b"ER,WP,02"), Some((false, "WP".into(), "02".into())));
+        assert_eq!(parse(b"4901234567894"), None);
+    }
+}
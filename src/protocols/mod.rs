@@ -0,0 +1,12 @@
+pub mod backup;
+pub mod cognex;
+pub mod feedback;
+pub mod handshake;
+pub mod honeywell;
+pub mod keyence;
+pub mod protocol;
+pub(crate) mod response;
+pub mod symbology;
+pub mod template;
+pub mod trigger;
+pub mod zebra;
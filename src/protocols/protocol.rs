@@ -0,0 +1,218 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::{Scanner, ScannerError, Symbology};
+
+/// 扫码枪指令的执行结果
+pub type Replying<'a, T> = Pin<Box<dyn Future<Output = Result<T, ScannerError>> + Send + 'a>>;
+
+/// 读取模式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ReadMode {
+    /// 连续读取
+    Continuous,
+    /// 收到触发信号或指令后读取
+    Triggered,
+    /// 感应模式，检测到条码进入视野后自动读取
+    Presentation,
+}
+
+/// 扫码枪设备信息
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    /// 型号
+    model: Option<String>,
+    /// 固件版本
+    firmware: Option<String>,
+    /// 序列号
+    serial_number: Option<String>,
+    /// 扫码枪返回的原始信息
+    raw: String,
+}
+
+impl DeviceInfo {
+    /// 创建设备信息
+    ///
+    /// * `raw` 扫码枪返回的原始信息
+    pub fn new(raw: impl Into<String>) -> Self {
+        DeviceInfo {
+            raw: raw.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 设置型号
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// 设置固件版本
+    pub fn with_firmware(mut self, firmware: impl Into<String>) -> Self {
+        self.firmware = Some(firmware.into());
+        self
+    }
+
+    /// 设置序列号
+    pub fn with_serial_number(mut self, serial_number: impl Into<String>) -> Self {
+        self.serial_number = Some(serial_number.into());
+        self
+    }
+
+    /// 获取型号
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// 获取固件版本
+    pub fn firmware(&self) -> Option<&str> {
+        self.firmware.as_deref()
+    }
+
+    /// 获取序列号
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
+
+    /// 获取原始信息
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// 扫码枪控制协议
+///
+/// 各厂商的协议模块都实现了该接口，业务代码面向该接口编写即可在
+/// [`crate::KeyenceSr`]、[`crate::CognexDataMan`]、[`crate::ZebraSsi`]、[`crate::Honeywell`]之间切换。
+/// 设备不支持的操作返回[`ScannerError::Device`]
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// async fn inspect(device: &dyn ScannerProtocol) -> Result<(), ScannerError> {
+///     device.trigger_start().await?;
+///     if let Some(ev) = device.scanner().recv().await {
+///         println!("{:?}", ev);
+///     }
+///     device.trigger_stop().await
+/// }
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004));
+/// let device: Box<dyn ScannerProtocol> = Box::new(KeyenceSr::new(scanner));
+/// device.scanner().start().await??;
+/// inspect(device.as_ref()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ScannerProtocol: Send + Sync {
+    /// 获取扫码枪，用于启动和接收条码
+    fn scanner(&self) -> &Scanner;
+
+    /// 开始读取
+    fn trigger_start(&self) -> Replying<'_, ()>;
+
+    /// 停止读取
+    fn trigger_stop(&self) -> Replying<'_, ()>;
+
+    /// 蜂鸣
+    fn beep(&self) -> Replying<'_, ()> {
+        unsupported("beep")
+    }
+
+    /// 查询设备信息
+    fn query_info(&self) -> Replying<'_, DeviceInfo> {
+        unsupported("query_info")
+    }
+
+    /// 切换读取模式
+    fn set_read_mode(&self, mode: ReadMode) -> Replying<'_, ()> {
+        let _ = mode;
+        unsupported("set_read_mode")
+    }
+
+    /// 启用或禁用码制
+    fn enable_symbology(&self, symbology: Symbology, enable: bool) -> Replying<'_, ()> {
+        let _ = (symbology, enable);
+        unsupported("enable_symbology")
+    }
+
+    /// 读取设置，名称的格式见各厂商的协议模块
+    fn get_parameter<'a>(&'a self, name: &'a str) -> Replying<'a, String> {
+        let _ = name;
+        unsupported("get_parameter")
+    }
+
+    /// 修改设置，名称和值的格式见各厂商的协议模块
+    fn set_parameter<'a>(&'a self, name: &'a str, value: &'a str) -> Replying<'a, ()> {
+        let _ = (name, value);
+        unsupported("set_parameter")
+    }
+
+    /// 把修改的设置保存到扫码枪，断电后保留；修改即保存的设备不需要实现
+    fn save_parameters(&self) -> Replying<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 设备不支持的操作
+fn unsupported<'a, T: 'a>(operation: &str) -> Replying<'a, T> {
+    let err = ScannerError::Device(format!("扫码枪不支持该操作,operation={}", operation));
+    Box::pin(async move { Err(err) })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn protocol() {
+        let mock = MockConnector::new();
+        let device: Box<dyn ScannerProtocol> =
+            Box::new(CognexDataMan::new(Scanner::new(mock.clone())));
+        device.scanner().start().await.unwrap().unwrap();
+        let reader = tokio::spawn(async move {
+            assert_eq!(mock.next_command().await.unwrap(), "||0:0>TRIGGER ON\r\n");
+            mock.push("||0:0[0]");
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:1>GET DEVICE.TYPE\r\n"
+            );
+            mock.push("||0:1[0]DM262");
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:2>GET DEVICE.FIRMWARE-VER\r\n"
+            );
+            mock.push("||0:2[0]6.1.0");
+            assert_eq!(
+                mock.next_command().await.unwrap(),
+                "||0:3>GET DEVICE.SERIAL-NUMBER\r\n"
+            );
+            mock.push("||0:3[0]1A2B3C");
+        });
+        device.trigger_start().await.unwrap();
+        let info = device.scanner().device_info().await.unwrap();
+        assert_eq!(info.model(), Some("DM262"));
+        assert_eq!(info.firmware(), Some("6.1.0"));
+        assert_eq!(info.serial_number(), Some("1A2B3C"));
+        reader.await.unwrap();
+        // 已缓存，不再查询
+        assert_eq!(device.scanner().device_info().await.unwrap(), info);
+
+        let device: Box<dyn ScannerProtocol> =
+            Box::new(Honeywell::new(Scanner::new(MockConnector::new())));
+        assert!(matches!(
+            device.scanner().device_info().await,
+            Err(ScannerError::Device(_))
+        ));
+        let scanner = Scanner::new(MockConnector::new());
+        assert!(matches!(
+            scanner.device_info().await,
+            Err(ScannerError::Param(_))
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+
+use crate::{Scanner, ScannerError};
+
+/// 指令应答路由
+///
+/// 扫码枪的指令应答和条码从同一个连接返回，
+/// 匹配`matcher`的数据作为应答交给等待中的指令，不再作为条码发出
+pub(crate) struct Responses {
+    matcher: fn(&[u8]) -> bool,
+    sender: UnboundedSender<Vec<u8>>,
+    receiver: Mutex<UnboundedReceiver<Vec<u8>>>,
+}
+
+impl Responses {
+    /// 创建应答路由
+    ///
+    /// * `matcher` 判断一帧数据是否为指令应答
+    pub(crate) fn new(matcher: fn(&[u8]) -> bool) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Responses {
+            matcher,
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+
+    /// 截获指令应答，返回`true`表示该帧是应答
+    pub(crate) fn intercept(&self, frame: &[u8]) -> bool {
+        if !(self.matcher)(frame) {
+            return false;
+        }
+        let _ = self.sender.send(frame.to_vec());
+        true
+    }
+
+    /// 发送指令并等待`accept`接受的应答，同一时间只有一条指令在等待应答
+    pub(crate) async fn request(
+        &self,
+        scanner: &Scanner,
+        cmd: String,
+        timeout: Duration,
+        accept: impl Fn(&[u8]) -> bool,
+    ) -> Result<Vec<u8>, ScannerError> {
+        let mut receiver = self.receiver.lock().await;
+        // 丢弃之前超时指令的迟到应答
+        while receiver.try_recv().is_ok() {}
+        let name = cmd.trim().to_owned();
+        scanner.send_message(cmd).await??;
+        let wait = async {
+            while let Some(frame) = receiver.recv().await {
+                if accept(&frame) {
+                    return Some(frame);
+                }
+            }
+            None
+        };
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(Some(frame)) => Ok(frame),
+            Ok(None) => Err(ScannerError::Comm(format!("应答通道已关闭,cmd={}", name))),
+            Err(_) => Err(ScannerError::Timeout(format!("cmd={}", name))),
+        }
+    }
+}
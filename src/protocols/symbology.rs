@@ -0,0 +1,72 @@
+/// 码制，用于在扫码枪上启用或禁用
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Symbology {
+    Code128,
+    Code39,
+    Code93,
+    Codabar,
+    Interleaved2of5,
+    UpcA,
+    UpcE,
+    Ean8,
+    Ean13,
+    Gs1DataBar,
+    Pdf417,
+    QrCode,
+    DataMatrix,
+    Aztec,
+}
+
+impl Symbology {
+    /// 所有码制
+    pub const ALL: [Symbology; 14] = [
+        Symbology::Code128,
+        Symbology::Code39,
+        Symbology::Code93,
+        Symbology::Codabar,
+        Symbology::Interleaved2of5,
+        Symbology::UpcA,
+        Symbology::UpcE,
+        Symbology::Ean8,
+        Symbology::Ean13,
+        Symbology::Gs1DataBar,
+        Symbology::Pdf417,
+        Symbology::QrCode,
+        Symbology::DataMatrix,
+        Symbology::Aztec,
+    ];
+
+    /// 按数据开头的 AIM 符号标识(ISO/IEC 15424，例如`]C1`、`]d2`)识别码制，
+    /// 需要扫码枪配置为输出符号标识。`]E0`按 EAN-13 识别(UPC-A、UPC-E 与其相同)
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert_eq!(Symbology::from_aim(b"]d2010952123454321"), Some(Symbology::DataMatrix));
+    /// assert_eq!(Symbology::from_aim(b"SN0001"), None);
+    /// ```
+    pub fn from_aim(data: &[u8]) -> Option<Symbology> {
+        let [b']', code, modifier, ..] = data else {
+            return None;
+        };
+        let symbology = match (code, modifier) {
+            (b'A', _) => Symbology::Code39,
+            (b'C', _) => Symbology::Code128,
+            (b'E', b'4') => Symbology::Ean8,
+            (b'E', _) => Symbology::Ean13,
+            (b'F', _) => Symbology::Codabar,
+            (b'G', _) => Symbology::Code93,
+            (b'I', _) => Symbology::Interleaved2of5,
+            (b'e', _) => Symbology::Gs1DataBar,
+            (b'L', _) => Symbology::Pdf417,
+            (b'Q', _) => Symbology::QrCode,
+            (b'd', _) => Symbology::DataMatrix,
+            (b'z', _) => Symbology::Aztec,
+            _ => return None,
+        };
+        Some(symbology)
+    }
+}
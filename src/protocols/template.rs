@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::ScannerError;
+
+/// 指令模板库，按名称调用扫码枪指令，接入新型号时只需修改配置文件
+///
+/// 模板文件为 TOML 格式，值可以是字符串(支持`\r`、`\u0016`等转义)或字节数组，
+/// 子表中的指令名称用`.`连接。字节数组必须是有效的 UTF-8
+///
+/// ```toml
+/// trigger_on = "LON\r"
+/// trigger_off = "LOFF\r"
+/// beep = [0x16, 0x4D, 0x0D, 0x42, 0x45, 0x50, 0x45, 0x58, 0x45, 0x30, 0x2E]
+///
+/// [honeywell]
+/// trigger_on = "\u0016T\r"
+/// ```
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let templates = CommandTemplates::parse("trigger_on = \"LON\\r\"\n[honeywell]\ntrigger_on = \"\\u0016T\\r\"")?;
+/// assert_eq!(templates.get("honeywell.trigger_on"), Some("\x16T\r"));
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004))
+///     .commands(CommandTemplates::load("commands.toml")?);
+/// scanner.start().await??;
+/// scanner.command("trigger_on").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommandTemplates {
+    commands: BTreeMap<String, String>,
+}
+
+impl CommandTemplates {
+    /// 创建空的指令模板库
+    pub fn new() -> Self {
+        CommandTemplates::default()
+    }
+
+    /// 添加指令模板，同名的指令会被替换
+    pub fn with(mut self, name: &str, cmd: &str) -> Self {
+        self.commands.insert(name.into(), cmd.into());
+        self
+    }
+
+    /// 解析 TOML 格式的指令模板
+    pub fn parse(text: &str) -> Result<Self, ScannerError> {
+        let table = text
+            .parse::<Table>()
+            .map_err(|err| ScannerError::Param(format!("指令模板格式错误,{}", err)))?;
+        let mut templates = CommandTemplates::new();
+        templates.extend("", &table)?;
+        Ok(templates)
+    }
+
+    /// 读取并解析指令模板文件
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScannerError> {
+        let text = std::fs::read_to_string(path).map_err(ScannerError::Io)?;
+        CommandTemplates::parse(&text)
+    }
+
+    /// 获取指令
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.commands.get(name).map(String::as_str)
+    }
+
+    /// 获取所有指令名称
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
+    /// 添加表中的指令，子表名称作为前缀
+    fn extend(&mut self, prefix: &str, table: &Table) -> Result<(), ScannerError> {
+        for (key, value) in table {
+            let name = match prefix {
+                "" => key.clone(),
+                _ => format!("{}.{}", prefix, key),
+            };
+            let cmd = match value {
+                Value::String(cmd) => cmd.clone(),
+                Value::Array(bytes) => {
+                    let bytes = bytes
+                        .iter()
+                        .map(|b| b.as_integer().and_then(|b| u8::try_from(b).ok()))
+                        .collect::<Option<Vec<u8>>>()
+                        .ok_or_else(|| invalid(&name, "字节数组只能包含0~255的整数"))?;
+                    String::from_utf8(bytes).map_err(|_| invalid(&name, "不是有效的UTF-8"))?
+                }
+                Value::Table(table) => {
+                    self.extend(&name, table)?;
+                    continue;
+                }
+                _ => return Err(invalid(&name, "只能是字符串或字节数组")),
+            };
+            self.commands.insert(name, cmd);
+        }
+        Ok(())
+    }
+}
+
+fn invalid(name: &str, reason: &str) -> ScannerError {
+    ScannerError::Param(format!("指令模板无效,{},name={}", reason, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandTemplates;
+    use crate::prelude::*;
+
+    #[test]
+    fn parse() {
+        let templates = CommandTemplates::parse(
+            "trigger_on = \"LON\\r\"\nbeep = [0x02, 0x42, 0x03]\n[zebra]\nstart = [0xE4]\n",
+        );
+        assert!(templates.is_err());
+        let templates = CommandTemplates::parse(
+            "trigger_on = \"LON\\r\"\nbeep = [0x02, 0x42, 0x03]\n[cognex]\ntrigger_on = \"||>TRIGGER ON\\r\\n\"\n",
+        )
+        .unwrap();
+        assert_eq!(templates.get("trigger_on"), Some("LON\r"));
+        assert_eq!(templates.get("beep"), Some("\x02B\x03"));
+        assert_eq!(
+            templates.get("cognex.trigger_on"),
+            Some("||>TRIGGER ON\r\n")
+        );
+        assert_eq!(
+            templates.names().collect::<Vec<_>>(),
+            ["beep", "cognex.trigger_on", "trigger_on"]
+        );
+        assert!(CommandTemplates::parse("beep = [256]").is_err());
+        assert!(CommandTemplates::parse("beep = 1").is_err());
+        assert!(CommandTemplates::parse("beep = ").is_err());
+    }
+
+    #[tokio::test]
+    async fn command_templates() {
+        let mock = MockConnector::new();
+        let templates = CommandTemplates::parse("trigger_on = \"LON\\r\"").unwrap();
+        let scanner = Scanner::new(mock.clone()).commands(templates);
+        scanner.start().await.unwrap().unwrap();
+        scanner.command("trigger_on").await.unwrap();
+        assert!(matches!(
+            scanner.command("trigger_off").await,
+            Err(ScannerError::Param(_))
+        ));
+        assert_eq!(mock.next_command().await.unwrap(), "LON\r");
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::task::AbortHandle;
+
+/// 软件触发指令，用于[`crate::Scanner::trigger`]
+///
+/// 在防抖时长内重复触发会被忽略，避免按钮抖动或 PLC 信号重复导致多次扫码。
+/// 克隆的扫码枪共享防抖状态
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004)).soft_trigger(
+///     SoftTrigger::new("LON\r")
+///         .with_stop("LOFF\r")
+///         .with_debounce(Duration::from_millis(500)),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftTrigger {
+    start: String,
+    stop: Option<String>,
+    debounce: Duration,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: Arc<Mutex<TriggerState>>,
+}
+
+/// 防抖和自动停止状态
+#[derive(Debug, Default)]
+struct TriggerState {
+    /// 上一次触发的时间
+    last: Option<Instant>,
+    /// 等待发送的自动停止指令
+    stop: Option<AbortHandle>,
+}
+
+impl SoftTrigger {
+    /// 创建软件触发指令
+    ///
+    /// * `start` 开始扫码的指令
+    pub fn new(start: &str) -> Self {
+        SoftTrigger {
+            start: start.into(),
+            stop: None,
+            debounce: Duration::from_millis(200),
+            state: Arc::new(Mutex::new(TriggerState::default())),
+        }
+    }
+
+    /// 设置停止扫码的指令
+    pub fn with_stop(mut self, stop: &str) -> Self {
+        self.stop = Some(stop.into());
+        self
+    }
+
+    /// 设置防抖时长，默认200毫秒
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// 获取开始扫码的指令
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    /// 获取停止扫码的指令
+    pub fn stop(&self) -> Option<&str> {
+        self.stop.as_deref()
+    }
+
+    /// 获取防抖时长
+    pub fn debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    /// 防抖，返回`false`表示本次触发应忽略；触发时取消上一次等待中的自动停止
+    pub(crate) fn arm(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state
+            .last
+            .is_some_and(|last| now.duration_since(last) < self.debounce)
+        {
+            return false;
+        }
+        state.last = Some(now);
+        if let Some(stop) = state.stop.take() {
+            stop.abort();
+        }
+        true
+    }
+
+    /// 记录等待中的自动停止
+    pub(crate) fn schedule_stop(&self, stop: AbortHandle) {
+        self.state.lock().unwrap().stop = Some(stop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn soft_trigger() {
+        use std::time::Duration;
+
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone()).soft_trigger(
+            SoftTrigger::new("LON\r")
+                .with_stop("LOFF\r")
+                .with_debounce(Duration::from_millis(100)),
+        );
+        scanner.start().await.unwrap().unwrap();
+        assert!(scanner
+            .trigger(Some(Duration::from_millis(50)))
+            .await
+            .unwrap());
+        assert!(!scanner.trigger(None).await.unwrap());
+        assert_eq!(mock.next_command().await.unwrap(), "LON\r");
+        assert_eq!(mock.next_command().await.unwrap(), "LOFF\r");
+        // 再次触发会取消上一次的自动停止
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(scanner
+            .trigger(Some(Duration::from_millis(200)))
+            .await
+            .unwrap());
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(scanner.trigger(None).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(mock.try_commands(), ["LON\r", "LON\r"]);
+
+        let scanner = Scanner::new(MockConnector::new());
+        assert!(matches!(
+            scanner.trigger(None).await,
+            Err(ScannerError::Param(_))
+        ));
+    }
+}
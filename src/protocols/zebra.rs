@@ -0,0 +1,635 @@
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Mutex};
+use tracing::{event, Level};
+
+use crate::protocols::protocol::{DeviceInfo, ReadMode, Replying, ScannerProtocol};
+use crate::telemetry;
+use crate::{Connecting, Connector, Scanner, ScannerError, Symbology, Transport, TransportStream};
+
+/// 主机发出的数据包
+const SOURCE_HOST: u8 = 0x04;
+/// 状态位：后面还有数据包
+const STATUS_CONTINUATION: u8 = 0x02;
+
+const CMD_ACK: u8 = 0xD0;
+const CMD_NAK: u8 = 0xD1;
+const DECODE_DATA: u8 = 0xF3;
+const START_DECODE: u8 = 0xE4;
+const STOP_DECODE: u8 = 0xE5;
+const BEEP: u8 = 0xE6;
+const LED_ON: u8 = 0xE7;
+const LED_OFF: u8 = 0xE8;
+const SCAN_ENABLE: u8 = 0xE9;
+const SCAN_DISABLE: u8 = 0xEA;
+const PARAM_SEND: u8 = 0xC6;
+const REQUEST_REVISION: u8 = 0xA3;
+const REPLY_REVISION: u8 = 0xA4;
+
+/// NAK 原因：校验错误，请求重发
+const NAK_RESEND: u8 = 0x01;
+
+/// Zebra/Symbol 扫码枪(SSI 协议)
+///
+/// SSI 是二进制协议，数据包格式为`长度 指令 来源 状态 数据 校验(2字节)`，
+/// 扫码枪发出的条码数据包自动应答 ACK 并拆出条码，作为条码事件发出；
+/// 主机发出的指令等待扫码枪应答 ACK，应答 NAK 时返回[`ScannerError::Device`]。
+///
+/// 扫码枪需要设置为 SSI 通讯模式，支持串口、网络客户端和自定义传输层连接器
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let serial = Serial::new("COM3", 9600, 8, StopBits::One, Parity::None);
+/// let zebra = ZebraSsi::new(Scanner::new(serial));
+/// zebra.scanner().start().await??;
+///
+/// zebra.start_decode().await?;
+/// let barcode = zebra.scanner().recv().await;
+/// zebra.beep(0x00).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ZebraSsi {
+    scanner: Scanner,
+    requests: UnboundedSender<Request>,
+    timeout: Duration,
+}
+
+impl ZebraSsi {
+    /// 创建 Zebra 扫码枪，扫码枪的连接器替换为 SSI 协议层
+    pub fn new(mut scanner: Scanner) -> Self {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        let transport = SsiTransport {
+            inner: scanner.connector.clone(),
+            requests: Arc::new(Mutex::new(receiver)),
+        };
+        scanner.connector = Connector::transport(transport);
+        ZebraSsi {
+            scanner,
+            requests,
+            timeout: Duration::from_secs(3),
+        }
+        .install()
+    }
+
+    /// 设置等待指令应答的时长，默认3秒
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.install()
+    }
+
+    /// 获取扫码枪，用于启动和接收条码
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// 把协议注册到扫码枪，供[`Scanner::device_info`]使用
+    fn install(mut self) -> Self {
+        self.scanner.protocol = None;
+        self.scanner = self.scanner.clone().protocol(Arc::new(self.clone()));
+        self
+    }
+
+    /// 开始扫码(START_DECODE)
+    pub async fn start_decode(&self) -> Result<(), ScannerError> {
+        self.command(START_DECODE, &[]).await
+    }
+
+    /// 停止扫码(STOP_DECODE)
+    pub async fn stop_decode(&self) -> Result<(), ScannerError> {
+        self.command(STOP_DECODE, &[]).await
+    }
+
+    /// 蜂鸣(BEEP)
+    ///
+    /// * `code` 蜂鸣代码，`0x00`~`0x1A`，不同代码的音调和次数不同
+    pub async fn beep(&self, code: u8) -> Result<(), ScannerError> {
+        self.command(BEEP, &[code]).await
+    }
+
+    /// 点亮指示灯(LED_ON)
+    ///
+    /// * `leds` 指示灯位掩码，第0位为解码指示灯
+    pub async fn led_on(&self, leds: u8) -> Result<(), ScannerError> {
+        self.command(LED_ON, &[leds]).await
+    }
+
+    /// 熄灭指示灯(LED_OFF)
+    pub async fn led_off(&self, leds: u8) -> Result<(), ScannerError> {
+        self.command(LED_OFF, &[leds]).await
+    }
+
+    /// 允许扫码(SCAN_ENABLE)
+    pub async fn scan_enable(&self) -> Result<(), ScannerError> {
+        self.command(SCAN_ENABLE, &[]).await
+    }
+
+    /// 禁止扫码(SCAN_DISABLE)，禁止后扳机和[`ZebraSsi::start_decode`]都不会扫码
+    pub async fn scan_disable(&self) -> Result<(), ScannerError> {
+        self.command(SCAN_DISABLE, &[]).await
+    }
+
+    /// 发送 SSI 指令并等待扫码枪应答 ACK
+    ///
+    /// * `opcode` 指令代码
+    /// * `data` 指令数据，不含包头和校验
+    pub async fn command(&self, opcode: u8, data: &[u8]) -> Result<(), ScannerError> {
+        self.request(opcode, data).await?;
+        Ok(())
+    }
+
+    /// 发送 SSI 指令并等待应答，返回应答包的数据
+    async fn request(&self, opcode: u8, data: &[u8]) -> Result<Vec<u8>, ScannerError> {
+        let (reply, receiver) = oneshot::channel();
+        let request = Request {
+            packet: encode(opcode, data),
+            reply,
+        };
+        if self.requests.send(request).is_err() {
+            return Err(ScannerError::Comm("SSI协议层已关闭".into()));
+        }
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(r)) => r,
+            Ok(Err(_)) => Err(ScannerError::Comm(format!(
+                "连接已断开,opcode=0x{:02X}",
+                opcode
+            ))),
+            Err(_) => Err(ScannerError::Timeout(format!(
+                "opcode=0x{:02X},timeout={:?}",
+                opcode, self.timeout
+            ))),
+        }
+    }
+}
+
+impl ScannerProtocol for ZebraSsi {
+    fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    fn trigger_start(&self) -> Replying<'_, ()> {
+        Box::pin(self.start_decode())
+    }
+
+    fn trigger_stop(&self) -> Replying<'_, ()> {
+        Box::pin(self.stop_decode())
+    }
+
+    fn beep(&self) -> Replying<'_, ()> {
+        Box::pin(ZebraSsi::beep(self, 0x00))
+    }
+
+    /// 查询软件版本(REQUEST_REVISION)，作为固件版本
+    fn query_info(&self) -> Replying<'_, DeviceInfo> {
+        Box::pin(async move {
+            let data = self.request(REQUEST_REVISION, &[]).await?;
+            let revision = String::from_utf8_lossy(&data).trim().to_owned();
+            let firmware = revision.split_whitespace().next().unwrap_or_default();
+            Ok(DeviceInfo::new(revision.clone()).with_firmware(firmware))
+        })
+    }
+
+    /// 修改触发模式参数(0x8A)，触发读取为主机触发(0x08)，感应模式为 0x07，不支持连续读取
+    fn set_read_mode(&self, mode: ReadMode) -> Replying<'_, ()> {
+        Box::pin(async move {
+            let value = match mode {
+                ReadMode::Triggered => 0x08,
+                ReadMode::Presentation => 0x07,
+                ReadMode::Continuous => {
+                    return Err(ScannerError::Device("SSI扫码枪不支持连续读取模式".into()))
+                }
+            };
+            self.command(PARAM_SEND, &[0xFF, 0x8A, value]).await
+        })
+    }
+
+    /// 修改码制参数(PARAM_SEND)，编号大于`0xFF`的参数带`0xF0`/`0xF1`前缀
+    fn enable_symbology(&self, symbology: Symbology, enable: bool) -> Replying<'_, ()> {
+        let param: &[u8] = match symbology {
+            Symbology::Code39 => &[0x00],
+            Symbology::UpcA => &[0x01],
+            Symbology::UpcE => &[0x02],
+            Symbology::Ean13 => &[0x03],
+            Symbology::Ean8 => &[0x04],
+            Symbology::Interleaved2of5 => &[0x06],
+            Symbology::Codabar => &[0x07],
+            Symbology::Code128 => &[0x08],
+            Symbology::Code93 => &[0x09],
+            Symbology::Pdf417 => &[0x0F],
+            Symbology::DataMatrix => &[0xF0, 0x24],
+            Symbology::QrCode => &[0xF0, 0x25],
+            Symbology::Gs1DataBar => &[0xF0, 0x52],
+            Symbology::Aztec => &[0xF1, 0x3E],
+        };
+        let mut data = vec![0xFF];
+        data.extend_from_slice(param);
+        data.push(enable as u8);
+        Box::pin(async move { self.command(PARAM_SEND, &data).await })
+    }
+
+    /// 临时修改参数(PARAM_SEND)，`name`和`value`为十进制的参数编号和参数值，
+    /// 仅支持编号小于`0xF0`的单字节参数
+    fn set_parameter<'a>(&'a self, name: &'a str, value: &'a str) -> Replying<'a, ()> {
+        Box::pin(async move {
+            let (Ok(num @ 0..=0xEF), Ok(value)) = (name.parse::<u8>(), value.parse::<u8>()) else {
+                return Err(ScannerError::Param(format!(
+                    "无效的SSI参数,name={},value={}",
+                    name, value
+                )));
+            };
+            // 0xFF 表示修改后不蜂鸣
+            self.command(PARAM_SEND, &[0xFF, num, value]).await
+        })
+    }
+}
+
+/// 指令应答，ACK 没有数据，其它应答包返回数据
+type Reply = oneshot::Sender<Result<Vec<u8>, ScannerError>>;
+
+/// 等待应答的指令
+struct Request {
+    packet: Vec<u8>,
+    reply: Reply,
+}
+
+/// SSI 协议层，连接扫码枪后由后台任务收发数据包
+struct SsiTransport {
+    inner: Connector,
+    requests: Arc<Mutex<UnboundedReceiver<Request>>>,
+}
+
+impl Debug for SsiTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SsiTransport")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl Transport for SsiTransport {
+    fn name(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn connect(&self) -> Connecting<'_> {
+        Box::pin(async move {
+            let stream = match &self.inner {
+                Connector::Serial(serial) => serial.connect().await?,
+                Connector::Network(network) => network.connect().await?,
+                Connector::Custom(transport) => transport.connect().await?,
+                conn => {
+                    return Err(ScannerError::Param(format!(
+                        "SSI协议不支持该连接器,conn={}",
+                        conn
+                    )))
+                }
+            };
+            let (barcodes, barcode_receiver) = mpsc::unbounded_channel();
+            let (raw, raw_receiver) = mpsc::unbounded_channel();
+            let requests = Arc::clone(&self.requests).lock_owned().await;
+            telemetry::span::spawn(pump(self.name(), stream, barcodes, raw_receiver, requests));
+            Ok(Box::new(SsiStream {
+                barcodes: barcode_receiver,
+                raw,
+            }) as Box<dyn TransportStream>)
+        })
+    }
+}
+
+/// 提供给扫码枪的字节流，每次读取一个条码，写入的数据原样发给扫码枪
+struct SsiStream {
+    barcodes: UnboundedReceiver<Vec<u8>>,
+    raw: UnboundedSender<Vec<u8>>,
+}
+
+impl AsyncRead for SsiStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.barcodes.poll_recv(cx) {
+            Poll::Ready(Some(barcode)) => {
+                let n = barcode.len().min(buf.remaining());
+                buf.put_slice(&barcode[..n]);
+                Poll::Ready(Ok(()))
+            }
+            // 后台任务结束，返回 0 表示连接断开
+            Poll::Ready(None) => Poll::Ready(Ok(())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl AsyncWrite for SsiStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.raw.send(buf.to_vec()) {
+            Ok(()) => Poll::Ready(Ok(buf.len())),
+            Err(_) => Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into())),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 收发数据包，扫码枪断开或会话结束时退出
+async fn pump(
+    addr: String,
+    stream: Box<dyn TransportStream>,
+    barcodes: UnboundedSender<Vec<u8>>,
+    mut raw: UnboundedReceiver<Vec<u8>>,
+    mut requests: tokio::sync::OwnedMutexGuard<UnboundedReceiver<Request>>,
+) {
+    let (mut rx, mut tx) = tokio::io::split(stream);
+    let mut buf = vec![];
+    let mut read = [0u8; 512];
+    let mut decoded = vec![];
+    let mut pending: Option<Reply> = None;
+    loop {
+        // 上一条指令超时后不再等待它的应答
+        let idle = pending.as_ref().is_none_or(|reply| reply.is_closed());
+        let r = tokio::select! {
+            r = rx.read(&mut read) => match r {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    buf.extend_from_slice(&read[..n]);
+                    let mut r = Ok(());
+                    while let Some(packet) = take_packet(&mut buf) {
+                        r = match packet {
+                            Ok(packet) => {
+                                handle(&addr, &mut tx, packet, &mut pending, &mut decoded, &barcodes)
+                                    .await
+                            }
+                            Err(_) => tx.write_all(&encode(CMD_NAK, &[NAK_RESEND])).await,
+                        };
+                        if r.is_err() {
+                            break;
+                        }
+                    }
+                    r
+                }
+            },
+            data = raw.recv() => match data {
+                Some(data) => tx.write_all(&data).await,
+                None => break,
+            },
+            request = requests.recv(), if idle => match request {
+                Some(request) if !request.reply.is_closed() => {
+                    pending = Some(request.reply);
+                    tx.write_all(&request.packet).await
+                }
+                Some(_) => Ok(()),
+                None => break,
+            },
+        };
+        if let Err(err) = r {
+            event!(
+                Level::ERROR,
+                scanner.addr = %addr,
+                error = %err,
+                error.kind = "io",
+                "SSI发送数据错误",
+            );
+            break;
+        }
+    }
+}
+
+/// 处理扫码枪发出的数据包
+async fn handle<W: AsyncWrite + Unpin>(
+    addr: &str,
+    tx: &mut W,
+    packet: Packet,
+    pending: &mut Option<Reply>,
+    decoded: &mut Vec<u8>,
+    barcodes: &UnboundedSender<Vec<u8>>,
+) -> std::io::Result<()> {
+    match packet.opcode {
+        CMD_ACK => {
+            if let Some(reply) = pending.take() {
+                let _ = reply.send(Ok(vec![]));
+            }
+            return Ok(());
+        }
+        CMD_NAK => {
+            let cause = packet.data.first().copied().unwrap_or_default();
+            if let Some(reply) = pending.take() {
+                let _ = reply.send(Err(ScannerError::Device(format!(
+                    "{},原因=0x{:02X}",
+                    nak_message(cause),
+                    cause
+                ))));
+            }
+            return Ok(());
+        }
+        REPLY_REVISION => {
+            if let Some(reply) = pending.take() {
+                let _ = reply.send(Ok(packet.data));
+            }
+        }
+        DECODE_DATA => {
+            // 第一个字节为条码类型
+            decoded.extend_from_slice(packet.data.get(1..).unwrap_or_default());
+            if packet.status & STATUS_CONTINUATION == 0 {
+                let _ = barcodes.send(std::mem::take(decoded));
+            }
+        }
+        opcode => {
+            event!(
+                Level::DEBUG,
+                scanner.addr = %addr,
+                opcode = format_args!("0x{:02X}", opcode),
+                status = format_args!("0x{:02X}", packet.status),
+                "SSI数据包",
+            );
+        }
+    }
+    tx.write_all(&encode(CMD_ACK, &[])).await
+}
+
+/// 扫码枪发出的数据包
+#[derive(Debug, PartialEq)]
+struct Packet {
+    opcode: u8,
+    status: u8,
+    data: Vec<u8>,
+}
+
+/// 编码主机发出的数据包
+fn encode(opcode: u8, data: &[u8]) -> Vec<u8> {
+    let mut packet = vec![4 + data.len() as u8, opcode, SOURCE_HOST, 0x00];
+    packet.extend_from_slice(data);
+    packet.extend_from_slice(&checksum(&packet).to_be_bytes());
+    packet
+}
+
+/// 从缓冲区取出一个完整的数据包，校验失败返回`Err`
+fn take_packet(buf: &mut Vec<u8>) -> Option<Result<Packet, ()>> {
+    loop {
+        let len = *buf.first()? as usize;
+        if len < 4 {
+            // 长度无效，丢弃一个字节重新同步
+            buf.remove(0);
+            continue;
+        }
+        if buf.len() < len + 2 {
+            return None;
+        }
+        let packet = buf.drain(..len + 2).collect::<Vec<_>>();
+        let sum = u16::from_be_bytes([packet[len], packet[len + 1]]);
+        if checksum(&packet[..len]) != sum {
+            return Some(Err(()));
+        }
+        return Some(Ok(Packet {
+            opcode: packet[1],
+            status: packet[3],
+            data: packet[4..len].to_vec(),
+        }));
+    }
+}
+
+/// SSI 校验，所有字节之和的补码
+fn checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .fold(0u16, |sum, b| sum.wrapping_add(*b as u16))
+        .wrapping_neg()
+}
+
+/// NAK 原因说明
+fn nak_message(cause: u8) -> &'static str {
+    match cause {
+        0x01 => "校验错误,请求重发",
+        0x02 => "扫码枪无法执行该指令",
+        0x06 => "指令被拒绝",
+        _ => "指令执行失败",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+
+    use super::{encode, take_packet, Packet, CMD_ACK, DECODE_DATA, START_DECODE};
+    use crate::prelude::*;
+
+    #[derive(Debug)]
+    struct Pipe(std::sync::Mutex<Option<DuplexStream>>);
+
+    impl Transport for Pipe {
+        fn name(&self) -> String {
+            "PIPE".into()
+        }
+
+        fn connect(&self) -> Connecting<'_> {
+            Box::pin(async move {
+                match self.0.lock().unwrap().take() {
+                    Some(stream) => Ok(Box::new(stream) as Box<dyn TransportStream>),
+                    None => Err(ScannerError::Comm("已断开".into())),
+                }
+            })
+        }
+    }
+
+    /// 扫码枪发出的数据包
+    fn decoder(opcode: u8, status: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = encode(opcode, data);
+        packet[2] = 0x00;
+        packet[3] = status;
+        let len = packet.len() - 2;
+        let sum = super::checksum(&packet[..len]).to_be_bytes();
+        packet[len..].copy_from_slice(&sum);
+        packet
+    }
+
+    #[test]
+    fn packet() {
+        assert_eq!(
+            encode(START_DECODE, &[]),
+            [0x04, 0xE4, 0x04, 0x00, 0xFF, 0x14]
+        );
+        let mut buf = decoder(DECODE_DATA, 0, b"\x0bSN0001");
+        buf.extend_from_slice(&[0x04, 0xD0, 0x00]);
+        assert_eq!(
+            take_packet(&mut buf),
+            Some(Ok(Packet {
+                opcode: DECODE_DATA,
+                status: 0,
+                data: b"\x0bSN0001".to_vec(),
+            }))
+        );
+        assert_eq!(take_packet(&mut buf), None);
+        let mut buf = vec![0x04, 0xD0, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(take_packet(&mut buf), Some(Err(())));
+    }
+
+    #[tokio::test]
+    async fn command() {
+        let (device, stream) = tokio::io::duplex(256);
+        let (mut rx, mut tx) = tokio::io::split(device);
+        let conn = Connector::transport(Pipe(std::sync::Mutex::new(Some(stream))));
+        let zebra = ZebraSsi::new(Scanner::new(conn));
+        zebra.scanner().start().await.unwrap().unwrap();
+
+        let ssi = zebra.clone();
+        let cmd = tokio::spawn(async move { ssi.start_decode().await });
+        let mut buf = [0u8; 6];
+        rx.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [0x04, 0xE4, 0x04, 0x00, 0xFF, 0x14]);
+        tx.write_all(&decoder(CMD_ACK, 0, &[])).await.unwrap();
+        cmd.await.unwrap().unwrap();
+
+        // 分两包发送的条码
+        tx.write_all(&decoder(DECODE_DATA, 0x02, b"\x0bSN00"))
+            .await
+            .unwrap();
+        tx.write_all(&decoder(DECODE_DATA, 0x00, b"\x0b01"))
+            .await
+            .unwrap();
+        rx.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, encode(CMD_ACK, &[]).as_slice());
+        rx.read_exact(&mut buf).await.unwrap();
+        assert!(matches!(
+            zebra.scanner().recv().await,
+            Some(ScanEvent::Connected)
+        ));
+        assert_eq!(
+            zebra
+                .scanner()
+                .recv()
+                .await
+                .unwrap()
+                .as_str_lossy()
+                .unwrap(),
+            "SN0001"
+        );
+
+        let ssi = zebra.clone();
+        let cmd = tokio::spawn(async move { ssi.beep(1).await });
+        let mut buf = [0u8; 7];
+        rx.read_exact(&mut buf).await.unwrap();
+        tx.write_all(&decoder(super::CMD_NAK, 0, &[0x06]))
+            .await
+            .unwrap();
+        assert!(matches!(cmd.await.unwrap(), Err(ScannerError::Device(_))));
+    }
+}
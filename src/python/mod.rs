@@ -0,0 +1,284 @@
+//! Python 模块`kim_scanner`(PyO3)，供测试脚本使用，事件接收和发送指令支持 asyncio
+//!
+//! 用 maturin 打包(特性配置见 pyproject.toml)：
+//!
+//! ```text
+//! maturin build --release
+//! ```
+//!
+//! ```python
+//! import asyncio
+//! import kim_scanner
+//!
+//! async def main():
+//!     scanner = kim_scanner.Scanner("tcp://192.168.1.10:9004", id="line1")
+//!     scanner.start()
+//!     await scanner.send("LON\r")
+//!     async for event in scanner:
+//!         if event.kind == "barcode":
+//!             print(event.scanner, event.barcode)
+//!
+//! asyncio.run(main())
+//! ```
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyStopAsyncIteration};
+use pyo3::prelude::*;
+use pyo3_async_runtimes::tokio::{future_into_py, get_runtime};
+
+use crate::prelude::{Connector, FleetEvent, Network, ScanEvent, Scanner};
+
+create_exception!(
+    kim_scanner,
+    ScannerError,
+    PyException,
+    "扫码枪错误(参数错误、通讯错误、应答超时等)"
+);
+
+/// 扫码枪错误转为 Python 异常
+fn error(err: crate::prelude::ScannerError) -> PyErr {
+    ScannerError::new_err(err.to_string())
+}
+
+/// 连接器，从 URL 创建，格式见[`Connector`]的`from_str`
+#[pyclass(name = "Connector", module = "kim_scanner", frozen)]
+#[derive(Clone)]
+struct PyConnector(Connector);
+
+#[pymethods]
+impl PyConnector {
+    #[new]
+    fn new(url: &str) -> PyResult<Self> {
+        url.parse().map(PyConnector).map_err(error)
+    }
+
+    /// 网络客户端模式
+    #[staticmethod]
+    fn tcp(host: &str, port: u16) -> Self {
+        PyConnector(Network::new_client(host, port).into())
+    }
+
+    /// 网络服务器模式，等待扫码枪连接
+    #[staticmethod]
+    fn tcp_server(ip: &str, port: u16) -> Self {
+        PyConnector(Network::new_server(ip, port).into())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Connector('{}')", self.0)
+    }
+}
+
+/// 扫码枪
+///
+/// 条码末尾的回车换行会被去掉。可以用`async for`遍历事件，也可以`await scanner.recv()`
+#[pyclass(name = "Scanner", module = "kim_scanner", frozen)]
+struct PyScanner(Scanner);
+
+#[pymethods]
+impl PyScanner {
+    /// * `connector` 连接器或连接 URL
+    /// * `id` 扫码枪编号，事件中的`scanner`，未设置时为连接地址
+    #[new]
+    #[pyo3(signature = (connector, id = None))]
+    fn new(connector: &Bound<'_, PyAny>, id: Option<&str>) -> PyResult<Self> {
+        let connector = match connector.downcast::<PyConnector>() {
+            Ok(connector) => connector.get().0.clone(),
+            Err(_) => connector.extract::<&str>()?.parse().map_err(error)?,
+        };
+        let mut scanner = Scanner::new(connector).trim_end([b'\r', b'\n']);
+        if let Some(id) = id {
+            scanner = scanner.id(id);
+        }
+        Ok(PyScanner(scanner))
+    }
+
+    /// 扫码枪编号，未设置时为`None`
+    #[getter]
+    fn id(&self) -> Option<&str> {
+        self.0.get_id()
+    }
+
+    /// 连接器
+    #[getter]
+    fn connector(&self) -> PyConnector {
+        PyConnector(self.0.connector.clone())
+    }
+
+    /// 是否已启动且没有停止
+    #[getter]
+    fn is_running(&self) -> bool {
+        self.0.is_running()
+    }
+
+    /// 启动扫码枪，后台连接并自动重连
+    fn start(&self) -> PyResult<()> {
+        let runtime = get_runtime();
+        let _guard = runtime.enter();
+        runtime
+            .block_on(self.0.start())
+            .and_then(|r| r)
+            .map_err(error)
+    }
+
+    /// 停止扫码枪，断开连接
+    fn stop(&self) {
+        self.0.stop();
+    }
+
+    /// 接收一个事件，返回可等待对象，事件队列关闭时结果为`None`
+    fn recv<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let scanner = self.0.clone();
+        future_into_py(py, async move {
+            Ok(scanner.recv_tagged().await.map(PyEvent::from))
+        })
+    }
+
+    /// 给扫码枪发送指令，返回可等待对象
+    fn send<'py>(&self, py: Python<'py>, cmd: String) -> PyResult<Bound<'py, PyAny>> {
+        let scanner = self.0.clone();
+        future_into_py(py, async move {
+            scanner
+                .send_message(cmd)
+                .await
+                .and_then(|r| r)
+                .map_err(error)
+        })
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let scanner = self.0.clone();
+        future_into_py(py, async move {
+            match scanner.recv_tagged().await {
+                Some(ev) => Ok(PyEvent::from(ev)),
+                None => Err(PyStopAsyncIteration::new_err(())),
+            }
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Scanner('{}')", self.0.connector)
+    }
+}
+
+/// 扫码枪事件
+#[pyclass(name = "Event", module = "kim_scanner", frozen, get_all)]
+struct PyEvent {
+    /// 扫码枪编号
+    scanner: String,
+    /// 事件类型，与 JSON 的`type`字段一致，例如`barcode`、`no_read`、`error`
+    kind: &'static str,
+    /// 条码文本，条码类事件才有
+    barcode: Option<String>,
+    /// 条码原始字节，条码类事件才有
+    raw: Option<Vec<u8>>,
+    /// 事件说明，见[`ScanEvent::printable`]
+    message: String,
+}
+
+impl From<FleetEvent> for PyEvent {
+    fn from(ev: FleetEvent) -> Self {
+        let event: &ScanEvent = ev.event();
+        PyEvent {
+            scanner: ev.id().into(),
+            kind: event.kind(),
+            barcode: event.barcode().map(|barcode| barcode.text().into_owned()),
+            raw: event.payload().map(|raw| raw.to_vec()),
+            message: event.printable(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyEvent {
+    fn __repr__(&self) -> String {
+        format!(
+            "Event(scanner='{}', kind='{}', message='{}')",
+            self.scanner, self.kind, self.message
+        )
+    }
+}
+
+/// Python 模块入口
+#[pymodule]
+fn kim_scanner(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyConnector>()?;
+    m.add_class::<PyScanner>()?;
+    m.add_class::<PyEvent>()?;
+    m.add("ScannerError", m.py().get_type::<ScannerError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    #[test]
+    fn python() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut client, _) = listener.accept().unwrap();
+            client.write_all(b"SN0001\r\n").unwrap();
+            client
+        });
+
+        pyo3::append_to_inittab!(kim_scanner);
+        pyo3::prepare_freethreaded_python();
+        let code = format!(
+            r#"
+import asyncio
+import kim_scanner
+
+async def main():
+    scanner = kim_scanner.Scanner(kim_scanner.Connector.tcp("127.0.0.1", {port}), id="py")
+    assert scanner.id == "py"
+    scanner.start()
+    assert scanner.is_running
+    await scanner.send("LON\r")
+    async for event in scanner:
+        if event.kind == "barcode":
+            scanner.stop()
+            return event
+
+event = asyncio.run(asyncio.wait_for(main(), 5))
+try:
+    kim_scanner.Connector("ftp://127.0.0.1")
+    error = None
+except kim_scanner.ScannerError as err:
+    error = str(err)
+"#
+        );
+        Python::with_gil(|py| {
+            let globals = PyDict::new(py);
+            py.run(&CString::new(code).unwrap(), Some(&globals), None)
+                .unwrap();
+            let event = globals.get_item("event").unwrap().unwrap();
+            let event = event.downcast::<PyEvent>().unwrap().get();
+            assert_eq!(event.scanner, "py");
+            assert_eq!(event.barcode.as_deref(), Some("SN0001"));
+            assert_eq!(event.raw.as_deref(), Some(&b"SN0001"[..]));
+            let error: String = globals
+                .get_item("error")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(error.contains("ftp"));
+        });
+        server.join().unwrap();
+    }
+}
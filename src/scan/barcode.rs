@@ -0,0 +1,267 @@
+use std::{borrow::Cow, fmt::Display};
+
+use bytes::Bytes;
+
+use crate::{Encoding, Quality, Record, Symbology};
+
+/// 条码数据，可能是文本，也可能是二进制数据(部分二维码)
+///
+/// 启用`serde`功能后序列化为`{"text": "...", "raw": "<Base64>", "encoding": "utf8", ...}`，
+/// `text`为按编码解码的文本，方便下游系统直接使用，反序列化时以`raw`为准
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "BarcodeRepr", try_from = "BarcodeRepr")
+)]
+pub struct Barcode {
+    /// 原始字节
+    raw: Bytes,
+    /// 文本编码
+    encoding: Encoding,
+    /// 多字段记录
+    record: Option<Record>,
+    /// 校验结果
+    verified: Option<bool>,
+    /// 读码质量
+    quality: Option<Quality>,
+}
+
+impl Barcode {
+    /// 创建条码数据
+    ///
+    /// * `raw` 原始字节
+    /// * `encoding` 文本编码
+    pub fn new(raw: impl Into<Bytes>, encoding: Encoding) -> Self {
+        Barcode {
+            raw: raw.into(),
+            encoding,
+            record: None,
+            verified: None,
+            quality: None,
+        }
+    }
+
+    /// 根据文本创建条码数据
+    ///
+    /// * `text` 文本
+    /// * `encoding` 文本编码
+    pub fn from_text(text: &str, encoding: Encoding) -> Self {
+        let raw = Bytes::copy_from_slice(&encoding.encode(text));
+        Barcode {
+            raw,
+            encoding,
+            record: None,
+            verified: None,
+            quality: None,
+        }
+    }
+
+    /// 获取原始字节
+    pub fn raw(&self) -> &Bytes {
+        &self.raw
+    }
+
+    /// 获取文本编码
+    pub fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    /// 替换文本(按原编码重新编码)，保留其它信息
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.raw = Bytes::copy_from_slice(&self.encoding.encode(text));
+        self
+    }
+
+    /// 设置多字段记录
+    pub fn with_record(mut self, record: Record) -> Self {
+        self.record = Some(record);
+        self
+    }
+
+    /// 获取多字段记录，需要先通过[`Scanner::fields`](crate::Scanner::fields)配置拆分方式
+    pub fn record(&self) -> Option<&Record> {
+        self.record.as_ref()
+    }
+
+    /// 设置校验结果
+    pub fn with_verified(mut self, verified: bool) -> Self {
+        self.verified = Some(verified);
+        self
+    }
+
+    /// 获取校验结果(例如校验位)，`None`表示未校验
+    pub fn verified(&self) -> Option<bool> {
+        self.verified
+    }
+
+    /// 设置读码质量
+    pub fn with_quality(mut self, quality: Quality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// 获取读码质量，需要先通过[`Scanner::quality`](crate::Scanner::quality)配置质量数据格式
+    pub fn quality(&self) -> Option<&Quality> {
+        self.quality.as_ref()
+    }
+
+    /// 按数据开头的 AIM 符号标识识别码制，见[`Symbology::from_aim`]
+    pub fn symbology(&self) -> Option<Symbology> {
+        Symbology::from_aim(&self.raw)
+    }
+
+    /// 按配置的编码解码为文本
+    pub fn text(&self) -> Cow<'_, str> {
+        self.encoding.decode(&self.raw)
+    }
+
+    /// 按 UTF-8 解码为文本，无效字节替换为`U+FFFD`
+    pub fn as_str_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.raw)
+    }
+
+    /// 转为大写十六进制字符串，字节之间以空格分隔
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let barcode = Barcode::new(&b"\x01\xAB\xFF"[..], Encoding::Utf8);
+    /// assert_eq!(barcode.as_hex(), "01 AB FF");
+    /// ```
+    pub fn as_hex(&self) -> String {
+        self.raw
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// 转为可打印字符串，控制字符显示为`<STX>`、`<GS>`等助记符，无效字节显示为`\xNN`
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let barcode = Barcode::new(&b"\x02SN\x1d01\xff\r\n"[..], Encoding::Utf8);
+    /// assert_eq!(barcode.printable(), "<STX>SN<GS>01\\xFF<CR><LF>");
+    /// ```
+    pub fn printable(&self) -> String {
+        let mut out = String::new();
+        match self.encoding {
+            Encoding::Utf8 => {
+                for chunk in self.raw.utf8_chunks() {
+                    chunk
+                        .valid()
+                        .chars()
+                        .for_each(|c| push_printable(&mut out, c));
+                    for b in chunk.invalid() {
+                        out.push_str(&format!("\\x{:02X}", b));
+                    }
+                }
+            }
+            _ => self
+                .text()
+                .chars()
+                .for_each(|c| push_printable(&mut out, c)),
+        }
+        out
+    }
+
+    /// 是否为文本数据(按配置的编码可以无损解码，且不含控制字符)
+    ///
+    /// GS1 使用的分隔符(GS/RS/EOT)以及空白字符视为文本
+    pub fn is_text(&self) -> bool {
+        let text = self.text();
+        !text.contains('\u{FFFD}')
+            && text.chars().all(|c| {
+                !c.is_control() || matches!(c, '\t' | '\r' | '\n' | '\x04' | '\x1d' | '\x1e')
+            })
+    }
+}
+
+/// 文本数据输出文本，二进制数据输出十六进制
+impl Display for Barcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_text() {
+            write!(f, "{}", self.text())
+        } else {
+            write!(f, "HEX[{}]", self.as_hex())
+        }
+    }
+}
+
+/// ASCII 控制字符助记符
+const CONTROL_NAMES: [&str; 32] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FS", "GS", "RS", "US",
+];
+
+/// 追加可打印字符，控制字符转为助记符
+fn push_printable(out: &mut String, c: char) {
+    match c {
+        '\0'..='\x1f' => {
+            out.push('<');
+            out.push_str(CONTROL_NAMES[c as usize]);
+            out.push('>');
+        }
+        '\x7f' => out.push_str("<DEL>"),
+        c if c.is_control() => out.push_str(&format!("\\u{{{:04X}}}", c as u32)),
+        c => out.push(c),
+    }
+}
+
+/// 序列化格式
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BarcodeRepr {
+    #[serde(default)]
+    text: String,
+    raw: String,
+    #[serde(default)]
+    encoding: Encoding,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    record: Option<Record>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quality: Option<Quality>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Barcode> for BarcodeRepr {
+    fn from(barcode: Barcode) -> Self {
+        use base64::Engine;
+
+        BarcodeRepr {
+            text: barcode.text().into_owned(),
+            raw: base64::engine::general_purpose::STANDARD.encode(&barcode.raw),
+            encoding: barcode.encoding,
+            record: barcode.record,
+            verified: barcode.verified,
+            quality: barcode.quality,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BarcodeRepr> for Barcode {
+    type Error = String;
+
+    fn try_from(repr: BarcodeRepr) -> Result<Self, Self::Error> {
+        use base64::Engine;
+
+        let raw = base64::engine::general_purpose::STANDARD
+            .decode(&repr.raw)
+            .map_err(|err| format!("raw 不是有效的 Base64,{}", err))?;
+        Ok(Barcode {
+            raw: raw.into(),
+            encoding: repr.encoding,
+            record: repr.record,
+            verified: repr.verified,
+            quality: repr.quality,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use std::borrow::Cow;
+
+/// 条码数据字符编码
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Encoding {
+    /// UTF-8(默认)，无效字节替换为`U+FFFD`
+    #[default]
+    Utf8,
+    /// GBK(兼容 GB2312)，常见于中文标签
+    Gbk,
+    /// Shift-JIS，常见于日文标签
+    ShiftJis,
+    /// ISO-8859-1(Latin-1)，每个字节对应一个字符
+    Latin1,
+}
+
+impl Encoding {
+    /// 将原始字节解码为字符串
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert_eq!(Encoding::Utf8.decode("条码".as_bytes()), "条码");
+    /// assert_eq!(Encoding::Gbk.decode(b"\xcc\xf5\xc2\xeb"), "条码");
+    /// assert_eq!(Encoding::ShiftJis.decode(b"\x83\x6f\x81\x5b"), "バー");
+    /// assert_eq!(Encoding::Latin1.decode(b"\xc4\xd6"), "ÄÖ");
+    /// ```
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes),
+            Encoding::Gbk => encoding_rs::GBK.decode_without_bom_handling(bytes).0,
+            Encoding::ShiftJis => encoding_rs::SHIFT_JIS.decode_without_bom_handling(bytes).0,
+            Encoding::Latin1 => Cow::Owned(bytes.iter().map(|b| *b as char).collect()),
+        }
+    }
+
+    /// 将字符串编码为字节，无法编码的字符会被替换
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert_eq!(Encoding::Gbk.encode("条码"), &b"\xcc\xf5\xc2\xeb"[..]);
+    /// assert_eq!(Encoding::Latin1.encode("ÄÖ"), &b"\xc4\xd6"[..]);
+    /// ```
+    pub fn encode<'a>(&self, text: &'a str) -> Cow<'a, [u8]> {
+        match self {
+            Encoding::Utf8 => Cow::Borrowed(text.as_bytes()),
+            Encoding::Gbk => encoding_rs::GBK.encode(text).0,
+            Encoding::ShiftJis => encoding_rs::SHIFT_JIS.encode(text).0,
+            Encoding::Latin1 => Cow::Owned(
+                text.chars()
+                    .map(|c| u8::try_from(c).unwrap_or(b'?'))
+                    .collect(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+
+use bytes::Bytes;
+
+use crate::{Barcode, Quality, ScannerError};
+
+/// 扫码枪事件
+///
+/// 启用`serde`功能后序列化为`{"type": "barcode", "data": {...}}`，无内容的事件没有`data`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "data", rename_all = "snake_case")
+)]
+pub enum ScanEvent {
+    /// 接收到条码
+    Barcode(Barcode),
+    /// 扫码枪未能解码(例如输出`NoRead`、`NG`)，内容为扫码枪输出的原始数据
+    NoRead(Barcode),
+    /// 条码与基准条码或格式一致，见[`crate::MatchCode`]
+    Match(Barcode),
+    /// 条码与基准条码或格式不一致，见[`crate::MatchCode`]
+    Mismatch(Barcode),
+    /// 扫码枪错误(非致命，例如校验失败)
+    Error(ScannerError),
+    /// 扫码枪已连接，所有连接方式都产生，包括重连成功、USB 串口重新插入后自动重连
+    Connected,
+    /// 扫码枪连接断开，所有连接方式都产生，例如 USB 串口被拔出、网络连接断开
+    Disconnected,
+    /// 扫码枪无响应(例如连续没有收到保活应答，见[`crate::Keepalive`])，之后断开并重新连接
+    Unhealthy(String),
+    /// 主扫码枪断开，工位切换到备用扫码枪，内容为备用扫码枪编号，见[`crate::Failover`]
+    Failover(String),
+    /// 主扫码枪恢复，工位切回主扫码枪，内容为主扫码枪编号，见[`crate::Failover`]
+    Failback(String),
+    /// 扫码枪任务退出后被重新启动，内容为连续重启次数，见[`crate::Supervisor`]
+    Restarted(u32),
+    /// 超过重连策略的限制，放弃重连，内容为最后一次的错误，见[`crate::RetryPolicy`]
+    ///
+    /// 之后扫码枪不再产生事件，直到重新启动
+    Failed(ScannerError),
+}
+
+impl ScanEvent {
+    /// 获取条码数据(包括比对结果事件)，`NoRead`事件返回扫码枪输出的原始数据
+    pub fn barcode(&self) -> Option<&Barcode> {
+        match self {
+            ScanEvent::Barcode(barcode)
+            | ScanEvent::NoRead(barcode)
+            | ScanEvent::Match(barcode)
+            | ScanEvent::Mismatch(barcode) => Some(barcode),
+            _ => None,
+        }
+    }
+
+    /// 转换事件中的条码(包括未解码和比对结果事件)，其他事件不变
+    pub fn map_barcode(self, f: impl FnOnce(Barcode) -> Barcode) -> ScanEvent {
+        match self {
+            ScanEvent::Barcode(barcode) => ScanEvent::Barcode(f(barcode)),
+            ScanEvent::NoRead(barcode) => ScanEvent::NoRead(f(barcode)),
+            ScanEvent::Match(barcode) => ScanEvent::Match(f(barcode)),
+            ScanEvent::Mismatch(barcode) => ScanEvent::Mismatch(f(barcode)),
+            ev => ev,
+        }
+    }
+
+    /// 事件类型名称，与 JSON 的`type`字段一致
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScanEvent::Barcode(_) => "barcode",
+            ScanEvent::NoRead(_) => "no_read",
+            ScanEvent::Match(_) => "match",
+            ScanEvent::Mismatch(_) => "mismatch",
+            ScanEvent::Error(_) => "error",
+            ScanEvent::Connected => "connected",
+            ScanEvent::Disconnected => "disconnected",
+            ScanEvent::Unhealthy(_) => "unhealthy",
+            ScanEvent::Failover(_) => "failover",
+            ScanEvent::Failback(_) => "failback",
+            ScanEvent::Restarted(_) => "restarted",
+            ScanEvent::Failed(_) => "failed",
+        }
+    }
+
+    /// 获取条码原始字节
+    pub fn payload(&self) -> Option<&Bytes> {
+        self.barcode().map(Barcode::raw)
+    }
+
+    /// 获取读码质量，见[`Barcode::quality`]
+    pub fn quality(&self) -> Option<&Quality> {
+        self.barcode().and_then(Barcode::quality)
+    }
+
+    /// 按 UTF-8 解码条码数据
+    pub fn as_str_lossy(&self) -> Option<Cow<'_, str>> {
+        self.barcode().map(Barcode::as_str_lossy)
+    }
+
+    /// 转为可打印字符串，控制字符显示为`<STX>`、`<GS>`等助记符，见[`Barcode::printable`]
+    pub fn printable(&self) -> String {
+        match self {
+            ScanEvent::Barcode(barcode)
+            | ScanEvent::NoRead(barcode)
+            | ScanEvent::Match(barcode)
+            | ScanEvent::Mismatch(barcode) => barcode.printable(),
+            ScanEvent::Error(err) => err.to_string(),
+            ScanEvent::Connected => "已连接".into(),
+            ScanEvent::Disconnected => "连接断开".into(),
+            ScanEvent::Unhealthy(reason) => format!("无响应:{}", reason),
+            ScanEvent::Failover(id) => format!("切换到备用扫码枪:{}", id),
+            ScanEvent::Failback(id) => format!("切回主扫码枪:{}", id),
+            ScanEvent::Restarted(count) => format!("任务已重启:第{}次", count),
+            ScanEvent::Failed(err) => format!("已放弃重连:{}", err),
+        }
+    }
+
+    /// 条码数据的十六进制字符串
+    pub fn as_hex(&self) -> Option<String> {
+        self.barcode().map(Barcode::as_hex)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn serde() {
+        let ev = ScanEvent::Barcode(Barcode::from_text("SN0001", Encoding::Gbk));
+        let json = serde_json::to_string(&ev).unwrap();
+        let ScanEvent::Barcode(barcode) = serde_json::from_str(&json).unwrap() else {
+            panic!("event is not barcode");
+        };
+        assert_eq!(barcode.as_str_lossy(), "SN0001");
+        assert_eq!(barcode.encoding(), &Encoding::Gbk);
+
+        let ev = ScanEvent::Error(ScannerError::Checksum("BCC".into()));
+        let json = serde_json::to_string(&ev).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"error","data":{"kind":"checksum","message":"BCC"}}"#
+        );
+        let ScanEvent::Error(ScannerError::Checksum(msg)) = serde_json::from_str(&json).unwrap()
+        else {
+            panic!("event is not checksum error");
+        };
+        assert_eq!(msg, "BCC");
+
+        let json = serde_json::to_string(&ScanEvent::Connected).unwrap();
+        assert_eq!(json, r#"{"type":"connected"}"#);
+        let err: ScannerError = serde_json::from_str(r#"{"kind":"io","message":"断开"}"#).unwrap();
+        assert_eq!(err.to_string(), "断开");
+    }
+}
@@ -0,0 +1,6 @@
+pub mod barcode;
+pub mod encoding;
+pub mod event;
+pub mod quality;
+pub mod record;
+pub mod rejected;
@@ -0,0 +1,168 @@
+/// 读码质量字段
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum QualityField {
+    /// 综合等级，例如`A`~`F`或`4.0`~`0.0`
+    Grade,
+    /// 符号对比度(Symbol Contrast)，百分比
+    Contrast,
+    /// 每单元像素数(Pixels Per Element)
+    Ppe,
+    /// 其它字段，按名称保存
+    Other(String),
+}
+
+/// 读码质量，视觉读码器在条码后追加的质量数据
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quality {
+    grade: Option<String>,
+    /// 数值字段保存原文，解析时已检查格式
+    contrast: Option<String>,
+    ppe: Option<String>,
+    others: Vec<(String, String)>,
+}
+
+impl Quality {
+    /// 获取综合等级
+    pub fn grade(&self) -> Option<&str> {
+        self.grade.as_deref()
+    }
+
+    /// 获取符号对比度
+    pub fn contrast(&self) -> Option<f64> {
+        self.contrast.as_deref().and_then(|v| v.parse().ok())
+    }
+
+    /// 获取每单元像素数
+    pub fn ppe(&self) -> Option<f64> {
+        self.ppe.as_deref().and_then(|v| v.parse().ok())
+    }
+
+    /// 获取其它字段
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.others
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 获取所有其它字段
+    pub fn others(&self) -> &[(String, String)] {
+        &self.others
+    }
+}
+
+/// 读码质量数据的格式
+///
+/// 读码器通过输出格式设置在条码后追加质量数据，例如 Cognex 输出`SN0001;B;72;5.3`、
+/// Keyence 输出`SN0001:A:85`。按分隔符从末尾取出质量字段，剩余部分仍作为条码数据
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// let format = QualityFormat::new(";", [QualityField::Grade, QualityField::Contrast, QualityField::Ppe]);
+/// let (data, quality) = format.parse("SN;0001;B;72;5.3\r\n").unwrap();
+/// assert_eq!(data, "SN;0001");
+/// assert_eq!(quality.grade(), Some("B"));
+/// assert_eq!(quality.contrast(), Some(72.0));
+/// assert_eq!(quality.ppe(), Some(5.3));
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.0.50", 23)).quality(format);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QualityFormat {
+    separator: String,
+    fields: Vec<QualityField>,
+}
+
+impl QualityFormat {
+    /// 创建质量数据格式
+    ///
+    /// * `separator` 条码和质量字段之间、质量字段之间的分隔符
+    /// * `fields` 条码之后依次输出的质量字段
+    pub fn new(separator: &str, fields: impl IntoIterator<Item = QualityField>) -> Self {
+        QualityFormat {
+            separator: separator.into(),
+            fields: fields.into_iter().collect(),
+        }
+    }
+
+    /// 获取分隔符
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// 获取质量字段
+    pub fn fields(&self) -> &[QualityField] {
+        &self.fields
+    }
+
+    /// 拆分条码和质量数据，字段数量不足或数值无效时返回`None`
+    pub fn parse(&self, text: &str) -> Option<(String, Quality)> {
+        let text = text.trim_end_matches(['\r', '\n']);
+        let mut parts = text.rsplitn(self.fields.len() + 1, self.separator.as_str());
+        let mut values = (&mut parts).take(self.fields.len()).collect::<Vec<_>>();
+        let data = parts.next()?;
+        values.reverse();
+        let mut quality = Quality::default();
+        for (field, value) in self.fields.iter().zip(values) {
+            let value = value.trim();
+            match field {
+                QualityField::Grade => quality.grade = Some(value.into()),
+                QualityField::Contrast => quality.contrast = Some(number(value)?),
+                QualityField::Ppe => quality.ppe = Some(number(value)?),
+                QualityField::Other(name) => quality.others.push((name.clone(), value.into())),
+            }
+        }
+        Some((data.into(), quality))
+    }
+}
+
+/// 检查数值字段
+fn number(value: &str) -> Option<String> {
+    value.parse::<f64>().ok().map(|_| value.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QualityField, QualityFormat};
+    use crate::prelude::*;
+
+    #[test]
+    fn parse() {
+        let format = QualityFormat::new(
+            ":",
+            [
+                QualityField::Other("level".into()),
+                QualityField::Grade,
+                QualityField::Contrast,
+            ],
+        );
+        let (data, quality) = format.parse("SN0001:86:C:40.5").unwrap();
+        assert_eq!(data, "SN0001");
+        assert_eq!(quality.get("level"), Some("86"));
+        assert_eq!(quality.grade(), Some("C"));
+        assert_eq!(quality.contrast(), Some(40.5));
+        assert_eq!(quality.ppe(), None);
+        assert!(format.parse("86:C:40.5").is_none());
+        assert!(format.parse("SN0001:86:C:high").is_none());
+    }
+
+    #[tokio::test]
+    async fn quality() {
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone())
+            .quality(QualityFormat::new(";", [QualityField::Grade]))
+            .filter(Regex::new("^SN\\d+$").unwrap());
+        scanner.start().await.unwrap().unwrap();
+        mock.push("SN0001;B\r\n");
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        let ev = scanner.recv().await.unwrap();
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0001");
+        assert_eq!(ev.quality().unwrap().grade(), Some("B"));
+    }
+}
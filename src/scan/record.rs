@@ -0,0 +1,134 @@
+/// 字段拆分方式
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FieldSplitter {
+    /// 按分隔符拆分，例如`;`、`,`
+    Delimiter(String),
+    /// 按定宽拆分，每个字段的字符数
+    FixedWidth(Vec<usize>),
+}
+
+/// 多字段条码数据的拆分配置，视觉读码器一次触发常会输出多个字段(`code;grade;x;y`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fields {
+    splitter: FieldSplitter,
+    names: Vec<String>,
+}
+
+impl Fields {
+    /// 按分隔符拆分
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let fields = Fields::delimited(";").names(["code", "grade", "x", "y"]);
+    /// let record = fields.split("SN0001;A;120;45\r\n");
+    /// assert_eq!(record.get(0), Some("SN0001"));
+    /// assert_eq!(record.field("grade"), Some("A"));
+    /// assert_eq!(record.len(), 4);
+    /// ```
+    pub fn delimited(delimiter: &str) -> Self {
+        Fields {
+            splitter: FieldSplitter::Delimiter(delimiter.into()),
+            names: vec![],
+        }
+    }
+
+    /// 按定宽拆分
+    ///
+    /// * `widths` 每个字段的字符数
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let record = Fields::fixed_width(vec![6, 1, 3]).split("SN0001A120");
+    /// assert_eq!(record.fields(), &["SN0001", "A", "120"]);
+    /// ```
+    pub fn fixed_width(widths: Vec<usize>) -> Self {
+        Fields {
+            splitter: FieldSplitter::FixedWidth(widths),
+            names: vec![],
+        }
+    }
+
+    /// 设置字段名称，按顺序与字段对应
+    pub fn names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 获取拆分方式
+    pub fn splitter(&self) -> &FieldSplitter {
+        &self.splitter
+    }
+
+    /// 拆分条码数据，末尾的回车换行会被忽略
+    pub fn split(&self, text: &str) -> Record {
+        let text = text.trim_end_matches(['\r', '\n']);
+        let fields = match &self.splitter {
+            FieldSplitter::Delimiter(delimiter) => {
+                text.split(delimiter.as_str()).map(Into::into).collect()
+            }
+            FieldSplitter::FixedWidth(widths) => {
+                let mut chars = text.chars();
+                widths
+                    .iter()
+                    .map(|width| chars.by_ref().take(*width).collect())
+                    .collect()
+            }
+        };
+        Record {
+            fields,
+            names: self.names.clone(),
+        }
+    }
+}
+
+/// 拆分后的多字段记录
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Record {
+    fields: Vec<String>,
+    names: Vec<String>,
+}
+
+impl Record {
+    /// 获取所有字段
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// 获取字段名称
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// 按序号获取字段
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).map(String::as_str)
+    }
+
+    /// 按名称获取字段
+    pub fn field(&self, name: &str) -> Option<&str> {
+        let index = self.names.iter().position(|n| n == name)?;
+        self.get(index)
+    }
+
+    /// 字段数量
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// 是否没有字段
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
@@ -0,0 +1,32 @@
+use crate::Barcode;
+
+/// 被过滤器拒绝的条码
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rejected {
+    barcode: Barcode,
+    reason: String,
+}
+
+impl Rejected {
+    /// 创建被拒绝的条码
+    ///
+    /// * `barcode` 条码数据
+    /// * `reason` 拒绝原因
+    pub fn new(barcode: Barcode, reason: impl Into<String>) -> Self {
+        Rejected {
+            barcode,
+            reason: reason.into(),
+        }
+    }
+
+    /// 获取条码数据
+    pub fn barcode(&self) -> &Barcode {
+        &self.barcode
+    }
+
+    /// 获取拒绝原因
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
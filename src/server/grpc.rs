@@ -0,0 +1,281 @@
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, ScanEvent, ScannerError, ScannerManager};
+use proto::command_request::Command;
+use proto::scanner_service_server::{ScannerService, ScannerServiceServer};
+use proto::EventType;
+
+/// 由`proto/scanner.proto`生成的消息、服务端和客户端
+pub mod proto {
+    tonic::include_proto!("kim_scanner.v1");
+}
+
+/// gRPC 服务，非 Rust 的 MES 组件可以按`proto/scanner.proto`生成客户端，订阅扫码事件和发送指令
+///
+/// * `ListScanners` 列出所有扫码枪
+/// * `Subscribe` 订阅扫码事件(见[`ScannerManager::subscribe`])，可以只订阅部分扫码枪
+/// * `SendCommand` 发送原始指令或[`crate::CommandTemplates`]中的指令
+///
+/// 扫码枪不存在返回`NOT_FOUND`，参数错误返回`INVALID_ARGUMENT`，
+/// 应答超时返回`DEADLINE_EXCEEDED`，其它错误返回`UNAVAILABLE`
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = Arc::new(
+///     ScannerManager::new()
+///         .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004))),
+/// );
+/// manager.start().await?;
+/// GrpcServer::new(Arc::clone(&manager)).serve(([0, 0, 0, 0], 50051)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GrpcServer {
+    manager: Arc<ScannerManager>,
+}
+
+impl GrpcServer {
+    /// 创建 gRPC 服务
+    pub fn new(manager: Arc<ScannerManager>) -> Self {
+        GrpcServer { manager }
+    }
+
+    /// 获取 tonic 服务，可以加入应用已有的 gRPC 服务中
+    pub fn service(self) -> ScannerServiceServer<GrpcServer> {
+        ScannerServiceServer::new(self)
+    }
+
+    /// 监听指定地址并提供服务，直到出错才返回，需要在 tokio 运行时中调用
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        let addr = addr.into();
+        event!(Level::INFO, server.addr = %addr, "gRPC服务启动");
+        tonic::transport::Server::builder()
+            .add_service(self.service())
+            .serve(addr)
+            .await
+            .map_err(|err| ScannerError::Comm(format!("gRPC服务运行失败,{}", err)))
+    }
+}
+
+/// 扫码枪错误转换为 gRPC 状态
+fn status(err: ScannerError) -> Status {
+    match err {
+        ScannerError::Param(msg) => Status::invalid_argument(msg),
+        ScannerError::Timeout(msg) => Status::deadline_exceeded(msg),
+        err => Status::unavailable(err.to_string()),
+    }
+}
+
+impl From<&FleetEvent> for proto::ScanEvent {
+    fn from(ev: &FleetEvent) -> Self {
+        let (r#type, message) = match ev.event() {
+            ScanEvent::Barcode(_) => (EventType::Barcode, String::new()),
+            ScanEvent::NoRead(_) => (EventType::NoRead, String::new()),
+            ScanEvent::Match(_) => (EventType::Match, String::new()),
+            ScanEvent::Mismatch(_) => (EventType::Mismatch, String::new()),
+            ScanEvent::Error(err) => (EventType::Error, err.to_string()),
+            ScanEvent::Connected => (EventType::Connected, String::new()),
+            ScanEvent::Disconnected => (EventType::Disconnected, String::new()),
+            ScanEvent::Unhealthy(reason) => (EventType::Unhealthy, reason.clone()),
+            ScanEvent::Failover(id) => (EventType::Failover, id.clone()),
+            ScanEvent::Failback(id) => (EventType::Failback, id.clone()),
+            ScanEvent::Restarted(count) => (EventType::Restarted, count.to_string()),
+            ScanEvent::Failed(err) => (EventType::Failed, err.to_string()),
+        };
+        let barcode = ev.event().barcode().map(|barcode| proto::Barcode {
+            text: barcode.text().into_owned(),
+            raw: barcode.raw().to_vec(),
+            symbology: barcode
+                .symbology()
+                .map(|symbology| format!("{:?}", symbology)),
+        });
+        proto::ScanEvent {
+            scanner: ev.id().to_owned(),
+            labels: ev
+                .labels()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            r#type: r#type.into(),
+            barcode,
+            message,
+        }
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<proto::ScanEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl ScannerService for GrpcServer {
+    async fn list_scanners(
+        &self,
+        _request: Request<proto::ListScannersRequest>,
+    ) -> Result<Response<proto::ListScannersResponse>, Status> {
+        let scanners = self
+            .manager
+            .ids()
+            .into_iter()
+            .filter_map(|id| {
+                let scanner = self.manager.get(&id)?;
+                Some(proto::ScannerInfo {
+                    connector: scanner.connector.to_string(),
+                    labels: scanner
+                        .labels()
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                    running: scanner.is_running(),
+                    id,
+                })
+            })
+            .collect();
+        Ok(Response::new(proto::ListScannersResponse { scanners }))
+    }
+
+    type SubscribeStream = EventStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let filter = request.into_inner().scanners;
+        let mut events = self.manager.subscribe();
+        let (sender, receiver) = mpsc::channel(100);
+        tokio::spawn(async move {
+            loop {
+                let ev = match events.recv().await {
+                    Ok(ev) => ev,
+                    Err(RecvError::Lagged(n)) => {
+                        event!(Level::WARN, dropped = n, "gRPC订阅者接收过慢,丢弃事件");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if !filter.is_empty() && !filter.iter().any(|id| id == ev.id()) {
+                    continue;
+                }
+                if sender.send(Ok((&ev).into())).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn send_command(
+        &self,
+        request: Request<proto::CommandRequest>,
+    ) -> Result<Response<proto::CommandResponse>, Status> {
+        let request = request.into_inner();
+        let Some(scanner) = self.manager.get(&request.scanner) else {
+            return Err(Status::not_found(format!(
+                "扫码枪不存在,id={}",
+                request.scanner
+            )));
+        };
+        match request.command {
+            Some(Command::Raw(cmd)) => scanner
+                .send_message(cmd)
+                .await
+                .and_then(|r| r)
+                .map_err(status)?,
+            Some(Command::Name(name)) => scanner.command(&name).await.map_err(status)?,
+            None => return Err(Status::invalid_argument("没有设置指令")),
+        }
+        event!(Level::INFO, scanner.id = %request.scanner, "gRPC发送指令");
+        Ok(Response::new(proto::CommandResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    use super::proto::scanner_service_client::ScannerServiceClient;
+    use super::proto::{command_request::Command, CommandRequest, EventType};
+    use super::proto::{ListScannersRequest, SubscribeRequest};
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn grpc() {
+        let mock = MockConnector::new();
+        let manager = Arc::new(
+            ScannerManager::new().with_scanner("A", Scanner::new(mock.clone()).label("line", "1")),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let service = GrpcServer::new(Arc::clone(&manager)).service();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        let mut client = ScannerServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+
+        let mut stream = client
+            .subscribe(SubscribeRequest { scanners: vec![] })
+            .await
+            .unwrap()
+            .into_inner();
+        manager.start().await.unwrap();
+        let ev = stream.message().await.unwrap().unwrap();
+        assert_eq!(ev.scanner, "A");
+        assert_eq!(ev.r#type(), EventType::Connected);
+        mock.push("]d2SN0001");
+        let ev = stream.message().await.unwrap().unwrap();
+        assert_eq!(ev.r#type(), EventType::Barcode);
+        assert_eq!(ev.labels["line"], "1");
+        let barcode = ev.barcode.unwrap();
+        assert_eq!(barcode.symbology.as_deref(), Some("DataMatrix"));
+        assert_eq!(barcode.raw, b"]d2SN0001");
+
+        let scanners = client
+            .list_scanners(ListScannersRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .scanners;
+        assert_eq!(scanners.len(), 1);
+        assert!(scanners[0].running);
+
+        let command = |scanner: &str, command| CommandRequest {
+            scanner: scanner.into(),
+            command: Some(command),
+        };
+        client
+            .send_command(command("A", Command::Raw("LON\r".into())))
+            .await
+            .unwrap();
+        let err = client
+            .send_command(command("B", Command::Raw("LON\r".into())))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+        let err = client
+            .send_command(command("A", Command::Name("off".into())))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(mock.try_commands(), vec!["LON\r"]);
+    }
+}
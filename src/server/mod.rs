@@ -0,0 +1,10 @@
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "modbus")]
+pub mod modbus;
+#[cfg(feature = "opcua")]
+pub mod opcua;
+#[cfg(feature = "ws-push")]
+pub mod push;
+#[cfg(feature = "api")]
+pub mod rest;
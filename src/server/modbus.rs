@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{event, Level};
+
+use crate::{ScannerError, ScannerManager};
+
+/// 条码前面的寄存器数量：状态、扫码计数(2个)、条码长度
+const HEADER: u16 = 4;
+
+/// 异常码
+const ILLEGAL_FUNCTION: u8 = 0x01;
+const ILLEGAL_DATA_ADDRESS: u8 = 0x02;
+const ILLEGAL_DATA_VALUE: u8 = 0x03;
+const GATEWAY_TARGET_FAILED: u8 = 0x0B;
+
+/// Modbus TCP 从站，PLC 不需要任何自定义协议就可以读取最后的条码
+///
+/// 每台扫码枪对应一个从站地址(单元标识)，保持寄存器(功能码03)和输入寄存器(功能码04)的内容相同：
+///
+/// | 地址 | 说明 |
+/// |------|------|
+/// | 0 | 状态，bit0 已连接，bit1 正在运行 |
+/// | 1-2 | 扫码计数，32位，高字在前 |
+/// | 3 | 条码长度(字节) |
+/// | 4- | 条码，每个寄存器两个字符，高字节在前，不足补0，默认32个寄存器(64个字符) |
+///
+/// 线圈0为握手信号：扫到新条码后为1，PLC 读取条码后写0确认(功能码05或15)。
+/// 数据来自[`ScannerManager`]的统计(见[`ScannerManager::stats`])
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = Arc::new(
+///     ScannerManager::new()
+///         .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004))),
+/// );
+/// manager.start().await?;
+/// ModbusServer::new(Arc::clone(&manager))
+///     .with_unit(1, "line1-in")
+///     .serve(([0, 0, 0, 0], 502))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ModbusServer {
+    manager: Arc<ScannerManager>,
+    units: BTreeMap<u8, String>,
+    barcode_registers: u16,
+    /// 每个从站已确认的扫码计数
+    acked: Arc<Mutex<HashMap<u8, u64>>>,
+}
+
+impl ModbusServer {
+    /// 创建 Modbus TCP 从站
+    pub fn new(manager: Arc<ScannerManager>) -> Self {
+        ModbusServer {
+            manager,
+            units: BTreeMap::new(),
+            barcode_registers: 32,
+            acked: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 把从站地址对应到扫码枪
+    pub fn with_unit(mut self, unit: u8, scanner: &str) -> Self {
+        self.units.insert(unit, scanner.into());
+        self
+    }
+
+    /// 设置条码占用的寄存器数量，超过的部分被截断，默认32个
+    pub fn with_barcode_registers(mut self, registers: u16) -> Self {
+        self.barcode_registers = registers.clamp(1, 125 - HEADER);
+        self
+    }
+
+    /// 获取从站地址和扫码枪编号
+    pub fn units(&self) -> &BTreeMap<u8, String> {
+        &self.units
+    }
+
+    /// 监听指定地址并提供服务，直到出错才返回，需要在 tokio 运行时中调用
+    ///
+    /// 没有配置从站时返回参数错误
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        if self.units.is_empty() {
+            return Err(ScannerError::Param("没有配置Modbus从站".into()));
+        }
+        let addr = addr.into();
+        let listener = TcpListener::bind(addr).await.map_err(ScannerError::Io)?;
+        event!(Level::INFO, server.addr = %addr, "Modbus TCP服务启动成功");
+        self.run(listener).await
+    }
+
+    /// 接受连接，每个连接一个任务
+    async fn run(self, listener: TcpListener) -> Result<(), ScannerError> {
+        loop {
+            let (client, peer) = listener.accept().await.map_err(ScannerError::Io)?;
+            tokio::spawn(self.clone().session(client, peer));
+        }
+    }
+
+    /// 处理一个主站的请求，直到主站断开
+    async fn session(self, mut client: TcpStream, peer: SocketAddr) {
+        event!(Level::INFO, client.addr = %peer, "Modbus主站已连接");
+        let mut header = [0u8; 7];
+        loop {
+            if client.read_exact(&mut header).await.is_err() {
+                break;
+            }
+            let len = u16::from_be_bytes([header[4], header[5]]) as usize;
+            // 长度包含单元标识，功能码至少一个字节
+            if !(2..=254).contains(&len) {
+                event!(Level::WARN, client.addr = %peer, len, "Modbus报文长度无效");
+                break;
+            }
+            let mut pdu = vec![0u8; len - 1];
+            if client.read_exact(&mut pdu).await.is_err() {
+                break;
+            }
+            let reply = match self.handle(header[6], &pdu) {
+                Ok(reply) => reply,
+                Err(code) => vec![pdu[0] | 0x80, code],
+            };
+            let mut frame = Vec::with_capacity(reply.len() + 7);
+            frame.extend_from_slice(&header[..4]);
+            frame.extend_from_slice(&(reply.len() as u16 + 1).to_be_bytes());
+            frame.push(header[6]);
+            frame.extend_from_slice(&reply);
+            if client.write_all(&frame).await.is_err() {
+                break;
+            }
+        }
+        event!(Level::INFO, client.addr = %peer, "Modbus主站已断开");
+    }
+
+    /// 处理一个请求，返回应答或异常码
+    fn handle(&self, unit: u8, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+        let id = self.units.get(&unit).ok_or(GATEWAY_TARGET_FAILED)?;
+        let word = |index: usize| -> Result<u16, u8> {
+            match pdu.get(index..index + 2) {
+                Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+                None => Err(ILLEGAL_DATA_VALUE),
+            }
+        };
+        match pdu[0] {
+            // 读线圈
+            0x01 => {
+                let (start, count) = (word(1)?, word(3)?);
+                if !(1..=2000).contains(&count) {
+                    return Err(ILLEGAL_DATA_VALUE);
+                }
+                if start != 0 || count != 1 {
+                    return Err(ILLEGAL_DATA_ADDRESS);
+                }
+                Ok(vec![0x01, 1, self.pending(unit, id) as u8])
+            }
+            // 读保持寄存器、读输入寄存器
+            0x03 | 0x04 => {
+                let (start, count) = (word(1)?, word(3)?);
+                if !(1..=125).contains(&count) {
+                    return Err(ILLEGAL_DATA_VALUE);
+                }
+                let registers = self.registers(id);
+                let end = start as usize + count as usize;
+                let Some(registers) = registers.get(start as usize..end) else {
+                    return Err(ILLEGAL_DATA_ADDRESS);
+                };
+                let mut reply = vec![pdu[0], count as u8 * 2];
+                for register in registers {
+                    reply.extend_from_slice(&register.to_be_bytes());
+                }
+                Ok(reply)
+            }
+            // 写单个线圈
+            0x05 => {
+                let (address, value) = (word(1)?, word(3)?);
+                if address != 0 {
+                    return Err(ILLEGAL_DATA_ADDRESS);
+                }
+                match value {
+                    0x0000 => self.acknowledge(unit, id),
+                    0xFF00 => {}
+                    _ => return Err(ILLEGAL_DATA_VALUE),
+                }
+                Ok(pdu[..5].to_vec())
+            }
+            // 写多个线圈
+            0x0F => {
+                let (start, count) = (word(1)?, word(3)?);
+                let value = *pdu.get(6).ok_or(ILLEGAL_DATA_VALUE)?;
+                if start != 0 || count != 1 {
+                    return Err(ILLEGAL_DATA_ADDRESS);
+                }
+                if value & 0x01 == 0 {
+                    self.acknowledge(unit, id);
+                }
+                Ok(pdu[..5].to_vec())
+            }
+            _ => Err(ILLEGAL_FUNCTION),
+        }
+    }
+
+    /// 是否有未确认的新条码
+    fn pending(&self, unit: u8, id: &str) -> bool {
+        let scans = self.manager.stats(id).unwrap_or_default().scans();
+        let acked = self.acked.lock().unwrap();
+        scans != acked.get(&unit).copied().unwrap_or_default()
+    }
+
+    /// 确认当前的条码
+    fn acknowledge(&self, unit: u8, id: &str) {
+        let scans = self.manager.stats(id).unwrap_or_default().scans();
+        self.acked.lock().unwrap().insert(unit, scans);
+    }
+
+    /// 一台扫码枪的全部寄存器
+    fn registers(&self, id: &str) -> Vec<u16> {
+        let stats = self.manager.stats(id).unwrap_or_default();
+        let running = self
+            .manager
+            .get(id)
+            .is_some_and(|scanner| scanner.is_running());
+        let text = stats
+            .last_barcode()
+            .map(|barcode| barcode.text().into_owned())
+            .unwrap_or_default();
+        let capacity = self.barcode_registers as usize * 2;
+        let bytes = &text.as_bytes()[..text.len().min(capacity)];
+        let scans = stats.scans() as u32;
+        let mut registers = vec![
+            stats.connected().unwrap_or(false) as u16 | (running as u16) << 1,
+            (scans >> 16) as u16,
+            scans as u16,
+            bytes.len() as u16,
+        ];
+        registers.extend(
+            bytes
+                .chunks(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)])),
+        );
+        registers.resize(HEADER as usize + self.barcode_registers as usize, 0);
+        registers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::ModbusServer;
+    use crate::prelude::*;
+
+    /// 发送一个请求，返回应答的 PDU
+    async fn request(stream: &mut TcpStream, unit: u8, pdu: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x00, 0x01, 0x00, 0x00];
+        frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+        frame.push(unit);
+        frame.extend_from_slice(pdu);
+        stream.write_all(&frame).await.unwrap();
+        let mut header = [0u8; 7];
+        stream.read_exact(&mut header).await.unwrap();
+        assert_eq!(header[..2], [0x00, 0x01]);
+        let mut reply = vec![0u8; u16::from_be_bytes([header[4], header[5]]) as usize - 1];
+        stream.read_exact(&mut reply).await.unwrap();
+        reply
+    }
+
+    #[tokio::test]
+    async fn modbus() {
+        let mock = MockConnector::new();
+        let manager = Arc::new(ScannerManager::new().with_scanner("A", Scanner::new(mock.clone())));
+        manager.start().await.unwrap();
+        manager.recv().await.unwrap();
+        mock.push("SN00001");
+        manager.recv().await.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = ModbusServer::new(Arc::clone(&manager))
+            .with_unit(1, "A")
+            .with_barcode_registers(4);
+        tokio::spawn(server.run(listener));
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+
+        let reply = request(&mut stream, 1, &[0x03, 0x00, 0x00, 0x00, 0x08]).await;
+        assert_eq!(
+            reply,
+            [
+                0x03, 16, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x07, b'S', b'N', b'0', b'0',
+                b'0', b'0', b'1', 0x00
+            ]
+        );
+        // 握手：新条码置位，写0确认
+        assert_eq!(
+            request(&mut stream, 1, &[0x01, 0x00, 0x00, 0x00, 0x01]).await,
+            [0x01, 1, 1]
+        );
+        let write = [0x05, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(request(&mut stream, 1, &write).await, write);
+        assert_eq!(
+            request(&mut stream, 1, &[0x01, 0x00, 0x00, 0x00, 0x01]).await,
+            [0x01, 1, 0]
+        );
+        mock.push("SN00002");
+        manager.recv().await.unwrap();
+        assert_eq!(
+            request(&mut stream, 1, &[0x01, 0x00, 0x00, 0x00, 0x01]).await,
+            [0x01, 1, 1]
+        );
+
+        // 地址超出范围、未知功能码和从站
+        assert_eq!(
+            request(&mut stream, 1, &[0x04, 0x00, 0x07, 0x00, 0x02]).await,
+            [0x84, 0x02]
+        );
+        assert_eq!(
+            request(&mut stream, 1, &[0x06, 0x00, 0x00, 0x00, 0x01]).await,
+            [0x86, 0x01]
+        );
+        assert_eq!(
+            request(&mut stream, 2, &[0x03, 0x00, 0x00, 0x00, 0x01]).await,
+            [0x83, 0x0B]
+        );
+    }
+}
@@ -0,0 +1,423 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// OPC UA 的 DateTime 从 1601-01-01 开始计数，单位 100 纳秒，这里是到 Unix 纪元的偏移
+const EPOCH_OFFSET: i64 = 116_444_736_000_000_000;
+
+/// 状态码
+pub(crate) const GOOD: u32 = 0;
+pub(crate) const BAD_DECODING_ERROR: u32 = 0x8007_0000;
+pub(crate) const BAD_SERVICE_UNSUPPORTED: u32 = 0x800B_0000;
+pub(crate) const BAD_NOTHING_TO_DO: u32 = 0x800F_0000;
+pub(crate) const BAD_SUBSCRIPTION_ID_INVALID: u32 = 0x8028_0000;
+pub(crate) const BAD_NODE_ID_UNKNOWN: u32 = 0x8034_0000;
+pub(crate) const BAD_ATTRIBUTE_ID_INVALID: u32 = 0x8035_0000;
+pub(crate) const BAD_MONITORED_ITEM_ID_INVALID: u32 = 0x8042_0000;
+pub(crate) const BAD_SECURITY_POLICY_REJECTED: u32 = 0x8055_0000;
+pub(crate) const BAD_NO_SUBSCRIPTION: u32 = 0x8079_0000;
+pub(crate) const BAD_MESSAGE_NOT_AVAILABLE: u32 = 0x807B_0000;
+pub(crate) const BAD_TCP_MESSAGE_TYPE_INVALID: u32 = 0x807E_0000;
+pub(crate) const BAD_TCP_MESSAGE_TOO_LARGE: u32 = 0x8080_0000;
+
+/// 节点标识，GUID、ByteString 类型的标识本服务不使用，统一为`Opaque`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum NodeId {
+    Numeric(u16, u32),
+    String(u16, String),
+    Opaque,
+}
+
+impl NodeId {
+    /// 标准命名空间(0)中的数字标识
+    pub(crate) fn ns0(id: u32) -> Self {
+        NodeId::Numeric(0, id)
+    }
+
+    /// 是否为空标识
+    pub(crate) fn is_null(&self) -> bool {
+        *self == NodeId::Numeric(0, 0)
+    }
+}
+
+/// 变量值，只包含本服务用到的类型
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Variant {
+    Empty,
+    Boolean(bool),
+    Byte(u8),
+    Int32(i32),
+    UInt32(u32),
+    UInt64(u64),
+    Double(f64),
+    String(String),
+    DateTime(i64),
+    NodeId(NodeId),
+    QualifiedName(u16, String),
+    LocalizedText(String),
+    StringArray(Vec<String>),
+    /// 编码后的结构体：编码类型标识和内容
+    ExtensionObject(u32, Vec<u8>),
+}
+
+/// 带状态和时间的值
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct DataValue {
+    pub(crate) value: Option<Variant>,
+    pub(crate) status: u32,
+    pub(crate) source_time: Option<i64>,
+    pub(crate) server_time: Option<i64>,
+}
+
+impl DataValue {
+    pub(crate) fn status(status: u32) -> Self {
+        DataValue {
+            value: None,
+            status,
+            source_time: None,
+            server_time: None,
+        }
+    }
+}
+
+/// 转换为 OPC UA 的 DateTime
+pub(crate) fn date_time(time: SystemTime) -> i64 {
+    let ticks = time
+        .duration_since(UNIX_EPOCH)
+        .map(|time| (time.as_nanos() / 100) as i64)
+        .unwrap_or_default();
+    ticks + EPOCH_OFFSET
+}
+
+/// 解码结果，数据不完整或格式错误时为`BAD_DECODING_ERROR`
+pub(crate) type Decoded<T> = Result<T, u32>;
+
+/// 二进制解码
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader { buf }
+    }
+
+    fn take(&mut self, n: usize) -> Decoded<&'a [u8]> {
+        if self.buf.len() < n {
+            return Err(BAD_DECODING_ERROR);
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Decoded<[u8; N]> {
+        self.take(N)?.try_into().map_err(|_| BAD_DECODING_ERROR)
+    }
+
+    pub(crate) fn u8(&mut self) -> Decoded<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn bool(&mut self) -> Decoded<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    pub(crate) fn u16(&mut self) -> Decoded<u16> {
+        self.fixed().map(u16::from_le_bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> Decoded<u32> {
+        self.fixed().map(u32::from_le_bytes)
+    }
+
+    pub(crate) fn i32(&mut self) -> Decoded<i32> {
+        self.fixed().map(i32::from_le_bytes)
+    }
+
+    pub(crate) fn i64(&mut self) -> Decoded<i64> {
+        self.fixed().map(i64::from_le_bytes)
+    }
+
+    pub(crate) fn f64(&mut self) -> Decoded<f64> {
+        self.fixed().map(f64::from_le_bytes)
+    }
+
+    /// ByteString，长度为-1时为空值
+    pub(crate) fn bytes(&mut self) -> Decoded<Option<&'a [u8]>> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Ok(None);
+        }
+        self.take(len as usize).map(Some)
+    }
+
+    /// String，长度为-1时为空值
+    pub(crate) fn string(&mut self) -> Decoded<Option<String>> {
+        Ok(self
+            .bytes()?
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    /// NodeId，也用于不带命名空间 URI 和服务器序号的 ExpandedNodeId
+    pub(crate) fn node_id(&mut self) -> Decoded<NodeId> {
+        let id = match self.u8()? & 0x3F {
+            0x00 => NodeId::Numeric(0, self.u8()? as u32),
+            0x01 => NodeId::Numeric(self.u8()? as u16, self.u16()? as u32),
+            0x02 => NodeId::Numeric(self.u16()?, self.u32()?),
+            0x03 => NodeId::String(self.u16()?, self.string()?.unwrap_or_default()),
+            0x04 => {
+                self.take(18)?;
+                NodeId::Opaque
+            }
+            0x05 => {
+                self.u16()?;
+                self.bytes()?;
+                NodeId::Opaque
+            }
+            _ => return Err(BAD_DECODING_ERROR),
+        };
+        Ok(id)
+    }
+
+    /// QualifiedName，本服务只需要跳过
+    pub(crate) fn qualified_name(&mut self) -> Decoded<()> {
+        self.u16()?;
+        self.string()?;
+        Ok(())
+    }
+
+    /// ExtensionObject，本服务只需要跳过
+    pub(crate) fn extension_object(&mut self) -> Decoded<()> {
+        self.node_id()?;
+        match self.u8()? {
+            0x00 => {}
+            0x01 | 0x02 => {
+                self.bytes()?;
+            }
+            _ => return Err(BAD_DECODING_ERROR),
+        }
+        Ok(())
+    }
+
+    /// 数组，长度为-1时为空数组
+    pub(crate) fn array<T>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Decoded<T>,
+    ) -> Decoded<Vec<T>> {
+        let len = self.i32()?.max(0) as usize;
+        // 每个元素至少一个字节，避免按错误的长度分配内存
+        let mut items = Vec::with_capacity(len.min(self.buf.len()));
+        for _ in 0..len {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    /// 剩余的字节数
+    #[cfg(test)]
+    pub(crate) fn remaining(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// 请求头，返回请求句柄
+    pub(crate) fn request_header(&mut self) -> Decoded<u32> {
+        self.node_id()?;
+        self.i64()?;
+        let handle = self.u32()?;
+        self.u32()?;
+        self.string()?;
+        self.u32()?;
+        self.extension_object()?;
+        Ok(handle)
+    }
+}
+
+/// 二进制编码
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub(crate) fn raw(&mut self, data: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(data);
+        self
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) -> &mut Self {
+        self.buf.push(value);
+        self
+    }
+
+    pub(crate) fn bool(&mut self, value: bool) -> &mut Self {
+        self.u8(value as u8)
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) -> &mut Self {
+        self.raw(&value.to_le_bytes())
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) -> &mut Self {
+        self.raw(&value.to_le_bytes())
+    }
+
+    pub(crate) fn i32(&mut self, value: i32) -> &mut Self {
+        self.raw(&value.to_le_bytes())
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) -> &mut Self {
+        self.raw(&value.to_le_bytes())
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) -> &mut Self {
+        self.raw(&value.to_le_bytes())
+    }
+
+    pub(crate) fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.i32(value.len() as i32).raw(value)
+    }
+
+    /// 空的 String 或 ByteString
+    pub(crate) fn null(&mut self) -> &mut Self {
+        self.i32(-1)
+    }
+
+    pub(crate) fn string(&mut self, value: &str) -> &mut Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub(crate) fn node_id(&mut self, id: &NodeId) -> &mut Self {
+        match id {
+            NodeId::Numeric(0, id) if *id <= 0xFF => self.u8(0x00).u8(*id as u8),
+            NodeId::Numeric(ns, id) if *ns <= 0xFF && *id <= 0xFFFF => {
+                self.u8(0x01).u8(*ns as u8).u16(*id as u16)
+            }
+            NodeId::Numeric(ns, id) => self.u8(0x02).u16(*ns).u32(*id),
+            NodeId::String(ns, id) => self.u8(0x03).u16(*ns).string(id),
+            NodeId::Opaque => self.u8(0x00).u8(0),
+        }
+    }
+
+    pub(crate) fn qualified_name(&mut self, ns: u16, name: &str) -> &mut Self {
+        self.u16(ns).string(name)
+    }
+
+    /// 不带语言的 LocalizedText
+    pub(crate) fn localized_text(&mut self, text: &str) -> &mut Self {
+        self.u8(0x02).string(text)
+    }
+
+    /// 数组长度
+    pub(crate) fn count(&mut self, len: usize) -> &mut Self {
+        self.i32(len as i32)
+    }
+
+    /// 二进制编码的 ExtensionObject，`kind`为编码类型标识
+    pub(crate) fn extension_object(&mut self, kind: u32, body: &[u8]) -> &mut Self {
+        self.node_id(&NodeId::ns0(kind)).u8(0x01).bytes(body)
+    }
+
+    /// 空的 ExtensionObject
+    pub(crate) fn null_extension_object(&mut self) -> &mut Self {
+        self.node_id(&NodeId::ns0(0)).u8(0x00)
+    }
+
+    pub(crate) fn variant(&mut self, value: &Variant) -> &mut Self {
+        match value {
+            Variant::Empty => self.u8(0),
+            Variant::Boolean(value) => self.u8(1).bool(*value),
+            Variant::Byte(value) => self.u8(3).u8(*value),
+            Variant::Int32(value) => self.u8(6).i32(*value),
+            Variant::UInt32(value) => self.u8(7).u32(*value),
+            Variant::UInt64(value) => self.u8(9).raw(&value.to_le_bytes()),
+            Variant::Double(value) => self.u8(11).f64(*value),
+            Variant::String(value) => self.u8(12).string(value),
+            Variant::DateTime(value) => self.u8(13).i64(*value),
+            Variant::NodeId(value) => self.u8(17).node_id(value),
+            Variant::QualifiedName(ns, name) => self.u8(20).qualified_name(*ns, name),
+            Variant::LocalizedText(text) => self.u8(21).localized_text(text),
+            Variant::StringArray(values) => {
+                self.u8(12 | 0x80).count(values.len());
+                for value in values {
+                    self.string(value);
+                }
+                self
+            }
+            Variant::ExtensionObject(kind, body) => self.u8(22).extension_object(*kind, body),
+        }
+    }
+
+    pub(crate) fn data_value(&mut self, value: &DataValue) -> &mut Self {
+        let mut mask = 0;
+        if value.value.is_some() {
+            mask |= 0x01;
+        }
+        if value.status != GOOD {
+            mask |= 0x02;
+        }
+        if value.source_time.is_some() {
+            mask |= 0x04;
+        }
+        if value.server_time.is_some() {
+            mask |= 0x08;
+        }
+        self.u8(mask);
+        if let Some(variant) = &value.value {
+            self.variant(variant);
+        }
+        if value.status != GOOD {
+            self.u32(value.status);
+        }
+        if let Some(time) = value.source_time {
+            self.i64(time);
+        }
+        if let Some(time) = value.server_time {
+            self.i64(time);
+        }
+        self
+    }
+
+    /// 应答头
+    pub(crate) fn response_header(&mut self, handle: u32, status: u32) -> &mut Self {
+        self.i64(date_time(SystemTime::now()))
+            .u32(handle)
+            .u32(status)
+            // 诊断信息
+            .u8(0)
+            // 字符串表
+            .count(0)
+            .null_extension_object()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NodeId, Reader, Writer};
+
+    #[test]
+    fn node_id() {
+        let ids = [
+            NodeId::ns0(85),
+            NodeId::ns0(2253),
+            NodeId::Numeric(1, 70000),
+            NodeId::String(1, "line1-in.Barcode".into()),
+        ];
+        let mut writer = Writer::default();
+        for id in &ids {
+            writer.node_id(id);
+        }
+        assert_eq!(writer.as_slice()[..6], [0x00, 85, 0x01, 0, 0xCD, 0x08]);
+        let mut reader = Reader::new(writer.as_slice());
+        for id in &ids {
+            assert_eq!(reader.node_id().as_ref(), Ok(id));
+        }
+        assert!(reader.u8().is_err());
+    }
+}
@@ -0,0 +1,1460 @@
+mod codec;
+
+use std::collections::hash_map::RandomState;
+use std::collections::VecDeque;
+use std::hash::BuildHasher;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{event, Level};
+
+use crate::{ScannerError, ScannerManager};
+use codec::*;
+
+/// 标准命名空间
+const UA_NAMESPACE: &str = "http://opcfoundation.org/UA/";
+/// 默认的应用命名空间(命名空间1)
+const NAMESPACE: &str = "urn:kim_scanner";
+/// 只支持不加密的安全策略
+const POLICY_NONE: &str = "http://opcfoundation.org/UA/SecurityPolicy#None";
+const TRANSPORT_PROFILE: &str = "http://opcfoundation.org/UA-Profile/Transport/uatcp-uasc-uabinary";
+/// 收发缓冲区大小
+const BUFFER_SIZE: u32 = 65536;
+/// 最大消息长度
+const MAX_MESSAGE: usize = 1 << 20;
+/// 会话超时(毫秒)，会话随连接关闭，不会超时
+const SESSION_TIMEOUT: f64 = 3_600_000.0;
+/// 订阅的检查周期，也是最短的发布周期
+const TICK: Duration = Duration::from_millis(100);
+
+/// 服务的请求编码标识，应答的编码标识比请求大3
+const OPEN_SECURE_CHANNEL: u32 = 446;
+const SERVICE_FAULT: u32 = 397;
+const FIND_SERVERS: u32 = 422;
+const GET_ENDPOINTS: u32 = 428;
+const CREATE_SESSION: u32 = 461;
+const ACTIVATE_SESSION: u32 = 467;
+const CLOSE_SESSION: u32 = 473;
+const BROWSE: u32 = 527;
+const READ: u32 = 631;
+const CREATE_MONITORED_ITEMS: u32 = 751;
+const SET_MONITORING_MODE: u32 = 769;
+const DELETE_MONITORED_ITEMS: u32 = 781;
+const CREATE_SUBSCRIPTION: u32 = 787;
+const MODIFY_SUBSCRIPTION: u32 = 793;
+const SET_PUBLISHING_MODE: u32 = 799;
+const PUBLISH: u32 = 826;
+const REPUBLISH: u32 = 832;
+const DELETE_SUBSCRIPTIONS: u32 = 847;
+const DATA_CHANGE_NOTIFICATION: u32 = 811;
+const SERVER_STATUS_DATA_TYPE: u32 = 864;
+
+/// 标准节点
+const ROOT: u32 = 84;
+const OBJECTS: u32 = 85;
+const SERVER: u32 = 2253;
+const SERVER_ARRAY: u32 = 2254;
+const NAMESPACE_ARRAY: u32 = 2255;
+const SERVER_STATUS: u32 = 2256;
+const CURRENT_TIME: u32 = 2258;
+const STATE: u32 = 2259;
+
+/// 引用类型
+const REFERENCES: u32 = 31;
+const HIERARCHICAL: u32 = 33;
+const HAS_CHILD: u32 = 34;
+const ORGANIZES: u32 = 35;
+const AGGREGATES: u32 = 44;
+const HAS_PROPERTY: u32 = 46;
+const HAS_COMPONENT: u32 = 47;
+
+/// 节点类别
+const OBJECT: u32 = 1;
+const VARIABLE: u32 = 2;
+
+/// 属性
+const NODE_ID: u32 = 1;
+const NODE_CLASS: u32 = 2;
+const BROWSE_NAME: u32 = 3;
+const DISPLAY_NAME: u32 = 4;
+const DESCRIPTION: u32 = 5;
+const WRITE_MASK: u32 = 6;
+const USER_WRITE_MASK: u32 = 7;
+const EVENT_NOTIFIER: u32 = 12;
+const VALUE: u32 = 13;
+const DATA_TYPE: u32 = 14;
+const VALUE_RANK: u32 = 15;
+const ARRAY_DIMENSIONS: u32 = 16;
+const ACCESS_LEVEL: u32 = 17;
+const USER_ACCESS_LEVEL: u32 = 18;
+const MINIMUM_SAMPLING_INTERVAL: u32 = 19;
+const HISTORIZING: u32 = 20;
+
+/// 返回的时间戳
+const SOURCE: u32 = 0;
+const SERVER_TIME: u32 = 1;
+const BOTH: u32 = 2;
+
+/// 浏览方向
+const INVERSE: u32 = 1;
+
+/// 监控模式
+const REPORTING: u32 = 2;
+
+/// 扫码枪的变量
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Barcode,
+    Connected,
+    Running,
+    Scans,
+    NoReads,
+    Mismatches,
+    Errors,
+    Disconnects,
+    LastScan,
+}
+
+const FIELDS: [Field; 9] = [
+    Field::Barcode,
+    Field::Connected,
+    Field::Running,
+    Field::Scans,
+    Field::NoReads,
+    Field::Mismatches,
+    Field::Errors,
+    Field::Disconnects,
+    Field::LastScan,
+];
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::Barcode => "Barcode",
+            Field::Connected => "Connected",
+            Field::Running => "Running",
+            Field::Scans => "Scans",
+            Field::NoReads => "NoReads",
+            Field::Mismatches => "Mismatches",
+            Field::Errors => "Errors",
+            Field::Disconnects => "Disconnects",
+            Field::LastScan => "LastScan",
+        }
+    }
+
+    /// 数据类型：Boolean(1)、UInt64(9)、String(12)、DateTime(13)
+    fn data_type(self) -> u32 {
+        match self {
+            Field::Barcode => 12,
+            Field::Connected | Field::Running => 1,
+            Field::LastScan => 13,
+            _ => 9,
+        }
+    }
+}
+
+/// 地址空间中的节点
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Root,
+    Objects,
+    Server,
+    ServerArray,
+    NamespaceArray,
+    ServerStatus,
+    CurrentTime,
+    State,
+    Scanners,
+    Scanner(String),
+    Variable(String, Field),
+}
+
+impl Node {
+    fn id(&self) -> NodeId {
+        match self {
+            Node::Root => NodeId::ns0(ROOT),
+            Node::Objects => NodeId::ns0(OBJECTS),
+            Node::Server => NodeId::ns0(SERVER),
+            Node::ServerArray => NodeId::ns0(SERVER_ARRAY),
+            Node::NamespaceArray => NodeId::ns0(NAMESPACE_ARRAY),
+            Node::ServerStatus => NodeId::ns0(SERVER_STATUS),
+            Node::CurrentTime => NodeId::ns0(CURRENT_TIME),
+            Node::State => NodeId::ns0(STATE),
+            Node::Scanners => NodeId::String(1, "Scanners".into()),
+            Node::Scanner(id) => NodeId::String(1, id.clone()),
+            Node::Variable(id, field) => NodeId::String(1, format!("{}.{}", id, field.name())),
+        }
+    }
+
+    /// 浏览名称，同时作为显示名称
+    fn browse_name(&self) -> (u16, String) {
+        let name = match self {
+            Node::Root => "Root",
+            Node::Objects => "Objects",
+            Node::Server => "Server",
+            Node::ServerArray => "ServerArray",
+            Node::NamespaceArray => "NamespaceArray",
+            Node::ServerStatus => "ServerStatus",
+            Node::CurrentTime => "CurrentTime",
+            Node::State => "State",
+            Node::Scanners => return (1, "Scanners".into()),
+            Node::Scanner(id) => return (1, id.clone()),
+            Node::Variable(_, field) => return (1, field.name().into()),
+        };
+        (0, name.into())
+    }
+
+    fn class(&self) -> u32 {
+        match self {
+            Node::Root | Node::Objects | Node::Server | Node::Scanners | Node::Scanner(_) => OBJECT,
+            _ => VARIABLE,
+        }
+    }
+
+    /// 类型定义：FolderType(61)、BaseObjectType(58)、ServerType(2004)、
+    /// BaseDataVariableType(63)、PropertyType(68)、ServerStatusType(2138)
+    fn type_definition(&self) -> u32 {
+        match self {
+            Node::Root | Node::Objects | Node::Scanners => 61,
+            Node::Server => 2004,
+            Node::Scanner(_) => 58,
+            Node::ServerArray | Node::NamespaceArray => 68,
+            Node::ServerStatus => 2138,
+            Node::CurrentTime | Node::State | Node::Variable(..) => 63,
+        }
+    }
+
+    /// 变量的数据类型和维数，对象为`None`
+    fn data_type(&self) -> Option<(u32, i32)> {
+        let data_type = match self {
+            Node::ServerArray | Node::NamespaceArray => return Some((12, 1)),
+            Node::ServerStatus => 862,
+            Node::CurrentTime => 13,
+            Node::State => 852,
+            Node::Variable(_, field) => field.data_type(),
+            _ => return None,
+        };
+        Some((data_type, -1))
+    }
+}
+
+/// 引用类型是否符合浏览条件，本服务的引用都是层级引用
+fn matches_reference(filter: &NodeId, subtypes: bool, kind: u32) -> bool {
+    match filter {
+        filter if filter.is_null() => true,
+        NodeId::Numeric(0, REFERENCES) => true,
+        NodeId::Numeric(0, id) if *id == kind => true,
+        NodeId::Numeric(0, HIERARCHICAL) => subtypes,
+        NodeId::Numeric(0, HAS_CHILD | AGGREGATES) => subtypes && kind != ORGANIZES,
+        _ => false,
+    }
+}
+
+/// 按请求设置时间戳
+fn stamp(mut value: DataValue, timestamps: u32) -> DataValue {
+    let now = date_time(SystemTime::now());
+    if matches!(timestamps, SOURCE | BOTH) {
+        value.source_time = Some(now);
+    }
+    if matches!(timestamps, SERVER_TIME | BOTH) {
+        value.server_time = Some(now);
+    }
+    value
+}
+
+/// 32字节随机数，不加密时只是为了兼容要求随机数的客户端
+fn nonce() -> Vec<u8> {
+    let state = RandomState::new();
+    (0..4u64)
+        .flat_map(|i| state.hash_one(i).to_le_bytes())
+        .collect()
+}
+
+/// 生成一个完整的消息
+fn chunk(kind: &[u8; 3], body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(body.len() + 8);
+    message.extend_from_slice(kind);
+    message.push(b'F');
+    message.extend_from_slice(&(body.len() as u32 + 8).to_le_bytes());
+    message.extend_from_slice(body);
+    message
+}
+
+/// 生成错误消息，发送后关闭连接
+fn error(status: u32, reason: &str) -> Vec<u8> {
+    let mut body = Writer::default();
+    body.u32(status).string(reason);
+    chunk(b"ERR", body.as_slice())
+}
+
+/// 等待应答的发布请求
+struct Publish {
+    request: u32,
+    handle: u32,
+    acks: usize,
+}
+
+/// 监控项
+struct Item {
+    id: u32,
+    handle: u32,
+    node: NodeId,
+    attribute: u32,
+    timestamps: u32,
+    reporting: bool,
+    last: Option<DataValue>,
+}
+
+/// 订阅
+struct Subscription {
+    id: u32,
+    interval: Duration,
+    keepalive: u32,
+    enabled: bool,
+    elapsed: Duration,
+    idle: u32,
+    sequence: u32,
+    items: Vec<Item>,
+}
+
+/// 一个连接的安全通道、会话和订阅，会话随连接关闭
+struct Channel {
+    id: u32,
+    token: u32,
+    sequence: u32,
+    endpoint: String,
+    closed: bool,
+    subscriptions: Vec<Subscription>,
+    publishes: VecDeque<Publish>,
+}
+
+impl Channel {
+    fn new(id: u32) -> Self {
+        Channel {
+            id,
+            token: 1,
+            sequence: 0,
+            endpoint: String::new(),
+            closed: false,
+            subscriptions: vec![],
+            publishes: VecDeque::new(),
+        }
+    }
+
+    /// 生成一个服务应答消息
+    fn reply(&mut self, request: u32, kind: u32, handle: u32, status: u32, body: &[u8]) -> Vec<u8> {
+        self.sequence += 1;
+        let mut message = Writer::default();
+        message
+            .u32(self.id)
+            .u32(self.token)
+            .u32(self.sequence)
+            .u32(request)
+            .node_id(&NodeId::ns0(kind))
+            .response_header(handle, status)
+            .raw(body);
+        chunk(b"MSG", message.as_slice())
+    }
+
+    fn subscription(&mut self, id: u32) -> Decoded<&mut Subscription> {
+        self.subscriptions
+            .iter_mut()
+            .find(|subscription| subscription.id == id)
+            .ok_or(BAD_SUBSCRIPTION_ID_INVALID)
+    }
+}
+
+/// OPC UA 服务，把每台扫码枪最后的条码、连接状态和计数发布为 OPC UA 节点，
+/// SCADA 可以用标准的 OPC UA 客户端读取或订阅
+///
+/// 节点在命名空间1(默认为`urn:kim_scanner`)中，`Objects/Scanners`下每台扫码枪一个对象，
+/// 对象下的变量标识为`<扫码枪编号>.<变量名>`，例如`ns=1;s=line1-in.Barcode`：
+///
+/// | 变量 | 类型 | 说明 |
+/// |------|------|------|
+/// | `Barcode` | String | 最后扫到的条码，没有时为空字符串 |
+/// | `Connected` | Boolean | 是否已连接 |
+/// | `Running` | Boolean | 是否正在运行 |
+/// | `Scans` | UInt64 | 条码数量 |
+/// | `NoReads` | UInt64 | 未读次数 |
+/// | `Mismatches` | UInt64 | 比对不一致次数 |
+/// | `Errors` | UInt64 | 错误次数 |
+/// | `Disconnects` | UInt64 | 断开次数 |
+/// | `LastScan` | DateTime | 最后一次扫码的时间 |
+///
+/// 数据来自[`ScannerManager`]的统计(见[`ScannerManager::stats`])。
+/// 提供浏览(Browse)、读取(Read)和订阅(数据变化通知)，不支持写入和历史数据
+///
+/// # 安全
+///
+/// 这是内置的简化实现，**没有任何安全措施**：只支持不加密(`SecurityPolicy#None`)和匿名登录，
+/// 不校验证书，任何能连上端口的客户端都可以读取所有扫码枪的条码和状态，报文可以被窃听和篡改。
+/// 默认只监听本机([`OpcUaServer::DEFAULT_ADDR`])；需要对外提供服务时只能放在隔离的控制网络中，
+/// 或者经过加密隧道/网关转发，监听非本机地址时会记录安全警告日志
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = Arc::new(
+///     ScannerManager::new()
+///         .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004))),
+/// );
+/// manager.start().await?;
+/// OpcUaServer::new(Arc::clone(&manager)).serve(OpcUaServer::DEFAULT_ADDR).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct OpcUaServer {
+    manager: Arc<ScannerManager>,
+    namespace: String,
+    started: SystemTime,
+    ids: Arc<AtomicU32>,
+}
+
+impl OpcUaServer {
+    /// 默认的监听地址`127.0.0.1:4840`，只允许本机的客户端连接
+    pub const DEFAULT_ADDR: SocketAddr =
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 4840));
+
+    /// 创建 OPC UA 服务
+    pub fn new(manager: Arc<ScannerManager>) -> Self {
+        OpcUaServer {
+            manager,
+            namespace: NAMESPACE.into(),
+            started: SystemTime::now(),
+            ids: Arc::new(AtomicU32::new(1)),
+        }
+    }
+
+    /// 设置应用命名空间，也作为服务的应用 URI，默认为`urn:kim_scanner`
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// 获取应用命名空间
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// 监听指定地址并提供服务，直到出错才返回，需要在 tokio 运行时中调用
+    ///
+    /// 服务不加密也不认证，一般使用[`OpcUaServer::DEFAULT_ADDR`]，详见[安全](OpcUaServer#安全)
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        let addr = addr.into();
+        let listener = TcpListener::bind(addr).await.map_err(ScannerError::Io)?;
+        if !addr.ip().is_loopback() {
+            event!(
+                target: "kim_scanner::security",
+                Level::WARN,
+                server.addr = %addr,
+                "OPC UA服务不加密也不认证,监听了非本机地址,任何能连上的客户端都可以读取数据",
+            );
+        }
+        event!(Level::INFO, server.addr = %addr, "OPC UA服务启动成功");
+        self.run(listener).await
+    }
+
+    /// 接受连接，每个连接一个任务
+    async fn run(self, listener: TcpListener) -> Result<(), ScannerError> {
+        loop {
+            let (client, peer) = listener.accept().await.map_err(ScannerError::Io)?;
+            tokio::spawn(self.clone().session(client, peer));
+        }
+    }
+
+    fn next_id(&self) -> u32 {
+        self.ids.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// 处理一个客户端的请求，并定时发布订阅的数据，直到客户端断开
+    async fn session(self, mut client: TcpStream, peer: SocketAddr) {
+        event!(Level::INFO, client.addr = %peer, "OPC UA客户端已连接");
+        let mut channel = Channel::new(self.next_id());
+        let mut buf = Vec::with_capacity(BUFFER_SIZE as usize);
+        let mut tick = tokio::time::interval(TICK);
+        while !channel.closed {
+            let replies = tokio::select! {
+                read = client.read_buf(&mut buf) => match read {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => self.receive(&mut channel, &mut buf),
+                },
+                _ = tick.tick() => self.publish(&mut channel),
+            };
+            for reply in replies {
+                if client.write_all(&reply).await.is_err() {
+                    channel.closed = true;
+                    break;
+                }
+            }
+        }
+        event!(Level::INFO, client.addr = %peer, "OPC UA客户端已断开");
+    }
+
+    /// 处理缓冲区中完整的消息
+    fn receive(&self, channel: &mut Channel, buf: &mut Vec<u8>) -> Vec<Vec<u8>> {
+        let mut replies = vec![];
+        while buf.len() >= 8 && !channel.closed {
+            let size = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+            if !(8..=MAX_MESSAGE).contains(&size) {
+                replies.push(error(BAD_TCP_MESSAGE_TOO_LARGE, "消息长度无效"));
+                channel.closed = true;
+                break;
+            }
+            if buf.len() < size {
+                break;
+            }
+            let message = buf.drain(..size).collect::<Vec<_>>();
+            let body = &message[8..];
+            let reply = match &message[..4] {
+                b"HELF" => self.hello(channel, body).map(|reply| vec![reply]),
+                b"OPNF" => self.open(channel, body).map(|reply| vec![reply]),
+                b"MSGF" => self.message(channel, body),
+                b"CLOF" => {
+                    channel.closed = true;
+                    Ok(vec![])
+                }
+                // 客户端中止的分块
+                [_, _, _, b'A'] => Ok(vec![]),
+                _ => Err(BAD_TCP_MESSAGE_TYPE_INVALID),
+            };
+            match reply {
+                Ok(reply) => replies.extend(reply),
+                Err(status) => {
+                    replies.push(error(status, "消息无效"));
+                    channel.closed = true;
+                }
+            }
+        }
+        replies
+    }
+
+    /// 连接请求，协商缓冲区大小
+    fn hello(&self, channel: &mut Channel, body: &[u8]) -> Decoded<Vec<u8>> {
+        let mut r = Reader::new(body);
+        r.u32()?;
+        let receive = r.u32()?;
+        let send = r.u32()?;
+        r.u32()?;
+        r.u32()?;
+        channel.endpoint = r.string()?.unwrap_or_default();
+        let mut ack = Writer::default();
+        ack.u32(0)
+            .u32(BUFFER_SIZE.min(send))
+            .u32(BUFFER_SIZE.min(receive))
+            .u32(MAX_MESSAGE as u32)
+            .u32(0);
+        Ok(chunk(b"ACK", ack.as_slice()))
+    }
+
+    /// 打开或续期安全通道
+    fn open(&self, channel: &mut Channel, body: &[u8]) -> Decoded<Vec<u8>> {
+        let mut r = Reader::new(body);
+        r.u32()?;
+        let policy = r.string()?;
+        r.bytes()?;
+        r.bytes()?;
+        r.u32()?;
+        let request = r.u32()?;
+        r.node_id()?;
+        let handle = r.request_header()?;
+        r.u32()?;
+        let renew = r.u32()? == 1;
+        r.u32()?;
+        r.bytes()?;
+        let lifetime = r.u32()?;
+        if policy.as_deref() != Some(POLICY_NONE) {
+            return Err(BAD_SECURITY_POLICY_REJECTED);
+        }
+        if renew {
+            channel.token += 1;
+        }
+        channel.sequence += 1;
+        let mut reply = Writer::default();
+        reply
+            .u32(channel.id)
+            .string(POLICY_NONE)
+            .null()
+            .null()
+            .u32(channel.sequence)
+            .u32(request)
+            .node_id(&NodeId::ns0(OPEN_SECURE_CHANNEL + 3))
+            .response_header(handle, GOOD)
+            .u32(0)
+            .u32(channel.id)
+            .u32(channel.token)
+            .i64(date_time(SystemTime::now()))
+            .u32(if lifetime == 0 { 3_600_000 } else { lifetime })
+            .bytes(&[]);
+        Ok(chunk(b"OPN", reply.as_slice()))
+    }
+
+    /// 服务请求
+    fn message(&self, channel: &mut Channel, body: &[u8]) -> Decoded<Vec<Vec<u8>>> {
+        let mut r = Reader::new(body);
+        // 安全通道、令牌、序号
+        r.u32()?;
+        r.u32()?;
+        r.u32()?;
+        let request = r.u32()?;
+        let kind = r.node_id()?;
+        let handle = match r.request_header() {
+            Ok(handle) => handle,
+            Err(status) => return Ok(vec![channel.reply(request, SERVICE_FAULT, 0, status, &[])]),
+        };
+        let NodeId::Numeric(0, kind) = kind else {
+            let fault = channel.reply(request, SERVICE_FAULT, handle, BAD_SERVICE_UNSUPPORTED, &[]);
+            return Ok(vec![fault]);
+        };
+        let mut replies = vec![];
+        if kind == PUBLISH {
+            replies.extend(self.queue_publish(channel, request, handle, &mut r));
+        } else {
+            let result = match kind {
+                FIND_SERVERS => self.find_servers(channel, &mut r),
+                GET_ENDPOINTS => self.get_endpoints(channel, &mut r),
+                CREATE_SESSION => self.create_session(channel),
+                ACTIVATE_SESSION => self.activate_session(),
+                CLOSE_SESSION => {
+                    channel.subscriptions.clear();
+                    Ok(Writer::default())
+                }
+                BROWSE => self.browse(&mut r),
+                READ => self.read(&mut r),
+                CREATE_SUBSCRIPTION => self.create_subscription(channel, &mut r),
+                MODIFY_SUBSCRIPTION => self.modify_subscription(channel, &mut r),
+                SET_PUBLISHING_MODE => self.set_publishing_mode(channel, &mut r),
+                DELETE_SUBSCRIPTIONS => self.delete_subscriptions(channel, &mut r),
+                CREATE_MONITORED_ITEMS => self.create_monitored_items(channel, &mut r),
+                SET_MONITORING_MODE => self.set_monitoring_mode(channel, &mut r),
+                DELETE_MONITORED_ITEMS => self.delete_monitored_items(channel, &mut r),
+                REPUBLISH => Err(BAD_MESSAGE_NOT_AVAILABLE),
+                _ => Err(BAD_SERVICE_UNSUPPORTED),
+            };
+            replies.push(match result {
+                Ok(body) => channel.reply(request, kind + 3, handle, GOOD, body.as_slice()),
+                Err(status) => channel.reply(request, SERVICE_FAULT, handle, status, &[]),
+            });
+        }
+        // 没有订阅时，等待中的发布请求不会再有应答
+        if channel.subscriptions.is_empty() {
+            while let Some(publish) = channel.publishes.pop_front() {
+                let fault = channel.reply(
+                    publish.request,
+                    SERVICE_FAULT,
+                    publish.handle,
+                    BAD_NO_SUBSCRIPTION,
+                    &[],
+                );
+                replies.push(fault);
+            }
+        }
+        Ok(replies)
+    }
+
+    /// 服务的应用描述
+    fn application(&self, w: &mut Writer, url: &str) {
+        w.string(&self.namespace)
+            .string(NAMESPACE)
+            .localized_text("kim_scanner")
+            // 服务端
+            .u32(0)
+            .null()
+            .null()
+            .count(1)
+            .string(url);
+    }
+
+    /// 服务的端点描述，只有不加密、匿名登录一个端点
+    fn endpoint(&self, w: &mut Writer, url: &str) {
+        w.string(url);
+        self.application(w, url);
+        w.null()
+            // 不加密
+            .u32(1)
+            .string(POLICY_NONE)
+            .count(1)
+            .string("anonymous")
+            .u32(0)
+            .null()
+            .null()
+            .null()
+            .string(TRANSPORT_PROFILE)
+            .u8(0);
+    }
+
+    fn find_servers(&self, channel: &Channel, r: &mut Reader) -> Decoded<Writer> {
+        let url = r.string()?.unwrap_or_else(|| channel.endpoint.clone());
+        let mut w = Writer::default();
+        w.count(1);
+        self.application(&mut w, &url);
+        Ok(w)
+    }
+
+    fn get_endpoints(&self, channel: &Channel, r: &mut Reader) -> Decoded<Writer> {
+        let url = r.string()?.unwrap_or_else(|| channel.endpoint.clone());
+        let mut w = Writer::default();
+        w.count(1);
+        self.endpoint(&mut w, &url);
+        Ok(w)
+    }
+
+    fn create_session(&self, channel: &Channel) -> Decoded<Writer> {
+        let mut w = Writer::default();
+        w.node_id(&NodeId::Numeric(1, self.next_id()))
+            .node_id(&NodeId::Numeric(1, self.next_id()))
+            .f64(SESSION_TIMEOUT)
+            .bytes(&nonce())
+            .null()
+            .count(1);
+        self.endpoint(&mut w, &channel.endpoint);
+        w.count(0).null().null().u32(0);
+        Ok(w)
+    }
+
+    fn activate_session(&self) -> Decoded<Writer> {
+        let mut w = Writer::default();
+        w.bytes(&nonce()).count(0).count(0);
+        Ok(w)
+    }
+
+    fn browse(&self, r: &mut Reader) -> Decoded<Writer> {
+        // 视图
+        r.node_id()?;
+        r.i64()?;
+        r.u32()?;
+        r.u32()?;
+        let nodes = r.array(|r| {
+            let id = r.node_id()?;
+            let direction = r.u32()?;
+            let reference = r.node_id()?;
+            let subtypes = r.bool()?;
+            let classes = r.u32()?;
+            r.u32()?;
+            Ok((id, direction, reference, subtypes, classes))
+        })?;
+        if nodes.is_empty() {
+            return Err(BAD_NOTHING_TO_DO);
+        }
+        let mut w = Writer::default();
+        w.count(nodes.len());
+        for (id, direction, reference, subtypes, classes) in nodes {
+            let Some(node) = self.node(&id) else {
+                w.u32(BAD_NODE_ID_UNKNOWN).null().count(0);
+                continue;
+            };
+            // 只有正向引用
+            let children = if direction == INVERSE {
+                vec![]
+            } else {
+                self.children(&node)
+                    .into_iter()
+                    .filter(|(kind, child)| {
+                        matches_reference(&reference, subtypes, *kind)
+                            && (classes == 0 || classes & child.class() != 0)
+                    })
+                    .collect()
+            };
+            w.u32(GOOD).null().count(children.len());
+            for (kind, child) in children {
+                let (ns, name) = child.browse_name();
+                w.node_id(&NodeId::ns0(kind))
+                    .bool(true)
+                    .node_id(&child.id())
+                    .qualified_name(ns, &name)
+                    .localized_text(&name)
+                    .u32(child.class())
+                    .node_id(&NodeId::ns0(child.type_definition()));
+            }
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn read(&self, r: &mut Reader) -> Decoded<Writer> {
+        r.f64()?;
+        let timestamps = r.u32()?;
+        let nodes = r.array(|r| {
+            let id = r.node_id()?;
+            let attribute = r.u32()?;
+            r.string()?;
+            r.qualified_name()?;
+            Ok((id, attribute))
+        })?;
+        if nodes.is_empty() {
+            return Err(BAD_NOTHING_TO_DO);
+        }
+        let mut w = Writer::default();
+        w.count(nodes.len());
+        for (id, attribute) in nodes {
+            let value = self.read_value(&id, attribute);
+            let value = if attribute == VALUE {
+                stamp(value, timestamps)
+            } else {
+                value
+            };
+            w.data_value(&value);
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn create_subscription(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let interval = revise(r.f64()?);
+        let lifetime = r.u32()?;
+        let keepalive = r.u32()?.max(1);
+        r.u32()?;
+        let enabled = r.bool()?;
+        r.u8()?;
+        let id = self.next_id();
+        channel.subscriptions.push(Subscription {
+            id,
+            interval,
+            keepalive,
+            enabled,
+            elapsed: Duration::ZERO,
+            idle: 0,
+            sequence: 0,
+            items: vec![],
+        });
+        let mut w = Writer::default();
+        w.u32(id)
+            .f64(interval.as_millis() as f64)
+            .u32(lifetime.max(keepalive.saturating_mul(3)))
+            .u32(keepalive);
+        Ok(w)
+    }
+
+    fn modify_subscription(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let subscription = channel.subscription(r.u32()?)?;
+        subscription.interval = revise(r.f64()?);
+        let lifetime = r.u32()?;
+        subscription.keepalive = r.u32()?.max(1);
+        let mut w = Writer::default();
+        w.f64(subscription.interval.as_millis() as f64)
+            .u32(lifetime.max(subscription.keepalive.saturating_mul(3)))
+            .u32(subscription.keepalive);
+        Ok(w)
+    }
+
+    fn set_publishing_mode(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let enabled = r.bool()?;
+        let ids = r.array(Reader::u32)?;
+        let mut w = Writer::default();
+        w.count(ids.len());
+        for id in ids {
+            match channel.subscription(id) {
+                Ok(subscription) => {
+                    subscription.enabled = enabled;
+                    w.u32(GOOD)
+                }
+                Err(status) => w.u32(status),
+            };
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn delete_subscriptions(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let ids = r.array(Reader::u32)?;
+        let mut w = Writer::default();
+        w.count(ids.len());
+        for id in ids {
+            let count = channel.subscriptions.len();
+            channel
+                .subscriptions
+                .retain(|subscription| subscription.id != id);
+            w.u32(if channel.subscriptions.len() < count {
+                GOOD
+            } else {
+                BAD_SUBSCRIPTION_ID_INVALID
+            });
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn create_monitored_items(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let id = r.u32()?;
+        let timestamps = r.u32()?;
+        let items = r.array(|r| {
+            let node = r.node_id()?;
+            let attribute = r.u32()?;
+            r.string()?;
+            r.qualified_name()?;
+            let mode = r.u32()?;
+            let handle = r.u32()?;
+            r.f64()?;
+            r.extension_object()?;
+            r.u32()?;
+            r.bool()?;
+            Ok((node, attribute, mode, handle))
+        })?;
+        let subscription = channel.subscription(id)?;
+        if items.is_empty() {
+            return Err(BAD_NOTHING_TO_DO);
+        }
+        let mut w = Writer::default();
+        w.count(items.len());
+        for (node, attribute, mode, handle) in items {
+            let status = self.read_value(&node, attribute).status;
+            if matches!(status, BAD_NODE_ID_UNKNOWN | BAD_ATTRIBUTE_ID_INVALID) {
+                w.u32(status).u32(0).f64(0.0).u32(0).null_extension_object();
+                continue;
+            }
+            let item = self.next_id();
+            subscription.items.push(Item {
+                id: item,
+                handle,
+                node,
+                attribute,
+                timestamps,
+                reporting: mode == REPORTING,
+                last: None,
+            });
+            w.u32(GOOD)
+                .u32(item)
+                .f64(subscription.interval.as_millis() as f64)
+                .u32(1)
+                .null_extension_object();
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn set_monitoring_mode(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let subscription = channel.subscription(r.u32()?)?;
+        let reporting = r.u32()? == REPORTING;
+        let ids = r.array(Reader::u32)?;
+        let mut w = Writer::default();
+        w.count(ids.len());
+        for id in ids {
+            match subscription.items.iter_mut().find(|item| item.id == id) {
+                Some(item) => {
+                    item.reporting = reporting;
+                    w.u32(GOOD)
+                }
+                None => w.u32(BAD_MONITORED_ITEM_ID_INVALID),
+            };
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    fn delete_monitored_items(&self, channel: &mut Channel, r: &mut Reader) -> Decoded<Writer> {
+        let subscription = channel.subscription(r.u32()?)?;
+        let ids = r.array(Reader::u32)?;
+        let mut w = Writer::default();
+        w.count(ids.len());
+        for id in ids {
+            let count = subscription.items.len();
+            subscription.items.retain(|item| item.id != id);
+            w.u32(if subscription.items.len() < count {
+                GOOD
+            } else {
+                BAD_MONITORED_ITEM_ID_INVALID
+            });
+        }
+        w.count(0);
+        Ok(w)
+    }
+
+    /// 发布请求在有数据变化或需要保活时才应答
+    fn queue_publish(
+        &self,
+        channel: &mut Channel,
+        request: u32,
+        handle: u32,
+        r: &mut Reader,
+    ) -> Option<Vec<u8>> {
+        let status = match r.array(|r| {
+            r.u32()?;
+            r.u32()
+        }) {
+            Ok(_) if channel.subscriptions.is_empty() => BAD_NO_SUBSCRIPTION,
+            Ok(acks) => {
+                channel.publishes.push_back(Publish {
+                    request,
+                    handle,
+                    acks: acks.len(),
+                });
+                return None;
+            }
+            Err(status) => status,
+        };
+        Some(channel.reply(request, SERVICE_FAULT, handle, status, &[]))
+    }
+
+    /// 检查到期的订阅，用等待中的发布请求发送数据变化通知或保活消息
+    fn publish(&self, channel: &mut Channel) -> Vec<Vec<u8>> {
+        let mut available = channel.publishes.len();
+        let mut messages = vec![];
+        for subscription in &mut channel.subscriptions {
+            subscription.elapsed += TICK;
+            if subscription.elapsed < subscription.interval || available == 0 {
+                continue;
+            }
+            subscription.elapsed = Duration::ZERO;
+            let mut changes = vec![];
+            if subscription.enabled {
+                for item in subscription.items.iter_mut().filter(|item| item.reporting) {
+                    let value = self.read_value(&item.node, item.attribute);
+                    if item.last.as_ref() != Some(&value) {
+                        item.last = Some(value.clone());
+                        changes.push((item.handle, stamp(value, item.timestamps)));
+                    }
+                }
+            }
+            if !changes.is_empty() {
+                subscription.sequence += 1;
+                subscription.idle = 0;
+                let mut notification = Writer::default();
+                notification.count(changes.len());
+                for (handle, value) in &changes {
+                    notification.u32(*handle).data_value(value);
+                }
+                notification.count(0);
+                messages.push((subscription.id, subscription.sequence, Some(notification)));
+            } else {
+                subscription.idle += 1;
+                if subscription.idle < subscription.keepalive {
+                    continue;
+                }
+                subscription.idle = 0;
+                // 保活消息的序号是下一个通知的序号
+                messages.push((subscription.id, subscription.sequence + 1, None));
+            }
+            available -= 1;
+        }
+        let mut replies = vec![];
+        for (id, sequence, notification) in messages {
+            let Some(publish) = channel.publishes.pop_front() else {
+                break;
+            };
+            let mut w = Writer::default();
+            w.u32(id)
+                .count(0)
+                .bool(false)
+                .u32(sequence)
+                .i64(date_time(SystemTime::now()));
+            match notification {
+                Some(notification) => {
+                    w.count(1)
+                        .extension_object(DATA_CHANGE_NOTIFICATION, notification.as_slice());
+                }
+                None => {
+                    w.count(0);
+                }
+            }
+            w.count(publish.acks);
+            for _ in 0..publish.acks {
+                w.u32(GOOD);
+            }
+            w.count(0);
+            replies.push(channel.reply(
+                publish.request,
+                PUBLISH + 3,
+                publish.handle,
+                GOOD,
+                w.as_slice(),
+            ));
+        }
+        replies
+    }
+
+    /// 查找节点
+    fn node(&self, id: &NodeId) -> Option<Node> {
+        let node = match id {
+            NodeId::Numeric(0, ROOT) => Node::Root,
+            NodeId::Numeric(0, OBJECTS) => Node::Objects,
+            NodeId::Numeric(0, SERVER) => Node::Server,
+            NodeId::Numeric(0, SERVER_ARRAY) => Node::ServerArray,
+            NodeId::Numeric(0, NAMESPACE_ARRAY) => Node::NamespaceArray,
+            NodeId::Numeric(0, SERVER_STATUS) => Node::ServerStatus,
+            NodeId::Numeric(0, CURRENT_TIME) => Node::CurrentTime,
+            NodeId::Numeric(0, STATE) => Node::State,
+            NodeId::String(1, name) if name == "Scanners" => Node::Scanners,
+            NodeId::String(1, name) if self.manager.get(name).is_some() => {
+                Node::Scanner(name.clone())
+            }
+            NodeId::String(1, name) => {
+                let (id, field) = name.rsplit_once('.')?;
+                let field = FIELDS
+                    .into_iter()
+                    .find(|candidate| candidate.name() == field)?;
+                self.manager.get(id)?;
+                Node::Variable(id.into(), field)
+            }
+            _ => return None,
+        };
+        Some(node)
+    }
+
+    /// 节点的子节点和引用类型
+    fn children(&self, node: &Node) -> Vec<(u32, Node)> {
+        match node {
+            Node::Root => vec![(ORGANIZES, Node::Objects)],
+            Node::Objects => vec![(ORGANIZES, Node::Server), (ORGANIZES, Node::Scanners)],
+            Node::Server => vec![
+                (HAS_PROPERTY, Node::ServerArray),
+                (HAS_PROPERTY, Node::NamespaceArray),
+                (HAS_COMPONENT, Node::ServerStatus),
+            ],
+            Node::ServerStatus => vec![
+                (HAS_COMPONENT, Node::CurrentTime),
+                (HAS_COMPONENT, Node::State),
+            ],
+            Node::Scanners => {
+                let mut ids = self.manager.ids();
+                ids.sort();
+                ids.into_iter()
+                    .map(|id| (ORGANIZES, Node::Scanner(id)))
+                    .collect()
+            }
+            Node::Scanner(id) => FIELDS
+                .into_iter()
+                .map(|field| (HAS_COMPONENT, Node::Variable(id.clone(), field)))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// 读取节点的属性，不带时间戳
+    fn read_value(&self, id: &NodeId, attribute: u32) -> DataValue {
+        let Some(node) = self.node(id) else {
+            return DataValue::status(BAD_NODE_ID_UNKNOWN);
+        };
+        let (ns, name) = node.browse_name();
+        let variable = node.data_type();
+        let value = match (attribute, variable) {
+            (NODE_ID, _) => Variant::NodeId(node.id()),
+            (NODE_CLASS, _) => Variant::Int32(node.class() as i32),
+            (BROWSE_NAME, _) => Variant::QualifiedName(ns, name),
+            (DISPLAY_NAME, _) => Variant::LocalizedText(name),
+            (DESCRIPTION, _) => Variant::LocalizedText(String::new()),
+            (WRITE_MASK | USER_WRITE_MASK, _) => Variant::UInt32(0),
+            (EVENT_NOTIFIER, None) => Variant::Byte(0),
+            (VALUE, Some(_)) => self.value(&node),
+            (DATA_TYPE, Some((data_type, _))) => Variant::NodeId(NodeId::ns0(data_type)),
+            (VALUE_RANK, Some((_, rank))) => Variant::Int32(rank),
+            (ARRAY_DIMENSIONS, Some(_)) => Variant::Empty,
+            // 只读
+            (ACCESS_LEVEL | USER_ACCESS_LEVEL, Some(_)) => Variant::Byte(1),
+            (MINIMUM_SAMPLING_INTERVAL, Some(_)) => Variant::Double(0.0),
+            (HISTORIZING, Some(_)) => Variant::Boolean(false),
+            _ => return DataValue::status(BAD_ATTRIBUTE_ID_INVALID),
+        };
+        DataValue {
+            value: Some(value),
+            status: GOOD,
+            source_time: None,
+            server_time: None,
+        }
+    }
+
+    /// 变量的值
+    fn value(&self, node: &Node) -> Variant {
+        let now = date_time(SystemTime::now());
+        match node {
+            Node::ServerArray => Variant::StringArray(vec![self.namespace.clone()]),
+            Node::NamespaceArray => {
+                Variant::StringArray(vec![UA_NAMESPACE.into(), self.namespace.clone()])
+            }
+            Node::ServerStatus => {
+                let mut status = Writer::default();
+                status
+                    .i64(date_time(self.started))
+                    .i64(now)
+                    // 运行中
+                    .i32(0)
+                    .string(NAMESPACE)
+                    .string("kim-lcs")
+                    .string("kim_scanner")
+                    .string(env!("CARGO_PKG_VERSION"))
+                    .string(env!("CARGO_PKG_VERSION"))
+                    .i64(date_time(self.started))
+                    .u32(0)
+                    .localized_text("");
+                Variant::ExtensionObject(SERVER_STATUS_DATA_TYPE, status.into_inner())
+            }
+            Node::CurrentTime => Variant::DateTime(now),
+            Node::State => Variant::Int32(0),
+            Node::Variable(id, field) => {
+                let stats = self.manager.stats(id).unwrap_or_default();
+                match field {
+                    Field::Barcode => Variant::String(
+                        stats
+                            .last_barcode()
+                            .map(|barcode| barcode.text().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                    Field::Connected => Variant::Boolean(stats.connected().unwrap_or(false)),
+                    Field::Running => Variant::Boolean(
+                        self.manager
+                            .get(id)
+                            .is_some_and(|scanner| scanner.is_running()),
+                    ),
+                    Field::Scans => Variant::UInt64(stats.scans()),
+                    Field::NoReads => Variant::UInt64(stats.no_reads()),
+                    Field::Mismatches => Variant::UInt64(stats.mismatches()),
+                    Field::Errors => Variant::UInt64(stats.errors()),
+                    Field::Disconnects => Variant::UInt64(stats.disconnects()),
+                    Field::LastScan => {
+                        Variant::DateTime(stats.last_scan().map(date_time).unwrap_or_default())
+                    }
+                }
+            }
+            _ => Variant::Empty,
+        }
+    }
+}
+
+/// 修正发布周期，不小于检查周期
+fn revise(interval: f64) -> Duration {
+    if interval.is_finite() && interval > 0.0 {
+        Duration::from_millis(interval.min(3_600_000.0) as u64).max(TICK)
+    } else {
+        Duration::from_secs(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::codec::{NodeId, Reader, Writer, BAD_NODE_ID_UNKNOWN};
+    use super::{chunk, OpcUaServer, POLICY_NONE};
+    use crate::prelude::*;
+
+    /// 最简单的客户端，只检查本服务的应答
+    struct Client {
+        stream: TcpStream,
+        channel: u32,
+        request: u32,
+    }
+
+    impl Client {
+        async fn send(&mut self, kind: &[u8; 3], body: &[u8]) -> Vec<u8> {
+            self.stream.write_all(&chunk(kind, body)).await.unwrap();
+            let mut header = [0u8; 8];
+            self.stream.read_exact(&mut header).await.unwrap();
+            let size = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+            let mut body = vec![0u8; size - 8];
+            self.stream.read_exact(&mut body).await.unwrap();
+            assert_ne!(&header[..3], b"ERR", "{:?}", body);
+            body
+        }
+
+        fn request(&mut self, service: u32) -> Writer {
+            self.request += 1;
+            let mut w = Writer::default();
+            w.u32(self.channel)
+                .u32(1)
+                .u32(self.request)
+                .u32(self.request)
+                .node_id(&NodeId::ns0(service))
+                .node_id(&NodeId::ns0(0))
+                .i64(0)
+                .u32(self.request)
+                .u32(0)
+                .null()
+                .u32(0)
+                .null_extension_object();
+            w
+        }
+
+        /// 调用服务，返回应答类型和应答头之后的内容
+        async fn call(&mut self, request: Writer) -> (u32, Vec<u8>) {
+            let reply = self.send(b"MSG", request.as_slice()).await;
+            self.response(reply)
+        }
+
+        fn response(&self, reply: Vec<u8>) -> (u32, Vec<u8>) {
+            let mut r = Reader::new(&reply);
+            assert_eq!(r.u32().unwrap(), self.channel);
+            r.u32().unwrap();
+            r.u32().unwrap();
+            r.u32().unwrap();
+            let NodeId::Numeric(0, kind) = r.node_id().unwrap() else {
+                panic!("应答类型无效");
+            };
+            r.i64().unwrap();
+            r.u32().unwrap();
+            assert_eq!(r.u32().unwrap(), 0, "服务状态");
+            r.u8().unwrap();
+            r.array(Reader::string).unwrap();
+            r.extension_object().unwrap();
+            let rest = reply.len() - r.remaining();
+            (kind, reply[rest..].to_vec())
+        }
+
+        /// 读取一个订阅通知
+        async fn publish(&mut self) -> (u32, u64) {
+            let mut request = self.request(826);
+            request.count(0);
+            let (kind, body) = self.call(request).await;
+            assert_eq!(kind, 829);
+            let mut r = Reader::new(&body);
+            r.u32().unwrap();
+            r.array(Reader::u32).unwrap();
+            r.bool().unwrap();
+            r.u32().unwrap();
+            r.i64().unwrap();
+            assert_eq!(r.i32().unwrap(), 1);
+            assert_eq!(r.node_id().unwrap(), NodeId::ns0(811));
+            r.u8().unwrap();
+            r.i32().unwrap();
+            assert_eq!(r.i32().unwrap(), 1);
+            let handle = r.u32().unwrap();
+            // 值、源时间戳、服务器时间戳
+            assert_eq!(r.u8().unwrap(), 0x0D);
+            assert_eq!(r.u8().unwrap(), 9);
+            let value = u64::from_le_bytes(r.i64().unwrap().to_le_bytes());
+            (handle, value)
+        }
+    }
+
+    #[tokio::test]
+    async fn opcua() {
+        let mock = MockConnector::new();
+        let manager = Arc::new(ScannerManager::new().with_scanner("A", Scanner::new(mock.clone())));
+        manager.start().await.unwrap();
+        manager.recv().await.unwrap();
+        mock.push("SN0001");
+        manager.recv().await.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(OpcUaServer::new(Arc::clone(&manager)).run(listener));
+        let mut client = Client {
+            stream: TcpStream::connect(addr).await.unwrap(),
+            channel: 0,
+            request: 0,
+        };
+
+        let mut hello = Writer::default();
+        hello
+            .u32(0)
+            .u32(65536)
+            .u32(65536)
+            .u32(0)
+            .u32(0)
+            .string(&format!("opc.tcp://{}", addr));
+        client.send(b"HEL", hello.as_slice()).await;
+        let mut open = Writer::default();
+        open.u32(0)
+            .string(POLICY_NONE)
+            .null()
+            .null()
+            .u32(1)
+            .u32(1)
+            .node_id(&NodeId::ns0(446))
+            .node_id(&NodeId::ns0(0))
+            .i64(0)
+            .u32(1)
+            .u32(0)
+            .null()
+            .u32(0)
+            .null_extension_object()
+            .u32(0)
+            .u32(0)
+            .u32(1)
+            .bytes(&[])
+            .u32(600000);
+        let reply = client.send(b"OPN", open.as_slice()).await;
+        client.channel = Reader::new(&reply).u32().unwrap();
+        let request = client.request(461);
+        assert_eq!(client.call(request).await.0, 464);
+        let request = client.request(467);
+        assert_eq!(client.call(request).await.0, 470);
+
+        // 浏览扫码枪文件夹
+        let mut request = client.request(527);
+        request
+            .node_id(&NodeId::ns0(0))
+            .i64(0)
+            .u32(0)
+            .u32(0)
+            .count(1)
+            .node_id(&NodeId::String(1, "Scanners".into()))
+            .u32(0)
+            .node_id(&NodeId::ns0(33))
+            .bool(true)
+            .u32(0)
+            .u32(63);
+        let (kind, body) = client.call(request).await;
+        assert_eq!(kind, 530);
+        let mut r = Reader::new(&body);
+        assert_eq!(r.i32().unwrap(), 1);
+        assert_eq!(r.u32().unwrap(), 0);
+        r.bytes().unwrap();
+        assert_eq!(r.i32().unwrap(), 1);
+        assert_eq!(r.node_id().unwrap(), NodeId::ns0(35));
+        assert!(r.bool().unwrap());
+        assert_eq!(r.node_id().unwrap(), NodeId::String(1, "A".into()));
+
+        // 读取条码和不存在的节点
+        let mut request = client.request(631);
+        request.f64(0.0).u32(3).count(2);
+        for id in ["A.Barcode", "B.Barcode"] {
+            request
+                .node_id(&NodeId::String(1, id.into()))
+                .u32(13)
+                .null()
+                .u16(0)
+                .null();
+        }
+        let (kind, body) = client.call(request).await;
+        assert_eq!(kind, 634);
+        let mut r = Reader::new(&body);
+        assert_eq!(r.i32().unwrap(), 2);
+        assert_eq!(r.u8().unwrap(), 0x01);
+        assert_eq!(r.u8().unwrap(), 12);
+        assert_eq!(r.string().unwrap().as_deref(), Some("SN0001"));
+        assert_eq!(r.u8().unwrap(), 0x02);
+        assert_eq!(r.u32().unwrap(), BAD_NODE_ID_UNKNOWN);
+
+        // 订阅条码数量
+        let mut request = client.request(787);
+        request.f64(100.0).u32(100).u32(10).u32(0).bool(true).u8(0);
+        let (kind, body) = client.call(request).await;
+        assert_eq!(kind, 790);
+        let subscription = Reader::new(&body).u32().unwrap();
+        let mut request = client.request(751);
+        request
+            .u32(subscription)
+            .u32(2)
+            .count(1)
+            .node_id(&NodeId::String(1, "A.Scans".into()))
+            .u32(13)
+            .null()
+            .u16(0)
+            .null()
+            .u32(2)
+            .u32(7)
+            .f64(100.0)
+            .null_extension_object()
+            .u32(1)
+            .bool(true);
+        let (kind, body) = client.call(request).await;
+        assert_eq!(kind, 754);
+        let mut r = Reader::new(&body);
+        assert_eq!(r.i32().unwrap(), 1);
+        assert_eq!(r.u32().unwrap(), 0);
+        assert_eq!(client.publish().await, (7, 1));
+        mock.push("SN0002");
+        manager.recv().await.unwrap();
+        assert_eq!(client.publish().await, (7, 2));
+    }
+}
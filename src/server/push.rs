@@ -0,0 +1,182 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{event, Level};
+
+use crate::{ScannerError, ScannerManager};
+
+/// WebSocket 推送服务，把扫码事件以 JSON 文本消息实时推送给 HMI、网页看板，不需要轮询
+///
+/// 每条消息是一个[`crate::FleetEvent`]，格式与[`crate::FleetEvent`]的 JSON 序列化相同。
+/// 连接地址可以带`scanners`参数只接收部分扫码枪的事件，例如`ws://127.0.0.1:9002/?scanners=line1-in,line1-out`。
+/// 客户端发送的消息被忽略；客户端接收过慢时丢弃最早的事件
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = Arc::new(
+///     ScannerManager::new()
+///         .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004))),
+/// );
+/// manager.start().await?;
+/// PushServer::new(Arc::clone(&manager)).serve(([0, 0, 0, 0], 9002)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct PushServer {
+    manager: Arc<ScannerManager>,
+}
+
+impl PushServer {
+    /// 创建 WebSocket 推送服务
+    pub fn new(manager: Arc<ScannerManager>) -> Self {
+        PushServer { manager }
+    }
+
+    /// 监听指定地址并提供服务，直到出错才返回，需要在 tokio 运行时中调用
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        let addr = addr.into();
+        let listener = TcpListener::bind(addr).await.map_err(ScannerError::Io)?;
+        event!(Level::INFO, server.addr = %addr, "WebSocket推送服务启动成功");
+        self.run(listener).await
+    }
+
+    /// 接受连接，每个连接一个推送任务
+    async fn run(self, listener: TcpListener) -> Result<(), ScannerError> {
+        loop {
+            let (client, peer) = listener.accept().await.map_err(ScannerError::Io)?;
+            tokio::spawn(self.clone().push(client, peer));
+        }
+    }
+
+    /// 给一个客户端推送事件，直到客户端断开
+    async fn push(self, client: TcpStream, peer: SocketAddr) {
+        let mut filter = vec![];
+        // 握手时读取连接地址中的查询参数
+        #[allow(clippy::result_large_err)]
+        let callback = |request: &Request, response: Response| {
+            filter = scanners(request.uri().query());
+            Ok(response)
+        };
+        let stream = match tokio_tungstenite::accept_hdr_async(client, callback).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                event!(
+                    Level::ERROR,
+                    client.addr = %peer,
+                    error = %err,
+                    error.kind = "comm",
+                    "WebSocket握手失败",
+                );
+                return;
+            }
+        };
+        event!(Level::INFO, client.addr = %peer, "看板已连接");
+        let mut events = self.manager.subscribe();
+        let (mut tx, mut rx) = stream.split();
+        loop {
+            let ev = tokio::select! {
+                ev = events.recv() => ev,
+                msg = rx.next() => match msg {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                },
+            };
+            let ev = match ev {
+                Ok(ev) => ev,
+                Err(RecvError::Lagged(n)) => {
+                    event!(Level::WARN, client.addr = %peer, dropped = n, "看板接收过慢,丢弃事件");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            if !filter.is_empty() && !filter.iter().any(|id| id == ev.id()) {
+                continue;
+            }
+            let text = match serde_json::to_string(&ev) {
+                Ok(text) => text,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        client.addr = %peer,
+                        error = %err,
+                        error.kind = "parse",
+                        "事件序列化失败",
+                    );
+                    continue;
+                }
+            };
+            if tx.send(Message::text(text)).await.is_err() {
+                break;
+            }
+        }
+        event!(Level::INFO, client.addr = %peer, "看板已断开");
+    }
+}
+
+/// 从查询参数中解析`scanners=a,b`
+fn scanners(query: Option<&str>) -> Vec<String> {
+    query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter_map(|pair| pair.strip_prefix("scanners="))
+        .flat_map(|ids| ids.split(','))
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+    use tokio::net::TcpListener;
+
+    use super::{scanners, PushServer};
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn push() {
+        assert_eq!(scanners(Some("scanners=A,B&x=1")), vec!["A", "B"]);
+        assert!(scanners(None).is_empty());
+
+        let (a, b) = (MockConnector::new(), MockConnector::new());
+        let manager = Arc::new(
+            ScannerManager::new()
+                .with_scanner("A", Scanner::new(a.clone()))
+                .with_scanner("B", Scanner::new(b.clone())),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(PushServer::new(Arc::clone(&manager)).run(listener));
+        let (mut client, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/?scanners=B", addr))
+                .await
+                .unwrap();
+        // 等待服务端订阅
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        manager.start().await.unwrap();
+        a.push("SN0001");
+        b.push("SN0002");
+        let mut barcodes = vec![];
+        while barcodes.is_empty() {
+            let text = client.next().await.unwrap().unwrap().into_text().unwrap();
+            let ev: FleetEvent = serde_json::from_str(&text).unwrap();
+            assert_eq!(ev.id(), "B");
+            if let Some(barcode) = ev.event().barcode() {
+                barcodes.push(barcode.text().into_owned());
+            }
+        }
+        assert_eq!(barcodes, vec!["SN0002"]);
+    }
+}
@@ -0,0 +1,272 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, Scanner, ScannerError, ScannerManager};
+
+/// 最近条码的默认返回数量
+const LIMIT: usize = 20;
+
+/// 内置 HTTP 控制接口，为集成方提供查询扫码枪状态、最近的条码和发送指令的本地接口
+///
+/// | 方法 | 路径 | 说明 |
+/// |------|------|------|
+/// | GET | `/scanners` | 所有扫码枪的编号、连接、标签、运行状态和统计 |
+/// | GET | `/scanners/{id}` | 一台扫码枪的状态和统计 |
+/// | GET | `/scans?limit=20` | 最近的条码事件，新的在前，最多100个 |
+/// | POST | `/scanners/{id}/commands` | 发送指令，`{"command":"LON\r"}`发送原始指令，`{"name":"on"}`发送[`crate::CommandTemplates`]中的指令 |
+///
+/// 统计来自[`ScannerManager`]转发的事件，路由到其它输出的条码同样计入。
+/// 出错时按[`ScannerError`]的 JSON 格式返回，参数错误为400，扫码枪不存在为404，
+/// 应答超时为504，其它错误为502
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let manager = Arc::new(
+///     ScannerManager::new()
+///         .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004))),
+/// );
+/// manager.start().await?;
+/// RestApi::new(Arc::clone(&manager)).serve(([127, 0, 0, 1], 8080)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RestApi {
+    manager: Arc<ScannerManager>,
+}
+
+impl RestApi {
+    /// 创建 HTTP 控制接口
+    pub fn new(manager: Arc<ScannerManager>) -> Self {
+        RestApi { manager }
+    }
+
+    /// 获取路由，可以合并到应用已有的 axum 服务中
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/scanners", get(scanners))
+            .route("/scanners/{id}", get(scanner))
+            .route("/scanners/{id}/commands", post(command))
+            .route("/scans", get(scans))
+            .with_state(Arc::clone(&self.manager))
+    }
+
+    /// 监听指定地址并提供服务，直到出错才返回，需要在 tokio 运行时中调用
+    pub async fn serve(self, addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+        let addr = addr.into();
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(ScannerError::Io)?;
+        event!(Level::INFO, server.addr = %addr, "HTTP控制接口启动成功");
+        axum::serve(listener, self.router())
+            .await
+            .map_err(ScannerError::Io)
+    }
+}
+
+/// 接口错误，按[`ScannerError`]的 JSON 格式返回
+struct ApiError(StatusCode, ScannerError);
+
+impl From<ScannerError> for ApiError {
+    fn from(err: ScannerError) -> Self {
+        let status = match err {
+            ScannerError::Param(_) => StatusCode::BAD_REQUEST,
+            ScannerError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        ApiError(status, err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(self.1)).into_response()
+    }
+}
+
+/// 按编号查找扫码枪
+fn find(manager: &ScannerManager, id: &str) -> Result<Scanner, ApiError> {
+    manager.get(id).ok_or_else(|| {
+        ApiError(
+            StatusCode::NOT_FOUND,
+            ScannerError::Param(format!("扫码枪不存在,id={}", id)),
+        )
+    })
+}
+
+/// 扫码枪的状态和统计
+fn describe(manager: &ScannerManager, id: &str, scanner: &Scanner) -> Value {
+    let stats = manager.stats(id).unwrap_or_default();
+    let last_scan = stats
+        .last_scan()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|time| time.as_millis() as u64);
+    json!({
+        "id": id,
+        "connector": scanner.connector.to_string(),
+        "labels": scanner.labels(),
+        "running": scanner.is_running(),
+        "stats": {
+            "scans": stats.scans(),
+            "no_reads": stats.no_reads(),
+            "mismatches": stats.mismatches(),
+            "errors": stats.errors(),
+            "disconnects": stats.disconnects(),
+            "connected": stats.connected(),
+            "last_scan": last_scan,
+        },
+    })
+}
+
+async fn scanners(State(manager): State<Arc<ScannerManager>>) -> Json<Vec<Value>> {
+    let scanners = manager
+        .ids()
+        .into_iter()
+        .filter_map(|id| {
+            let scanner = manager.get(&id)?;
+            Some(describe(&manager, &id, &scanner))
+        })
+        .collect();
+    Json(scanners)
+}
+
+async fn scanner(
+    State(manager): State<Arc<ScannerManager>>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let scanner = find(&manager, &id)?;
+    Ok(Json(describe(&manager, &id, &scanner)))
+}
+
+/// 最近条码的查询参数
+#[derive(Deserialize)]
+struct ScansQuery {
+    limit: Option<usize>,
+}
+
+async fn scans(
+    State(manager): State<Arc<ScannerManager>>,
+    Query(query): Query<ScansQuery>,
+) -> Json<Vec<FleetEvent>> {
+    Json(manager.recent(query.limit.unwrap_or(LIMIT)))
+}
+
+/// 发送指令的请求
+#[derive(Deserialize)]
+struct CommandRequest {
+    /// 原始指令
+    command: Option<String>,
+    /// 指令模板名称
+    name: Option<String>,
+}
+
+async fn command(
+    State(manager): State<Arc<ScannerManager>>,
+    Path(id): Path<String>,
+    Json(request): Json<CommandRequest>,
+) -> Result<StatusCode, ApiError> {
+    let scanner = find(&manager, &id)?;
+    match (request.command, request.name) {
+        (Some(command), None) => scanner.send_message(command).await??,
+        (None, Some(name)) => scanner.command(&name).await?,
+        _ => {
+            return Err(ScannerError::Param("command 和 name 需要且只能设置一个".into()).into());
+        }
+    }
+    event!(Level::INFO, scanner.id = %id, "HTTP接口发送指令");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use crate::prelude::*;
+
+    /// 发送一个请求，返回(状态码，应答内容)
+    async fn request(addr: &str, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or_default();
+        (status, body.to_owned())
+    }
+
+    #[tokio::test]
+    async fn rest() {
+        let mock = MockConnector::new();
+        let manager = Arc::new(
+            ScannerManager::new().with_scanner(
+                "A",
+                Scanner::new(mock.clone())
+                    .label("line", "1")
+                    .commands(CommandTemplates::new().with("on", "LON\r")),
+            ),
+        );
+        manager.start().await.unwrap();
+        assert!(matches!(
+            manager.recv().await.unwrap().event(),
+            ScanEvent::Connected
+        ));
+        mock.push("SN0001");
+        manager.recv().await.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let router = RestApi::new(Arc::clone(&manager)).router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let (status, body) = request(&addr, "GET", "/scanners", "").await;
+        assert_eq!(status, 200);
+        let scanners: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(scanners[0]["id"], "A");
+        assert_eq!(scanners[0]["labels"]["line"], "1");
+        assert_eq!(scanners[0]["running"], true);
+        assert_eq!(scanners[0]["stats"]["scans"], 1);
+        assert_eq!(scanners[0]["stats"]["connected"], true);
+        assert_eq!(request(&addr, "GET", "/scanners/B", "").await.0, 404);
+
+        let (status, body) = request(&addr, "GET", "/scans?limit=5", "").await;
+        assert_eq!(status, 200);
+        assert!(body.contains("SN0001"));
+
+        let path = "/scanners/A/commands";
+        assert_eq!(
+            request(&addr, "POST", path, r#"{"name":"on"}"#).await.0,
+            204
+        );
+        let (status, _) = request(&addr, "POST", path, r#"{"command":"LOFF\r"}"#).await;
+        assert_eq!(status, 204);
+        let (status, body) = request(&addr, "POST", path, r#"{"name":"off"}"#).await;
+        assert_eq!(status, 400);
+        assert!(body.contains(r#""kind":"param""#));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(mock.try_commands(), vec!["LON\r", "LOFF\r"]);
+    }
+}
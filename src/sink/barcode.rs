@@ -0,0 +1,181 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+use tracing::{event, Level};
+
+use crate::{ScanEvent, ScannerError};
+
+/// 事件投递结果
+pub type Delivering<'a> = Pin<Box<dyn Future<Output = Result<(), ScannerError>> + Send + 'a>>;
+
+/// 扫码枪的事件输出，见[`Scanner::sink`](crate::Scanner::sink)
+///
+/// 扫码枪的所有事件(经过中间件处理后)依次投递给每个输出，每个输出有独立的队列和任务，
+/// 慢的输出不影响读取和其它输出；队列满时丢弃事件。投递失败只记录日志
+///
+/// 写入文件使用[`ScannerManager::with_sink`](crate::ScannerManager::with_sink)添加`FileSink`(`sink-file`特性)
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// /// 只保存条码的输出
+/// struct Mes;
+///
+/// impl BarcodeSink for Mes {
+///     fn deliver(&self, event: ScanEvent) -> Delivering<'_> {
+///         Box::pin(async move {
+///             if let Some(barcode) = event.barcode() {
+///                 println!("上传 {}", barcode.text());
+///             }
+///             Ok(())
+///         })
+///     }
+/// }
+///
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004))
+///     .sink(Mes)
+///     .sink(LogSink::new());
+/// ```
+pub trait BarcodeSink: Send + Sync {
+    /// 投递一个事件
+    fn deliver(&self, event: ScanEvent) -> Delivering<'_>;
+}
+
+/// 依次投递队列中的事件，发送端关闭后结束
+pub(crate) async fn pump(
+    addr: String,
+    sink: Arc<dyn BarcodeSink>,
+    mut receiver: Receiver<ScanEvent>,
+) {
+    while let Some(ev) = receiver.recv().await {
+        if let Err(err) = sink.deliver(ev).await {
+            event!(
+                Level::ERROR,
+                scanner.addr = %addr,
+                error = %err,
+                error.kind = err.kind(),
+                "事件投递失败",
+            );
+        }
+    }
+}
+
+/// 转发到通道的输出，通道满时等待
+pub struct ChannelSink(Sender<ScanEvent>);
+
+impl ChannelSink {
+    /// 创建转发到通道的输出
+    pub fn new(sender: Sender<ScanEvent>) -> Self {
+        ChannelSink(sender)
+    }
+}
+
+impl BarcodeSink for ChannelSink {
+    fn deliver(&self, event: ScanEvent) -> Delivering<'_> {
+        Box::pin(async move {
+            self.0
+                .send(event)
+                .await
+                .map_err(|_| ScannerError::Comm("通道已关闭".into()))
+        })
+    }
+}
+
+/// 记录日志的输出，默认级别为 INFO
+pub struct LogSink {
+    level: Level,
+}
+
+impl LogSink {
+    /// 创建 INFO 级别的日志输出
+    pub fn new() -> Self {
+        LogSink { level: Level::INFO }
+    }
+
+    /// 设置日志级别
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+impl Default for LogSink {
+    fn default() -> Self {
+        LogSink::new()
+    }
+}
+
+impl BarcodeSink for LogSink {
+    fn deliver(&self, ev: ScanEvent) -> Delivering<'_> {
+        // event! 的级别必须是常量
+        macro_rules! log {
+            ($level:expr) => {
+                event!($level, event = ev.kind(), text = %ev.printable(), "扫码枪事件")
+            };
+        }
+        match self.level {
+            Level::ERROR => log!(Level::ERROR),
+            Level::WARN => log!(Level::WARN),
+            Level::INFO => log!(Level::INFO),
+            Level::DEBUG => log!(Level::DEBUG),
+            Level::TRACE => log!(Level::TRACE),
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 由闭包实现的输出，闭包在输出任务中调用
+pub struct CallbackSink<F>(F);
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(ScanEvent) + Send + Sync,
+{
+    /// 创建由闭包实现的输出
+    pub fn new(f: F) -> Self {
+        CallbackSink(f)
+    }
+}
+
+impl<F> BarcodeSink for CallbackSink<F>
+where
+    F: Fn(ScanEvent) + Send + Sync,
+{
+    fn deliver(&self, event: ScanEvent) -> Delivering<'_> {
+        (self.0)(event);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn sinks() {
+        let (sender, mut receiver) = mpsc::channel(10);
+        let (callback, mut called) = mpsc::unbounded_channel();
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone())
+            .sink(ChannelSink::new(sender))
+            .sink(CallbackSink::new(move |ev: ScanEvent| {
+                let _ = callback.send(ev.kind());
+            }))
+            .sink(LogSink::new());
+        scanner.start().await.unwrap().unwrap();
+        mock.push("SN0001");
+        assert!(matches!(receiver.recv().await, Some(ScanEvent::Connected)));
+        assert_eq!(
+            receiver.recv().await.unwrap().as_str_lossy().unwrap(),
+            "SN0001"
+        );
+        assert_eq!(called.recv().await, Some("connected"));
+        assert_eq!(called.recv().await, Some("barcode"));
+        scanner.stop();
+    }
+}
@@ -0,0 +1,316 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, ScannerError};
+
+/// 文件格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Jsonl,
+    Csv,
+}
+
+/// 文件输出，每个事件写一行 JSON(JSONL)或 CSV，下游程序只需要跟踪(tail)文件
+///
+/// * JSONL：事件的 JSON(格式见[`FleetEvent`]的序列化)加上`time`字段(Unix 毫秒)
+/// * CSV：`time,scanner,event,text`，依次为 Unix 毫秒、扫码枪编号、事件类型、条码或事件说明
+///
+/// 文件超过设置的大小或时长后轮转：当前文件重命名为`<文件名>.<Unix 毫秒>.<扩展名>`，
+/// 默认压缩为`.gz`，然后重新创建文件。时长在收到下一个事件时检查
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let file = FileSink::jsonl("/var/log/scans/scans.jsonl")
+///     .with_max_size(64 * 1024 * 1024)
+///     .with_rotate_every(Duration::from_secs(24 * 3600))
+///     .spawn()?;
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_sink("file", file)
+///     .with_route(Route::new("file"));
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileSink {
+    path: PathBuf,
+    format: Format,
+    max_size: Option<u64>,
+    rotate_every: Option<Duration>,
+    gzip: bool,
+    capacity: usize,
+}
+
+impl FileSink {
+    /// 创建 JSONL 文件输出
+    pub fn jsonl(path: impl AsRef<Path>) -> Self {
+        FileSink::new(path.as_ref(), Format::Jsonl)
+    }
+
+    /// 创建 CSV 文件输出，新文件先写表头
+    pub fn csv(path: impl AsRef<Path>) -> Self {
+        FileSink::new(path.as_ref(), Format::Csv)
+    }
+
+    fn new(path: &Path, format: Format) -> Self {
+        FileSink {
+            path: path.into(),
+            format,
+            max_size: None,
+            rotate_every: None,
+            gzip: true,
+            capacity: 1000,
+        }
+    }
+
+    /// 文件超过指定字节数后轮转
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// 文件创建超过指定时长后轮转
+    pub fn with_rotate_every(mut self, every: Duration) -> Self {
+        self.rotate_every = Some(every);
+        self
+    }
+
+    /// 设置轮转后的文件是否压缩为`.gz`，默认压缩
+    pub fn with_gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// 设置写入队列长度
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// 获取文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 打开文件并开始写入，返回的发送端可以作为[`crate::ScannerManager::with_sink`]的输出
+    ///
+    /// 需要在 tokio 运行时中调用，文件无法打开时返回错误，发送端全部关闭后结束
+    pub fn spawn(self) -> Result<Sender<FleetEvent>, ScannerError> {
+        let writer = Writer::open(&self).map_err(ScannerError::Io)?;
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        tokio::task::spawn_blocking(move || self.run(writer, receiver));
+        Ok(sender)
+    }
+
+    /// 在阻塞线程中写入事件
+    fn run(self, mut writer: Writer, mut receiver: Receiver<FleetEvent>) {
+        let path = self.path.display().to_string();
+        while let Some(ev) = receiver.blocking_recv() {
+            let expired = self
+                .rotate_every
+                .is_some_and(|every| writer.opened.elapsed() >= every);
+            let full = self.max_size.is_some_and(|max| writer.size >= max);
+            if expired || full {
+                match self.rotate(writer) {
+                    Ok(next) => writer = next,
+                    Err(err) => {
+                        event!(
+                            Level::ERROR,
+                            file = %path,
+                            error = %err,
+                            error.kind = "io",
+                            "文件轮转失败",
+                        );
+                        return;
+                    }
+                }
+            }
+            let line = match self.line(&ev) {
+                Ok(line) => line,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        file = %path,
+                        error = %err,
+                        error.kind = "parse",
+                        "事件序列化失败",
+                    );
+                    continue;
+                }
+            };
+            if let Err(err) = writer.write(line.as_bytes()) {
+                event!(
+                    Level::ERROR,
+                    file = %path,
+                    error = %err,
+                    error.kind = "io",
+                    "写入文件失败",
+                );
+            }
+        }
+    }
+
+    /// 关闭当前文件，重命名(并压缩)后重新创建
+    fn rotate(&self, writer: Writer) -> std::io::Result<Writer> {
+        drop(writer);
+        let millis = unix_millis(SystemTime::now());
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match self.path.extension() {
+            Some(ext) => format!("{}.{}.{}", stem, millis, ext.to_string_lossy()),
+            None => format!("{}.{}", stem, millis),
+        };
+        let rotated = self.path.with_file_name(name);
+        std::fs::rename(&self.path, &rotated)?;
+        if self.gzip {
+            let mut gz = rotated.clone().into_os_string();
+            gz.push(".gz");
+            let mut encoder = GzEncoder::new(File::create(&gz)?, Compression::default());
+            std::io::copy(&mut File::open(&rotated)?, &mut encoder)?;
+            encoder.finish()?;
+            std::fs::remove_file(&rotated)?;
+        }
+        event!(
+            Level::INFO,
+            file = %self.path.display(),
+            rotated = %rotated.display(),
+            "文件已轮转",
+        );
+        Writer::open(self)
+    }
+
+    /// 一个事件对应的一行
+    fn line(&self, ev: &FleetEvent) -> Result<String, serde_json::Error> {
+        let time = unix_millis(SystemTime::now());
+        match self.format {
+            Format::Jsonl => {
+                let mut value = serde_json::to_value(ev)?;
+                if let Some(object) = value.as_object_mut() {
+                    object.insert("time".into(), time.into());
+                }
+                Ok(format!("{}\n", value))
+            }
+            Format::Csv => {
+                let text = match ev.event().barcode() {
+                    Some(barcode) => barcode.text().into_owned(),
+                    None => ev.event().printable(),
+                };
+                Ok(format!(
+                    "{},{},{},{}\n",
+                    time,
+                    escape(ev.id()),
+                    ev.event().kind(),
+                    escape(&text)
+                ))
+            }
+        }
+    }
+}
+
+/// 正在写入的文件
+struct Writer {
+    file: BufWriter<File>,
+    size: u64,
+    opened: Instant,
+}
+
+impl Writer {
+    /// 以追加方式打开文件，CSV 新文件写表头
+    fn open(sink: &FileSink) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&sink.path)?;
+        let mut writer = Writer {
+            size: file.metadata()?.len(),
+            file: BufWriter::new(file),
+            opened: Instant::now(),
+        };
+        if sink.format == Format::Csv && writer.size == 0 {
+            writer.write(b"time,scanner,event,text\n")?;
+        }
+        Ok(writer)
+    }
+
+    /// 写入一行并刷新，保证下游立即读到
+    fn write(&mut self, line: &[u8]) -> std::io::Result<()> {
+        self.file.write_all(line)?;
+        self.file.flush()?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// CSV 字段转义，包含逗号、引号或换行时加引号
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn rotate() {
+        let dir = std::env::temp_dir().join(format!("kim_scanner-file-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("scans.csv");
+        let sink = FileSink::csv(&path).with_max_size(60).spawn().unwrap();
+        let barcode = |text| ScanEvent::Barcode(Barcode::from_text(text, Encoding::Utf8));
+        sink.send(FleetEvent::new("st1", barcode("SN0001")))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", barcode("SN,0002")))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", barcode("SN0003")))
+            .await
+            .unwrap();
+        drop(sink);
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        // 表头加一行超过60字节，第三个事件写入新文件
+        let current = std::fs::read_to_string(&path).unwrap();
+        let lines = current.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "time,scanner,event,text");
+        assert!(lines[1].ends_with(",st1,barcode,SN0003"));
+        let rotated = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().ends_with(".csv.gz"))
+            .unwrap();
+        let mut text = String::new();
+        GzDecoder::new(std::fs::File::open(rotated).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert!(text.contains(",st1,barcode,SN0001\n"));
+        assert!(text.contains(",st1,barcode,\"SN,0002\"\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+pub mod barcode;
+#[cfg(feature = "sink-file")]
+pub mod file;
+#[cfg(feature = "sink-mqtt")]
+pub mod mqtt;
+#[cfg(feature = "sink-plc")]
+pub mod plc;
+#[cfg(feature = "sink-redis")]
+pub mod redis;
+#[cfg(feature = "sink-webhook")]
+pub mod webhook;
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use tokio::sync::mpsc::{self, Sender};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, ScanEvent};
+
+/// MQTT 输出，把扫码枪事件以 JSON 发布到 MQTT 服务器
+///
+/// * `<topic>/<扫码枪编号>` 事件，格式见[`FleetEvent`]的序列化
+/// * `<topic>/<扫码枪编号>/status` 扫码枪连接状态，保留消息`online`/`offline`
+/// * `<topic>/status` 本程序的连接状态，保留消息`online`，断开后由服务器发布遗嘱消息`offline`
+///
+/// 连接断开后自动重连，断开期间的事件在发送队列中等待
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let mqtt = MqttSink::new("192.168.1.2", 1883, "factory/line1")
+///     .with_credentials("edge", "secret")
+///     .with_qos(1)
+///     .spawn();
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)));
+/// manager.start().await?;
+/// // 发布所有事件
+/// while let Some(ev) = manager.recv().await {
+///     let _ = mqtt.send(ev).await;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MqttSink {
+    host: String,
+    port: u16,
+    topic: String,
+    client_id: String,
+    credentials: Option<(String, String)>,
+    qos: u8,
+    keep_alive: Duration,
+    capacity: usize,
+}
+
+impl MqttSink {
+    /// 创建 MQTT 输出，默认 QoS 1
+    ///
+    /// * `host` 服务器地址
+    /// * `port` 服务器端口
+    /// * `topic` 主题前缀
+    pub fn new(host: &str, port: u16, topic: &str) -> Self {
+        MqttSink {
+            host: host.into(),
+            port,
+            topic: topic.trim_end_matches('/').into(),
+            client_id: format!("kim_scanner-{}", std::process::id()),
+            credentials: None,
+            qos: 1,
+            keep_alive: Duration::from_secs(30),
+            capacity: 1000,
+        }
+    }
+
+    /// 设置客户端编号，默认为`kim_scanner-<进程号>`
+    pub fn with_client_id(mut self, client_id: &str) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// 设置用户名和密码
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// 设置 QoS，0~2
+    pub fn with_qos(mut self, qos: u8) -> Self {
+        self.qos = qos.min(2);
+        self
+    }
+
+    /// 设置心跳间隔
+    pub fn with_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// 设置发送队列长度
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// 获取主题前缀
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// 连接服务器并开始发布，返回的发送端可以作为[`crate::ScannerManager::with_sink`]的输出
+    ///
+    /// 需要在 tokio 运行时中调用，发送端全部关闭后断开连接
+    pub fn spawn(self) -> Sender<FleetEvent> {
+        let qos = match self.qos {
+            0 => QoS::AtMostOnce,
+            1 => QoS::AtLeastOnce,
+            _ => QoS::ExactlyOnce,
+        };
+        let status = format!("{}/status", self.topic);
+        let mut options = MqttOptions::new(&self.client_id, &self.host, self.port);
+        options
+            .set_keep_alive(self.keep_alive)
+            .set_last_will(LastWill::new(&status, "offline", qos, true));
+        if let Some((username, password)) = &self.credentials {
+            options.set_credentials(username, password);
+        }
+        let (client, mut eventloop) = AsyncClient::new(options, self.capacity);
+        let addr = format!("mqtt://{}:{}", self.host, self.port);
+        let (sender, mut receiver) = mpsc::channel::<FleetEvent>(self.capacity);
+
+        let online = client.clone();
+        let connection = tokio::spawn(async move {
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        event!(Level::INFO, sink.target = %addr, "MQTT连接成功");
+                        let _ = online.try_publish(&status, qos, true, "online");
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        event!(
+                            Level::ERROR,
+                            sink.target = %addr,
+                            error = %err,
+                            error.kind = "comm",
+                            "MQTT连接失败,3秒后重连",
+                        );
+                        tokio::time::sleep(Duration::from_secs(3)).await;
+                    }
+                }
+            }
+        });
+        tokio::spawn(async move {
+            while let Some(ev) = receiver.recv().await {
+                for (topic, retain, payload) in messages(&self.topic, &ev) {
+                    if let Err(err) = client.publish(&topic, qos, retain, payload).await {
+                        event!(
+                            Level::ERROR,
+                            topic = %topic,
+                            error = %err,
+                            error.kind = "comm",
+                            "MQTT发布失败",
+                        );
+                    }
+                }
+            }
+            let _ = client.disconnect().await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            connection.abort();
+        });
+        sender
+    }
+}
+
+/// 事件对应的 MQTT 消息，(主题，是否保留，内容)
+fn messages(topic: &str, ev: &FleetEvent) -> Vec<(String, bool, Vec<u8>)> {
+    let mut messages = vec![];
+    match serde_json::to_vec(ev) {
+        Ok(payload) => messages.push((format!("{}/{}", topic, ev.id()), false, payload)),
+        Err(err) => event!(
+            Level::ERROR,
+            scanner.id = %ev.id(),
+            error = %err,
+            error.kind = "parse",
+            "事件序列化失败",
+        ),
+    }
+    let status = match ev.event() {
+        ScanEvent::Connected | ScanEvent::Restarted(_) => Some("online"),
+        ScanEvent::Disconnected | ScanEvent::Unhealthy(_) | ScanEvent::Failed(_) => Some("offline"),
+        _ => None,
+    };
+    if let Some(status) = status {
+        let topic = format!("{}/{}/status", topic, ev.id());
+        messages.push((topic, true, status.into()));
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::messages;
+    use crate::prelude::*;
+
+    #[test]
+    fn topics() {
+        let ev = FleetEvent::new(
+            "st1",
+            ScanEvent::Barcode(Barcode::from_text("SN0001", Encoding::Utf8)),
+        );
+        let messages = messages("factory/line1", &ev);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "factory/line1/st1");
+        assert!(!messages[0].1);
+        let json: serde_json::Value = serde_json::from_slice(&messages[0].2).unwrap();
+        assert_eq!(json["id"], "st1");
+        assert_eq!(json["event"]["type"], "barcode");
+
+        let messages = super::messages(
+            "factory/line1",
+            &FleetEvent::new("st1", ScanEvent::Disconnected),
+        );
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[1],
+            ("factory/line1/st1/status".into(), true, b"offline".to_vec())
+        );
+    }
+}
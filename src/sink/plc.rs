@@ -0,0 +1,711 @@
+use std::future::Future;
+use std::io::{Error, ErrorKind};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::time::Instant;
+use tracing::{event, Level};
+
+use crate::{FleetEvent, RetryPolicy, ScanEvent, ScannerError};
+
+/// 连接和每次请求的超时时间
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// 等待握手信号复位时的查询间隔
+const POLL: Duration = Duration::from_millis(50);
+
+/// PLC 通讯协议
+#[derive(Clone, Debug)]
+enum Protocol {
+    /// 三菱 MC 协议(3E 帧，二进制)
+    Mc,
+    /// 西门子 S7 协议
+    S7 { rack: u8, slot: u8 },
+}
+
+/// PLC 地址
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Address {
+    /// MC 字软元件：软元件代码、编号
+    McWord(u8, u32),
+    /// MC 位软元件：软元件代码、编号
+    McBit(u8, u32),
+    /// S7 字节地址：区域、DB 号、字节偏移
+    S7Byte(u8, u16, u32),
+    /// S7 位地址：区域、DB 号、字节偏移、位
+    S7Bit(u8, u16, u32, u8),
+}
+
+/// PLC 输出，把每个条码写入 PLC 的寄存器或数据块，然后置位握手信号，
+/// 是国内装配线上常见的"扫码枪 → PLC"对接方式
+///
+/// * 三菱 MC 协议(3E 帧，二进制)：数据地址为字软元件(`D`、`W`、`R`、`ZR`)，
+///   第一个字为条码长度(字节)，后面每个字两个字符，低字节在前，例如`D100`；
+///   握手地址为位软元件(`M`、`B`、`L`、`X`、`Y`)，例如`M100`。`W`、`B`、`X`、`Y`的编号为十六进制
+/// * 西门子 S7 协议：数据地址为字节地址(`DB10.DBB0`、`MB100`)，按 S7 的`STRING`格式写入
+///   (最大长度、实际长度、字符)；握手地址为位地址(`DB10.DBX100.0`、`M100.0`)
+///
+/// 设置握手地址后，写入条码前等待 PLC 把握手信号复位(超时后覆盖上一个条码)，
+/// 写入条码后置位握手信号。只写入有效的条码(条码事件和比对一致事件)，未读和比对不一致不写入，
+/// 也不置位握手信号，PLC 可以通过握手超时判断没有读到合格的条码。不足最大长度的部分补0，超过的部分被截断。
+/// 通讯失败时重新连接，按重试策略重写同一个条码，超过最大重试次数后丢弃
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let plc = PlcSink::mc("192.168.1.20", 5000)
+///     .with_data("D100")
+///     .with_handshake("M100")
+///     .spawn()?;
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_sink("plc", plc)
+///     .with_route(Route::new("plc").with_scanner("line1-in"));
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PlcSink {
+    host: String,
+    port: u16,
+    protocol: Protocol,
+    data: String,
+    handshake: Option<String>,
+    max_len: usize,
+    handshake_timeout: Duration,
+    retry: RetryPolicy,
+    capacity: usize,
+}
+
+impl PlcSink {
+    /// 创建三菱 MC 协议(3E 帧，二进制)输出
+    ///
+    /// * `host` PLC 地址
+    /// * `port` PLC 中设置的 MC 协议端口
+    pub fn mc(host: &str, port: u16) -> Self {
+        PlcSink::new(host, port, Protocol::Mc)
+    }
+
+    /// 创建西门子 S7 协议输出，端口为102
+    ///
+    /// * `host` PLC 地址
+    /// * `rack` 机架号，S7-1200/1500 为0
+    /// * `slot` 槽号，S7-300 为2，S7-1200/1500 为1
+    pub fn s7(host: &str, rack: u8, slot: u8) -> Self {
+        PlcSink::new(host, 102, Protocol::S7 { rack, slot })
+    }
+
+    fn new(host: &str, port: u16, protocol: Protocol) -> Self {
+        PlcSink {
+            host: host.into(),
+            port,
+            protocol,
+            data: String::new(),
+            handshake: None,
+            max_len: 64,
+            handshake_timeout: Duration::from_secs(5),
+            retry: RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)),
+            capacity: 1000,
+        }
+    }
+
+    /// 设置条码的写入地址，例如`D100`、`DB10.DBB0`
+    pub fn with_data(mut self, address: &str) -> Self {
+        self.data = address.into();
+        self
+    }
+
+    /// 设置握手信号的地址，例如`M100`、`DB10.DBX100.0`
+    pub fn with_handshake(mut self, address: &str) -> Self {
+        self.handshake = Some(address.into());
+        self
+    }
+
+    /// 设置条码的最大长度(字节)，默认64，S7 最大254
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.clamp(1, 254);
+        self
+    }
+
+    /// 设置等待 PLC 复位握手信号的超时时间，默认5秒
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// 设置重试策略，超过最大重试次数后丢弃该条码
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 设置发送队列长度
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// 获取 PLC 地址
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// 开始写入，返回的发送端可以作为[`crate::ScannerManager::with_sink`]的输出
+    ///
+    /// 需要在 tokio 运行时中调用，地址无效时返回参数错误，发送端全部关闭后结束
+    pub fn spawn(self) -> Result<Sender<FleetEvent>, ScannerError> {
+        let data = self.parse(&self.data)?;
+        if !matches!(data, Address::McWord(..) | Address::S7Byte(..)) {
+            return Err(ScannerError::Param(format!(
+                "条码地址必须是字地址或字节地址,地址={}",
+                self.data
+            )));
+        }
+        let handshake = match &self.handshake {
+            Some(text) => {
+                let address = self.parse(text)?;
+                if !matches!(address, Address::McBit(..) | Address::S7Bit(..)) {
+                    return Err(ScannerError::Param(format!(
+                        "握手地址必须是位地址,地址={}",
+                        text
+                    )));
+                }
+                Some(address)
+            }
+            None => None,
+        };
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        tokio::spawn(self.run(data, handshake, receiver));
+        Ok(sender)
+    }
+
+    /// 按协议解析地址
+    fn parse(&self, text: &str) -> Result<Address, ScannerError> {
+        let address = match self.protocol {
+            Protocol::Mc => parse_mc(text),
+            Protocol::S7 { .. } => parse_s7(text),
+        };
+        address.ok_or_else(|| ScannerError::Param(format!("PLC地址无效,地址={}", text)))
+    }
+
+    /// 按顺序写入条码
+    async fn run(
+        self,
+        data: Address,
+        handshake: Option<Address>,
+        mut receiver: Receiver<FleetEvent>,
+    ) {
+        let addr = format!("{}:{}", self.host, self.port);
+        let mut client: Option<Client> = None;
+        while let Some(ev) = receiver.recv().await {
+            // 只写入有效的条码，避免 PLC 把未读、比对不一致当成读码成功
+            let barcode = match ev.event() {
+                ScanEvent::Barcode(barcode) | ScanEvent::Match(barcode) => barcode,
+                _ => continue,
+            };
+            let text = barcode.text();
+            let bytes = &text.as_bytes()[..text.len().min(self.max_len)];
+            let mut failures = 0u32;
+            loop {
+                let result = match &mut client {
+                    Some(client) => self.write(client, &data, handshake.as_ref(), bytes).await,
+                    None => match self.connect().await {
+                        Ok(connected) => {
+                            event!(Level::INFO, sink.target = %addr, "PLC连接成功");
+                            let connected = client.insert(connected);
+                            self.write(connected, &data, handshake.as_ref(), bytes)
+                                .await
+                        }
+                        Err(err) => Err(err),
+                    },
+                };
+                let Err(err) = result else {
+                    break;
+                };
+                client = None;
+                failures += 1;
+                if self.retry.exhausted(failures) {
+                    event!(
+                        Level::ERROR,
+                        sink.target = %addr,
+                        scanner.id = %ev.id(),
+                        failures,
+                        error = %err,
+                        error.kind = "io",
+                        "连续失败,丢弃条码",
+                    );
+                    break;
+                }
+                let delay = self.retry.delay(failures);
+                event!(
+                    Level::WARN,
+                    sink.target = %addr,
+                    delay = ?delay,
+                    error = %err,
+                    error.kind = "io",
+                    "条码写入失败,等待重试",
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    async fn connect(&self) -> std::io::Result<Client> {
+        let stream = timeout(TcpStream::connect((self.host.as_str(), self.port))).await?;
+        stream.set_nodelay(true)?;
+        match self.protocol {
+            Protocol::Mc => Ok(Client::Mc(stream)),
+            Protocol::S7 { rack, slot } => {
+                let mut client = S7 { stream, pdu_ref: 0 };
+                client.setup(rack, slot).await?;
+                Ok(Client::S7(client))
+            }
+        }
+    }
+
+    /// 等待握手信号复位，写入条码后置位握手信号
+    async fn write(
+        &self,
+        client: &mut Client,
+        data: &Address,
+        handshake: Option<&Address>,
+        bytes: &[u8],
+    ) -> std::io::Result<()> {
+        if let Some(handshake) = handshake {
+            let deadline = Instant::now() + self.handshake_timeout;
+            while client.read_bit(handshake).await? {
+                if Instant::now() >= deadline {
+                    event!(
+                        Level::WARN,
+                        sink.target = %self.host,
+                        "PLC未复位握手信号,覆盖上一个条码",
+                    );
+                    break;
+                }
+                tokio::time::sleep(POLL).await;
+            }
+        }
+        client.write_text(data, bytes, self.max_len).await?;
+        if let Some(handshake) = handshake {
+            client.write_bit(handshake, true).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析 MC 地址，例如`D100`、`ZR2000`、`M100`、`B1F`
+fn parse_mc(text: &str) -> Option<Address> {
+    let text = text.trim().to_ascii_uppercase();
+    // 软元件名称、代码、是否为字软元件、编号的进制
+    const DEVICES: [(&str, u8, bool, u32); 9] = [
+        ("ZR", 0xB0, true, 10),
+        ("D", 0xA8, true, 10),
+        ("W", 0xB4, true, 16),
+        ("R", 0xAF, true, 10),
+        ("M", 0x90, false, 10),
+        ("B", 0xA0, false, 16),
+        ("L", 0x92, false, 10),
+        ("X", 0x9C, false, 16),
+        ("Y", 0x9D, false, 16),
+    ];
+    let (name, code, word, radix) = DEVICES
+        .into_iter()
+        .find(|(name, ..)| text.starts_with(name))?;
+    let number = u32::from_str_radix(&text[name.len()..], radix).ok()?;
+    if number > 0xFF_FFFF {
+        return None;
+    }
+    Some(if word {
+        Address::McWord(code, number)
+    } else {
+        Address::McBit(code, number)
+    })
+}
+
+/// 解析 S7 地址，例如`DB10.DBB0`、`DB10.DBX100.0`、`MB100`、`M100.0`
+fn parse_s7(text: &str) -> Option<Address> {
+    const DB: u8 = 0x84;
+    const MERKER: u8 = 0x83;
+    let text = text.trim().to_ascii_uppercase();
+    let bit = |text: &str| -> Option<(u32, u8)> {
+        let (offset, bit) = text.split_once('.')?;
+        let bit = bit.parse().ok().filter(|bit| *bit < 8)?;
+        Some((offset.parse().ok()?, bit))
+    };
+    if let Some(rest) = text.strip_prefix("DB") {
+        let (db, rest) = rest.split_once('.')?;
+        let db = db.parse().ok()?;
+        if let Some(offset) = rest.strip_prefix("DBB") {
+            return Some(Address::S7Byte(DB, db, offset.parse().ok()?));
+        }
+        let (offset, bit) = bit(rest.strip_prefix("DBX")?)?;
+        return Some(Address::S7Bit(DB, db, offset, bit));
+    }
+    if let Some(offset) = text.strip_prefix("MB") {
+        return Some(Address::S7Byte(MERKER, 0, offset.parse().ok()?));
+    }
+    let (offset, bit) = bit(text.strip_prefix('M')?)?;
+    Some(Address::S7Bit(MERKER, 0, offset, bit))
+}
+
+/// 给请求加上超时
+async fn timeout<T>(future: impl Future<Output = std::io::Result<T>>) -> std::io::Result<T> {
+    tokio::time::timeout(TIMEOUT, future)
+        .await
+        .map_err(|_| Error::new(ErrorKind::TimedOut, "PLC应答超时"))?
+}
+
+/// 已连接的 PLC
+enum Client {
+    Mc(TcpStream),
+    S7(S7),
+}
+
+impl Client {
+    async fn read_bit(&mut self, address: &Address) -> std::io::Result<bool> {
+        match (self, address) {
+            (Client::Mc(stream), Address::McBit(code, number)) => {
+                let reply = mc(stream, 0x0401, 0x0001, &mc_device(*code, *number, 1)).await?;
+                Ok(reply.first().is_some_and(|bits| bits & 0x10 != 0))
+            }
+            (Client::S7(s7), Address::S7Bit(area, db, offset, bit)) => {
+                s7.read_bit(*area, *db, offset * 8 + *bit as u32).await
+            }
+            _ => Err(Error::new(ErrorKind::InvalidInput, "地址与协议不一致")),
+        }
+    }
+
+    async fn write_bit(&mut self, address: &Address, on: bool) -> std::io::Result<()> {
+        match (self, address) {
+            (Client::Mc(stream), Address::McBit(code, number)) => {
+                let mut data = mc_device(*code, *number, 1);
+                // 每个点4位，高4位为第一个点
+                data.push(if on { 0x10 } else { 0x00 });
+                mc(stream, 0x1401, 0x0001, &data).await.map(|_| ())
+            }
+            (Client::S7(s7), Address::S7Bit(area, db, offset, bit)) => {
+                s7.write(*area, *db, offset * 8 + *bit as u32, true, &[on as u8])
+                    .await
+            }
+            _ => Err(Error::new(ErrorKind::InvalidInput, "地址与协议不一致")),
+        }
+    }
+
+    /// 写入条码，不足最大长度的部分补0
+    async fn write_text(
+        &mut self,
+        address: &Address,
+        bytes: &[u8],
+        max_len: usize,
+    ) -> std::io::Result<()> {
+        match (self, address) {
+            (Client::Mc(stream), Address::McWord(code, number)) => {
+                let words = max_len.div_ceil(2);
+                let mut data = mc_device(*code, *number, words as u16 + 1);
+                data.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+                let mut text = bytes.to_vec();
+                text.resize(words * 2, 0);
+                data.extend_from_slice(&text);
+                mc(stream, 0x1401, 0x0000, &data).await.map(|_| ())
+            }
+            (Client::S7(s7), Address::S7Byte(area, db, offset)) => {
+                let mut data = vec![max_len as u8, bytes.len() as u8];
+                data.extend_from_slice(bytes);
+                data.resize(max_len + 2, 0);
+                s7.write(*area, *db, offset * 8, false, &data).await
+            }
+            _ => Err(Error::new(ErrorKind::InvalidInput, "地址与协议不一致")),
+        }
+    }
+}
+
+/// MC 协议的软元件：编号(3字节)、软元件代码、点数
+fn mc_device(code: u8, number: u32, points: u16) -> Vec<u8> {
+    let mut data = number.to_le_bytes()[..3].to_vec();
+    data.push(code);
+    data.extend_from_slice(&points.to_le_bytes());
+    data
+}
+
+/// 发送一个 MC 协议(3E 帧，二进制)请求，返回应答数据
+async fn mc(
+    stream: &mut TcpStream,
+    command: u16,
+    subcommand: u16,
+    data: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    // 副标题、网络号、PLC 号、目标模块 I/O 号、目标模块站号
+    let mut frame = vec![0x50, 0x00, 0x00, 0xFF, 0xFF, 0x03, 0x00];
+    frame.extend_from_slice(&(data.len() as u16 + 6).to_le_bytes());
+    // 监视定时器，单位250毫秒
+    frame.extend_from_slice(&16u16.to_le_bytes());
+    frame.extend_from_slice(&command.to_le_bytes());
+    frame.extend_from_slice(&subcommand.to_le_bytes());
+    frame.extend_from_slice(data);
+    timeout(async {
+        stream.write_all(&frame).await?;
+        let mut header = [0u8; 9];
+        stream.read_exact(&mut header).await?;
+        if header[..2] != [0xD0, 0x00] {
+            return Err(Error::new(ErrorKind::InvalidData, "MC应答格式错误"));
+        }
+        let len = u16::from_le_bytes([header[7], header[8]]) as usize;
+        if len < 2 {
+            return Err(Error::new(ErrorKind::InvalidData, "MC应答格式错误"));
+        }
+        let mut reply = vec![0u8; len];
+        stream.read_exact(&mut reply).await?;
+        let code = u16::from_le_bytes([reply[0], reply[1]]);
+        if code != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("MC应答错误,结束代码=0x{:04X}", code),
+            ));
+        }
+        Ok(reply.split_off(2))
+    })
+    .await
+}
+
+/// S7 协议连接
+struct S7 {
+    stream: TcpStream,
+    pdu_ref: u16,
+}
+
+impl S7 {
+    /// 发送一个 TPKT 报文，返回应答的内容(不含 TPKT 头)
+    async fn tpkt(&mut self, payload: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut frame = vec![0x03, 0x00];
+        frame.extend_from_slice(&(payload.len() as u16 + 4).to_be_bytes());
+        frame.extend_from_slice(payload);
+        timeout(async {
+            self.stream.write_all(&frame).await?;
+            let mut header = [0u8; 4];
+            self.stream.read_exact(&mut header).await?;
+            let len = u16::from_be_bytes([header[2], header[3]]) as usize;
+            if header[0] != 0x03 || len < 7 {
+                return Err(Error::new(ErrorKind::InvalidData, "S7应答格式错误"));
+            }
+            let mut reply = vec![0u8; len - 4];
+            self.stream.read_exact(&mut reply).await?;
+            Ok(reply)
+        })
+        .await
+    }
+
+    /// 建立 COTP 连接并协商 PDU 长度
+    async fn setup(&mut self, rack: u8, slot: u8) -> std::io::Result<()> {
+        let connect = [
+            0x11,
+            0xE0,
+            0x00,
+            0x00,
+            0x00,
+            0x01,
+            0x00,
+            // TPDU 长度1024
+            0xC0,
+            0x01,
+            0x0A,
+            // 本地 TSAP
+            0xC1,
+            0x02,
+            0x01,
+            0x00,
+            // 远程 TSAP：PG 连接、机架和槽号
+            0xC2,
+            0x02,
+            0x01,
+            rack * 0x20 + slot,
+        ];
+        let reply = self.tpkt(&connect).await?;
+        if reply.get(1) != Some(&0xD0) {
+            return Err(Error::new(ErrorKind::ConnectionRefused, "S7连接被拒绝"));
+        }
+        // 最大并发任务数1、1，PDU 长度480
+        self.job(&[0xF0, 0x00, 0x00, 0x01, 0x00, 0x01, 0x01, 0xE0], &[])
+            .await
+            .map(|_| ())
+    }
+
+    /// 发送一个 S7 任务，返回应答的参数和数据
+    async fn job(&mut self, params: &[u8], data: &[u8]) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+        self.pdu_ref = self.pdu_ref.wrapping_add(1);
+        // COTP 数据报文
+        let mut payload = vec![0x02, 0xF0, 0x80, 0x32, 0x01, 0x00, 0x00];
+        payload.extend_from_slice(&self.pdu_ref.to_be_bytes());
+        payload.extend_from_slice(&(params.len() as u16).to_be_bytes());
+        payload.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        payload.extend_from_slice(params);
+        payload.extend_from_slice(data);
+        let reply = self.tpkt(&payload).await?;
+        // COTP(3字节)、S7 头(应答为12字节)
+        if reply.len() < 15 || reply[3] != 0x32 || reply[4] != 0x03 {
+            return Err(Error::new(ErrorKind::InvalidData, "S7应答格式错误"));
+        }
+        if reply[13] != 0 || reply[14] != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("S7应答错误,错误代码=0x{:02X}{:02X}", reply[13], reply[14]),
+            ));
+        }
+        let params_len = u16::from_be_bytes([reply[9], reply[10]]) as usize;
+        let data_len = u16::from_be_bytes([reply[11], reply[12]]) as usize;
+        let Some(body) = reply.get(15..15 + params_len + data_len) else {
+            return Err(Error::new(ErrorKind::InvalidData, "S7应答格式错误"));
+        };
+        let (params, data) = body.split_at(params_len);
+        Ok((params.to_vec(), data.to_vec()))
+    }
+
+    /// 变量地址：传输类型、数量、DB 号、区域、位地址
+    fn item(bit: bool, count: u16, area: u8, db: u16, address: u32) -> Vec<u8> {
+        let mut item = vec![0x12, 0x0A, 0x10, if bit { 0x01 } else { 0x02 }];
+        item.extend_from_slice(&count.to_be_bytes());
+        item.extend_from_slice(&db.to_be_bytes());
+        item.push(area);
+        item.extend_from_slice(&address.to_be_bytes()[1..]);
+        item
+    }
+
+    async fn read_bit(&mut self, area: u8, db: u16, address: u32) -> std::io::Result<bool> {
+        let mut params = vec![0x04, 0x01];
+        params.extend(S7::item(true, 1, area, db, address));
+        let (_, data) = self.job(&params, &[]).await?;
+        match data.as_slice() {
+            [0xFF, _, _, _, value, ..] => Ok(*value & 0x01 != 0),
+            _ => Err(Error::new(ErrorKind::InvalidData, "S7读取失败")),
+        }
+    }
+
+    async fn write(
+        &mut self,
+        area: u8,
+        db: u16,
+        address: u32,
+        bit: bool,
+        value: &[u8],
+    ) -> std::io::Result<()> {
+        let mut params = vec![0x05, 0x01];
+        params.extend(S7::item(bit, value.len() as u16, area, db, address));
+        // 返回码、传输类型(位或字节)、长度(位数，位写入时为1)
+        let mut data = vec![0x00, if bit { 0x03 } else { 0x04 }];
+        let len = if bit { 1 } else { value.len() as u16 * 8 };
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(value);
+        let (_, reply) = self.job(&params, &data).await?;
+        match reply.first() {
+            Some(0xFF) => Ok(()),
+            code => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("S7写入失败,返回码={:?}", code),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::{parse_mc, parse_s7, Address};
+    use crate::prelude::*;
+
+    #[test]
+    fn address() {
+        assert_eq!(parse_mc("D100"), Some(Address::McWord(0xA8, 100)));
+        assert_eq!(parse_mc("zr2000"), Some(Address::McWord(0xB0, 2000)));
+        assert_eq!(parse_mc("B1F"), Some(Address::McBit(0xA0, 0x1F)));
+        assert_eq!(parse_mc("M1F"), None);
+        assert_eq!(parse_s7("DB10.DBB0"), Some(Address::S7Byte(0x84, 10, 0)));
+        assert_eq!(
+            parse_s7("DB10.DBX100.7"),
+            Some(Address::S7Bit(0x84, 10, 100, 7))
+        );
+        assert_eq!(parse_s7("M100.0"), Some(Address::S7Bit(0x83, 0, 100, 0)));
+        assert_eq!(parse_s7("DB10.DBX100.8"), None);
+        assert!(PlcSink::s7("127.0.0.1", 0, 1)
+            .with_data("DB10.DBX0.0")
+            .spawn()
+            .is_err());
+    }
+
+    /// 模拟 MC 协议的 PLC，保存写入的字软元件和位软元件，读取位软元件时先复位
+    #[tokio::test]
+    async fn mc() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let words = Arc::new(Mutex::new(vec![]));
+        let bits = Arc::new(Mutex::new(vec![]));
+        let (w, b) = (Arc::clone(&words), Arc::clone(&bits));
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            loop {
+                let mut header = [0u8; 9];
+                if stream.read_exact(&mut header).await.is_err() {
+                    break;
+                }
+                let len = u16::from_le_bytes([header[7], header[8]]) as usize;
+                let mut request = vec![0u8; len];
+                stream.read_exact(&mut request).await.unwrap();
+                let command = [request[2], request[3], request[4]];
+                let mut reply = vec![0x00, 0x00];
+                match command {
+                    // 读位：PLC 已处理上一个条码
+                    [0x01, 0x04, 0x01] => reply.push(0x00),
+                    [0x01, 0x14, 0x00] => w.lock().unwrap().push(request[12..].to_vec()),
+                    [0x01, 0x14, 0x01] => b.lock().unwrap().push(request[6..].to_vec()),
+                    _ => panic!("未知指令"),
+                }
+                let mut frame = vec![0xD0, 0x00, 0x00, 0xFF, 0xFF, 0x03, 0x00];
+                frame.extend_from_slice(&(reply.len() as u16).to_le_bytes());
+                frame.extend_from_slice(&reply);
+                stream.write_all(&frame).await.unwrap();
+            }
+        });
+
+        let sink = PlcSink::mc("127.0.0.1", port)
+            .with_data("D100")
+            .with_handshake("M100")
+            .with_max_len(6)
+            .spawn()
+            .unwrap();
+        let barcode = |text| ScanEvent::Barcode(Barcode::from_text(text, Encoding::Utf8));
+        sink.send(FleetEvent::new("st1", barcode("SN001")))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", ScanEvent::Connected))
+            .await
+            .unwrap();
+        // 未读和比对不一致不写入
+        let text = |text| Barcode::from_text(text, Encoding::Utf8);
+        sink.send(FleetEvent::new("st1", ScanEvent::NoRead(text("ERROR"))))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", ScanEvent::Mismatch(text("SN9"))))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", barcode("SN0000002")))
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(
+            *words.lock().unwrap(),
+            vec![
+                vec![5, 0, b'S', b'N', b'0', b'0', b'1', 0],
+                vec![6, 0, b'S', b'N', b'0', b'0', b'0', b'0'],
+            ]
+        );
+        // M100 置位
+        let bit = vec![100, 0, 0, 0x90, 1, 0, 0x10];
+        assert_eq!(*bits.lock().unwrap(), vec![bit.clone(), bit]);
+    }
+}
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use redis::aio::ConnectionManager;
+use redis::{Client, Cmd};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, RetryPolicy, ScannerError};
+
+/// 发布方式
+#[derive(Clone, Debug)]
+enum Target {
+    /// `PUBLISH`到频道
+    Channel(String),
+    /// `XADD`到 Stream
+    Stream(String),
+}
+
+/// Redis 输出，把扫码枪事件以 JSON 发布到 Redis 频道(pub/sub)或追加到 Redis Stream
+///
+/// * 频道：消息内容为事件的 JSON，格式见[`FleetEvent`]的序列化
+/// * Stream：每个事件一条记录，字段`scanner`为扫码枪编号，`event`为事件的 JSON
+///
+/// 连接断开后自动重连，发送失败时按重试策略重发同一个事件，超过最大重试次数后丢弃
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let andon = RedisSink::stream("redis://192.168.1.2/", "scans")
+///     .with_max_len(100000)
+///     .spawn()?;
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_sink("andon", andon)
+///     .with_route(Route::new("andon"));
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RedisSink {
+    url: String,
+    target: Target,
+    max_len: Option<usize>,
+    retry: RetryPolicy,
+    capacity: usize,
+}
+
+impl RedisSink {
+    /// 创建发布到频道的 Redis 输出
+    ///
+    /// * `url` 服务器地址，例如`redis://:password@192.168.1.2:6379/0`
+    /// * `channel` 频道名称
+    pub fn publish(url: &str, channel: &str) -> Self {
+        RedisSink::new(url, Target::Channel(channel.into()))
+    }
+
+    /// 创建追加到 Stream 的 Redis 输出
+    ///
+    /// * `url` 服务器地址，例如`redis://:password@192.168.1.2:6379/0`
+    /// * `key` Stream 的键
+    pub fn stream(url: &str, key: &str) -> Self {
+        RedisSink::new(url, Target::Stream(key.into()))
+    }
+
+    fn new(url: &str, target: Target) -> Self {
+        RedisSink {
+            url: url.into(),
+            target,
+            max_len: None,
+            retry: RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)),
+            capacity: 1000,
+        }
+    }
+
+    /// 设置 Stream 的最大长度(近似裁剪，`MAXLEN ~`)，对频道无效
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// 设置重试策略，超过最大重试次数后丢弃该事件
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 设置发送队列长度
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// 获取频道名称或 Stream 的键
+    pub fn key(&self) -> &str {
+        match &self.target {
+            Target::Channel(key) | Target::Stream(key) => key,
+        }
+    }
+
+    /// 开始发布，返回的发送端可以作为[`crate::ScannerManager::with_sink`]的输出
+    ///
+    /// 需要在 tokio 运行时中调用，地址无效时返回参数错误，发送端全部关闭后结束
+    pub fn spawn(self) -> Result<Sender<FleetEvent>, ScannerError> {
+        let client = Client::open(self.url.as_str())
+            .map_err(|err| ScannerError::Param(format!("Redis地址无效,{}", err)))?;
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        tokio::spawn(self.run(client, receiver));
+        Ok(sender)
+    }
+
+    /// 按顺序发送事件
+    async fn run(self, client: Client, mut receiver: Receiver<FleetEvent>) {
+        let addr = client.get_connection_info().addr.to_string();
+        let mut connection: Option<ConnectionManager> = None;
+        while let Some(ev) = receiver.recv().await {
+            let cmd = match self.command(&ev) {
+                Ok(cmd) => cmd,
+                Err(err) => {
+                    event!(
+                        Level::ERROR,
+                        sink.target = %addr,
+                        error = %err,
+                        error.kind = "parse",
+                        "事件序列化失败",
+                    );
+                    continue;
+                }
+            };
+            let mut failures = 0u32;
+            loop {
+                let result = match &mut connection {
+                    Some(connection) => cmd.query_async::<()>(connection).await,
+                    None => match ConnectionManager::new(client.clone()).await {
+                        Ok(manager) => {
+                            event!(Level::INFO, sink.target = %addr, "Redis连接成功");
+                            let manager = connection.insert(manager);
+                            cmd.query_async::<()>(manager).await
+                        }
+                        Err(err) => Err(err),
+                    },
+                };
+                let Err(err) = result else {
+                    break;
+                };
+                failures += 1;
+                if self.retry.exhausted(failures) {
+                    event!(
+                        Level::ERROR,
+                        sink.target = %addr,
+                        scanner.id = %ev.id(),
+                        failures,
+                        error = %err,
+                        error.kind = "comm",
+                        "连续失败,丢弃事件",
+                    );
+                    break;
+                }
+                let delay = self.retry.delay(failures);
+                event!(
+                    Level::WARN,
+                    sink.target = %addr,
+                    delay = ?delay,
+                    error = %err,
+                    error.kind = "comm",
+                    "事件发送失败,等待重试",
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    /// 生成发布一个事件的命令
+    fn command(&self, ev: &FleetEvent) -> Result<Cmd, serde_json::Error> {
+        let json = serde_json::to_string(ev)?;
+        let cmd = match &self.target {
+            Target::Channel(channel) => redis::cmd("PUBLISH").arg(channel).arg(json).to_owned(),
+            Target::Stream(key) => {
+                let mut cmd = redis::cmd("XADD");
+                cmd.arg(key);
+                if let Some(max_len) = self.max_len {
+                    cmd.arg("MAXLEN").arg("~").arg(max_len);
+                }
+                cmd.arg("*")
+                    .arg("scanner")
+                    .arg(ev.id())
+                    .arg("event")
+                    .arg(json);
+                cmd
+            }
+        };
+        Ok(cmd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn command() {
+        let ev = FleetEvent::new(
+            "st1",
+            ScanEvent::Barcode(Barcode::from_text("SN0001", Encoding::Utf8)),
+        );
+        let args = |sink: RedisSink| {
+            let cmd = sink.command(&ev).unwrap();
+            cmd.args_iter()
+                .map(|arg| match arg {
+                    redis::Arg::Simple(arg) => String::from_utf8_lossy(arg).into_owned(),
+                    redis::Arg::Cursor => String::new(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let publish = args(RedisSink::publish("redis://127.0.0.1/", "scans"));
+        assert_eq!(publish[..2], ["PUBLISH", "scans"]);
+        assert!(publish[2].contains("SN0001"));
+        let stream = args(RedisSink::stream("redis://127.0.0.1/", "scans").with_max_len(1000));
+        assert_eq!(
+            stream[..8],
+            ["XADD", "scans", "MAXLEN", "~", "1000", "*", "scanner", "st1"]
+        );
+        assert_eq!(stream[8], "event");
+        assert!(stream[9].contains("SN0001"));
+    }
+}
@@ -0,0 +1,309 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, StatusCode};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing::{event, Level};
+
+use crate::{FleetEvent, RetryPolicy, ScannerError};
+
+/// HTTP 输出(Webhook)，把每个事件以 JSON POST 到指定地址
+///
+/// 请求失败(网络错误、超时、5xx、408、429)时事件留在内存队列中，按重试策略重发，
+/// 保证 MES 短暂不可用时不丢失条码；其它 4xx 应答视为事件无效，丢弃并记录日志。
+/// 队列已满时丢弃最早的事件。事件按顺序发送，前一个成功后才发送下一个
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// let mes = WebhookSink::new("https://mes.example.com/api/scans")
+///     .with_bearer("token")
+///     .with_header("X-Line", "1")?
+///     .with_retry(RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)))
+///     .with_capacity(10000)
+///     .spawn()?;
+/// let manager = ScannerManager::new()
+///     .with_scanner("line1-in", Scanner::new(Network::new_client("192.168.1.10", 9004)))
+///     .with_sink("mes", mes)
+///     .with_route(Route::new("mes"));
+/// manager.start().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WebhookSink {
+    url: String,
+    headers: HeaderMap,
+    timeout: Duration,
+    retry: RetryPolicy,
+    capacity: usize,
+}
+
+impl WebhookSink {
+    /// 创建 HTTP 输出，默认超时5秒，失败后指数退避重试(1秒~1分钟)，队列长度1000
+    ///
+    /// * `url` 接收事件的地址
+    pub fn new(url: &str) -> Self {
+        WebhookSink {
+            url: url.into(),
+            headers: HeaderMap::new(),
+            timeout: Duration::from_secs(5),
+            retry: RetryPolicy::exponential(Duration::from_secs(1), Duration::from_secs(60)),
+            capacity: 1000,
+        }
+    }
+
+    /// 添加请求头，名称或值无效时返回错误
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, ScannerError> {
+        let name = HeaderName::try_from(name)
+            .map_err(|err| ScannerError::Param(format!("请求头名称无效,{},name={}", err, name)))?;
+        let value = HeaderValue::try_from(value)
+            .map_err(|err| ScannerError::Param(format!("请求头无效,{},name={}", err, name)))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// 设置 Bearer 令牌认证
+    pub fn with_bearer(mut self, token: &str) -> Self {
+        if let Ok(mut value) = HeaderValue::try_from(format!("Bearer {}", token)) {
+            value.set_sensitive(true);
+            self.headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置重试策略，超过最大重试次数后丢弃该事件
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 设置等待发送的队列长度
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// 获取接收事件的地址
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 开始发送，返回的发送端可以作为[`crate::ScannerManager::with_sink`]的输出
+    ///
+    /// 需要在 tokio 运行时中调用，发送端全部关闭且队列中的事件发送完成后结束
+    pub fn spawn(self) -> Result<Sender<FleetEvent>, ScannerError> {
+        let client = Client::builder()
+            .default_headers(self.headers.clone())
+            .timeout(self.timeout)
+            .build()
+            .map_err(|err| ScannerError::Param(format!("HTTP客户端创建失败,{}", err)))?;
+        let (sender, receiver) = mpsc::channel(self.capacity);
+        tokio::spawn(self.run(client, receiver));
+        Ok(sender)
+    }
+
+    /// 接收事件放入队列，并按顺序发送
+    async fn run(self, client: Client, mut receiver: Receiver<FleetEvent>) {
+        let mut queue = VecDeque::new();
+        let mut closed = false;
+        let mut failures = 0u32;
+        loop {
+            // 队列为空时等待新的事件
+            if queue.is_empty() {
+                match receiver.recv().await {
+                    Some(ev) => queue.push_back(ev),
+                    None => break,
+                }
+            }
+            while let Ok(ev) = receiver.try_recv() {
+                self.push(&mut queue, ev);
+            }
+            let Some(ev) = queue.front() else {
+                continue;
+            };
+            match self.post(&client, ev).await {
+                Post::Sent => {
+                    failures = 0;
+                    queue.pop_front();
+                }
+                Post::Rejected(reason) => {
+                    event!(
+                        Level::ERROR,
+                        sink.target = %self.url,
+                        scanner.id = %ev.id(),
+                        reason = %reason,
+                        "事件被拒绝,丢弃",
+                    );
+                    failures = 0;
+                    queue.pop_front();
+                }
+                Post::Failed(reason) => {
+                    failures += 1;
+                    if self.retry.exhausted(failures) {
+                        event!(
+                            Level::ERROR,
+                            sink.target = %self.url,
+                            scanner.id = %ev.id(),
+                            failures,
+                            error = %reason,
+                            "连续失败,丢弃事件",
+                        );
+                        failures = 0;
+                        queue.pop_front();
+                        continue;
+                    }
+                    let delay = self.retry.delay(failures);
+                    event!(
+                        Level::WARN,
+                        sink.target = %self.url,
+                        delay = ?delay,
+                        pending = queue.len(),
+                        error = %reason,
+                        "事件发送失败,等待重试",
+                    );
+                    // 等待重试期间继续接收事件
+                    let sleep = tokio::time::sleep(delay);
+                    tokio::pin!(sleep);
+                    loop {
+                        tokio::select! {
+                            _ = &mut sleep => break,
+                            ev = receiver.recv(), if !closed => match ev {
+                                Some(ev) => self.push(&mut queue, ev),
+                                None => closed = true,
+                            },
+                        }
+                    }
+                }
+            }
+            if closed && queue.is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// 放入队列，已满时丢弃最早的事件
+    fn push(&self, queue: &mut VecDeque<FleetEvent>, ev: FleetEvent) {
+        if queue.len() >= self.capacity {
+            if let Some(dropped) = queue.pop_front() {
+                event!(
+                    Level::WARN,
+                    sink.target = %self.url,
+                    scanner.id = %dropped.id(),
+                    event = %dropped.event().printable(),
+                    "发送队列已满,丢弃最早的事件",
+                );
+            }
+        }
+        queue.push_back(ev);
+    }
+
+    /// 发送一个事件
+    async fn post(&self, client: &Client, ev: &FleetEvent) -> Post {
+        let body = match serde_json::to_vec(ev) {
+            Ok(body) => body,
+            Err(err) => return Post::Rejected(err.to_string()),
+        };
+        let response = client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => Post::Sent,
+            Ok(response) => {
+                let status = response.status();
+                if status.is_server_error()
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+                {
+                    Post::Failed(status.to_string())
+                } else {
+                    Post::Rejected(status.to_string())
+                }
+            }
+            Err(err) => Post::Failed(err.to_string()),
+        }
+    }
+}
+
+/// 发送结果
+enum Post {
+    /// 发送成功
+    Sent,
+    /// 服务器拒绝，不再重试
+    Rejected(String),
+    /// 发送失败，需要重试
+    Failed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::prelude::*;
+
+    /// 接收一个请求，返回请求内容
+    async fn accept(listener: &TcpListener, status: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = vec![];
+        let mut buf = [0u8; 1024];
+        while !String::from_utf8_lossy(&request).ends_with('}') {
+            let n = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+        }
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    #[tokio::test]
+    async fn retry() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/scans", listener.local_addr().unwrap());
+        let sink = WebhookSink::new(&url)
+            .with_bearer("token")
+            .with_retry(RetryPolicy::fixed(Duration::from_millis(10)))
+            .spawn()
+            .unwrap();
+        let barcode = |text| ScanEvent::Barcode(Barcode::from_text(text, Encoding::Utf8));
+        sink.send(FleetEvent::new("st1", barcode("SN0001")))
+            .await
+            .unwrap();
+        sink.send(FleetEvent::new("st1", barcode("SN0002")))
+            .await
+            .unwrap();
+
+        // 服务器错误后重发同一个事件，之后按顺序发送
+        let request = accept(&listener, "503 Service Unavailable").await;
+        assert!(request.starts_with("POST /scans"));
+        assert!(request.contains("authorization: Bearer token"));
+        assert!(request.contains("SN0001"));
+        assert!(accept(&listener, "200 OK").await.contains("SN0001"));
+        // 4xx 丢弃
+        assert!(accept(&listener, "400 Bad Request")
+            .await
+            .contains("SN0002"));
+        sink.send(FleetEvent::new("st1", barcode("SN0003")))
+            .await
+            .unwrap();
+        assert!(accept(&listener, "200 OK").await.contains("SN0003"));
+    }
+}
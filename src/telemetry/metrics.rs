@@ -0,0 +1,132 @@
+use crate::{ScanEvent, Scanner};
+
+/// 扫码次数
+#[cfg(feature = "metrics")]
+pub(crate) const SCANS_TOTAL: &str = "scanner_scans_total";
+/// 重连次数
+#[cfg(feature = "metrics")]
+pub(crate) const RECONNECTS_TOTAL: &str = "scanner_reconnects_total";
+/// 每帧数据的字节数
+#[cfg(feature = "metrics")]
+pub(crate) const FRAME_BYTES: &str = "scanner_frame_bytes";
+
+/// 注册指标说明，安装导出器后调用一次
+///
+/// 启用`metrics`功能后扫码枪通过[metrics](https://docs.rs/metrics)门面发出以下指标，
+/// 由应用安装的导出器(Prometheus、StatsD 等)收集：
+///
+/// | 名称 | 类型 | 标签 |
+/// | --- | --- | --- |
+/// | `scanner_scans_total` | 计数器 | `scanner`、`kind`(`barcode`/`no_read`/`match`/`mismatch`) |
+/// | `scanner_reconnects_total` | 计数器 | `scanner` |
+/// | `scanner_frame_bytes` | 直方图 | `scanner` |
+///
+/// `scanner`标签为扫码枪编号(见[`crate::Scanner::id`])，未设置编号时为连接地址
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// describe_metrics();
+/// ```
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    use ::metrics::{describe_counter, describe_histogram, Unit};
+
+    describe_counter!(SCANS_TOTAL, Unit::Count, "扫码枪接收的条码数量");
+    describe_counter!(RECONNECTS_TOTAL, Unit::Count, "扫码枪重新连接次数");
+    describe_histogram!(FRAME_BYTES, Unit::Bytes, "扫码枪每帧数据的字节数");
+}
+
+/// 记录一次扫码，只统计条码事件
+pub(crate) fn scan(scanner: &Scanner, ev: &ScanEvent) {
+    let kind = match ev {
+        ScanEvent::Barcode(_) => "barcode",
+        ScanEvent::NoRead(_) => "no_read",
+        ScanEvent::Match(_) => "match",
+        ScanEvent::Mismatch(_) => "mismatch",
+        _ => return,
+    };
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(SCANS_TOTAL, "scanner" => label(scanner), "kind" => kind).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (scanner, kind);
+}
+
+/// 记录一次重连
+pub(crate) fn reconnect(scanner: &Scanner) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(RECONNECTS_TOTAL, "scanner" => label(scanner)).increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = scanner;
+}
+
+/// 记录接收到的一帧数据
+pub(crate) fn frame(scanner: &Scanner, len: usize) {
+    #[cfg(feature = "metrics")]
+    ::metrics::histogram!(FRAME_BYTES, "scanner" => label(scanner)).record(len as f64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (scanner, len);
+}
+
+/// 指标的`scanner`标签
+#[cfg(feature = "metrics")]
+fn label(scanner: &Scanner) -> String {
+    match scanner.get_id() {
+        Some(id) => id.to_owned(),
+        None => scanner.connector.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn record() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let scanner = Scanner::new(MockConnector::new()).id("st1");
+        metrics::with_local_recorder(&recorder, || {
+            let barcode = Barcode::from_text("SN0001", Encoding::Utf8);
+            super::scan(&scanner, &ScanEvent::Barcode(barcode.clone()));
+            super::scan(&scanner, &ScanEvent::Mismatch(barcode));
+            super::scan(&scanner, &ScanEvent::Connected);
+            super::reconnect(&scanner);
+            super::frame(&scanner, 8);
+        });
+        let snapshot = snapshotter.snapshot().into_vec();
+        let value = |name: &str, kind: Option<&str>| {
+            snapshot
+                .iter()
+                .find(|(key, ..)| {
+                    let key = key.key();
+                    key.name() == name
+                        && key
+                            .labels()
+                            .any(|l| l.key() == "scanner" && l.value() == "st1")
+                        && kind.is_none_or(|kind| key.labels().any(|l| l.value() == kind))
+                })
+                .map(|(.., value)| value)
+        };
+        assert_eq!(
+            value(super::SCANS_TOTAL, Some("barcode")),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(
+            value(super::SCANS_TOTAL, Some("mismatch")),
+            Some(&DebugValue::Counter(1))
+        );
+        assert_eq!(value(super::SCANS_TOTAL, Some("connected")), None);
+        assert_eq!(
+            value(super::RECONNECTS_TOTAL, None),
+            Some(&DebugValue::Counter(1))
+        );
+        assert!(matches!(
+            value(super::FRAME_BYTES, None),
+            Some(DebugValue::Histogram(values)) if values.len() == 1
+        ));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod metrics;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub(crate) mod span;
@@ -0,0 +1,65 @@
+use std::net::SocketAddr;
+
+use metrics_exporter_prometheus::PrometheusBuilder;
+use tracing::{event, Level};
+
+use crate::{describe_metrics, ScannerError};
+
+/// 启动内置的 Prometheus 指标服务，通过`http://<addr>/metrics`导出扫码枪指标，
+/// 指标说明见[`describe_metrics`]
+///
+/// 安装为全局的 metrics 导出器，每个进程只能调用一次，需要在 tokio 运行时中调用
+///
+/// # Examples
+/// ```no_run
+/// use kim_scanner::prelude::*;
+///
+/// # async fn run() -> Result<(), ScannerError> {
+/// serve_metrics(([0, 0, 0, 0], 9100))?;
+/// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).id("line1-in");
+/// scanner.start().await??;
+/// # Ok(())
+/// # }
+/// ```
+pub fn serve_metrics(addr: impl Into<SocketAddr>) -> Result<(), ScannerError> {
+    let addr = addr.into();
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .map_err(|err| ScannerError::Param(format!("Prometheus指标服务启动失败,{}", err)))?;
+    describe_metrics();
+    event!(Level::INFO, server.addr = %addr, "Prometheus指标服务启动成功");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn serve_metrics() {
+        super::serve_metrics(([127, 0, 0, 1], 6009)).unwrap();
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone()).id("st1");
+        scanner.start().await.unwrap().unwrap();
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        mock.push("SN0001");
+        assert!(scanner.recv().await.is_some());
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let mut stream = TcpStream::connect("127.0.0.1:6009").await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut body = String::new();
+        stream.read_to_string(&mut body).await.unwrap();
+        assert!(body.contains("scanner_scans_total{scanner=\"st1\",kind=\"barcode\"} 1"));
+        assert!(body.contains("scanner_frame_bytes"));
+        // 只能安装一次
+        assert!(super::serve_metrics(([127, 0, 0, 1], 6009)).is_err());
+    }
+}
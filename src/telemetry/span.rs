@@ -0,0 +1,30 @@
+//! 连接的日志跨度，同一次连接的收发日志带相同的连接编号，多台扫码枪的日志交错时可以按连接筛选
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::task::JoinHandle;
+use tracing::{info_span, Instrument, Span};
+
+/// 进程内递增的连接编号，从 1 开始
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 生成新的连接编号
+fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// 创建一次连接尝试(服务器模式为一个接入的会话)的跨度，字段`conn.id`为新生成的连接编号
+///
+/// 连接中启动的收发任务需要用[`spawn`]继承该跨度
+pub(crate) fn connection() -> Span {
+    info_span!("connection", conn.id = next_id())
+}
+
+/// 在当前跨度内启动任务，连接中的收发任务用它代替`tokio::spawn`
+pub(crate) fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future.in_current_span())
+}
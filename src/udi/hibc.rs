@@ -0,0 +1,129 @@
+use super::parser::{Udi, UdiDate, UdiIssuer};
+use crate::ScannerError;
+
+/// HIBC 字符集，字符的位置即为其模 43 校验值
+const CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// 计算模 43 校验字符
+pub(crate) fn check_char(data: &str) -> Option<char> {
+    let sum = data
+        .chars()
+        .map(|c| CHARSET.find(c))
+        .sum::<Option<usize>>()?;
+    CHARSET.chars().nth(sum % 43)
+}
+
+/// 解析 HIBC LIC 条码(主数据、主次数据合并或单独的次数据)
+pub(crate) fn parse(data: &str) -> Result<Udi, ScannerError> {
+    let data = data.trim_matches('*');
+    let check = data.chars().last();
+    let body = &data[..data.len() - check.map_or(0, char::len_utf8)];
+    if check.is_none() || check_char(body) != check {
+        return Err(ScannerError::Parse(format!(
+            "HIBC校验字符错误,data={}",
+            data
+        )));
+    }
+    let body = &body[1..];
+    let (primary, secondary) = match body.split_once('/') {
+        Some((primary, secondary)) => (primary, Some(secondary)),
+        // 单独的次数据条码以`$`或日期开头，末尾带一个链接字符
+        None if body.starts_with('$') || body.starts_with(|c: char| c.is_ascii_digit()) => {
+            ("", body.get(..body.len().saturating_sub(1)))
+        }
+        None => (body, None),
+    };
+    // 单独的次数据条码没有主数据
+    let is_secondary_only = primary.is_empty() && secondary.is_some();
+    if !is_secondary_only
+        && (primary.len() < 6 || !primary.starts_with(|c: char| c.is_ascii_uppercase()))
+    {
+        return Err(ScannerError::Parse(format!(
+            "HIBC主数据无效,data={}",
+            primary
+        )));
+    }
+    let mut udi = Udi {
+        issuer: UdiIssuer::Hibc,
+        di: primary.into(),
+        lot: None,
+        serial: None,
+        expiry: None,
+        manufactured: None,
+    };
+    if let Some(secondary) = secondary {
+        parse_secondary(&mut udi, secondary)?;
+    }
+    Ok(udi)
+}
+
+/// 解析次数据(失效日期、批号、序列号)
+fn parse_secondary(udi: &mut Udi, s: &str) -> Result<(), ScannerError> {
+    let invalid = || ScannerError::Parse(format!("HIBC次数据无效,data={}", s));
+    let (is_serial, rest) = if let Some(rest) = s.strip_prefix("$$+") {
+        (true, parse_date(udi, rest).ok_or_else(invalid)?)
+    } else if let Some(rest) = s.strip_prefix("$$") {
+        (false, parse_date(udi, rest).ok_or_else(invalid)?)
+    } else if let Some(rest) = s.strip_prefix("$+") {
+        (true, rest)
+    } else if let Some(rest) = s.strip_prefix('$') {
+        (false, rest)
+    } else {
+        udi.expiry = Some(UdiDate::from_julian(s).ok_or_else(invalid)?);
+        (false, &s[5..])
+    };
+    if !rest.is_empty() {
+        if is_serial {
+            udi.serial = Some(rest.into());
+        } else {
+            udi.lot = Some(rest.into());
+        }
+    }
+    Ok(())
+}
+
+/// 按日期格式标志解析失效日期，返回剩余数据
+fn parse_date<'a>(udi: &mut Udi, s: &'a str) -> Option<&'a str> {
+    let flag = s.chars().next()?;
+    let (date, len) = match flag {
+        // MMYY
+        '0' | '1' => {
+            let yymm = format!("{}{}", s.get(2..4)?, s.get(0..2)?);
+            (UdiDate::from_yymmdd(&format!("{}00", yymm)), 4)
+        }
+        // MMDDYY
+        '2' => {
+            let (mm, dd, yy) = (s.get(1..3)?, s.get(3..5)?, s.get(5..7)?);
+            (UdiDate::from_yymmdd(&format!("{}{}{}", yy, mm, dd)), 7)
+        }
+        '3' => (UdiDate::from_yymmdd(s.get(1..7)?), 7),
+        '4' => (UdiDate::from_yymmdd(s.get(1..7)?), 9),
+        '5' => (UdiDate::from_julian(s.get(1..6)?), 6),
+        '6' => (UdiDate::from_julian(s.get(1..6)?), 8),
+        '7' => return s.get(1..),
+        // 数量(2 位或 5 位)之后再跟日期格式标志
+        '8' => return parse_date(udi, s.get(3..)?),
+        '9' => return parse_date(udi, s.get(6..)?),
+        _ => return None,
+    };
+    udi.expiry = Some(date?);
+    s.get(len..)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn primary_only() {
+        let udi = Udi::parse("*+A999123457*").unwrap();
+        assert_eq!(udi.di(), "A99912345");
+        assert_eq!(udi.lot(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Udi::parse("+A99912345/$$52001510X34").is_err());
+        assert!(Udi::parse("+X").is_err());
+    }
+}
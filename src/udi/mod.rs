@@ -0,0 +1,2 @@
+pub mod hibc;
+pub mod parser;
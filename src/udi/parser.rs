@@ -0,0 +1,152 @@
+use super::hibc;
+use crate::{Gs1, ScannerError};
+
+/// UDI 发码机构
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum UdiIssuer {
+    /// GS1
+    Gs1,
+    /// HIBCC(HIBC LIC)
+    Hibc,
+}
+
+/// UDI 日期，`day`为 0 时表示只精确到月
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UdiDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl UdiDate {
+    /// 解析`YYMMDD`格式的日期
+    pub(crate) fn from_yymmdd(s: &str) -> Option<UdiDate> {
+        let n = |r: std::ops::Range<usize>| s.get(r)?.parse::<u8>().ok();
+        UdiDate::new(n(0..2)?, n(2..4)?, n(4..6)?)
+    }
+
+    /// 解析`YYJJJ`格式(年份 + 一年中的第几天)的日期
+    pub(crate) fn from_julian(s: &str) -> Option<UdiDate> {
+        let yy = s.get(0..2)?.parse::<u8>().ok()?;
+        let mut days = s.get(2..5)?.parse::<u16>().ok()?;
+        let year = 2000 + yy as u16;
+        for month in 1..=12u8 {
+            let len = days_in_month(year, month) as u16;
+            if days <= len {
+                return UdiDate::new(yy, month, days as u8);
+            }
+            days -= len;
+        }
+        None
+    }
+
+    fn new(yy: u8, month: u8, day: u8) -> Option<UdiDate> {
+        let year = 2000 + yy as u16;
+        if !(1..=12).contains(&month) || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(UdiDate { year, month, day })
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 医疗器械唯一标识(UDI)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Udi {
+    pub(crate) issuer: UdiIssuer,
+    pub(crate) di: String,
+    pub(crate) lot: Option<String>,
+    pub(crate) serial: Option<String>,
+    pub(crate) expiry: Option<UdiDate>,
+    pub(crate) manufactured: Option<UdiDate>,
+}
+
+impl Udi {
+    /// 解析 UDI 条码，以`+`开头的按 HIBC 解析，否则按 GS1 解析
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let udi = Udi::parse("]d2010884385200032517271231100ABC\x1d21SN1").unwrap();
+    /// assert_eq!(udi.issuer(), &UdiIssuer::Gs1);
+    /// assert_eq!(udi.di(), "08843852000325");
+    /// assert_eq!(udi.lot(), Some("0ABC"));
+    /// assert_eq!(udi.serial(), Some("SN1"));
+    /// assert_eq!(udi.expiry(), Some(UdiDate { year: 2027, month: 12, day: 31 }));
+    ///
+    /// let udi = Udi::parse("+A99912345/$$52001510X33").unwrap();
+    /// assert_eq!(udi.issuer(), &UdiIssuer::Hibc);
+    /// assert_eq!(udi.di(), "A99912345");
+    /// assert_eq!(udi.lot(), Some("10X3"));
+    /// assert_eq!(udi.expiry(), Some(UdiDate { year: 2020, month: 1, day: 15 }));
+    /// ```
+    pub fn parse(data: &str) -> Result<Udi, ScannerError> {
+        let data = data.trim();
+        if data.trim_start_matches('*').starts_with('+') {
+            return hibc::parse(data);
+        }
+        let gs1 = Gs1::parse(data)?;
+        let di = gs1
+            .get("01")
+            .ok_or_else(|| ScannerError::Parse("GS1 UDI缺少DI(01)".into()))?;
+        let date = |ai: &str| match gs1.get(ai) {
+            Some(s) => UdiDate::from_yymmdd(s)
+                .map(Some)
+                .ok_or_else(|| ScannerError::Parse(format!("AI({})日期无效,value={}", ai, s))),
+            None => Ok(None),
+        };
+        Ok(Udi {
+            issuer: UdiIssuer::Gs1,
+            di: di.into(),
+            lot: gs1.get("10").map(Into::into),
+            serial: gs1.get("21").map(Into::into),
+            expiry: date("17")?,
+            manufactured: date("11")?,
+        })
+    }
+
+    /// 获取发码机构
+    pub fn issuer(&self) -> &UdiIssuer {
+        &self.issuer
+    }
+
+    /// 获取器械标识(DI)
+    pub fn di(&self) -> &str {
+        &self.di
+    }
+
+    /// 获取批号
+    pub fn lot(&self) -> Option<&str> {
+        self.lot.as_deref()
+    }
+
+    /// 获取序列号
+    pub fn serial(&self) -> Option<&str> {
+        self.serial.as_deref()
+    }
+
+    /// 获取失效日期
+    pub fn expiry(&self) -> Option<UdiDate> {
+        self.expiry
+    }
+
+    /// 获取生产日期(仅 GS1)
+    pub fn manufactured(&self) -> Option<UdiDate> {
+        self.manufactured
+    }
+}
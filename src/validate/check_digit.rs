@@ -0,0 +1,177 @@
+/// 校验位算法
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CheckDigit {
+    /// EAN-8
+    Ean8,
+    /// EAN-13
+    Ean13,
+    /// UPC-A
+    UpcA,
+    /// UPC-E(8 位，含数制位和校验位)
+    UpcE,
+    /// ITF-14
+    Itf14,
+    /// Code 11，数据不少于 10 位时带两个校验位(C、K)，否则只带一个校验位(C)
+    Code11,
+}
+
+impl CheckDigit {
+    /// 验证条码的校验位
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert!(CheckDigit::Ean13.verify("6901234567892"));
+    /// assert!(!CheckDigit::Ean13.verify("6901234567891"));
+    /// assert!(CheckDigit::Ean8.verify("96385074"));
+    /// assert!(CheckDigit::UpcA.verify("036000291452"));
+    /// assert!(CheckDigit::UpcE.verify("04252614"));
+    /// assert!(CheckDigit::Itf14.verify("15400141288763"));
+    /// assert!(CheckDigit::Code11.verify("123-455"));
+    /// assert!(CheckDigit::Code11.verify("123456789019"));
+    /// ```
+    pub fn verify(&self, code: &str) -> bool {
+        let len = match self {
+            CheckDigit::Ean8 | CheckDigit::UpcE => 8,
+            CheckDigit::Ean13 => 13,
+            CheckDigit::UpcA => 12,
+            CheckDigit::Itf14 => 14,
+            CheckDigit::Code11 => return verify_code11(code),
+        };
+        if code.len() != len || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        let digits: Vec<u8> = code.bytes().map(|b| b - b'0').collect();
+        let (data, check) = digits.split_at(len - 1);
+        match self {
+            CheckDigit::UpcE => matches!(data[0], 0 | 1) && mod10(&expand_upce(data)) == check[0],
+            _ => mod10(data) == check[0],
+        }
+    }
+}
+
+/// GS1 模 10 校验位，从右往左权重依次为 3、1
+pub(crate) fn mod10(data: &[u8]) -> u8 {
+    let sum: u32 = data
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| *d as u32 * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// 将 UPC-E(数制位 + 6 位数据)展开为 UPC-A 的 11 位数据
+fn expand_upce(data: &[u8]) -> Vec<u8> {
+    let ns = data[0];
+    let d = &data[1..7];
+    let body: [u8; 10] = match d[5] {
+        0..=2 => [d[0], d[1], d[5], 0, 0, 0, 0, d[2], d[3], d[4]],
+        3 => [d[0], d[1], d[2], 0, 0, 0, 0, 0, d[3], d[4]],
+        4 => [d[0], d[1], d[2], d[3], 0, 0, 0, 0, 0, d[4]],
+        _ => [d[0], d[1], d[2], d[3], d[4], 0, 0, 0, 0, d[5]],
+    };
+    std::iter::once(ns).chain(body).collect()
+}
+
+/// Code 11 字符值，`-`为 10
+fn code11_value(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'-' => Some(10),
+        _ => None,
+    }
+}
+
+/// Code 11 校验位，权重从右往左为 1~`max_weight` 循环
+fn code11_check(values: &[u32], max_weight: u32) -> u32 {
+    let sum: u32 = values
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, v)| v * (i as u32 % max_weight + 1))
+        .sum();
+    sum % 11
+}
+
+fn verify_code11(code: &str) -> bool {
+    let values: Option<Vec<u32>> = code.bytes().map(code11_value).collect();
+    let Some(values) = values else {
+        return false;
+    };
+    // 数据不少于 10 位时带 C、K 两个校验位
+    let checks = if values.len() >= 12 { 2 } else { 1 };
+    if values.len() <= checks {
+        return false;
+    }
+    let data_len = values.len() - checks;
+    let c = code11_check(&values[..data_len], 10);
+    if values[data_len] != c {
+        return false;
+    }
+    checks == 1 || values[data_len + 1] == code11_check(&values[..=data_len], 9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_upce, mod10, CheckDigit};
+
+    fn digits(code: &str) -> Vec<u8> {
+        code.bytes().map(|b| b - b'0').collect()
+    }
+
+    #[test]
+    fn gs1_mod10() {
+        assert_eq!(mod10(&digits("400638133393")), 1);
+        assert_eq!(mod10(&digits("690123456789")), 2);
+        // 校验和正好是 10 的倍数时校验位为 0
+        assert_eq!(mod10(&digits("0000000")), 0);
+        assert!(CheckDigit::Ean13.verify("4006381333931"));
+        assert!(!CheckDigit::Ean13.verify("4006381333932"));
+        assert!(!CheckDigit::Ean8.verify("96385075"));
+        assert!(!CheckDigit::UpcA.verify("036000291453"));
+        assert!(!CheckDigit::Itf14.verify("15400141288764"));
+    }
+
+    #[test]
+    fn length_and_digits() {
+        assert!(!CheckDigit::Ean13.verify("690123456789"));
+        assert!(!CheckDigit::Ean13.verify("69012345678920"));
+        assert!(!CheckDigit::Ean8.verify("9638507A"));
+        assert!(!CheckDigit::UpcA.verify(""));
+        // EAN-8 的条码不能按 EAN-13 验证
+        assert!(!CheckDigit::Ean13.verify("96385074"));
+    }
+
+    #[test]
+    fn upce() {
+        // 最后一位数据决定展开方式
+        assert_eq!(expand_upce(&digits("0123450")), digits("01200000345"));
+        assert_eq!(expand_upce(&digits("0123453")), digits("01230000045"));
+        assert_eq!(expand_upce(&digits("0123454")), digits("01234000005"));
+        assert_eq!(expand_upce(&digits("0123457")), digits("01234500007"));
+        for code in ["01234505", "01234531", "01234543", "01234572", "16543214"] {
+            assert!(CheckDigit::UpcE.verify(code), "{}", code);
+        }
+        assert!(!CheckDigit::UpcE.verify("01234506"));
+        // 数制位只能是 0 或 1
+        assert!(!CheckDigit::UpcE.verify("21234505"));
+    }
+
+    #[test]
+    fn code11() {
+        assert!(CheckDigit::Code11.verify("124"));
+        // 校验值 10 编码为`-`
+        assert!(CheckDigit::Code11.verify("18-"));
+        assert!(!CheckDigit::Code11.verify("123-454"));
+        // 带 C、K 两个校验位，K 错误
+        assert!(!CheckDigit::Code11.verify("123456789018"));
+        assert!(!CheckDigit::Code11.verify("12A4"));
+        // 只有校验位没有数据
+        assert!(!CheckDigit::Code11.verify("1"));
+        assert!(!CheckDigit::Code11.verify(""));
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+
+/// 比对目标
+#[derive(Clone, Debug)]
+enum Target {
+    /// 与基准条码完全相同，`None`表示下一个条码作为基准
+    Master(Arc<Mutex<Option<String>>>),
+    /// 匹配正则表达式
+    Pattern(Regex),
+}
+
+/// 比对结果
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Matched {
+    /// 条码成为新的基准条码
+    Taught,
+    Match,
+    Mismatch,
+}
+
+/// 条码比对(Match Code)，用于防错料、防混料工位
+///
+/// 设置后条码产生[`crate::ScanEvent::Match`]或[`crate::ScanEvent::Mismatch`]事件，
+/// 而不是条码事件，并可以自动给扫码枪发送 OK/NG 输出指令。
+/// 克隆的配置共享同一个基准条码
+///
+/// # Examples
+/// ```
+/// use kim_scanner::prelude::*;
+///
+/// // 与基准条码比对，比对结果控制扫码枪的输出端口
+/// let master = MatchCode::master("SN0001")
+///     .with_ok("OUTON,1\r")
+///     .with_ng("OUTON,2\r");
+/// let scanner = Scanner::new(Network::new_client("192.168.100.100", 9004)).match_code(master.clone());
+/// // 换型时更换基准条码
+/// master.set_master("SN0002");
+///
+/// // 示教：下一个条码作为基准条码
+/// let teach = MatchCode::teach();
+///
+/// // 按格式比对
+/// let pattern = MatchCode::pattern(Regex::new("^LOT-A\\d{4}$").unwrap());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MatchCode {
+    target: Target,
+    ok: Option<String>,
+    ng: Option<String>,
+}
+
+impl MatchCode {
+    /// 与基准条码比对
+    pub fn master(code: &str) -> Self {
+        MatchCode::new(Target::Master(Arc::new(Mutex::new(Some(code.into())))))
+    }
+
+    /// 示教模式，下一个条码作为基准条码(产生条码事件)，之后的条码与其比对
+    pub fn teach() -> Self {
+        MatchCode::new(Target::Master(Arc::new(Mutex::new(None))))
+    }
+
+    /// 按正则表达式比对
+    pub fn pattern(pattern: Regex) -> Self {
+        MatchCode::new(Target::Pattern(pattern))
+    }
+
+    fn new(target: Target) -> Self {
+        MatchCode {
+            target,
+            ok: None,
+            ng: None,
+        }
+    }
+
+    /// 设置比对一致时发送给扫码枪的指令
+    pub fn with_ok(mut self, cmd: &str) -> Self {
+        self.ok = Some(cmd.into());
+        self
+    }
+
+    /// 设置比对不一致时发送给扫码枪的指令
+    pub fn with_ng(mut self, cmd: &str) -> Self {
+        self.ng = Some(cmd.into());
+        self
+    }
+
+    /// 更换基准条码，按正则表达式比对时无效
+    pub fn set_master(&self, code: &str) {
+        if let Target::Master(master) = &self.target {
+            *master.lock().unwrap() = Some(code.into());
+        }
+    }
+
+    /// 清除基准条码，下一个条码作为基准条码
+    pub fn reset(&self) {
+        if let Target::Master(master) = &self.target {
+            master.lock().unwrap().take();
+        }
+    }
+
+    /// 获取基准条码
+    pub fn get_master(&self) -> Option<String> {
+        match &self.target {
+            Target::Master(master) => master.lock().unwrap().clone(),
+            Target::Pattern(_) => None,
+        }
+    }
+
+    /// 获取比对一致时的指令
+    pub fn ok(&self) -> Option<&str> {
+        self.ok.as_deref()
+    }
+
+    /// 获取比对不一致时的指令
+    pub fn ng(&self) -> Option<&str> {
+        self.ng.as_deref()
+    }
+
+    /// 比对条码
+    pub(crate) fn check(&self, code: &str) -> Matched {
+        let matched = match &self.target {
+            Target::Master(master) => {
+                let mut master = master.lock().unwrap();
+                match master.as_deref() {
+                    Some(master) => master == code,
+                    None => {
+                        *master = Some(code.into());
+                        return Matched::Taught;
+                    }
+                }
+            }
+            Target::Pattern(pattern) => pattern.is_match(code),
+        };
+        if matched {
+            Matched::Match
+        } else {
+            Matched::Mismatch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{MatchCode, Matched};
+    use crate::prelude::*;
+
+    #[test]
+    fn check() {
+        let teach = MatchCode::teach();
+        assert_eq!(teach.check("SN0001"), Matched::Taught);
+        assert_eq!(teach.check("SN0001"), Matched::Match);
+        assert_eq!(teach.check("SN0002"), Matched::Mismatch);
+        teach.clone().set_master("SN0002");
+        assert_eq!(teach.check("SN0002"), Matched::Match);
+        teach.reset();
+        assert_eq!(teach.get_master(), None);
+    }
+
+    #[tokio::test]
+    async fn match_code() {
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone())
+            .match_code(MatchCode::master("SN0001").with_ok("OK").with_ng("NG"));
+        scanner.start().await.unwrap().unwrap();
+        mock.push("SN0001");
+        mock.push("SN0002");
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Match(_))));
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Mismatch(_)));
+        assert_eq!(ev.as_str_lossy().unwrap(), "SN0002");
+        assert_eq!(mock.next_command().await.unwrap(), "OK");
+        assert_eq!(mock.next_command().await.unwrap(), "NG");
+    }
+
+    #[tokio::test]
+    async fn match_code_refused() {
+        let match_code = || MatchCode::master("SN0001").with_ok("OK").with_ng("NG");
+        // 只能接收数据的连接方式不把比对结果指令放入队列
+        let replay = Scanner::new(Replay::parse("0 SN0001").unwrap()).match_code(match_code());
+        // 未连接时按策略不放入队列
+        let offline = Scanner::new(Network::new_client("127.0.0.1", 9))
+            .queue_policy(QueuePolicy::FailFast)
+            .match_code(match_code());
+        for scanner in [replay, offline] {
+            for _ in 0..200 {
+                scanner.dispatch("test", Bytes::from_static(b"SN0001"));
+                scanner.dispatch("test", Bytes::from_static(b"SN0002"));
+            }
+            tokio::task::yield_now().await;
+            let sender = scanner.sender.lock().await;
+            assert_eq!(sender.capacity(), sender.max_capacity());
+        }
+    }
+
+    #[tokio::test]
+    async fn match_code_transform() {
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone())
+            .match_code(MatchCode::master("SN0001"))
+            .layer(Transform::Prefix("L01-".into()));
+        scanner.start().await.unwrap().unwrap();
+        mock.push("SN0001");
+        mock.push("SN0002");
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        // 比对使用原始条码，比对结果事件中的条码经过转换
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Match(_)));
+        assert_eq!(ev.as_str_lossy().unwrap(), "L01-SN0001");
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Mismatch(_)));
+        assert_eq!(ev.as_str_lossy().unwrap(), "L01-SN0002");
+    }
+
+    #[tokio::test]
+    async fn match_code_gs_normalizer() {
+        let mock = MockConnector::new();
+        let scanner = Scanner::new(mock.clone())
+            .match_code(MatchCode::master("]C1010952123454321310ABC"))
+            .layer(GsNormalizer::default());
+        scanner.start().await.unwrap().unwrap();
+        mock.push("]C1010952123454321310ABC");
+        assert!(matches!(scanner.recv().await, Some(ScanEvent::Connected)));
+        let ev = scanner.recv().await.unwrap();
+        assert!(matches!(ev, ScanEvent::Match(_)));
+        assert_eq!(ev.as_str_lossy().unwrap(), "010952123454321310ABC");
+    }
+}
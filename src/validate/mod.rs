@@ -0,0 +1,4 @@
+pub mod check_digit;
+pub mod match_code;
+pub mod validator;
+pub mod vin;
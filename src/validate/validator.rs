@@ -0,0 +1,22 @@
+use crate::CheckDigit;
+
+/// 条码校验器，见[`Scanner::validator`](crate::Scanner::validator)
+pub trait Validator: Send + Sync {
+    /// 校验条码，返回是否通过
+    fn validate(&self, code: &str) -> bool;
+}
+
+impl<F> Validator for F
+where
+    F: Fn(&str) -> bool + Send + Sync,
+{
+    fn validate(&self, code: &str) -> bool {
+        self(code)
+    }
+}
+
+impl Validator for CheckDigit {
+    fn validate(&self, code: &str) -> bool {
+        self.verify(code)
+    }
+}
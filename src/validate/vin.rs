@@ -0,0 +1,140 @@
+use crate::ScannerError;
+
+/// 车辆识别代号(VIN)各位置的权重
+const WEIGHTS: [u32; 17] = [8, 7, 6, 5, 4, 3, 2, 10, 0, 9, 8, 7, 6, 5, 4, 3, 2];
+
+/// 年份代码，每 30 年循环一次
+const YEAR_CODES: &str = "ABCDEFGHJKLMNPRSTVWXY123456789";
+
+/// 车辆识别代号(VIN)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vin {
+    code: String,
+}
+
+impl Vin {
+    /// 解析并校验 VIN，进口车辆 Code39 标签开头的`I`会被忽略
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// let vin = Vin::parse("1M8GDM9AXKP042788").unwrap();
+    /// assert_eq!(vin.wmi(), "1M8");
+    /// assert_eq!(vin.vds(), "GDM9AX");
+    /// assert_eq!(vin.vis(), "KP042788");
+    /// assert_eq!(vin.model_year(), Some(1989));
+    /// assert_eq!(vin.plant(), 'P');
+    /// assert_eq!(vin.serial(), "042788");
+    ///
+    /// assert!(Vin::parse("1M8GDM9AYKP042788").is_err());
+    /// ```
+    pub fn parse(code: &str) -> Result<Vin, ScannerError> {
+        let code = code.trim();
+        let code = match code.strip_prefix('I') {
+            Some(rest) if code.len() == 18 => rest,
+            _ => code,
+        };
+        if code.len() != 17 {
+            return Err(ScannerError::Parse(format!("VIN长度应为17,vin={}", code)));
+        }
+        let check = check_digit(code)
+            .ok_or_else(|| ScannerError::Parse(format!("VIN包含无效字符,vin={}", code)))?;
+        if code.as_bytes()[8] as char != check {
+            return Err(ScannerError::Parse(format!(
+                "VIN校验位错误,期望={},vin={}",
+                check, code
+            )));
+        }
+        Ok(Vin { code: code.into() })
+    }
+
+    /// 校验 VIN，可直接作为扫码枪的校验器使用
+    ///
+    /// # Examples
+    /// ```
+    /// use kim_scanner::prelude::*;
+    ///
+    /// assert!(Vin::verify("1M8GDM9AXKP042788"));
+    /// let scanner = Scanner::new(Network::new_client("192.168.1.10", 9004)).validator(Vin::verify);
+    /// ```
+    pub fn verify(code: &str) -> bool {
+        Vin::parse(code).is_ok()
+    }
+
+    /// 获取完整 VIN
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// 世界制造厂识别代号(WMI)，第 1~3 位
+    pub fn wmi(&self) -> &str {
+        &self.code[0..3]
+    }
+
+    /// 车辆说明部分(VDS)，第 4~9 位
+    pub fn vds(&self) -> &str {
+        &self.code[3..9]
+    }
+
+    /// 车辆指示部分(VIS)，第 10~17 位
+    pub fn vis(&self) -> &str {
+        &self.code[9..17]
+    }
+
+    /// 车型年份，第 10 位
+    ///
+    /// 年份代码每 30 年循环一次，第 7 位为字母时取 2010 年之后的年份
+    pub fn model_year(&self) -> Option<u16> {
+        let code = self.code.as_bytes()[9] as char;
+        let index = YEAR_CODES.find(code)? as u16;
+        let base = if self.code.as_bytes()[6].is_ascii_alphabetic() {
+            2010
+        } else {
+            1980
+        };
+        Some(base + index)
+    }
+
+    /// 装配厂代码，第 11 位
+    pub fn plant(&self) -> char {
+        self.code.as_bytes()[10] as char
+    }
+
+    /// 生产序号，第 12~17 位
+    pub fn serial(&self) -> &str {
+        &self.code[11..17]
+    }
+}
+
+/// 字符对应的数值，`I`、`O`、`Q`不允许出现在 VIN 中
+fn transliterate(c: u8) -> Option<u32> {
+    let value = match c {
+        b'0'..=b'9' => c - b'0',
+        b'A' | b'J' => 1,
+        b'B' | b'K' | b'S' => 2,
+        b'C' | b'L' | b'T' => 3,
+        b'D' | b'M' | b'U' => 4,
+        b'E' | b'N' | b'V' => 5,
+        b'F' | b'W' => 6,
+        b'G' | b'P' | b'X' => 7,
+        b'H' | b'Y' => 8,
+        b'R' | b'Z' => 9,
+        _ => return None,
+    };
+    Some(value as u32)
+}
+
+/// 计算第 9 位校验位
+fn check_digit(code: &str) -> Option<char> {
+    let sum = code
+        .bytes()
+        .zip(WEIGHTS)
+        .map(|(c, w)| transliterate(c).map(|v| v * w))
+        .sum::<Option<u32>>()?;
+    match sum % 11 {
+        10 => Some('X'),
+        n => char::from_digit(n, 10),
+    }
+}